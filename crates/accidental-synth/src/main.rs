@@ -86,5 +86,8 @@ fn main() {
     application
         .run()
         .expect("Could not create the user interface.");
+
+    log::debug!(target: "main", "Save the global settings");
+    synthesizer.save_global_settings();
     log::info!(target: "main", "Exiting Accidental Synthesizer");
 }
@@ -5,13 +5,17 @@ mod structs;
 mod update_listener;
 
 use super::{
-    AccidentalSynth, AudioDevice, EffectsValues, EnvelopeValues, FilterCutoff, FilterOptions,
-    GlobalOptions, LFOValues, MidiPort, Mixer, Oscillator, PatchStatus,
+    AccidentalSynth, AudioDevice, BankLFOValues, CrossModulationValues, EffectsValues,
+    EnvelopeValues, ExpressionOptions, FilterCutoff, FilterOptions, FilterRoutingValues,
+    GlobalOptions, LFOValues, LfoBankOptions, MidiPort, Mixer, Oscillator, PatchStatus,
+    SecondFilterOptions, TuningValues, VoiceOptions,
 };
 use crate::ui::callbacks::register_callbacks;
+use crate::ui::constants::MAX_PHASE_VALUE;
 use crate::ui::structs::{
-    UIAudioDevice, UIEnvelope, UIFilterCutoff, UIFilterOptions, UILfo, UIMidiPort, UIMixer,
-    UIOscillator,
+    UIAudioDevice, UICrossModulation, UIEnvelope, UIExpressionOptions, UIFilterCutoff,
+    UIFilterOptions, UIFilterRouting, UILfo, UILfoBank, UIMidiPort, UIMixer, UIOscillator,
+    UISecondFilterOptions, UITuning, UIVoiceOptions,
 };
 use crate::ui::update_listener::start_ui_update_listener;
 use accsyn_core::audio_events::AudioDeviceUpdateEvents;
@@ -46,16 +50,27 @@ pub(super) struct ParameterValues {
     midi_port: UIMidiPort,
     oscillators: Vec<UIOscillator>,
     oscillator_fine_tune: Vec<i32>,
+    oscillator_wavetables: Vec<String>,
+    oscillator_samples: Vec<String>,
+    sub_octave: i32,
+    cross_modulation: Vec<UICrossModulation>,
     amp_envelope: UIEnvelope,
     filter_envelope: UIEnvelope,
     pitch_envelope: UIEnvelope,
     mod_wheel_lfo: UILfo,
     filter_lfo: UILfo,
+    lfo_bank: UILfoBank,
     filter_cutoff: UIFilterCutoff,
     filter_options: UIFilterOptions,
+    second_filter_cutoff: UIFilterCutoff,
+    second_filter_options: UISecondFilterOptions,
+    filter_routing: UIFilterRouting,
     output_mixer: UIMixer,
     oscillator_mixer: Vec<UIMixer>,
     global_options: UIGlobalOptions,
+    voice_options: UIVoiceOptions,
+    expression_options: UIExpressionOptions,
+    tuning: UITuning,
     midi_screen: Vec<String>,
     effects: Vec<EffectParameters>,
     selected_patch_index: Option<i32>,
@@ -189,12 +204,28 @@ pub(super) fn push_values_to_ui(
         ui.set_filter_lfo_frequency_display(exponential_curve_lfo_frequency_from_normal_value(
             ui_default_values.filter_lfo.frequency,
         ));
+        ui.set_lfo_bank_options(slint_lfo_bank_options_from_ui_lfo_bank(
+            &ui_default_values.lfo_bank,
+        ));
+        ui.set_bank_lfo_values(slint_bank_lfo_values_from_ui_lfo_bank(
+            &ui_default_values.lfo_bank,
+        ));
         ui.set_oscillator_values(slint_oscillators_from_oscillators(
             &ui_default_values.oscillators,
         ));
         ui.set_osc_fine_tune_cents(slint_oscillator_fine_tune_from_fine_tune_cents(
             ui_default_values.oscillator_fine_tune,
         ));
+        ui.set_osc_wavetable_names(vec_to_model_rc_shared_string(
+            &ui_default_values.oscillator_wavetables,
+        ));
+        ui.set_osc_sample_names(vec_to_model_rc_shared_string(
+            &ui_default_values.oscillator_samples,
+        ));
+        ui.set_osc_sub_octave(ui_default_values.sub_octave);
+        ui.set_cross_modulation_values(slint_cross_modulation_from_ui_cross_modulation(
+            &ui_default_values.cross_modulation,
+        ));
         ui.set_output_mixer_values(slint_mixer_from_ui_mixer_options(
             &ui_default_values.output_mixer,
         ));
@@ -204,12 +235,30 @@ pub(super) fn push_values_to_ui(
         ui.set_global_options_values(slint_global_options_from_ui_global_options(
             &ui_default_values.global_options,
         ));
+        ui.set_voice_options_values(slint_voice_options_from_ui_voice_options(
+            &ui_default_values.voice_options,
+        ));
+        ui.set_expression_options_values(slint_expression_options_from_ui_expression_options(
+            &ui_default_values.expression_options,
+        ));
+        ui.set_tuning_values(slint_tuning_from_ui_tuning(&ui_default_values.tuning));
         ui.set_filter_cutoff_values(slint_filter_cutoff_from_ui_filter_cutoff(
             &ui_default_values.filter_cutoff,
         ));
         ui.set_filter_options_values(slint_filter_options_from_ui_filter_options(
             &ui_default_values.filter_options,
         ));
+        ui.set_second_filter_cutoff_values(slint_filter_cutoff_from_ui_filter_cutoff(
+            &ui_default_values.second_filter_cutoff,
+        ));
+        ui.set_second_filter_options_values(
+            slint_second_filter_options_from_ui_second_filter_options(
+                &ui_default_values.second_filter_options,
+            ),
+        );
+        ui.set_filter_routing_values(slint_filter_routing_from_ui_filter_routing(
+            &ui_default_values.filter_routing,
+        ));
 
         ui.set_patch_generation(ui.get_patch_generation().wrapping_add(1));
     })?;
@@ -231,6 +280,18 @@ pub(super) fn update_ui_values_from_module_parameters(
         oscillator_fine_tune: oscillator_fine_tune_to_ui_oscillator_fine_tune(
             &parameters.oscillators,
         ),
+        oscillator_wavetables: oscillator_wavetable_to_ui_oscillator_wavetable(
+            &parameters.oscillators,
+        ),
+        oscillator_samples: oscillator_sample_to_ui_oscillator_sample(&parameters.oscillators),
+        sub_octave: i32::from(
+            parameters.oscillators[OscillatorIndex::Sub as usize]
+                .sub_octave
+                .load(Relaxed),
+        ),
+        cross_modulation: oscillator_cross_modulation_to_ui_cross_modulation(
+            &parameters.oscillators,
+        ),
         amp_envelope: UIEnvelope::from_synth_parameters(
             &parameters.envelopes[EnvelopeIndex::Amp as usize],
         ),
@@ -242,12 +303,18 @@ pub(super) fn update_ui_values_from_module_parameters(
         ),
         mod_wheel_lfo: UILfo::from_synth_parameters(&parameters.lfos[LFOIndex::ModWheel as usize]),
         filter_lfo: UILfo::from_synth_parameters(&parameters.lfos[LFOIndex::Filter as usize]),
+        lfo_bank: UILfoBank::from_synth_parameters(&parameters.lfos, &parameters.lfo_routing),
         filter_cutoff: UIFilterCutoff::from_synth_parameters(&parameters.filter),
         filter_options: UIFilterOptions::from_synth_parameters(
             &parameters.filter,
             &parameters.envelopes[EnvelopeIndex::Filter as usize],
-            &parameters.lfos[LFOIndex::Filter as usize],
+            &parameters.lfos[parameters.lfo_routing.filter_lfo()],
         ),
+        second_filter_cutoff: UIFilterCutoff::from_synth_parameters(&parameters.second_filter),
+        second_filter_options: UISecondFilterOptions::from_synth_parameters(
+            &parameters.second_filter,
+        ),
+        filter_routing: UIFilterRouting::from_synth_parameters(&parameters.filter_routing),
         output_mixer: UIMixer::from_synth_parameters(&parameters.mixer),
         oscillator_mixer: oscillator_mixer_to_ui_oscillator_mixer(
             &parameters.mixer.quad_mixer_inputs,
@@ -258,6 +325,9 @@ pub(super) fn update_ui_values_from_module_parameters(
             &parameters.envelopes[EnvelopeIndex::Amp as usize],
             &parameters.mixer,
         ),
+        voice_options: UIVoiceOptions::from_synth_parameters(&parameters.voices),
+        expression_options: UIExpressionOptions::from_synth_parameters(&parameters.keyboard),
+        tuning: UITuning::from_synth_parameters(&parameters.tuning),
         midi_screen: Vec::new(),
         effects: synthesizer_effects_to_ui_effects(&parameters.effects),
     }
@@ -274,6 +344,15 @@ fn oscillator_values_to_ui_oscillator_values(
     ui_oscillators
 }
 
+fn oscillator_cross_modulation_to_ui_cross_modulation(
+    oscillators: &[OscillatorParameters],
+) -> Vec<UICrossModulation> {
+    oscillators
+        .iter()
+        .map(UICrossModulation::from_synth_parameters)
+        .collect()
+}
+
 fn oscillator_fine_tune_to_ui_oscillator_fine_tune(
     oscillators: &[OscillatorParameters],
 ) -> Vec<i32> {
@@ -283,6 +362,16 @@ fn oscillator_fine_tune_to_ui_oscillator_fine_tune(
         .collect()
 }
 
+fn oscillator_wavetable_to_ui_oscillator_wavetable(
+    oscillators: &[OscillatorParameters],
+) -> Vec<String> {
+    oscillators.iter().map(|osc| osc.wavetable.name()).collect()
+}
+
+fn oscillator_sample_to_ui_oscillator_sample(oscillators: &[OscillatorParameters]) -> Vec<String> {
+    oscillators.iter().map(|osc| osc.sample.name()).collect()
+}
+
 fn synthesizer_effects_to_ui_effects(effects: &[AudioEffectParameters]) -> Vec<EffectParameters> {
     let mut ui_effects: Vec<EffectParameters> = Vec::new();
     for effect in effects {
@@ -375,6 +464,9 @@ fn slint_envelope_from_ui_envelope(envelope_values: &UIEnvelope) -> EnvelopeValu
         decay: envelope_values.decay,
         sustain: envelope_values.sustain,
         release: envelope_values.release,
+        attack_curve: envelope_values.attack_curve,
+        decay_curve: envelope_values.decay_curve,
+        release_curve: envelope_values.release_curve,
         inverted: envelope_values.inverted,
     }
 }
@@ -389,6 +481,35 @@ fn slint_lfo_from_ui_lfo(lfo_values: &UILfo) -> LFOValues {
     }
 }
 
+fn slint_lfo_bank_options_from_ui_lfo_bank(lfo_bank_values: &UILfoBank) -> LfoBankOptions {
+    LfoBankOptions {
+        lfo_count: lfo_bank_values.lfo_count,
+        vibrato_lfo: lfo_bank_values.vibrato_lfo,
+        filter_lfo: lfo_bank_values.filter_lfo,
+    }
+}
+
+fn slint_bank_lfo_values_from_ui_lfo_bank(lfo_bank_values: &UILfoBank) -> ModelRc<BankLFOValues> {
+    let bank_lfos: VecModel<BankLFOValues> = lfo_bank_values
+        .lfos
+        .iter()
+        .map(|lfo_values| {
+            // Bounded to [0, MAX_PHASE_VALUE] (0–360 degrees), safely within i32 range
+            #[allow(clippy::cast_possible_truncation)]
+            let phase_display = (lfo_values.phase * MAX_PHASE_VALUE).ceil() as i32;
+
+            BankLFOValues {
+                values: slint_lfo_from_ui_lfo(lfo_values),
+                frequency_display: exponential_curve_lfo_frequency_from_normal_value(
+                    lfo_values.frequency,
+                ),
+                phase_display,
+            }
+        })
+        .collect();
+    ModelRc::from(Rc::new(bank_lfos))
+}
+
 fn slint_midi_port_from_ui_midi_port(midi_port_values: &UIMidiPort) -> MidiPort {
     MidiPort {
         input_ports: vec_to_model_rc_shared_string(&midi_port_values.input_ports),
@@ -412,12 +533,34 @@ fn slint_filter_options_from_ui_filter_options(
 ) -> FilterOptions {
     FilterOptions {
         poles: filter_option_values.poles,
+        filter_type: filter_option_values.filter_type,
+        drive: filter_option_values.drive,
         key_track: filter_option_values.key_track,
         envelope_amount: filter_option_values.envelope_amount,
         lfo_amount: filter_option_values.lfo_amount,
     }
 }
 
+fn slint_second_filter_options_from_ui_second_filter_options(
+    second_filter_option_values: &UISecondFilterOptions,
+) -> SecondFilterOptions {
+    SecondFilterOptions {
+        poles: second_filter_option_values.poles,
+        filter_type: second_filter_option_values.filter_type,
+        drive: second_filter_option_values.drive,
+        key_track: second_filter_option_values.key_track,
+    }
+}
+
+fn slint_filter_routing_from_ui_filter_routing(
+    filter_routing_values: &UIFilterRouting,
+) -> FilterRoutingValues {
+    FilterRoutingValues {
+        routing: filter_routing_values.routing,
+        crossfade: filter_routing_values.crossfade,
+    }
+}
+
 fn slint_mixer_from_ui_mixer_options(mixer_values: &UIMixer) -> Mixer {
     Mixer {
         balance: mixer_values.balance,
@@ -426,6 +569,20 @@ fn slint_mixer_from_ui_mixer_options(mixer_values: &UIMixer) -> Mixer {
     }
 }
 
+fn slint_cross_modulation_from_ui_cross_modulation(
+    cross_modulation_values: &[UICrossModulation],
+) -> ModelRc<CrossModulationValues> {
+    let cross_modulation: VecModel<CrossModulationValues> = cross_modulation_values
+        .iter()
+        .map(|values| CrossModulationValues {
+            mode: values.mode,
+            source: values.source,
+            depth: values.depth,
+        })
+        .collect();
+    ModelRc::from(Rc::new(cross_modulation))
+}
+
 fn slint_oscillator_mixer_from_ui_oscillator_mixer(mixer_values: &[UIMixer]) -> ModelRc<Mixer> {
     let mixers: VecModel<Mixer> = mixer_values
         .iter()
@@ -454,6 +611,35 @@ fn slint_global_options_from_ui_global_options(
     }
 }
 
+fn slint_voice_options_from_ui_voice_options(voice_option_values: &UIVoiceOptions) -> VoiceOptions {
+    VoiceOptions {
+        voice_mode: voice_option_values.voice_mode,
+        voice_count: voice_option_values.voice_count,
+        stealing_policy: voice_option_values.stealing_policy,
+        note_priority: voice_option_values.note_priority,
+        legato_is_enabled: voice_option_values.legato_is_enabled,
+        paraphonic_assignment: voice_option_values.paraphonic_assignment,
+    }
+}
+
+fn slint_expression_options_from_ui_expression_options(
+    expression_option_values: &UIExpressionOptions,
+) -> ExpressionOptions {
+    ExpressionOptions {
+        mpe_pitch_bend_range: expression_option_values.mpe_pitch_bend_range,
+        release_velocity_amount: expression_option_values.release_velocity_amount,
+    }
+}
+
+fn slint_tuning_from_ui_tuning(tuning_values: &UITuning) -> TuningValues {
+    TuningValues {
+        master_tune: tuning_values.master_tune,
+        reference_pitch: tuning_values.reference_pitch,
+        scale_name: SharedString::from(tuning_values.scale_name.clone()),
+        keyboard_mapping_name: SharedString::from(tuning_values.keyboard_mapping_name.clone()),
+    }
+}
+
 fn slint_effect_values_from_effect_parameters(
     input_values: &[EffectParameters],
 ) -> ModelRc<EffectsValues> {
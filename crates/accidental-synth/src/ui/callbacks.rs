@@ -5,6 +5,7 @@ mod menubar;
 mod modulation;
 mod oscillators;
 mod settings;
+mod voices;
 
 use crate::AccidentalSynth;
 use accsyn_core::audio_events::AudioDeviceUpdateEvents;
@@ -15,6 +16,8 @@ use crossbeam_channel::Sender;
 use slint::Weak;
 use std::path::PathBuf;
 
+// Every UI callback is registered here, so it grows with each control.
+#[allow(clippy::too_many_lines)]
 pub fn register_callbacks(
     ui_weak: &Weak<AccidentalSynth>,
     midi_update_sender: Sender<MidiDeviceUpdateEvents>,
@@ -49,6 +52,10 @@ pub fn register_callbacks(
     );
     settings::callback_patch_saved(ui_weak, synthesizer_update_sender.clone());
     settings::callback_patch_deleted(ui_weak, synthesizer_update_sender.clone());
+    settings::callback_master_tune_changed(ui_weak, synthesizer_update_sender.clone());
+    settings::callback_reference_pitch_changed(ui_weak, synthesizer_update_sender.clone());
+    settings::callback_tuning_scale_changed(ui_weak, synthesizer_update_sender.clone());
+    settings::callback_tuning_keyboard_mapping_changed(ui_weak, synthesizer_update_sender.clone());
 
     oscillators::callback_osc_oscillator_shape_changed(ui_weak, synthesizer_update_sender.clone());
     oscillators::callback_osc_course_tune_changed(ui_weak, synthesizer_update_sender.clone());
@@ -57,25 +64,53 @@ pub fn register_callbacks(
     oscillators::callback_osc_parameter1_changed(ui_weak, synthesizer_update_sender.clone());
     oscillators::callback_osc_parameter2_changed(ui_weak, synthesizer_update_sender.clone());
     oscillators::callback_pitch_envelope_amount_changed(ui_weak, synthesizer_update_sender.clone());
+    oscillators::callback_osc_wavetable_changed(ui_weak, synthesizer_update_sender.clone());
+    oscillators::callback_osc_sample_changed(ui_weak, synthesizer_update_sender.clone());
+    oscillators::callback_osc_sub_octave_changed(ui_weak, synthesizer_update_sender.clone());
+    oscillators::callback_cross_modulation_mode_changed(ui_weak, synthesizer_update_sender.clone());
+    oscillators::callback_cross_modulation_source_changed(
+        ui_weak,
+        synthesizer_update_sender.clone(),
+    );
+    oscillators::callback_cross_modulation_depth_changed(
+        ui_weak,
+        synthesizer_update_sender.clone(),
+    );
 
     filter::callback_filter_cutoff_changed(ui_weak, synthesizer_update_sender.clone());
     filter::callback_filter_resonance_changed(ui_weak, synthesizer_update_sender.clone());
     filter::callback_filter_poles_changed(ui_weak, synthesizer_update_sender.clone());
+    filter::callback_filter_type_changed(ui_weak, synthesizer_update_sender.clone());
+    filter::callback_filter_drive_changed(ui_weak, synthesizer_update_sender.clone());
     filter::callback_filter_key_tracking_changed(ui_weak, synthesizer_update_sender.clone());
     filter::callback_filter_envelope_amount_changed(ui_weak, synthesizer_update_sender.clone());
     filter::callback_filter_lfo_amount_changed(ui_weak, synthesizer_update_sender.clone());
+    filter::callback_second_filter_cutoff_changed(ui_weak, synthesizer_update_sender.clone());
+    filter::callback_second_filter_resonance_changed(ui_weak, synthesizer_update_sender.clone());
+    filter::callback_second_filter_poles_changed(ui_weak, synthesizer_update_sender.clone());
+    filter::callback_second_filter_type_changed(ui_weak, synthesizer_update_sender.clone());
+    filter::callback_second_filter_drive_changed(ui_weak, synthesizer_update_sender.clone());
+    filter::callback_second_filter_key_tracking_changed(ui_weak, synthesizer_update_sender.clone());
+    filter::callback_filter_routing_changed(ui_weak, synthesizer_update_sender.clone());
+    filter::callback_filter_crossfade_changed(ui_weak, synthesizer_update_sender.clone());
 
     modulation::callback_envelope_attack_changed(ui_weak, synthesizer_update_sender.clone());
     modulation::callback_envelope_decay_changed(ui_weak, synthesizer_update_sender.clone());
     modulation::callback_envelope_sustain_changed(ui_weak, synthesizer_update_sender.clone());
     modulation::callback_envelope_release_changed(ui_weak, synthesizer_update_sender.clone());
     modulation::callback_envelope_invert_changed(ui_weak, synthesizer_update_sender.clone());
+    modulation::callback_envelope_attack_curve_changed(ui_weak, synthesizer_update_sender.clone());
+    modulation::callback_envelope_decay_curve_changed(ui_weak, synthesizer_update_sender.clone());
+    modulation::callback_envelope_release_curve_changed(ui_weak, synthesizer_update_sender.clone());
     modulation::callback_lfo_frequency_changed(ui_weak, synthesizer_update_sender.clone());
     modulation::callback_lfo_shape_changed(ui_weak, synthesizer_update_sender.clone());
     modulation::callback_lfo_phase_changed(ui_weak, synthesizer_update_sender.clone());
     modulation::callback_lfo_phase_reset(ui_weak, synthesizer_update_sender.clone());
     modulation::callback_lfo_clock_sync_enabled(ui_weak, synthesizer_update_sender.clone());
     modulation::callback_lfo_key_sync_enabled(ui_weak, synthesizer_update_sender.clone());
+    modulation::callback_lfo_count_changed(ui_weak, synthesizer_update_sender.clone());
+    modulation::callback_vibrato_lfo_changed(ui_weak, synthesizer_update_sender.clone());
+    modulation::callback_filter_lfo_changed(ui_weak, synthesizer_update_sender.clone());
 
     global::callback_portamento_enabled(ui_weak, synthesizer_update_sender.clone());
     global::callback_portamento_time_changed(ui_weak, synthesizer_update_sender.clone());
@@ -91,6 +126,15 @@ pub fn register_callbacks(
     global::callback_osc_mixer_level_update(ui_weak, synthesizer_update_sender.clone());
     global::callback_osc_mixer_mute_update(ui_weak, synthesizer_update_sender.clone());
 
+    voices::callback_voice_mode_changed(ui_weak, synthesizer_update_sender.clone());
+    voices::callback_voice_count_changed(ui_weak, synthesizer_update_sender.clone());
+    voices::callback_voice_stealing_policy_changed(ui_weak, synthesizer_update_sender.clone());
+    voices::callback_note_priority_changed(ui_weak, synthesizer_update_sender.clone());
+    voices::callback_legato_enabled(ui_weak, synthesizer_update_sender.clone());
+    voices::callback_paraphonic_assignment_changed(ui_weak, synthesizer_update_sender.clone());
+    voices::callback_mpe_pitch_bend_range_changed(ui_weak, synthesizer_update_sender.clone());
+    voices::callback_release_velocity_amount_changed(ui_weak, synthesizer_update_sender.clone());
+
     effects::callback_effect_enable(ui_weak, synthesizer_update_sender.clone());
     effects::callback_effect_parameter_changed(ui_weak, synthesizer_update_sender.clone());
 }
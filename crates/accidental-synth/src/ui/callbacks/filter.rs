@@ -54,6 +54,40 @@ pub fn callback_filter_poles_changed(
     }
 }
 
+pub fn callback_filter_type_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_filter_type_changed(move |index| {
+            log::trace!(target: "ui::filter", "callback_filter_type_changed(): Sending SynthesizerUpdateEvents::FilterType : {index}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::FilterType(index))
+                .expect(
+                    "callback_filter_type_changed(): Could not send new \
+            synthesizer filter type to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_filter_drive_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_filter_drive_changed(move |value| {
+            log::trace!(target: "ui::filter", "callback_filter_drive_changed(): Sending SynthesizerUpdateEvents::FilterDrive : {value}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::FilterDrive(value))
+                .expect(
+                    "callback_filter_drive_changed(): Could not send new \
+            synthesizer filter drive to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
 pub fn callback_filter_key_tracking_changed(
     ui_weak: &Weak<AccidentalSynth>,
     synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
@@ -104,3 +138,139 @@ pub fn callback_filter_lfo_amount_changed(
         });
     }
 }
+
+pub fn callback_second_filter_cutoff_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_second_filter_cutoff_changed(move |cutoff| {
+            log::trace!(target: "ui::filter", "callback_second_filter_cutoff_changed(): Sending SynthesizerUpdateEvents::SecondFilterCutoffFrequency : {cutoff}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::SecondFilterCutoffFrequency(cutoff))
+                .expect(
+                    "callback_second_filter_cutoff_changed(): Could not send new \
+            synthesizer second filter cutoff frequency to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_second_filter_resonance_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_second_filter_resonance_changed(move |value| {
+            log::trace!(target: "ui::filter", "callback_second_filter_resonance_changed(): Sending SynthesizerUpdateEvents::SecondFilterResonance : {value}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::SecondFilterResonance(value))
+                .expect(
+                    "callback_second_filter_resonance_changed(): Could not send new \
+            synthesizer second filter resonance to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_second_filter_poles_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_second_filter_poles_changed(move |value| {
+            log::trace!(target: "ui::filter", "callback_second_filter_poles_changed(): Sending SynthesizerUpdateEvents::SecondFilterPoleCount : {value}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::SecondFilterPoleCount(value))
+                .expect(
+                    "callback_second_filter_poles_changed(): Could not send new \
+            synthesizer second filter pole count to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_second_filter_type_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_second_filter_type_changed(move |index| {
+            log::trace!(target: "ui::filter", "callback_second_filter_type_changed(): Sending SynthesizerUpdateEvents::SecondFilterType : {index}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::SecondFilterType(index))
+                .expect(
+                    "callback_second_filter_type_changed(): Could not send new \
+            synthesizer second filter type to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_second_filter_drive_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_second_filter_drive_changed(move |value| {
+            log::trace!(target: "ui::filter", "callback_second_filter_drive_changed(): Sending SynthesizerUpdateEvents::SecondFilterDrive : {value}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::SecondFilterDrive(value))
+                .expect(
+                    "callback_second_filter_drive_changed(): Could not send new \
+            synthesizer second filter drive to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_second_filter_key_tracking_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_second_filter_key_tracking_changed(move |value| {
+            log::trace!(target: "ui::filter", "callback_second_filter_key_tracking_changed(): Sending SynthesizerUpdateEvents::SecondFilterKeyTrackingAmount : {value}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::SecondFilterKeyTrackingAmount(value))
+                .expect(
+                    "callback_second_filter_key_tracking_changed(): Could not send new \
+            synthesizer second filter key tracking amount to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_filter_routing_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_filter_routing_changed(move |index| {
+            log::trace!(target: "ui::filter", "callback_filter_routing_changed(): Sending SynthesizerUpdateEvents::FilterRouting : {index}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::FilterRouting(index))
+                .expect(
+                    "callback_filter_routing_changed(): Could not send new \
+            synthesizer filter routing to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_filter_crossfade_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_filter_crossfade_changed(move |value| {
+            log::trace!(target: "ui::filter", "callback_filter_crossfade_changed(): Sending SynthesizerUpdateEvents::FilterCrossfade : {value}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::FilterCrossfade(value))
+                .expect(
+                    "callback_filter_crossfade_changed(): Could not send new \
+            synthesizer filter crossfade to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
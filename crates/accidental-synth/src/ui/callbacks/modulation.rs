@@ -103,6 +103,57 @@ pub fn callback_envelope_invert_changed(
     }
 }
 
+pub fn callback_envelope_attack_curve_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_envelope_attack_curve_changed(move |envelope_index, curve| {
+            log::trace!(target: "ui::modulation", "callback_envelope_attack_curve_changed(): Sending SynthesizerUpdateEvents::EnvelopeAttackCurve : ({envelope_index}, {curve})");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::EnvelopeAttackCurve(envelope_index, curve))
+                .expect(
+                    "callback_envelope_attack_curve_changed(): Could not send new \
+            envelope attack curve to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_envelope_decay_curve_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_envelope_decay_curve_changed(move |envelope_index, curve| {
+            log::trace!(target: "ui::modulation", "callback_envelope_decay_curve_changed(): Sending SynthesizerUpdateEvents::EnvelopeDecayCurve : ({envelope_index}, {curve})");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::EnvelopeDecayCurve(envelope_index, curve))
+                .expect(
+                    "callback_envelope_decay_curve_changed(): Could not send new \
+            envelope decay curve to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_envelope_release_curve_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_envelope_release_curve_changed(move |envelope_index, curve| {
+            log::trace!(target: "ui::modulation", "callback_envelope_release_curve_changed(): Sending SynthesizerUpdateEvents::EnvelopeReleaseCurve : ({envelope_index}, {curve})");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::EnvelopeReleaseCurve(envelope_index, curve))
+                .expect(
+                    "callback_envelope_release_curve_changed(): Could not send new \
+            envelope release curve to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
 pub fn callback_lfo_frequency_changed(
     ui_weak: &Weak<AccidentalSynth>,
     synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
@@ -214,3 +265,54 @@ pub fn callback_lfo_key_sync_enabled(
         });
     }
 }
+
+pub fn callback_lfo_count_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_lfo_count_changed(move |lfo_count| {
+            log::trace!(target: "ui::modulation", "callback_lfo_count_changed(): Sending SynthesizerUpdateEvents::LfoCount : {lfo_count}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::LfoCount(lfo_count))
+                .expect(
+                    "callback_lfo_count_changed(): Could not send new \
+            LFO count to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_vibrato_lfo_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_vibrato_lfo_changed(move |lfo_index| {
+            log::trace!(target: "ui::modulation", "callback_vibrato_lfo_changed(): Sending SynthesizerUpdateEvents::VibratoLfo : {lfo_index}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::VibratoLfo(lfo_index))
+                .expect(
+                    "callback_vibrato_lfo_changed(): Could not send new \
+            vibrato LFO to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_filter_lfo_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_filter_lfo_changed(move |lfo_index| {
+            log::trace!(target: "ui::modulation", "callback_filter_lfo_changed(): Sending SynthesizerUpdateEvents::FilterLfo : {lfo_index}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::FilterLfo(lfo_index))
+                .expect(
+                    "callback_filter_lfo_changed(): Could not send new \
+            filter LFO to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
@@ -135,3 +135,111 @@ pub fn callback_pitch_envelope_amount_changed(
         });
     }
 }
+
+pub fn callback_osc_wavetable_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_osc_wavetable_changed(move |oscillator_index, wavetable_name| {
+            log::trace!(target: "ui::oscillators", "callback_osc_wavetable_changed(): Sending SynthesizerUpdateEvents::OscillatorWavetable : ({oscillator_index}, {wavetable_name})");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::OscillatorWavetable(
+                    oscillator_index,
+                    wavetable_name.to_string(),
+                ))
+                .expect(
+                    "callback_osc_wavetable_changed(): Could not send new \
+            synthesizer oscillator wavetable to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_osc_sample_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_osc_sample_changed(move |oscillator_index, sample_name| {
+            log::trace!(target: "ui::oscillators", "callback_osc_sample_changed(): Sending SynthesizerUpdateEvents::OscillatorSample : ({oscillator_index}, {sample_name})");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::OscillatorSample(
+                    oscillator_index,
+                    sample_name.to_string(),
+                ))
+                .expect(
+                    "callback_osc_sample_changed(): Could not send new \
+            synthesizer oscillator sample to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_osc_sub_octave_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_osc_sub_octave_changed(move |octave_index| {
+            log::trace!(target: "ui::oscillators", "callback_osc_sub_octave_changed(): Sending SynthesizerUpdateEvents::SubOctave : {octave_index}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::SubOctave(octave_index))
+                .expect(
+                    "callback_osc_sub_octave_changed(): Could not send new \
+            synthesizer sub oscillator octave to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_cross_modulation_mode_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_cross_modulation_mode_changed(move |oscillator_index, mode_index| {
+            log::trace!(target: "ui::oscillators", "callback_cross_modulation_mode_changed(): Sending SynthesizerUpdateEvents::CrossModulationMode : ({oscillator_index}, {mode_index})");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::CrossModulationMode(oscillator_index, mode_index))
+                .expect(
+                    "callback_cross_modulation_mode_changed(): Could not send new \
+            synthesizer oscillator cross modulation mode to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_cross_modulation_source_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_cross_modulation_source_changed(move |oscillator_index, source_index| {
+            log::trace!(target: "ui::oscillators", "callback_cross_modulation_source_changed(): Sending SynthesizerUpdateEvents::CrossModulationSource : ({oscillator_index}, {source_index})");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::CrossModulationSource(oscillator_index, source_index))
+                .expect(
+                    "callback_cross_modulation_source_changed(): Could not send new \
+            synthesizer oscillator cross modulation source to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_cross_modulation_depth_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_cross_modulation_depth_changed(move |oscillator_index, depth| {
+            log::trace!(target: "ui::oscillators", "callback_cross_modulation_depth_changed(): Sending SynthesizerUpdateEvents::CrossModulationDepth : ({oscillator_index}, {depth})");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::CrossModulationDepth(oscillator_index, depth))
+                .expect(
+                    "callback_cross_modulation_depth_changed(): Could not send new \
+            synthesizer oscillator cross modulation depth to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
@@ -189,3 +189,76 @@ pub fn callback_output_soft_clip_enabled(
         });
     }
 }
+
+pub fn callback_master_tune_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_master_tune_changed(move |cents| {
+            log::trace!(target: "ui::settings", "callback_master_tune_changed(): Sending SynthesizerUpdateEvents::MasterTune : {cents}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::MasterTune(cents))
+                .expect(
+                    "callback_master_tune_changed(): Could not send new \
+            master tune to the synthesizer module. Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_reference_pitch_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_reference_pitch_changed(move |hertz| {
+            log::trace!(target: "ui::settings", "callback_reference_pitch_changed(): Sending SynthesizerUpdateEvents::ReferencePitch : {hertz}");
+            // The reference pitch spin box only reaches 415-466 Hz, exactly representable in f32
+            #[allow(clippy::cast_precision_loss)]
+            let hertz = hertz as f32;
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::ReferencePitch(hertz))
+                .expect(
+                    "callback_reference_pitch_changed(): Could not send new \
+            reference pitch to the synthesizer module. Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_tuning_scale_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_tuning_scale_changed(move |scale_name| {
+            log::trace!(target: "ui::settings", "callback_tuning_scale_changed(): Sending SynthesizerUpdateEvents::TuningScale : {scale_name}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::TuningScale(scale_name.trim().to_string()))
+                .expect(
+                    "callback_tuning_scale_changed(): Could not send new \
+            tuning scale to the synthesizer module. Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_tuning_keyboard_mapping_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_tuning_keyboard_mapping_changed(move |keyboard_mapping_name| {
+            log::trace!(target: "ui::settings", "callback_tuning_keyboard_mapping_changed(): Sending SynthesizerUpdateEvents::TuningKeyboardMapping : {keyboard_mapping_name}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::TuningKeyboardMapping(
+                    keyboard_mapping_name.trim().to_string(),
+                ))
+                .expect(
+                    "callback_tuning_keyboard_mapping_changed(): Could not send new \
+            tuning keyboard mapping to the synthesizer module. Exiting.",
+                );
+        });
+    }
+}
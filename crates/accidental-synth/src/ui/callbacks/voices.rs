@@ -0,0 +1,140 @@
+use crate::AccidentalSynth;
+use accsyn_core::synth_events::SynthesizerUpdateEvents;
+use crossbeam_channel::Sender;
+use slint::Weak;
+
+pub fn callback_voice_mode_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_voice_mode_changed(move |mode_index| {
+            log::trace!(target: "ui::voices", "callback_voice_mode_changed(): Sending SynthesizerUpdateEvents::VoiceMode : {mode_index}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::VoiceMode(mode_index))
+                .expect(
+                    "callback_voice_mode_changed(): Could not send new \
+            voice mode to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_voice_count_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_voice_count_changed(move |voice_count| {
+            log::trace!(target: "ui::voices", "callback_voice_count_changed(): Sending SynthesizerUpdateEvents::VoiceCount : {voice_count}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::VoiceCount(voice_count))
+                .expect(
+                    "callback_voice_count_changed(): Could not send new \
+            voice count to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_voice_stealing_policy_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_voice_stealing_policy_changed(move |policy_index| {
+            log::trace!(target: "ui::voices", "callback_voice_stealing_policy_changed(): Sending SynthesizerUpdateEvents::VoiceStealingPolicy : {policy_index}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::VoiceStealingPolicy(policy_index))
+                .expect(
+                    "callback_voice_stealing_policy_changed(): Could not send new \
+            voice stealing policy to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_note_priority_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_note_priority_changed(move |priority_index| {
+            log::trace!(target: "ui::voices", "callback_note_priority_changed(): Sending SynthesizerUpdateEvents::NotePriority : {priority_index}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::NotePriority(priority_index))
+                .expect(
+                    "callback_note_priority_changed(): Could not send new \
+            note priority to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_legato_enabled(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_legato_enabled(move |is_enabled| {
+            log::trace!(target: "ui::voices", "callback_legato_enabled(): Sending SynthesizerUpdateEvents::LegatoEnabled : {is_enabled}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::LegatoEnabled(is_enabled))
+                .expect(
+                    "callback_legato_enabled(): Could not send new \
+            legato state to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_paraphonic_assignment_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_paraphonic_assignment_changed(move |assignment_index| {
+            log::trace!(target: "ui::voices", "callback_paraphonic_assignment_changed(): Sending SynthesizerUpdateEvents::ParaphonicAssignment : {assignment_index}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::ParaphonicAssignment(assignment_index))
+                .expect(
+                    "callback_paraphonic_assignment_changed(): Could not send new \
+            paraphonic assignment to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_mpe_pitch_bend_range_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_mpe_pitch_bend_range_changed(move |semitones| {
+            log::trace!(target: "ui::voices", "callback_mpe_pitch_bend_range_changed(): Sending SynthesizerUpdateEvents::MpePitchBendRange : {semitones}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::MpePitchBendRange(semitones))
+                .expect(
+                    "callback_mpe_pitch_bend_range_changed(): Could not send new \
+            MPE pitch bend range to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
+
+pub fn callback_release_velocity_amount_changed(
+    ui_weak: &Weak<AccidentalSynth>,
+    synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
+) {
+    if let Some(ui) = ui_weak.upgrade() {
+        ui.on_release_velocity_amount_changed(move |amount| {
+            log::trace!(target: "ui::voices", "callback_release_velocity_amount_changed(): Sending SynthesizerUpdateEvents::ReleaseVelocityAmount : {amount}");
+            synthesizer_update_sender
+                .send(SynthesizerUpdateEvents::ReleaseVelocityAmount(amount))
+                .expect(
+                    "callback_release_velocity_amount_changed(): Could not send new \
+            release velocity amount to the synthesizer module.Exiting.",
+                );
+        });
+    }
+}
@@ -1,9 +1,29 @@
+use accsyn_midi::constants::{MPE_LOWER_ZONE_CHANNEL_NAME, MPE_UPPER_ZONE_CHANNEL_NAME};
+
 pub const AUDIO_DEVICE_CHANNEL_NULL_VALUE: i32 = -1;
 pub const AUDIO_DEVICE_CHANNEL_INDEX_TO_NAME_OFFSET: u16 = 1;
 pub const DEFAULT_FINE_TUNE_NORMAL_VALUE: f32 = 0.5;
 pub const MONO_CHANNEL_COUNT: u16 = 1;
-pub const MIDI_CHANNEL_LIST: [&str; 17] = [
-    "Omni", "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12", "13", "14", "15", "16",
+pub const MIDI_CHANNEL_LIST: [&str; 19] = [
+    "Omni",
+    "1",
+    "2",
+    "3",
+    "4",
+    "5",
+    "6",
+    "7",
+    "8",
+    "9",
+    "10",
+    "11",
+    "12",
+    "13",
+    "14",
+    "15",
+    "16",
+    MPE_LOWER_ZONE_CHANNEL_NAME,
+    MPE_UPPER_ZONE_CHANNEL_NAME,
 ];
 
 pub const MAX_PHASE_VALUE: f32 = 360.0;
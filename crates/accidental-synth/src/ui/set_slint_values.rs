@@ -3,8 +3,9 @@ use crate::ui::constants::{
     MIDI_SCREEN_TOTAL_SLOTS, MONO_CHANNEL_COUNT,
 };
 use crate::ui::structs::{
-    UIAudioDevice, UIEnvelope, UIFilterCutoff, UIFilterOptions, UIGlobalOptions, UILfo, UIMidiPort,
-    UIMixer, UIOscillator,
+    UIAudioDevice, UICrossModulation, UIEnvelope, UIExpressionOptions, UIFilterCutoff,
+    UIFilterOptions, UIFilterRouting, UIGlobalOptions, UILfo, UILfoBank, UIMidiPort, UIMixer,
+    UIOscillator, UISecondFilterOptions, UIVoiceOptions,
 };
 use crate::ui::{
     slint_patches_list_from_ui_patches_list, slint_patches_save_status_from_ui_patch_save_status,
@@ -172,6 +173,21 @@ pub fn set_oscillator_values(
     );
 }
 
+pub fn set_cross_modulation_values(
+    ui_weak_thread: &Weak<AccidentalSynth>,
+    cross_modulation_values: &mut [UICrossModulation],
+) {
+    let ui_cross_modulation = cross_modulation_values.to_vec();
+    log_ui_upgrade_failure(
+        "set_cross_modulation_values",
+        ui_weak_thread.upgrade_in_event_loop(move |ui| {
+            ui.set_cross_modulation_values(ui::slint_cross_modulation_from_ui_cross_modulation(
+                &ui_cross_modulation,
+            ));
+        }),
+    );
+}
+
 pub fn set_lfo_values(
     ui_weak_thread: &Weak<AccidentalSynth>,
     lfo_index: LFOIndex,
@@ -189,6 +205,18 @@ pub fn set_lfo_values(
     );
 }
 
+pub fn set_lfo_bank_values(ui_weak_thread: &Weak<AccidentalSynth>, lfo_bank_values: &UILfoBank) {
+    let ui_lfo_bank_values = lfo_bank_values.clone();
+    log_ui_upgrade_failure(
+        "set_lfo_bank_values",
+        ui_weak_thread.upgrade_in_event_loop(move |ui| {
+            ui.set_bank_lfo_values(ui::slint_bank_lfo_values_from_ui_lfo_bank(
+                &ui_lfo_bank_values,
+            ));
+        }),
+    );
+}
+
 pub fn set_lfo_frequency_display(
     ui_weak_thread: &Weak<AccidentalSynth>,
     lfo_index: LFOIndex,
@@ -252,6 +280,30 @@ pub fn set_envelope_inverted(
     set_ui_envelope_values(ui_weak_thread, envelope_index, envelope_values);
 }
 
+pub fn set_envelope_curve(
+    ui_weak_thread: &Weak<AccidentalSynth>,
+    envelope_index: EnvelopeIndex,
+    stage: EnvelopeStage,
+    envelope_values: &mut UIEnvelope,
+    normal_value: f32,
+) {
+    match stage {
+        EnvelopeStage::Attack => {
+            envelope_values.attack_curve = normal_value;
+        }
+        EnvelopeStage::Decay => {
+            envelope_values.decay_curve = normal_value;
+        }
+        // The sustain stage holds a level, so it has no curve
+        EnvelopeStage::Sustain => return,
+        EnvelopeStage::Release => {
+            envelope_values.release_curve = normal_value;
+        }
+    }
+
+    set_ui_envelope_values(ui_weak_thread, envelope_index, envelope_values);
+}
+
 fn set_ui_envelope_values(
     ui_weak_thread: &Weak<AccidentalSynth>,
     envelope_index: EnvelopeIndex,
@@ -310,6 +362,53 @@ pub fn set_filter_options_values(
     );
 }
 
+pub fn set_second_filter_cutoff_values(
+    ui_weak_thread: &Weak<AccidentalSynth>,
+    second_filter_cutoff_values: &mut UIFilterCutoff,
+) {
+    let ui_second_filter_cutoff_values = second_filter_cutoff_values.clone();
+    log_ui_upgrade_failure(
+        "set_second_filter_cutoff_values",
+        ui_weak_thread.upgrade_in_event_loop(move |ui| {
+            ui.set_second_filter_cutoff_values(ui::slint_filter_cutoff_from_ui_filter_cutoff(
+                &ui_second_filter_cutoff_values,
+            ));
+        }),
+    );
+}
+
+pub fn set_second_filter_options_values(
+    ui_weak_thread: &Weak<AccidentalSynth>,
+    second_filter_option_values: &mut UISecondFilterOptions,
+) {
+    let ui_second_filter_option_values = second_filter_option_values.clone();
+    log_ui_upgrade_failure(
+        "set_second_filter_options_values",
+        ui_weak_thread.upgrade_in_event_loop(move |ui| {
+            ui.set_second_filter_options_values(
+                ui::slint_second_filter_options_from_ui_second_filter_options(
+                    &ui_second_filter_option_values,
+                ),
+            );
+        }),
+    );
+}
+
+pub fn set_filter_routing_values(
+    ui_weak_thread: &Weak<AccidentalSynth>,
+    filter_routing_values: &mut UIFilterRouting,
+) {
+    let ui_filter_routing_values = filter_routing_values.clone();
+    log_ui_upgrade_failure(
+        "set_filter_routing_values",
+        ui_weak_thread.upgrade_in_event_loop(move |ui| {
+            ui.set_filter_routing_values(ui::slint_filter_routing_from_ui_filter_routing(
+                &ui_filter_routing_values,
+            ));
+        }),
+    );
+}
+
 pub fn set_output_mixer_values(ui_weak_thread: &Weak<AccidentalSynth>, mixer_values: &UIMixer) {
     let ui_mixer_values = mixer_values.clone();
     log_ui_upgrade_failure(
@@ -354,6 +453,38 @@ pub fn set_global_options_values(
     );
 }
 
+pub fn set_voice_options_values(
+    ui_weak_thread: &Weak<AccidentalSynth>,
+    voice_option_values: &UIVoiceOptions,
+) {
+    let ui_voice_option_values = *voice_option_values;
+    log_ui_upgrade_failure(
+        "set_voice_options_values",
+        ui_weak_thread.upgrade_in_event_loop(move |ui| {
+            ui.set_voice_options_values(ui::slint_voice_options_from_ui_voice_options(
+                &ui_voice_option_values,
+            ));
+        }),
+    );
+}
+
+pub fn set_expression_options_values(
+    ui_weak_thread: &Weak<AccidentalSynth>,
+    expression_option_values: &UIExpressionOptions,
+) {
+    let ui_expression_option_values = expression_option_values.clone();
+    log_ui_upgrade_failure(
+        "set_expression_options_values",
+        ui_weak_thread.upgrade_in_event_loop(move |ui| {
+            ui.set_expression_options_values(
+                ui::slint_expression_options_from_ui_expression_options(
+                    &ui_expression_option_values,
+                ),
+            );
+        }),
+    );
+}
+
 pub fn set_effect_display(
     ui_weak_thread: &Weak<AccidentalSynth>,
     effect_values: &mut [EffectParameters],
@@ -9,22 +9,31 @@ use accsyn_core::math::{
     normal_value_from_exponential_curve_envelope_time, normal_value_from_exponential_level_curve,
     normalize_float_range, normalize_signed_integer_range, normalize_unsigned_integer_range,
 };
-use accsyn_core::synth_events::LfoSyncInterval;
+use accsyn_core::synth_events::{LFOIndex, LfoSyncInterval};
 use accsyn_engine::modules::envelope::{
-    DEFAULT_ENVELOPE_MILLISECONDS, DEFAULT_ENVELOPE_SUSTAIN_LEVEL, EnvelopeParameters,
-    MAX_ATTACK_MILLISECONDS, MAX_DECAY_MILLISECONDS, MAX_RELEASE_MILLISECONDS,
+    DEFAULT_ENVELOPE_CURVE, DEFAULT_ENVELOPE_MILLISECONDS, DEFAULT_ENVELOPE_SUSTAIN_LEVEL,
+    EnvelopeParameters, MAX_ATTACK_MILLISECONDS, MAX_DECAY_MILLISECONDS, MAX_RELEASE_MILLISECONDS,
     MIN_ATTACK_MILLISECONDS, MIN_DECAY_MILLISECONDS, MIN_RELEASE_MILLISECONDS,
 };
-use accsyn_engine::modules::filter::FilterParameters;
-use accsyn_engine::modules::lfo::LfoParameters;
+use accsyn_engine::modules::filter::{FilterParameters, FilterRoutingParameters};
+use accsyn_engine::modules::lfo::{
+    DEFAULT_LFO_COUNT, LfoBank, LfoParameters, LfoRoutingParameters, MAX_LFO_COUNT,
+};
 use accsyn_engine::modules::oscillator::OscillatorParameters;
 use accsyn_engine::modules::oscillator::constants::{
     DEFAULT_HARD_SYNC_ENABLED, DEFAULT_KEY_SYNC_ENABLED, DEFAULT_POLARITY_FLIPPED,
     DEFAULT_SUSTAIN_PEDAL_FLIPPED, MAX_CLIP_BOOST, MIN_CLIP_BOOST,
 };
+use accsyn_engine::modules::tuning::TuningParameters;
 use accsyn_engine::synthesizer::midi_value_converters::normal_value_from_exponential_lfo_frequency;
+use accsyn_engine::synthesizer::note_stack::NotePriority;
+use accsyn_engine::synthesizer::paraphonic::ParaphonicAssignment;
+use accsyn_engine::synthesizer::voices::{
+    DEFAULT_VOICE_COUNT, VoiceMode, VoiceParameters, VoiceStealingPolicy,
+};
 use accsyn_engine::synthesizer::{KeyboardParameters, MixerParameters};
 use std::sync::atomic::Ordering::Relaxed;
+use strum::EnumCount;
 
 #[derive(Clone, Debug)]
 pub struct UIAudioDevice {
@@ -135,6 +144,23 @@ impl UIOscillator {
     }
 }
 
+#[derive(Clone, Copy, Default, Debug)]
+pub struct UICrossModulation {
+    pub mode: i32,
+    pub source: i32,
+    pub depth: f32,
+}
+
+impl UICrossModulation {
+    pub fn from_synth_parameters(parameters: &OscillatorParameters) -> Self {
+        Self {
+            mode: i32::from(parameters.cross_modulation_mode.load(Relaxed)),
+            source: i32::from(parameters.cross_modulation_source.load(Relaxed)),
+            depth: parameters.cross_modulation_depth.load(),
+        }
+    }
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct UIFilterCutoff {
     pub cutoff: f32,
@@ -161,6 +187,8 @@ impl UIFilterCutoff {
 #[derive(Clone, Default, Debug)]
 pub struct UIFilterOptions {
     pub poles: i32,
+    pub filter_type: i32,
+    pub drive: f32,
     pub key_track: f32,
     pub envelope_amount: f32,
     pub lfo_amount: f32,
@@ -174,6 +202,8 @@ impl UIFilterOptions {
     ) -> Self {
         Self {
             poles: i32::from(parameters.filter_poles.load()),
+            filter_type: i32::from(parameters.filter_type.load(Relaxed)),
+            drive: parameters.drive.load(),
             key_track: parameters.key_tracking_amount.load(),
             envelope_amount: envelope.amount.load(),
             lfo_amount: lfo.range.load(),
@@ -181,6 +211,40 @@ impl UIFilterOptions {
     }
 }
 
+#[derive(Clone, Default, Debug)]
+pub struct UISecondFilterOptions {
+    pub poles: i32,
+    pub filter_type: i32,
+    pub drive: f32,
+    pub key_track: f32,
+}
+
+impl UISecondFilterOptions {
+    pub fn from_synth_parameters(parameters: &FilterParameters) -> Self {
+        Self {
+            poles: i32::from(parameters.filter_poles.load()),
+            filter_type: i32::from(parameters.filter_type.load(Relaxed)),
+            drive: parameters.drive.load(),
+            key_track: parameters.key_tracking_amount.load(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct UIFilterRouting {
+    pub routing: i32,
+    pub crossfade: f32,
+}
+
+impl UIFilterRouting {
+    pub fn from_synth_parameters(parameters: &FilterRoutingParameters) -> Self {
+        Self {
+            routing: i32::from(parameters.routing.load(Relaxed)),
+            crossfade: parameters.crossfade.load(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct UILfo {
     pub frequency: f32,
@@ -221,12 +285,62 @@ impl UILfo {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct UILfoBank {
+    pub lfo_count: i32,
+    pub vibrato_lfo: i32,
+    pub filter_lfo: i32,
+    // The LFOs after the mod wheel and filter LFOs, which have their own sections
+    pub lfos: Vec<UILfo>,
+}
+
+impl UILfoBank {
+    pub fn from_synth_parameters(lfos: &LfoBank, routing: &LfoRoutingParameters) -> Self {
+        // LFO counts and indexes are below MAX_LFO_COUNT, well within i32 range
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        Self {
+            lfo_count: lfos.lfo_count() as i32,
+            vibrato_lfo: routing.vibrato_lfo() as i32,
+            filter_lfo: routing.filter_lfo() as i32,
+            lfos: lfos
+                .iter()
+                .skip(LFOIndex::COUNT)
+                .map(UILfo::from_synth_parameters)
+                .collect(),
+        }
+    }
+
+    /// Returns the values of a bank LFO by its index in the whole bank, or None for the mod wheel and filter LFOs.
+    pub fn lfo_mut(&mut self, lfo_index: i32) -> Option<&mut UILfo> {
+        usize::try_from(lfo_index)
+            .ok()
+            .and_then(|index| index.checked_sub(LFOIndex::COUNT))
+            .and_then(|index| self.lfos.get_mut(index))
+    }
+}
+
+impl Default for UILfoBank {
+    fn default() -> Self {
+        // LFO counts and indexes are below MAX_LFO_COUNT, well within i32 range
+        #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+        Self {
+            lfo_count: DEFAULT_LFO_COUNT as i32,
+            vibrato_lfo: LFOIndex::ModWheel as i32,
+            filter_lfo: LFOIndex::Filter as i32,
+            lfos: vec![UILfo::default(); MAX_LFO_COUNT - LFOIndex::COUNT],
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct UIEnvelope {
     pub attack: f32,
     pub decay: f32,
     pub sustain: f32,
     pub release: f32,
+    pub attack_curve: f32,
+    pub decay_curve: f32,
+    pub release_curve: f32,
     pub inverted: bool,
 }
 
@@ -256,6 +370,9 @@ impl Default for UIEnvelope {
             decay,
             sustain: DEFAULT_ENVELOPE_SUSTAIN_LEVEL,
             release,
+            attack_curve: normalize_float_range(DEFAULT_ENVELOPE_CURVE, -1.0, 1.0),
+            decay_curve: normalize_float_range(DEFAULT_ENVELOPE_CURVE, -1.0, 1.0),
+            release_curve: normalize_float_range(DEFAULT_ENVELOPE_CURVE, -1.0, 1.0),
             inverted: false,
         }
     }
@@ -283,6 +400,9 @@ impl UIEnvelope {
                 MIN_RELEASE_MILLISECONDS,
                 MAX_RELEASE_MILLISECONDS,
             ),
+            attack_curve: normalize_float_range(parameters.attack_curve.load(), -1.0, 1.0),
+            decay_curve: normalize_float_range(parameters.decay_curve.load(), -1.0, 1.0),
+            release_curve: normalize_float_range(parameters.release_curve.load(), -1.0, 1.0),
             inverted: parameters.is_inverted.load(Relaxed),
         }
     }
@@ -377,3 +497,101 @@ impl Default for UIGlobalOptions {
         }
     }
 }
+
+#[derive(Clone, Debug)]
+pub struct UITuning {
+    pub master_tune: i32,
+    pub reference_pitch: i32,
+    pub scale_name: String,
+    pub keyboard_mapping_name: String,
+}
+
+impl UITuning {
+    pub fn from_synth_parameters(parameters: &TuningParameters) -> Self {
+        // The reference pitch is clamped to 415-466 Hz, the UI shows it in whole hertz
+        #[allow(clippy::cast_possible_truncation)]
+        let reference_pitch = parameters.reference_pitch.load().round() as i32;
+
+        Self {
+            master_tune: i32::from(parameters.master_tune.load()),
+            reference_pitch,
+            scale_name: parameters.scale_name(),
+            keyboard_mapping_name: parameters.keyboard_mapping_name(),
+        }
+    }
+}
+
+impl Default for UITuning {
+    fn default() -> Self {
+        // The default reference pitch is a whole number of hertz
+        #[allow(clippy::cast_possible_truncation)]
+        let reference_pitch = Defaults::REFERENCE_PITCH as i32;
+
+        Self {
+            master_tune: i32::from(Defaults::MASTER_TUNE_CENTS),
+            reference_pitch,
+            scale_name: String::new(),
+            keyboard_mapping_name: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct UIVoiceOptions {
+    pub voice_mode: i32,
+    pub voice_count: i32,
+    pub stealing_policy: i32,
+    pub note_priority: i32,
+    pub legato_is_enabled: bool,
+    pub paraphonic_assignment: i32,
+}
+
+impl UIVoiceOptions {
+    pub fn from_synth_parameters(voice_parameters: &VoiceParameters) -> Self {
+        Self {
+            voice_mode: i32::from(voice_parameters.mode.load(Relaxed)),
+            voice_count: i32::from(voice_parameters.voice_count.load(Relaxed)),
+            stealing_policy: i32::from(voice_parameters.stealing_policy.load(Relaxed)),
+            note_priority: i32::from(voice_parameters.note_priority.load(Relaxed)),
+            legato_is_enabled: voice_parameters.legato_enabled.load(Relaxed),
+            paraphonic_assignment: i32::from(voice_parameters.paraphonic_assignment.load(Relaxed)),
+        }
+    }
+}
+
+impl Default for UIVoiceOptions {
+    fn default() -> Self {
+        Self {
+            voice_mode: VoiceMode::default() as i32,
+            voice_count: i32::from(DEFAULT_VOICE_COUNT),
+            stealing_policy: VoiceStealingPolicy::default() as i32,
+            note_priority: NotePriority::default() as i32,
+            legato_is_enabled: false,
+            paraphonic_assignment: ParaphonicAssignment::default() as i32,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UIExpressionOptions {
+    pub mpe_pitch_bend_range: i32,
+    pub release_velocity_amount: f32,
+}
+
+impl UIExpressionOptions {
+    pub fn from_synth_parameters(keyboard_parameters: &KeyboardParameters) -> Self {
+        Self {
+            mpe_pitch_bend_range: i32::from(keyboard_parameters.mpe_pitch_bend_range.load(Relaxed)),
+            release_velocity_amount: keyboard_parameters.release_velocity_amount.load(),
+        }
+    }
+}
+
+impl Default for UIExpressionOptions {
+    fn default() -> Self {
+        Self {
+            mpe_pitch_bend_range: i32::from(Defaults::MPE_PITCH_BEND_RANGE),
+            release_velocity_amount: Defaults::RELEASE_VELOCITY_AMOUNT,
+        }
+    }
+}
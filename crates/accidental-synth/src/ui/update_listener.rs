@@ -3,21 +3,24 @@ use crate::ui::ParameterValues;
 use crate::ui::constants::MAX_PHASE_VALUE;
 use crate::ui::set_slint_values::{
     set_audio_device_channel_indexes, set_audio_device_channel_list, set_audio_device_values,
-    set_effect_display, set_envelope_inverted, set_envelope_stage_value, set_filter_cutoff_values,
-    set_filter_options_values, set_global_options_values, set_lfo_frequency_display,
-    set_lfo_phase_display, set_lfo_values, set_midi_clock_value, set_midi_port_values,
-    set_midi_screen_values, set_oscillator_fine_tune_display, set_oscillator_mixer_values,
-    set_oscillator_values, set_output_mixer_values, set_patch_delete_status, set_patch_list,
-    set_patch_save_status, set_user_patch_list,
+    set_cross_modulation_values, set_effect_display, set_envelope_curve, set_envelope_inverted,
+    set_envelope_stage_value, set_expression_options_values, set_filter_cutoff_values,
+    set_filter_options_values, set_filter_routing_values, set_global_options_values,
+    set_lfo_bank_values, set_lfo_frequency_display, set_lfo_phase_display, set_lfo_values,
+    set_midi_clock_value, set_midi_port_values, set_midi_screen_values,
+    set_oscillator_fine_tune_display, set_oscillator_mixer_values, set_oscillator_values,
+    set_output_mixer_values, set_patch_delete_status, set_patch_list, set_patch_save_status,
+    set_second_filter_cutoff_values, set_second_filter_options_values, set_user_patch_list,
+    set_voice_options_values,
 };
 use crate::ui::{push_values_to_ui, update_ui_values_from_module_parameters};
 use accsyn_core::defaults::Defaults;
 use accsyn_core::synth_events::{EnvelopeIndex, LFOIndex};
 use accsyn_core::ui_events::{EnvelopeStage, UIUpdates};
 use accsyn_engine::synthesizer::midi_value_converters::{
-    exponential_curve_lfo_frequency_from_normal_value, normal_value_to_bool,
+    exponential_curve_lfo_frequency_from_normal_value, normal_value_from_exponential_lfo_frequency,
+    normal_value_to_bool, normal_value_to_lfo_wave_shape_index,
     normal_value_to_number_of_filter_poles, normal_value_to_unsigned_integer_range,
-    normal_value_to_wave_shape_index,
 };
 use accsyn_engine::synthesizer::patches::{Patches, get_module_parameters_from_patch_index};
 use crossbeam_channel::Receiver;
@@ -174,6 +177,30 @@ pub fn start_ui_update_listener(
 
                     set_oscillator_values(&ui_weak_thread, &mut values.oscillators);
                 }
+                UIUpdates::CrossModulationMode(oscillator_index, value) => {
+                    #[allow(clippy::cast_sign_loss)]
+                    // Slint oscillator_index is always non-negative
+                    let idx = oscillator_index as usize;
+                    values.cross_modulation[idx].mode = value;
+
+                    set_cross_modulation_values(&ui_weak_thread, &mut values.cross_modulation);
+                }
+                UIUpdates::CrossModulationSource(oscillator_index, value) => {
+                    #[allow(clippy::cast_sign_loss)]
+                    // Slint oscillator_index is always non-negative
+                    let idx = oscillator_index as usize;
+                    values.cross_modulation[idx].source = value;
+
+                    set_cross_modulation_values(&ui_weak_thread, &mut values.cross_modulation);
+                }
+                UIUpdates::CrossModulationDepth(oscillator_index, value) => {
+                    #[allow(clippy::cast_sign_loss)]
+                    // Slint oscillator_index is always non-negative
+                    let idx = oscillator_index as usize;
+                    values.cross_modulation[idx].depth = value;
+
+                    set_cross_modulation_values(&ui_weak_thread, &mut values.cross_modulation);
+                }
                 UIUpdates::LFOFrequency(lfo_index, value) => {
                     if let Some(lfo_index) = LFOIndex::from_i32(lfo_index) {
                         let lfo_values = match lfo_index {
@@ -185,11 +212,18 @@ pub fn start_ui_update_listener(
                         let lfo_display_value =
                             exponential_curve_lfo_frequency_from_normal_value(value);
                         set_lfo_frequency_display(&ui_weak_thread, lfo_index, lfo_display_value);
+                    } else if let Some(lfo_values) = values.lfo_bank.lfo_mut(lfo_index) {
+                        lfo_values.frequency = value;
+                        set_lfo_bank_values(&ui_weak_thread, &values.lfo_bank);
                     }
                 }
                 UIUpdates::LFOFrequencyDisplay(lfo_index, value) => {
                     if let Some(lfo_index) = LFOIndex::from_i32(lfo_index) {
                         set_lfo_frequency_display(&ui_weak_thread, lfo_index, value);
+                    } else if let Some(lfo_values) = values.lfo_bank.lfo_mut(lfo_index) {
+                        // Bank LFOs derive their frequency display from the stored frequency
+                        lfo_values.frequency = normal_value_from_exponential_lfo_frequency(value);
+                        set_lfo_bank_values(&ui_weak_thread, &values.lfo_bank);
                     }
                 }
                 UIUpdates::LFOClockSyncIntervalDisplay(lfo_index, value) => {
@@ -200,6 +234,9 @@ pub fn start_ui_update_listener(
                         };
                         lfo_values.thirty_second_notes = value;
                         set_lfo_values(&ui_weak_thread, lfo_index, lfo_values);
+                    } else if let Some(lfo_values) = values.lfo_bank.lfo_mut(lfo_index) {
+                        lfo_values.thirty_second_notes = value;
+                        set_lfo_bank_values(&ui_weak_thread, &values.lfo_bank);
                     }
                 }
 
@@ -210,8 +247,12 @@ pub fn start_ui_update_listener(
                             LFOIndex::Filter => &mut values.filter_lfo,
                         };
                         lfo_values.wave_shape_index =
-                            i32::from(normal_value_to_wave_shape_index(value));
+                            i32::from(normal_value_to_lfo_wave_shape_index(value));
                         set_lfo_values(&ui_weak_thread, lfo_index, lfo_values);
+                    } else if let Some(lfo_values) = values.lfo_bank.lfo_mut(lfo_index) {
+                        lfo_values.wave_shape_index =
+                            i32::from(normal_value_to_lfo_wave_shape_index(value));
+                        set_lfo_bank_values(&ui_weak_thread, &values.lfo_bank);
                     }
                 }
                 UIUpdates::LFOPhase(lfo_index, value) => {
@@ -227,6 +268,9 @@ pub fn start_ui_update_listener(
                         #[allow(clippy::cast_possible_truncation)]
                         let lfo_display_value = (value * MAX_PHASE_VALUE).ceil() as i32;
                         set_lfo_phase_display(&ui_weak_thread, lfo_index, lfo_display_value);
+                    } else if let Some(lfo_values) = values.lfo_bank.lfo_mut(lfo_index) {
+                        lfo_values.phase = value;
+                        set_lfo_bank_values(&ui_weak_thread, &values.lfo_bank);
                     }
                 }
                 UIUpdates::LFOClockSync(lfo_index, value) => {
@@ -237,6 +281,9 @@ pub fn start_ui_update_listener(
                         };
                         lfo_values.clock_synced = value;
                         set_lfo_values(&ui_weak_thread, lfo_index, lfo_values);
+                    } else if let Some(lfo_values) = values.lfo_bank.lfo_mut(lfo_index) {
+                        lfo_values.clock_synced = value;
+                        set_lfo_bank_values(&ui_weak_thread, &values.lfo_bank);
                     }
                 }
                 UIUpdates::LFOKeySync(lfo_index, value) => {
@@ -247,6 +294,9 @@ pub fn start_ui_update_listener(
                         };
                         lfo_values.key_synced = value;
                         set_lfo_values(&ui_weak_thread, lfo_index, lfo_values);
+                    } else if let Some(lfo_values) = values.lfo_bank.lfo_mut(lfo_index) {
+                        lfo_values.key_synced = value;
+                        set_lfo_bank_values(&ui_weak_thread, &values.lfo_bank);
                     }
                 }
                 UIUpdates::EnvelopeAttackTime(envelope_index, value) => {
@@ -328,6 +378,54 @@ pub fn start_ui_update_listener(
                         );
                     }
                 }
+                UIUpdates::EnvelopeAttackCurve(envelope_index, value) => {
+                    if let Some(envelope_index) = EnvelopeIndex::from_i32(envelope_index) {
+                        let envelope_values = match envelope_index {
+                            EnvelopeIndex::Amp => &mut values.amp_envelope,
+                            EnvelopeIndex::Filter => &mut values.filter_envelope,
+                            EnvelopeIndex::Pitch => &mut values.pitch_envelope,
+                        };
+                        set_envelope_curve(
+                            &ui_weak_thread,
+                            envelope_index,
+                            EnvelopeStage::Attack,
+                            envelope_values,
+                            value,
+                        );
+                    }
+                }
+                UIUpdates::EnvelopeDecayCurve(envelope_index, value) => {
+                    if let Some(envelope_index) = EnvelopeIndex::from_i32(envelope_index) {
+                        let envelope_values = match envelope_index {
+                            EnvelopeIndex::Amp => &mut values.amp_envelope,
+                            EnvelopeIndex::Filter => &mut values.filter_envelope,
+                            EnvelopeIndex::Pitch => &mut values.pitch_envelope,
+                        };
+                        set_envelope_curve(
+                            &ui_weak_thread,
+                            envelope_index,
+                            EnvelopeStage::Decay,
+                            envelope_values,
+                            value,
+                        );
+                    }
+                }
+                UIUpdates::EnvelopeReleaseCurve(envelope_index, value) => {
+                    if let Some(envelope_index) = EnvelopeIndex::from_i32(envelope_index) {
+                        let envelope_values = match envelope_index {
+                            EnvelopeIndex::Amp => &mut values.amp_envelope,
+                            EnvelopeIndex::Filter => &mut values.filter_envelope,
+                            EnvelopeIndex::Pitch => &mut values.pitch_envelope,
+                        };
+                        set_envelope_curve(
+                            &ui_weak_thread,
+                            envelope_index,
+                            EnvelopeStage::Release,
+                            envelope_values,
+                            value,
+                        );
+                    }
+                }
                 UIUpdates::FilterCutoff(value) => {
                     let filter_cutoff_values = &mut values.filter_cutoff;
                     filter_cutoff_values.cutoff = value;
@@ -344,6 +442,16 @@ pub fn start_ui_update_listener(
                         i32::from(normal_value_to_number_of_filter_poles(value));
                     set_filter_options_values(&ui_weak_thread, filter_option_values);
                 }
+                UIUpdates::FilterType(filter_type_index) => {
+                    let filter_option_values = &mut values.filter_options;
+                    filter_option_values.filter_type = filter_type_index;
+                    set_filter_options_values(&ui_weak_thread, filter_option_values);
+                }
+                UIUpdates::FilterDrive(value) => {
+                    let filter_option_values = &mut values.filter_options;
+                    filter_option_values.drive = value;
+                    set_filter_options_values(&ui_weak_thread, filter_option_values);
+                }
                 UIUpdates::FilterKeyTracking(value) => {
                     let filter_option_values = &mut values.filter_options;
                     filter_option_values.key_track = value;
@@ -359,6 +467,47 @@ pub fn start_ui_update_listener(
                     filter_option_values.lfo_amount = value;
                     set_filter_options_values(&ui_weak_thread, filter_option_values);
                 }
+                UIUpdates::SecondFilterCutoff(value) => {
+                    let second_filter_cutoff_values = &mut values.second_filter_cutoff;
+                    second_filter_cutoff_values.cutoff = value;
+                    set_second_filter_cutoff_values(&ui_weak_thread, second_filter_cutoff_values);
+                }
+                UIUpdates::SecondFilterResonance(value) => {
+                    let second_filter_cutoff_values = &mut values.second_filter_cutoff;
+                    second_filter_cutoff_values.resonance = value;
+                    set_second_filter_cutoff_values(&ui_weak_thread, second_filter_cutoff_values);
+                }
+                UIUpdates::SecondFilterPoles(value) => {
+                    let second_filter_option_values = &mut values.second_filter_options;
+                    second_filter_option_values.poles =
+                        i32::from(normal_value_to_number_of_filter_poles(value));
+                    set_second_filter_options_values(&ui_weak_thread, second_filter_option_values);
+                }
+                UIUpdates::SecondFilterType(filter_type_index) => {
+                    let second_filter_option_values = &mut values.second_filter_options;
+                    second_filter_option_values.filter_type = filter_type_index;
+                    set_second_filter_options_values(&ui_weak_thread, second_filter_option_values);
+                }
+                UIUpdates::SecondFilterDrive(value) => {
+                    let second_filter_option_values = &mut values.second_filter_options;
+                    second_filter_option_values.drive = value;
+                    set_second_filter_options_values(&ui_weak_thread, second_filter_option_values);
+                }
+                UIUpdates::SecondFilterKeyTracking(value) => {
+                    let second_filter_option_values = &mut values.second_filter_options;
+                    second_filter_option_values.key_track = value;
+                    set_second_filter_options_values(&ui_weak_thread, second_filter_option_values);
+                }
+                UIUpdates::FilterRouting(routing_index) => {
+                    let filter_routing_values = &mut values.filter_routing;
+                    filter_routing_values.routing = routing_index;
+                    set_filter_routing_values(&ui_weak_thread, filter_routing_values);
+                }
+                UIUpdates::FilterCrossfade(value) => {
+                    let filter_routing_values = &mut values.filter_routing;
+                    filter_routing_values.crossfade = value;
+                    set_filter_routing_values(&ui_weak_thread, filter_routing_values);
+                }
 
                 UIUpdates::OutputMixerBalance(value) => {
                     let output_mixer_values = &mut values.output_mixer;
@@ -446,6 +595,26 @@ pub fn start_ui_update_listener(
                     global_options_values.sustain_is_enabled = normal_value_to_bool(is_enabled);
                     set_global_options_values(&ui_weak_thread, global_options_values);
                 }
+                UIUpdates::VoiceMode(mode_index) => {
+                    let voice_options_values = &mut values.voice_options;
+                    voice_options_values.voice_mode = mode_index;
+                    set_voice_options_values(&ui_weak_thread, voice_options_values);
+                }
+                UIUpdates::VoiceCount(voice_count) => {
+                    let voice_options_values = &mut values.voice_options;
+                    voice_options_values.voice_count = voice_count;
+                    set_voice_options_values(&ui_weak_thread, voice_options_values);
+                }
+                UIUpdates::VoiceStealingPolicy(policy_index) => {
+                    let voice_options_values = &mut values.voice_options;
+                    voice_options_values.stealing_policy = policy_index;
+                    set_voice_options_values(&ui_weak_thread, voice_options_values);
+                }
+                UIUpdates::ReleaseVelocityAmount(amount) => {
+                    let expression_options_values = &mut values.expression_options;
+                    expression_options_values.release_velocity_amount = amount;
+                    set_expression_options_values(&ui_weak_thread, expression_options_values);
+                }
                 UIUpdates::Effect(
                     effect_index,
                     is_enabled,
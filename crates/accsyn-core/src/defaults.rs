@@ -51,8 +51,26 @@ impl Defaults {
     // Global Panel Constants
     /// Default pitch bend range in semitones.
     pub const PITCH_BEND_RANGE: u8 = 12;
+    /// Default per-note pitch bend range of MPE member channels in semitones.
+    pub const MPE_PITCH_BEND_RANGE: u8 = 48;
     /// Default portamento time as a normalized value (0.0–1.0).
     pub const PORTAMENTO_TIME_NORMAL_VALUE: f32 = 0.0;
+    /// Default master tune offset in cents.
+    pub const MASTER_TUNE_CENTS: i8 = 0;
+    /// Maximum master tune offset in cents.
+    pub const MASTER_TUNE_MAX_CENTS: i8 = 100;
+    /// Minimum master tune offset in cents.
+    pub const MASTER_TUNE_MIN_CENTS: i8 = -100;
+    /// Default frequency of A4 in Hz that every tuning is scaled to.
+    pub const REFERENCE_PITCH: f32 = 440.0;
+    /// Maximum reference pitch of A4 in Hz.
+    pub const MAX_REFERENCE_PITCH: f32 = 466.0;
+    /// Minimum reference pitch of A4 in Hz.
+    pub const MIN_REFERENCE_PITCH: f32 = 415.0;
+    /// Release velocity of note offs from keyboards without release velocity sensing.
+    pub const RELEASE_VELOCITY: u8 = 64;
+    /// Default release velocity amount as a normalized value (0.0–1.0), off.
+    pub const RELEASE_VELOCITY_AMOUNT: f32 = 0.0;
     /// Default velocity curve as a normalized value (0.0–1.0).
     pub const VELOCITY_CURVE_NORMAL_VALUE: f32 = 0.5;
     /// Default velocity curve value.
@@ -155,6 +173,12 @@ impl Defaults {
     pub const MIN_HERTZ_VALUE: f32 = 0.0;
     /// Maximum value for the Hertz type.
     pub const MAX_HERTZ_VALUE: f32 = 20_000.0;
+    /// Default value for the `FrequencyRatio` type, playing at the note frequency.
+    pub const FREQUENCY_RATIO: f32 = 1.0;
+    /// Minimum value for the `FrequencyRatio` type, three octaves below the note.
+    pub const MIN_FREQUENCY_RATIO: f32 = 0.125;
+    /// Maximum value for the `FrequencyRatio` type, five octaves above the note.
+    pub const MAX_FREQUENCY_RATIO: f32 = 32.0;
 }
 
 impl Defaults {
@@ -1,18 +1,20 @@
 use strum_macros::FromRepr;
 
 /// MIDI events received from input devices and forwarded to the synthesizer.
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum MidiEvent {
     /// Note on event (note number, velocity).
     NoteOn(u8, u8),
-    /// Note off event.
-    NoteOff,
+    /// Note off event (note number, release velocity).
+    NoteOff(u8, u8),
     /// Control change message.
     ControlChange(CC),
     /// Pitch bend event (14-bit unsigned value, center at 8192).
     PitchBend(u16),
     /// Channel pressure (aftertouch) event (pressure value).
     ChannelPressure(u8),
+    /// Polyphonic key pressure (aftertouch) event (note number, pressure value).
+    PolyKeyPressure(u8, u8),
     /// Midi Transport Stop Message
     Stop,
     /// Clock pulse event
@@ -21,6 +23,30 @@ pub enum MidiEvent {
     Reset,
     /// Program change event (program number).
     ProgramChange(u8),
+    /// MIDI Tuning Standard retuning from a `SysEx` message.
+    TuningChange(TuningChange),
+    /// MPE note on event from a member channel (member channel, note number, velocity).
+    MpeNoteOn(u8, u8, u8),
+    /// MPE note off event from a member channel (member channel, note number, release velocity).
+    MpeNoteOff(u8, u8, u8),
+    /// MPE per-note pitch bend from a member channel (member channel, 14-bit unsigned value, center at 8192).
+    MpePitchBend(u8, u16),
+    /// MPE per-note pressure from a member channel (member channel, pressure value).
+    MpePressure(u8, u8),
+    /// MPE per-note timbre (CC 74) from a member channel (member channel, value).
+    MpeTimbre(u8, u8),
+}
+
+/// A MIDI Tuning Standard retuning of the note-to-frequency mapping.
+///
+/// Pitches are fractional MIDI note numbers in equal temperament at A4 = 440 Hz, so 69.5 is a quarter tone
+/// above A4.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TuningChange {
+    /// Retune individual notes (note number, pitch), from a single note change or a bulk tuning dump.
+    Notes(Vec<(u8, f32)>),
+    /// Retune every note to equal temperament offset by cents for each pitch class, starting from C.
+    ScaleOctave([f32; 12]),
 }
 
 /// MIDI Control Change message types mapped to synthesizer parameters.
@@ -74,6 +100,8 @@ pub enum CC {
     PitchEnvelopeReleaseTime(u8),
     /// Pitch envelope inversion toggle
     PitchEnvelopeInverted(u8),
+    /// Pitch envelope attack, decay and release curves together
+    PitchEnvelopeCurve(u8),
     /// Portamento glide time.
     PortamentoTime(u8),
     /// Toggle oscillator hard sync.
@@ -138,10 +166,30 @@ pub enum CC {
     Oscillator2ClipBoost(u8),
     /// Oscillator 3 clipper boost amount.
     Oscillator3ClipBoost(u8),
+    /// Sub-oscillator cross modulation mode and source together.
+    SubOscillatorCrossModulation(u8),
+    /// Oscillator 1 cross modulation mode and source together.
+    Oscillator1CrossModulation(u8),
+    /// Oscillator 2 cross modulation mode and source together.
+    Oscillator2CrossModulation(u8),
+    /// Oscillator 3 cross modulation mode and source together.
+    Oscillator3CrossModulation(u8),
+    /// Sub-oscillator cross modulation depth.
+    SubOscillatorCrossModulationDepth(u8),
+    /// Oscillator 1 cross modulation depth.
+    Oscillator1CrossModulationDepth(u8),
+    /// Oscillator 2 cross modulation depth.
+    Oscillator2CrossModulationDepth(u8),
+    /// Oscillator 3 cross modulation depth.
+    Oscillator3CrossModulationDepth(u8),
     /// Filter pole count selection.
     FilterPoles(u8),
     /// Filter resonance amount.
     FilterResonance(u8),
+    /// Filter response type selection.
+    FilterType(u8),
+    /// Filter drive amount.
+    FilterDrive(u8),
     /// Filter cutoff frequency.
     FilterCutoff(u8),
     /// Amplitude envelope release time.
@@ -154,6 +202,8 @@ pub enum CC {
     AmpEGSustainLevel(u8),
     /// Amplitude envelope inversion toggle.
     AmpEGInverted(u8),
+    /// Amplitude envelope attack, decay and release curves together.
+    AmpEGCurve(u8),
     /// Filter envelope attack time.
     FilterEnvelopeAttackTime(u8),
     /// Filter envelope decay time.
@@ -164,10 +214,28 @@ pub enum CC {
     FilterEnvelopeReleaseTime(u8),
     /// Filter envelope inversion toggle.
     FilterEnvelopeInverted(u8),
+    /// Filter envelope attack, decay and release curves together.
+    FilterEnvelopeCurve(u8),
     /// Filter envelope modulation amount.
     FilterEnvelopeAmount(u8),
     /// Filter key tracking amount.
     KeyTrackingAmount(u8),
+    /// Second filter cutoff frequency.
+    SecondFilterCutoff(u8),
+    /// Second filter resonance amount.
+    SecondFilterResonance(u8),
+    /// Second filter pole count selection.
+    SecondFilterPoles(u8),
+    /// Second filter response type selection.
+    SecondFilterType(u8),
+    /// Second filter drive amount.
+    SecondFilterDrive(u8),
+    /// Second filter key tracking amount.
+    SecondFilterKeyTracking(u8),
+    /// How the two filters are connected.
+    FilterRouting(u8),
+    /// Parallel filter routing mix between the two filters.
+    FilterCrossfade(u8),
     /// Toggle mod wheel lfo clock sync.
     ModWheelLFOClockSync(u8),
     /// Mod wheel LFO frequency.
@@ -198,6 +266,14 @@ pub enum CC {
     FilterModLFOKeySync(u8),
     /// Toggle filter lfo key sync.
     ModWheelLFOKeySync(u8),
+    /// Voice allocation mode.
+    VoiceMode(u8),
+    /// Number of voices available in poly mode.
+    VoiceCount(u8),
+    /// Voice stealing policy.
+    VoiceStealingPolicy(u8),
+    /// How strongly release velocity scales envelope release times.
+    ReleaseVelocityAmount(u8),
     /// Turn off all currently sounding notes.
     AllNotesOff,
 }
@@ -182,6 +182,66 @@ impl<'de> Deserialize<'de> for Hertz {
     }
 }
 
+/// Thread-safe frequency multiplier relative to the note frequency stored as atomic bits.
+#[derive(Debug)]
+pub struct FrequencyRatio {
+    value: AtomicU32,
+}
+
+impl FrequencyRatio {
+    /// Creates a new frequency ratio.
+    #[inline]
+    #[must_use]
+    pub fn new(ratio: f32) -> Self {
+        Self {
+            value: AtomicU32::new(FrequencyRatio::sanitize(ratio).to_bits()),
+        }
+    }
+
+    #[inline]
+    fn sanitize(value: f32) -> f32 {
+        if value.is_finite() {
+            value.clamp(Defaults::MIN_FREQUENCY_RATIO, Defaults::MAX_FREQUENCY_RATIO)
+        } else {
+            Defaults::FREQUENCY_RATIO
+        }
+    }
+
+    /// Loads the current frequency ratio.
+    #[inline]
+    pub fn load(&self) -> f32 {
+        f32::from_bits(self.value.load(Ordering::Relaxed))
+    }
+
+    /// Stores a new frequency ratio.
+    #[inline]
+    pub fn store(&self, ratio: f32) {
+        self.value
+            .store(Self::sanitize(ratio).to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl Default for FrequencyRatio {
+    fn default() -> Self {
+        Self {
+            value: AtomicU32::new(Defaults::FREQUENCY_RATIO.to_bits()),
+        }
+    }
+}
+
+impl Serialize for FrequencyRatio {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f32(self.load())
+    }
+}
+
+impl<'de> Deserialize<'de> for FrequencyRatio {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ratio = f32::deserialize(deserializer)?;
+        Ok(Self::new(ratio))
+    }
+}
+
 /// Thread-safe LFO synced interval in thirty-second notes stored atomically.
 #[derive(Debug)]
 pub struct ThirtySecondNotes {
@@ -688,4 +748,23 @@ mod tests {
     fn portamento_buffers_default_upholds_the_floor_invariant() {
         assert_eq!(PortamentoBuffers::default().load(), 1);
     }
+
+    #[test]
+    fn frequency_ratio_clamps_to_the_supported_range() {
+        assert_eq!(
+            FrequencyRatio::new(0.0).load(),
+            Defaults::MIN_FREQUENCY_RATIO
+        );
+        assert_eq!(
+            FrequencyRatio::new(100.0).load(),
+            Defaults::MAX_FREQUENCY_RATIO
+        );
+    }
+
+    #[test]
+    fn frequency_ratio_replaces_non_finite_values_with_the_default() {
+        let ratio = FrequencyRatio::new(2.0);
+        ratio.store(f32::NAN);
+        assert_eq!(ratio.load(), Defaults::FREQUENCY_RATIO);
+    }
 }
@@ -20,14 +20,56 @@ pub enum SynthesizerUpdateEvents {
     Parameter2(i32, f32),
     /// Change oscillator Pitch Envelope Amount (oscillator index, normalized value).
     PitchEnvelopeAmount(i32, f32),
+    /// Change oscillator wavetable (oscillator index, wavetable name).
+    OscillatorWavetable(i32, String),
+    /// Change oscillator sample (oscillator index, sample name).
+    OscillatorSample(i32, String),
+    /// Change how another oscillator modulates this one (oscillator index, cross modulation mode index).
+    CrossModulationMode(i32, i32),
+    /// Change the oscillator that modulates this one (oscillator index, source oscillator index).
+    CrossModulationSource(i32, i32),
+    /// Change oscillator cross modulation depth (oscillator index, normalized value).
+    CrossModulationDepth(i32, f32),
+    /// Change the oscillator that restarts this one's cycle under hard sync (oscillator index, hard sync source index).
+    HardSyncSource(i32, i32),
+    /// Change the octave the sub oscillator plays in (sub octave index).
+    SubOctave(i32),
+    /// Change the number of supersaw voices (oscillator index, voice count).
+    SupersawVoiceCount(i32, i32),
+    /// Change how far the supersaw voices spread across the stereo field (oscillator index, normalized value).
+    SupersawStereoSpread(i32, f32),
+    /// Toggle random supersaw voice phases on each note (oscillator index, random phase enabled).
+    SupersawRandomPhase(i32, bool),
+    /// Change the amplitude of one additive partial (oscillator index, partial index, normalized value).
+    AdditivePartial(i32, i32, f32),
     /// Change filter cutoff frequency (normalized value).
     FilterCutoffFrequency(f32),
     /// Change filter resonance (normalized value).
     FilterResonance(f32),
     /// Change filter pole count (normalized value).
     FilterPoleCount(f32),
+    /// Change filter response type (filter type index).
+    FilterType(i32),
     /// Change filter key tracking amount (normalized value).
     FilterKeyTrackingAmount(f32),
+    /// Change filter drive (normalized value).
+    FilterDrive(f32),
+    /// Change second filter cutoff frequency (normalized value).
+    SecondFilterCutoffFrequency(f32),
+    /// Change second filter resonance (normalized value).
+    SecondFilterResonance(f32),
+    /// Change second filter pole count (normalized value).
+    SecondFilterPoleCount(f32),
+    /// Change second filter response type (filter type index).
+    SecondFilterType(i32),
+    /// Change second filter key tracking amount (normalized value).
+    SecondFilterKeyTrackingAmount(f32),
+    /// Change second filter drive (normalized value).
+    SecondFilterDrive(f32),
+    /// Change how the two filters are connected (routing index).
+    FilterRouting(i32),
+    /// Change the parallel routing mix between the two filters (normalized value).
+    FilterCrossfade(f32),
     /// Change filter envelope modulation amount (normalized value).
     FilterEnvelopeAmount(f32),
     /// Change filter LFO modulation amount (normalized value).
@@ -42,6 +84,22 @@ pub enum SynthesizerUpdateEvents {
     FilterEnvelopeRelease(i32, f32),
     /// Toggle filter envelope inversion (envelope index, inverted).
     FilterEnvelopeInvert(i32, bool),
+    /// Change envelope attack curve (envelope index, normalized value).
+    EnvelopeAttackCurve(i32, f32),
+    /// Change envelope decay curve (envelope index, normalized value).
+    EnvelopeDecayCurve(i32, f32),
+    /// Change envelope release curve (envelope index, normalized value).
+    EnvelopeReleaseCurve(i32, f32),
+    /// Change envelope delay time (envelope index, normalized value).
+    EnvelopeDelay(i32, f32),
+    /// Change envelope hold time (envelope index, normalized value).
+    EnvelopeHold(i32, f32),
+    /// Toggle envelope segment looping while the gate is held (envelope index, loop enabled).
+    EnvelopeLoopEnabled(i32, bool),
+    /// Change the first looped envelope segment (envelope index, segment index).
+    EnvelopeLoopStart(i32, i32),
+    /// Change the last looped envelope segment (envelope index, segment index).
+    EnvelopeLoopEnd(i32, i32),
     /// Change LFO frequency (LFO index, normalized value).
     LfoFrequency(i32, f32),
     /// Change LFO waveform shape (LFO index, shape index).
@@ -58,10 +116,24 @@ pub enum SynthesizerUpdateEvents {
     PortamentoEnabled(bool),
     /// Change portamento glide time (normalized value).
     PortamentoTime(f32),
+    /// Change analog drift depth (normalized value).
+    DriftDepth(f32),
+    /// Change master tune (cents).
+    MasterTune(i32),
+    /// Change the reference pitch of A4 (Hz).
+    ReferencePitch(f32),
+    /// Change the Scala tuning scale (scale file name).
+    TuningScale(String),
+    /// Change the Scala keyboard mapping (keyboard mapping file name).
+    TuningKeyboardMapping(String),
+    /// Change the MPE per-note pitch bend range (semitones).
+    MpePitchBendRange(i32),
     /// Change pitch bend range in semitones (normalized value).
     PitchBendRange(f32),
     /// Change velocity sensitivity curve (normalized value).
     VelocityCurve(f32),
+    /// Change how strongly release velocity scales envelope release times (normalized value).
+    ReleaseVelocityAmount(f32),
     /// Toggle oscillator hard sync on/off.
     HardSyncEnabled(bool),
     /// Toggle oscillator key sync on/off.
@@ -72,6 +144,32 @@ pub enum SynthesizerUpdateEvents {
     SoftClipEnabled(bool),
     /// Sustain Pedal State on/off
     SustainPedal(bool),
+    /// Change the voice allocation mode (mode index).
+    VoiceMode(i32),
+    /// Change the number of voices available in poly mode (voice count).
+    VoiceCount(i32),
+    /// Change the voice stealing policy (policy index).
+    VoiceStealingPolicy(i32),
+    /// Change which held note sounds in mono mode (priority index).
+    NotePriority(i32),
+    /// Toggle mono legato on/off.
+    LegatoEnabled(bool),
+    /// Change how held notes are spread across the oscillators in paraphonic mode (assignment index).
+    ParaphonicAssignment(i32),
+    /// Change the number of running LFOs in the LFO bank (LFO count).
+    LfoCount(i32),
+    /// Change the LFO the mod wheel vibrato follows (LFO index).
+    VibratoLfo(i32),
+    /// Change the LFO the filter cutoff follows (LFO index).
+    FilterLfo(i32),
+    /// Change a modulation matrix slot's source (slot index, source index).
+    ModulationSource(i32, i32),
+    /// Change a modulation matrix slot's destination (slot index, destination index).
+    ModulationDestination(i32, i32),
+    /// Change a modulation matrix slot's amount (slot index, normalized value).
+    ModulationAmount(i32, f32),
+    /// Change a modulation matrix slot's via source (slot index, source index).
+    ModulationViaSource(i32, i32),
     /// Change the output mixer stereo balance (normalized value).
     OutputBalance(f32),
     /// Change the output mixer level (normalized value).
@@ -99,7 +197,7 @@ pub enum SynthesizerUpdateEvents {
 }
 
 /// Index identifying each oscillator in the synthesizer.
-#[derive(Debug, Clone, Copy, EnumCount, EnumIter, FromRepr)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
 #[repr(i32)]
 pub enum OscillatorIndex {
     /// Sub-oscillator (index 0).
@@ -120,7 +218,7 @@ impl OscillatorIndex {
     }
 }
 
-/// Index identifying each LFO in the synthesizer.
+/// Index identifying the LFOs with a fixed role. Further LFOs in the bank are addressed by number.
 #[derive(Debug, Clone, Copy, EnumCount, EnumIter, FromRepr)]
 #[repr(i32)]
 pub enum LFOIndex {
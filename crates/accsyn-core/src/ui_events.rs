@@ -44,6 +44,12 @@ pub enum UIUpdates {
     OscillatorParameter2(i32, f32),
     /// Oscillator pitch envelope modulation amount changed (oscillator index, normalized value).
     OscillatorPitchEnvelopeAmount(i32, f32),
+    /// Oscillator cross modulation mode changed (oscillator index, cross modulation mode index).
+    CrossModulationMode(i32, i32),
+    /// Oscillator cross modulation source changed (oscillator index, source oscillator index).
+    CrossModulationSource(i32, i32),
+    /// Oscillator cross modulation depth changed (oscillator index, normalized value).
+    CrossModulationDepth(i32, f32),
     /// LFO frequency changed (LFO index, normalized value).
     LFOFrequency(i32, f32),
     /// LFO frequency display value in Hz (LFO index, Hz).
@@ -68,18 +74,44 @@ pub enum UIUpdates {
     EnvelopeReleaseTime(i32, f32),
     /// Envelope inversion toggled (envelope index, normalized value).
     EnvelopeInverted(i32, f32),
+    /// Envelope attack curve changed (envelope index, normalized value).
+    EnvelopeAttackCurve(i32, f32),
+    /// Envelope decay curve changed (envelope index, normalized value).
+    EnvelopeDecayCurve(i32, f32),
+    /// Envelope release curve changed (envelope index, normalized value).
+    EnvelopeReleaseCurve(i32, f32),
     /// Filter cutoff frequency changed (normalized value).
     FilterCutoff(f32),
     /// Filter resonance changed (normalized value).
     FilterResonance(f32),
     /// Filter pole count changed (normalized value).
     FilterPoles(f32),
+    /// Filter response type changed (filter type index).
+    FilterType(i32),
     /// Filter key tracking amount changed (normalized value).
     FilterKeyTracking(f32),
+    /// Filter drive changed (normalized value).
+    FilterDrive(f32),
     /// Filter envelope modulation amount changed (normalized value).
     FilterEnvelopeAmount(f32),
     /// Filter LFO modulation amount changed (normalized value).
     FilterLFOAmount(f32),
+    /// Second filter cutoff frequency changed (normalized value).
+    SecondFilterCutoff(f32),
+    /// Second filter resonance changed (normalized value).
+    SecondFilterResonance(f32),
+    /// Second filter pole count changed (normalized value).
+    SecondFilterPoles(f32),
+    /// Second filter response type changed (filter type index).
+    SecondFilterType(i32),
+    /// Second filter key tracking amount changed (normalized value).
+    SecondFilterKeyTracking(f32),
+    /// Second filter drive changed (normalized value).
+    SecondFilterDrive(f32),
+    /// Filter routing changed (routing index).
+    FilterRouting(i32),
+    /// Parallel filter crossfade changed (normalized value).
+    FilterCrossfade(f32),
     /// Output mixer stereo balance changed (normalized value).
     OutputMixerBalance(f32),
     /// Output mixer level changed (normalized value).
@@ -106,6 +138,14 @@ pub enum UIUpdates {
     KeySync(bool),
     /// Sustain pedal enabled state changed (0.0 or 1.0).
     SustainPedal(f32),
+    /// Voice allocation mode changed (mode index).
+    VoiceMode(i32),
+    /// Poly mode voice count changed (voice count).
+    VoiceCount(i32),
+    /// Voice stealing policy changed (policy index).
+    VoiceStealingPolicy(i32),
+    /// Release velocity amount changed (normalized value).
+    ReleaseVelocityAmount(f32),
     /// Effect parameters changed (effect index, enabled, param1, param2, param3, param4).
     Effect(i32, bool, f32, f32, f32, f32),
     /// Patch changed — UI should reload all parameter values from the patch at this index.
@@ -18,6 +18,7 @@ use serde::{Deserialize, Serialize};
 use std::f32::consts::FRAC_PI_2;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering::Relaxed;
+use strum::EnumCount;
 
 mod autopan;
 mod bitcrusher;
@@ -79,6 +80,7 @@ impl Default for AudioEffectParameters {
 pub struct Effects {
     effects: Vec<Box<dyn AudioEffect>>,
     parameters: Vec<EffectParameters>,
+    unmodulated_parameters: Vec<EffectParameters>,
 }
 
 impl Effects {
@@ -104,6 +106,7 @@ impl Effects {
                 flanger, phaser, autopan, tremolo, delay,
             ],
             parameters: EffectParameters::default_all(),
+            unmodulated_parameters: EffectParameters::default_all(),
         }
     }
 
@@ -113,8 +116,32 @@ impl Effects {
             .iter()
             .enumerate()
             .for_each(|(index, effect_parameters)| {
-                self.parameters[index] = extract_parameters(effect_parameters);
+                self.unmodulated_parameters[index] = extract_parameters(effect_parameters);
             });
+        self.parameters.clone_from(&self.unmodulated_parameters);
+    }
+
+    /// Offsets the effect parameters set by the last update by the modulation matrix, clamped to the
+    /// normalized range. Offsets replace rather than accumulate, so this can be called every sample.
+    pub fn apply_modulation(
+        &mut self,
+        offsets: &[[f32; PARAMETERS_PER_EFFECT]; EffectIndex::COUNT],
+    ) {
+        for ((effect_parameters, unmodulated), effect_offsets) in self
+            .parameters
+            .iter_mut()
+            .zip(&self.unmodulated_parameters)
+            .zip(offsets)
+        {
+            for ((parameter, unmodulated_parameter), offset) in effect_parameters
+                .parameters
+                .iter_mut()
+                .zip(&unmodulated.parameters)
+                .zip(effect_offsets)
+            {
+                *parameter = (unmodulated_parameter + offset).clamp(0.0, 1.0);
+            }
+        }
     }
 
     /// Processes a stereo sample pair through all enabled effects in order.
@@ -297,6 +324,24 @@ mod tests {
         assert!(f32s_are_equal(result.1, -0.5));
     }
 
+    #[test]
+    fn apply_modulation_offsets_and_clamps_effect_parameters() {
+        let mut effects = Effects::new(48000);
+        let params = vec![AudioEffectParameters::default()];
+        params[0].parameters[0].store(0.5);
+        params[0].parameters[1].store(0.9);
+        effects.set_parameters(&params);
+        let mut offsets = [[0.0; PARAMETERS_PER_EFFECT]; EffectIndex::COUNT];
+        offsets[0][0] = 0.25;
+        offsets[0][1] = 0.5;
+
+        effects.apply_modulation(&offsets);
+        effects.apply_modulation(&offsets);
+
+        assert!(f32s_are_equal(effects.parameters[0].parameters[0], 0.75));
+        assert!(f32s_are_equal(effects.parameters[0].parameters[1], 1.0));
+    }
+
     #[test]
     fn effects_process_applies_enabled_effect() {
         let mut effects = Effects::new(48000);
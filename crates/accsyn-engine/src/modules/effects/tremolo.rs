@@ -3,7 +3,7 @@ use crate::modules::effects::constants::{
 };
 use crate::modules::lfo::Lfo;
 use crate::synthesizer::midi_value_converters::{
-    exponential_curve_lfo_frequency_from_normal_value, normal_value_to_wave_shape_index,
+    exponential_curve_lfo_frequency_from_normal_value, normal_value_to_lfo_wave_shape_index,
 };
 use accsyn_core::casting::f32_to_u8_clamped;
 use accsyn_core::defaults::Defaults;
@@ -51,7 +51,7 @@ impl AudioEffect for Tremolo {
 
         let new_frequency = effect.parameters[0];
         let new_depth = effect.parameters[1];
-        let new_shape = f32::from(normal_value_to_wave_shape_index(effect.parameters[2]));
+        let new_shape = f32::from(normal_value_to_lfo_wave_shape_index(effect.parameters[2]));
 
         if !f32s_are_equal(new_frequency, self.lfo_parameters.frequency) {
             self.lfo_parameters.frequency = new_frequency;
@@ -78,8 +78,7 @@ impl AudioEffect for Tremolo {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::modules::oscillator::WaveShape;
-    use strum::EnumCount;
+    use crate::modules::oscillator::LAST_LFO_WAVE_SHAPE_INDEX;
 
     #[test]
     fn tremolo_process_samples_returns_original_when_disabled() {
@@ -157,7 +156,7 @@ mod tests {
         let test_shape_normalized: f32 = 1.0;
         // Hand coded above with test values. Not dynamically created
         #[allow(clippy::cast_precision_loss)]
-        let test_shape_index = (WaveShape::COUNT - 1) as f32;
+        let test_shape_index = LAST_LFO_WAVE_SHAPE_INDEX as f32;
 
         let effect = EffectParameters {
             name: String::new(),
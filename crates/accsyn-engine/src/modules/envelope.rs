@@ -1,13 +1,15 @@
-use accsyn_core::parameter_types::{Milliseconds, NormalizedValue};
+use accsyn_core::parameter_types::{BiPolarNormalizedValue, Milliseconds, NormalizedValue};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::Ordering::Relaxed;
 use std::sync::atomic::{AtomicBool, AtomicU8};
+use strum_macros::{EnumCount, EnumIter, FromRepr};
 
 const ENVELOPE_MAX_LEVEL: f32 = 1.0;
 const ENVELOPE_MIN_LEVEL: f32 = 0.0;
 const MIN_SUSTAIN_LEVEL: f32 = 0.0;
 const MAX_SUSTAIN_LEVEL: f32 = 1.0;
 const DEFAULT_EG_AMOUNT: f32 = 1.0;
+const MAX_CURVE_EXPONENT: f32 = 5.0;
 /// Default per-sample level increment during the attack stage.
 pub const DEFAULT_ATTACK_LEVEL_INCREMENT: f32 = 0.0001;
 /// Default per-sample level increment during the decay stage.
@@ -16,6 +18,8 @@ pub const DEFAULT_DECAY_LEVEL_INCREMENT: f32 = 0.0001;
 pub const DEFAULT_DECAY_MILLISECONDS: f32 = 200.0;
 /// Default per-sample level increment during the release stage.
 pub const DEFAULT_RELEASE_LEVEL_INCREMENT: f32 = 0.0001;
+/// Default factor applied to the release time, leaving it unchanged.
+pub const DEFAULT_RELEASE_TIME_SCALE: f32 = 1.0;
 /// Default envelope modulation amount (1.0 = full).
 pub const DEFAULT_ENVELOPE_AMOUNT: f32 = 1.0;
 /// Default sustain level as a normalized value.
@@ -34,13 +38,64 @@ pub const MAX_DECAY_MILLISECONDS: u32 = 5000;
 pub const MIN_RELEASE_MILLISECONDS: u32 = 10;
 /// Maximum release time in milliseconds.
 pub const MAX_RELEASE_MILLISECONDS: u32 = 10000;
+/// Minimum delay time in milliseconds. Zero skips the delay segment.
+pub const MIN_DELAY_MILLISECONDS: u32 = 0;
+/// Maximum delay time in milliseconds.
+pub const MAX_DELAY_MILLISECONDS: u32 = 5000;
+/// Minimum hold time in milliseconds. Zero skips the hold segment.
+pub const MIN_HOLD_MILLISECONDS: u32 = 0;
+/// Maximum hold time in milliseconds.
+pub const MAX_HOLD_MILLISECONDS: u32 = 5000;
+/// Default segment curve: straight-line segments.
+pub const DEFAULT_ENVELOPE_CURVE: f32 = 0.0;
+
+/// The envelope segments that run while the gate is held, usable as loop start and end markers.
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr,
+)]
+#[repr(u8)]
+pub enum EnvelopeSegment {
+    /// Wait at the current level before the attack starts.
+    Delay,
+    /// Rise to the maximum level.
+    #[default]
+    Attack,
+    /// Stay at the maximum level before the decay starts.
+    Hold,
+    /// Fall to the sustain level.
+    Decay,
+}
+
+impl EnvelopeSegment {
+    /// Converts a numeric index to the corresponding envelope segment, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
 
-/// Shared atomic parameters for controlling an ADSR envelope from the UI thread.
+    fn stage(self) -> Stage {
+        match self {
+            EnvelopeSegment::Delay => Stage::Delay,
+            EnvelopeSegment::Attack => Stage::Attack,
+            EnvelopeSegment::Hold => Stage::Hold,
+            EnvelopeSegment::Decay => Stage::Decay,
+        }
+    }
+}
+
+/// Shared atomic parameters for controlling a DAHDSR envelope from the UI thread.
+///
+/// Delay and hold default to zero and looping defaults to off, so a patch saved before they
+/// existed still loads as a plain ADSR.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct EnvelopeParameters {
+    /// Delay time in milliseconds.
+    pub delay_ms: Milliseconds,
     /// Attack time in milliseconds.
     pub attack_ms: Milliseconds,
+    /// Hold time in milliseconds.
+    pub hold_ms: Milliseconds,
     /// Decay time in milliseconds.
     pub decay_ms: Milliseconds,
     /// Release time in milliseconds.
@@ -49,10 +104,22 @@ pub struct EnvelopeParameters {
     pub sustain_level: NormalizedValue,
     /// Envelope modulation depth amount.
     pub amount: NormalizedValue,
+    /// Attack curve from -1.0 (logarithmic) through 0.0 (linear) to 1.0 (exponential).
+    pub attack_curve: BiPolarNormalizedValue,
+    /// Decay curve from -1.0 (logarithmic) through 0.0 (linear) to 1.0 (exponential).
+    pub decay_curve: BiPolarNormalizedValue,
+    /// Release curve from -1.0 (logarithmic) through 0.0 (linear) to 1.0 (exponential).
+    pub release_curve: BiPolarNormalizedValue,
     /// Whether the MIDI sustain pedal is held.
     pub sustain_pedal: AtomicBool,
     /// Whether the envelope output is inverted.
     pub is_inverted: AtomicBool,
+    /// Whether the segments between the loop markers repeat while the gate is held.
+    pub loop_enabled: AtomicBool,
+    /// First looped segment as an `EnvelopeSegment` index.
+    pub loop_start: AtomicU8,
+    /// Last looped segment as an `EnvelopeSegment` index.
+    pub loop_end: AtomicU8,
     /// Gate state flag: 0 = waiting, 1 = gate on, 2 = gate off.
     pub gate_flag: AtomicU8, // 0 - waiting, 1 - gate on, 2 - gate off
 }
@@ -65,6 +132,18 @@ impl EnvelopeParameters {
     /// path entirely, so without this clamp an out-of-range value in a patch file would be
     /// stored and used as-is.
     pub fn assign_from(&self, parameters: &EnvelopeParameters) {
+        self.delay_ms.store(
+            parameters
+                .delay_ms
+                .load()
+                .clamp(MIN_DELAY_MILLISECONDS, MAX_DELAY_MILLISECONDS),
+        );
+        self.hold_ms.store(
+            parameters
+                .hold_ms
+                .load()
+                .clamp(MIN_HOLD_MILLISECONDS, MAX_HOLD_MILLISECONDS),
+        );
         self.attack_ms.store(
             parameters
                 .attack_ms
@@ -85,10 +164,23 @@ impl EnvelopeParameters {
         );
         self.sustain_level.store(parameters.sustain_level.load());
         self.amount.store(parameters.amount.load());
+        self.attack_curve.store(parameters.attack_curve.load());
+        self.decay_curve.store(parameters.decay_curve.load());
+        self.release_curve.store(parameters.release_curve.load());
         self.sustain_pedal
             .store(parameters.sustain_pedal.load(Relaxed), Relaxed);
         self.is_inverted
             .store(parameters.is_inverted.load(Relaxed), Relaxed);
+        self.loop_enabled
+            .store(parameters.loop_enabled.load(Relaxed), Relaxed);
+        self.loop_start.store(
+            EnvelopeSegment::from_index(parameters.loop_start.load(Relaxed)) as u8,
+            Relaxed,
+        );
+        self.loop_end.store(
+            EnvelopeSegment::from_index(parameters.loop_end.load(Relaxed)) as u8,
+            Relaxed,
+        );
         self.gate_flag
             .store(parameters.gate_flag.load(Relaxed), Relaxed);
     }
@@ -97,23 +189,33 @@ impl EnvelopeParameters {
 impl Default for EnvelopeParameters {
     fn default() -> Self {
         Self {
+            delay_ms: Milliseconds::new(MIN_DELAY_MILLISECONDS),
             attack_ms: Milliseconds::new(DEFAULT_ENVELOPE_MILLISECONDS),
+            hold_ms: Milliseconds::new(MIN_HOLD_MILLISECONDS),
             decay_ms: Milliseconds::new(DEFAULT_ENVELOPE_MILLISECONDS),
             sustain_level: NormalizedValue::new(DEFAULT_ENVELOPE_SUSTAIN_LEVEL),
             release_ms: Milliseconds::new(DEFAULT_ENVELOPE_MILLISECONDS),
             amount: NormalizedValue::new(DEFAULT_ENVELOPE_AMOUNT),
+            attack_curve: BiPolarNormalizedValue::new(DEFAULT_ENVELOPE_CURVE),
+            decay_curve: BiPolarNormalizedValue::new(DEFAULT_ENVELOPE_CURVE),
+            release_curve: BiPolarNormalizedValue::new(DEFAULT_ENVELOPE_CURVE),
             is_inverted: AtomicBool::new(false),
+            loop_enabled: AtomicBool::new(false),
+            loop_start: AtomicU8::new(EnvelopeSegment::Attack as u8),
+            loop_end: AtomicU8::new(EnvelopeSegment::Decay as u8),
             gate_flag: AtomicU8::new(0),
             sustain_pedal: AtomicBool::new(false),
         }
     }
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) enum Stage {
     #[default]
     Off,
+    Delay,
     Attack,
+    Hold,
     Decay,
     Sustain,
     Release,
@@ -126,22 +228,35 @@ enum StageAction {
     NextStage,
 }
 
-/// ADSR envelope generator that produces a control signal over time.
+/// DAHDSR envelope generator that produces a control signal over time.
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct Envelope {
     stage: Stage,
     level: f32,
+    curved_level: f32,
+    segment_start_level: f32,
+    attack_curve: f32,
+    decay_curve: f32,
+    release_curve: f32,
     sample_rate: u32,
     amount: f32,
+    amount_modulation: f32,
     is_inverted: bool,
     milliseconds_per_sample: f32,
+    delay_samples: u32,
     attack_level_increment: f32,
+    hold_samples: u32,
+    stage_sample_count: u32,
     decay_milliseconds: f32,
     decay_level_increment: f32,
     sustain_level: f32,
     sustain_pedal: bool,
     gate_hold: bool,
     release_level_increment: f32,
+    release_time_scale: f32,
+    loop_enabled: bool,
+    loop_start: EnvelopeSegment,
+    loop_end: EnvelopeSegment,
 }
 
 impl Envelope {
@@ -157,36 +272,71 @@ impl Envelope {
 
         Self {
             level: ENVELOPE_MIN_LEVEL,
+            curved_level: ENVELOPE_MIN_LEVEL,
+            segment_start_level: ENVELOPE_MIN_LEVEL,
+            attack_curve: DEFAULT_ENVELOPE_CURVE,
+            decay_curve: DEFAULT_ENVELOPE_CURVE,
+            release_curve: DEFAULT_ENVELOPE_CURVE,
             sample_rate,
             milliseconds_per_sample,
             is_inverted: false,
             amount: DEFAULT_EG_AMOUNT,
+            amount_modulation: 0.0,
             sustain_level: DEFAULT_ENVELOPE_SUSTAIN_LEVEL,
+            delay_samples: 0,
             attack_level_increment: DEFAULT_ATTACK_LEVEL_INCREMENT,
+            hold_samples: 0,
+            stage_sample_count: 0,
             decay_level_increment: DEFAULT_DECAY_LEVEL_INCREMENT,
             decay_milliseconds: DEFAULT_DECAY_MILLISECONDS,
             release_level_increment: DEFAULT_RELEASE_LEVEL_INCREMENT,
+            release_time_scale: DEFAULT_RELEASE_TIME_SCALE,
             stage: Stage::Off,
             gate_hold: false,
             sustain_pedal: false,
+            loop_enabled: false,
+            loop_start: EnvelopeSegment::Attack,
+            loop_end: EnvelopeSegment::Decay,
         }
     }
 
+    /// Sets the factor the release time is multiplied by when it is next set, e.g. from release velocity.
+    pub fn set_release_time_scale(&mut self, scale: f32) {
+        self.release_time_scale = scale.max(0.0);
+    }
+
+    /// Sets the modulation matrix offset added to the envelope amount.
+    pub fn set_amount_modulation(&mut self, amount_modulation: f32) {
+        self.amount_modulation = amount_modulation;
+    }
+
     /// Updates all envelope settings from the shared parameter block.
     pub fn set_parameters(&mut self, parameters: &EnvelopeParameters) {
+        self.set_delay_milliseconds(parameters.delay_ms.load());
         self.set_attack_milliseconds(parameters.attack_ms.load());
+        self.set_hold_milliseconds(parameters.hold_ms.load());
         self.set_decay_milliseconds(parameters.decay_ms.load());
         self.set_release_milliseconds(parameters.release_ms.load());
         self.set_sustain_level(parameters.sustain_level.load());
         self.set_sustain_pedal(parameters.sustain_pedal.load(Relaxed));
         self.set_amount(parameters.amount.load());
+        self.set_curves(
+            parameters.attack_curve.load(),
+            parameters.decay_curve.load(),
+            parameters.release_curve.load(),
+        );
         self.set_is_inverted(parameters.is_inverted.load(Relaxed));
+        self.set_loop(
+            parameters.loop_enabled.load(Relaxed),
+            EnvelopeSegment::from_index(parameters.loop_start.load(Relaxed)),
+            EnvelopeSegment::from_index(parameters.loop_end.load(Relaxed)),
+        );
     }
 
     /// Generates the next envelope output sample, advancing the stage state machine.
     pub fn generate(&mut self) -> f32 {
         let envelope_output_value = self.next_value();
-        envelope_output_value * self.amount
+        envelope_output_value * (self.amount + self.amount_modulation).clamp(0.0, 1.0)
     }
 
     /// Checks and consumes the gate flag to trigger note-on or note-off transitions.
@@ -223,6 +373,25 @@ impl Envelope {
         self.state_action(StageAction::Stop);
     }
 
+    fn set_delay_milliseconds(&mut self, milliseconds: u32) {
+        self.delay_samples = self.samples_from_milliseconds(milliseconds);
+    }
+
+    fn set_hold_milliseconds(&mut self, milliseconds: u32) {
+        self.hold_samples = self.samples_from_milliseconds(milliseconds);
+    }
+
+    fn set_loop(
+        &mut self,
+        loop_enabled: bool,
+        loop_start: EnvelopeSegment,
+        loop_end: EnvelopeSegment,
+    ) {
+        self.loop_enabled = loop_enabled;
+        self.loop_end = loop_end;
+        self.loop_start = loop_start.min(loop_end);
+    }
+
     fn set_attack_milliseconds(&mut self, milliseconds: u32) {
         // Envelope attack time in ms is ≤ 10_000, within f32 precision (2²³ = 8_388_608)
         #[allow(clippy::cast_precision_loss)]
@@ -300,7 +469,7 @@ impl Envelope {
 
         self.sustain_pedal = sustain_pedal;
 
-        if !sustain_pedal && self.stage == Stage::Sustain {
+        if !sustain_pedal && (self.stage == Stage::Sustain || self.gate_hold) {
             self.gate_off();
         }
     }
@@ -308,7 +477,7 @@ impl Envelope {
     fn set_release_milliseconds(&mut self, milliseconds: u32) {
         // Envelope release time in ms is ≤ 10_000, within f32 precision (2²³ = 8_388_608)
         #[allow(clippy::cast_precision_loss)]
-        let milliseconds_f32 = milliseconds as f32;
+        let milliseconds_f32 = milliseconds as f32 * self.release_time_scale;
         let clamped_milliseconds = milliseconds_f32.max(self.milliseconds_per_sample);
 
         self.release_level_increment = if self.is_inverted {
@@ -333,13 +502,55 @@ impl Envelope {
         self.amount = amount;
     }
 
+    fn set_curves(&mut self, attack_curve: f32, decay_curve: f32, release_curve: f32) {
+        let stage_curve = self.stage_curve();
+
+        self.attack_curve = attack_curve.clamp(-1.0, 1.0);
+        self.decay_curve = decay_curve.clamp(-1.0, 1.0);
+        self.release_curve = release_curve.clamp(-1.0, 1.0);
+
+        // Carry on from the current output so a curve change mid-segment doesn't jump
+        if self.stage_curve() != stage_curve {
+            self.start_segment_from_current_level();
+        }
+    }
+
+    fn stage_curve(&self) -> f32 {
+        match self.stage {
+            Stage::Attack => self.attack_curve,
+            Stage::Decay => self.decay_curve,
+            Stage::Release => self.release_curve,
+            _ => DEFAULT_ENVELOPE_CURVE,
+        }
+    }
+
     fn set_is_inverted(&mut self, is_inverted: bool) {
         if self.is_inverted != is_inverted {
             self.level = 1.0 - self.level;
+            self.curved_level = 1.0 - self.curved_level;
+            self.segment_start_level = 1.0 - self.segment_start_level;
         }
         self.is_inverted = is_inverted;
     }
 
+    /// Returns the envelope to the off stage at the minimum level, keeping its settings.
+    pub fn reset(&mut self) {
+        self.stage = Stage::Off;
+        self.level = ENVELOPE_MIN_LEVEL;
+        self.curved_level = ENVELOPE_MIN_LEVEL;
+        self.segment_start_level = ENVELOPE_MIN_LEVEL;
+        self.stage_sample_count = 0;
+        self.gate_hold = false;
+        self.amount_modulation = 0.0;
+        self.release_time_scale = DEFAULT_RELEASE_TIME_SCALE;
+    }
+
+    /// Returns whether the envelope is in any stage other than off.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.stage != Stage::Off
+    }
+
     /// Returns whether the envelope is currently inverted.
     #[must_use]
     pub fn get_is_inverted(&self) -> bool {
@@ -349,17 +560,23 @@ impl Envelope {
     fn state_action(&mut self, action: StageAction) {
         match (action, self.stage) {
             (StageAction::Start, _) => {
-                self.stage = Stage::Attack;
+                self.start_segment_from_current_level();
+                self.stage_sample_count = 0;
+                self.stage = if self.delay_samples > 0 {
+                    Stage::Delay
+                } else {
+                    Stage::Attack
+                };
             }
             (StageAction::Stop, Stage::Off | Stage::Release) => {}
             (StageAction::Stop, _) => {
+                self.start_segment_from_current_level();
                 self.stage = Stage::Release;
             }
-            (StageAction::NextStage, Stage::Attack) => {
-                self.stage = Stage::Decay;
-            }
-            (StageAction::NextStage, Stage::Decay) => {
-                self.stage = Stage::Sustain;
+            (StageAction::NextStage, Stage::Delay | Stage::Attack | Stage::Hold | Stage::Decay) => {
+                self.segment_start_level = self.level;
+                self.stage_sample_count = 0;
+                self.stage = self.stage_after_gated_stage(self.stage);
             }
             (StageAction::NextStage, Stage::Release) => {
                 self.level = if self.is_inverted {
@@ -367,6 +584,7 @@ impl Envelope {
                 } else {
                     ENVELOPE_MIN_LEVEL
                 };
+                self.curved_level = self.level;
                 self.stage = Stage::Off;
             }
             (StageAction::NextStage, _) => {
@@ -380,13 +598,71 @@ impl Envelope {
         }
     }
 
+    fn stage_after_gated_stage(&self, stage: Stage) -> Stage {
+        let next_stage = match stage {
+            Stage::Delay => Stage::Attack,
+            Stage::Attack if self.hold_samples > 0 => Stage::Hold,
+            Stage::Attack | Stage::Hold => Stage::Decay,
+            _ => Stage::Sustain,
+        };
+
+        // Comparing against the loop end rather than matching it means a skipped zero length
+        // hold segment still closes the loop.
+        if self.loop_enabled && next_stage > self.loop_end.stage() {
+            return self.loop_start.stage();
+        }
+
+        next_stage
+    }
+
+    fn start_segment_from_current_level(&mut self) {
+        self.level = self.curved_level;
+        self.segment_start_level = self.level;
+    }
+
     fn next_value(&mut self) -> f32 {
-        match self.stage {
+        let linear_level = match self.stage {
             Stage::Off => self.level,
+            Stage::Delay => self.timed_stage_next_value(self.delay_samples),
             Stage::Attack => self.attack_next_value(),
+            Stage::Hold => self.timed_stage_next_value(self.hold_samples),
             Stage::Decay => self.decay_next_value(),
             Stage::Sustain => self.sustain_next_value(),
             Stage::Release => self.release_next_value(),
+        };
+
+        self.curved_level = self.curved_level_from_linear_level(linear_level);
+        self.curved_level
+    }
+
+    // The segment advances linearly as before; the curve only reshapes the path between the
+    // level the segment started from and its target, so segment times don't change.
+    fn curved_level_from_linear_level(&self, linear_level: f32) -> f32 {
+        let curve = self.stage_curve();
+        if curve == 0.0 {
+            return linear_level;
+        }
+
+        let Some(target_level) = self.segment_target_level() else {
+            return linear_level;
+        };
+
+        let range = target_level - self.segment_start_level;
+        if range.abs() <= f32::EPSILON {
+            return linear_level;
+        }
+
+        let progress = ((linear_level - self.segment_start_level) / range).clamp(0.0, 1.0);
+        self.segment_start_level + range * curve_progress(progress, curve)
+    }
+
+    fn segment_target_level(&self) -> Option<f32> {
+        match (self.stage, self.is_inverted) {
+            (Stage::Attack, false) | (Stage::Release, true) => Some(ENVELOPE_MAX_LEVEL),
+            (Stage::Attack, true) | (Stage::Release, false) => Some(ENVELOPE_MIN_LEVEL),
+            (Stage::Decay, false) => Some(self.sustain_level),
+            (Stage::Decay, true) => Some(MAX_SUSTAIN_LEVEL),
+            _ => None,
         }
     }
 
@@ -429,6 +705,15 @@ impl Envelope {
         self.level
     }
 
+    fn timed_stage_next_value(&mut self, stage_samples: u32) -> f32 {
+        self.stage_sample_count += 1;
+        if self.stage_sample_count >= stage_samples {
+            self.state_action(StageAction::NextStage);
+        }
+
+        self.level
+    }
+
     fn sustain_next_value(&mut self) -> f32 {
         self.level
     }
@@ -452,6 +737,16 @@ impl Envelope {
         self.level
     }
 
+    fn samples_from_milliseconds(&self, milliseconds: u32) -> u32 {
+        // Envelope times in ms are ≤ 10_000, within f32 precision (2²³ = 8_388_608)
+        #[allow(clippy::cast_precision_loss)]
+        let milliseconds_f32 = milliseconds as f32;
+        // Bounded to 10_000 ms worth of samples at ≤ 192 kHz, non-negative; safe to cast to u32
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let samples = (milliseconds_f32 / self.milliseconds_per_sample).round() as u32;
+        samples
+    }
+
     fn level_increments_from_milliseconds(
         &self,
         current_level: f32,
@@ -468,6 +763,17 @@ impl Envelope {
     }
 }
 
+/// Bends linear segment progress: positive curves move quickly away from the start and ease into
+/// the target like an analog RC envelope, negative curves start slowly and rush the end.
+fn curve_progress(progress: f32, curve: f32) -> f32 {
+    let exponent = 1.0 + curve.abs() * (MAX_CURVE_EXPONENT - 1.0);
+    if curve > 0.0 {
+        1.0 - (1.0 - progress).powf(exponent)
+    } else {
+        progress.powf(exponent)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -584,6 +890,20 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn release_time_scale_multiplies_the_release_time() {
+        let sample_rate = 48000;
+        let mut envelope = Envelope::new(sample_rate);
+        let expected_increment = 0.000_694_4; // 30ms, as set directly
+
+        envelope.set_release_time_scale(0.5);
+        envelope.set_release_milliseconds(60);
+        assert!(f32s_are_equal(
+            envelope.release_level_increment,
+            expected_increment
+        ));
+    }
+
     #[test]
     fn start_correctly_initiates_attack_stage_from_all_stages() {
         let mut envelope = Envelope::new(44100);
@@ -947,4 +1267,236 @@ mod tests {
 
         assert_eq!(live.attack_ms.load(), in_range_attack_ms);
     }
+
+    #[test]
+    fn gate_on_enters_delay_stage_and_holds_level_for_the_delay_time() {
+        let mut envelope = Envelope::new(48000);
+        envelope.set_delay_milliseconds(1);
+        envelope.gate_on();
+        assert_eq!(envelope.stage, Stage::Delay);
+
+        for _ in 0..47 {
+            assert!(f32s_are_equal(envelope.generate(), ENVELOPE_MIN_LEVEL));
+        }
+        assert_eq!(envelope.stage, Stage::Delay);
+
+        envelope.generate();
+        assert_eq!(envelope.stage, Stage::Attack);
+    }
+
+    #[test]
+    fn attack_transitions_to_hold_and_holds_the_maximum_level() {
+        let mut envelope = Envelope::new(48000);
+        envelope.set_attack_milliseconds(0);
+        envelope.set_hold_milliseconds(1);
+        envelope.gate_on();
+
+        envelope.generate();
+        assert_eq!(envelope.stage, Stage::Hold);
+
+        while envelope.stage == Stage::Hold {
+            assert!(f32s_are_equal(envelope.generate(), ENVELOPE_MAX_LEVEL));
+        }
+        assert_eq!(envelope.stage, Stage::Decay);
+    }
+
+    #[test]
+    fn reset_returns_a_sounding_envelope_to_off() {
+        let mut envelope = Envelope::new(48000);
+        envelope.set_attack_milliseconds(0);
+        envelope.gate_on();
+        envelope.generate();
+        envelope.set_release_time_scale(2.0);
+
+        envelope.reset();
+
+        assert!(!envelope.is_active());
+        assert!(f32s_are_equal(envelope.generate(), ENVELOPE_MIN_LEVEL));
+        assert!(f32s_are_equal(
+            envelope.release_time_scale,
+            DEFAULT_RELEASE_TIME_SCALE
+        ));
+    }
+
+    #[test]
+    fn amount_modulation_scales_the_output_within_the_unit_range() {
+        let mut envelope = Envelope::new(48000);
+        envelope.set_amount(0.5);
+        envelope.set_attack_milliseconds(0);
+        envelope.set_hold_milliseconds(1);
+        envelope.gate_on();
+        envelope.generate();
+
+        envelope.set_amount_modulation(0.25);
+        assert!(f32s_are_equal(envelope.generate(), 0.75));
+
+        envelope.set_amount_modulation(1.0);
+        assert!(f32s_are_equal(envelope.generate(), ENVELOPE_MAX_LEVEL));
+
+        envelope.set_amount_modulation(-1.0);
+        assert!(f32s_are_equal(envelope.generate(), 0.0));
+    }
+
+    #[test]
+    fn looping_envelope_returns_to_loop_start_instead_of_sustaining() {
+        let mut envelope = Envelope::new(48000);
+        envelope.set_attack_milliseconds(1);
+        envelope.set_decay_milliseconds(1);
+        envelope.set_sustain_level(0.5);
+        envelope.set_loop(true, EnvelopeSegment::Attack, EnvelopeSegment::Decay);
+        envelope.gate_on();
+
+        for _ in 0..1000 {
+            envelope.generate();
+            assert_ne!(envelope.stage, Stage::Sustain);
+        }
+
+        envelope.gate_off();
+        assert_eq!(envelope.stage, Stage::Release);
+    }
+
+    #[test]
+    fn loop_end_on_a_skipped_hold_segment_still_loops() {
+        let mut envelope = Envelope::new(48000);
+        envelope.set_attack_milliseconds(0);
+        envelope.set_loop(true, EnvelopeSegment::Attack, EnvelopeSegment::Hold);
+        envelope.gate_on();
+
+        envelope.generate();
+        assert_eq!(envelope.stage, Stage::Attack);
+    }
+
+    #[test]
+    fn set_loop_clamps_loop_start_to_loop_end() {
+        let mut envelope = Envelope::new(48000);
+        envelope.set_loop(true, EnvelopeSegment::Decay, EnvelopeSegment::Attack);
+
+        assert_eq!(envelope.loop_start, EnvelopeSegment::Attack);
+        assert_eq!(envelope.loop_end, EnvelopeSegment::Attack);
+    }
+
+    #[test]
+    fn adsr_only_parameters_load_with_no_delay_hold_or_loop() {
+        let adsr_json =
+            r#"{"attack_ms": 10, "decay_ms": 20, "release_ms": 30, "sustain_level": 0.5}"#;
+
+        let parameters: EnvelopeParameters = serde_json::from_str(adsr_json).unwrap();
+
+        assert_eq!(parameters.delay_ms.load(), 0);
+        assert_eq!(parameters.hold_ms.load(), 0);
+        assert!(!parameters.loop_enabled.load(Relaxed));
+    }
+
+    #[test]
+    fn assign_from_clamps_out_of_range_delay_and_hold_ms() {
+        let live = EnvelopeParameters::default();
+        let preset = EnvelopeParameters::default();
+        preset.delay_ms.store(u32::MAX);
+        preset.hold_ms.store(u32::MAX);
+
+        live.assign_from(&preset);
+
+        assert_eq!(live.delay_ms.load(), MAX_DELAY_MILLISECONDS);
+        assert_eq!(live.hold_ms.load(), MAX_HOLD_MILLISECONDS);
+    }
+
+    #[test]
+    fn exponential_curve_decays_faster_than_linear_at_the_start() {
+        let mut linear = Envelope::new(48000);
+        linear.set_decay_milliseconds(100);
+        linear.set_sustain_level(0.0);
+        let mut exponential = linear;
+        exponential.set_curves(DEFAULT_ENVELOPE_CURVE, 1.0, DEFAULT_ENVELOPE_CURVE);
+
+        for envelope in [&mut linear, &mut exponential] {
+            envelope.level = ENVELOPE_MAX_LEVEL;
+            envelope.curved_level = ENVELOPE_MAX_LEVEL;
+            envelope.segment_start_level = ENVELOPE_MAX_LEVEL;
+            envelope.stage = Stage::Decay;
+        }
+
+        for _ in 0..100 {
+            linear.generate();
+            exponential.generate();
+        }
+        assert!(exponential.generate() < linear.generate());
+    }
+
+    #[test]
+    fn logarithmic_curve_attacks_slower_than_linear_at_the_start() {
+        let mut linear = Envelope::new(48000);
+        linear.set_attack_milliseconds(100);
+        let mut logarithmic = linear;
+        logarithmic.set_curves(-1.0, DEFAULT_ENVELOPE_CURVE, DEFAULT_ENVELOPE_CURVE);
+        linear.gate_on();
+        logarithmic.gate_on();
+
+        for _ in 0..100 {
+            linear.generate();
+            logarithmic.generate();
+        }
+        assert!(logarithmic.generate() < linear.generate());
+    }
+
+    #[test]
+    fn curved_segments_still_reach_their_targets() {
+        let mut envelope = Envelope::new(48000);
+        envelope.set_attack_milliseconds(10);
+        envelope.set_decay_milliseconds(10);
+        envelope.set_sustain_level(0.5);
+        envelope.set_curves(0.7, -0.7, 0.7);
+        envelope.gate_on();
+
+        while envelope.stage != Stage::Sustain {
+            envelope.generate();
+        }
+        assert!(f32s_are_equal(envelope.generate(), 0.5));
+    }
+
+    #[test]
+    fn release_from_a_curved_attack_starts_at_the_current_output() {
+        let mut envelope = Envelope::new(48000);
+        envelope.set_attack_milliseconds(100);
+        envelope.set_release_milliseconds(100);
+        envelope.set_curves(1.0, 1.0, 1.0);
+        envelope.gate_on();
+
+        let mut last_value = 0.0;
+        for _ in 0..1000 {
+            last_value = envelope.generate();
+        }
+        envelope.gate_off();
+        let first_release_value = envelope.generate();
+
+        assert!(first_release_value < last_value);
+        assert!(last_value - first_release_value < 0.01);
+    }
+
+    #[test]
+    fn each_segment_follows_its_own_curve() {
+        let mut linear = Envelope::new(48000);
+        linear.set_attack_milliseconds(100);
+        linear.set_release_milliseconds(100);
+        let mut release_curved = linear;
+        release_curved.set_curves(DEFAULT_ENVELOPE_CURVE, DEFAULT_ENVELOPE_CURVE, 1.0);
+        linear.gate_on();
+        release_curved.gate_on();
+
+        for _ in 0..100 {
+            assert!(f32s_are_equal(linear.generate(), release_curved.generate()));
+        }
+
+        while linear.stage != Stage::Sustain {
+            linear.generate();
+            release_curved.generate();
+        }
+        linear.gate_off();
+        release_curved.gate_off();
+
+        for _ in 0..100 {
+            linear.generate();
+            release_curved.generate();
+        }
+        assert!(release_curved.generate() < linear.generate());
+    }
 }
@@ -1,11 +1,16 @@
 // Derived from https://www.musicdsp.org/en/latest/Filters/253-perfect-lp4-filter.html
+// Non-lowpass responses mix the ladder stage outputs, as in the Oberheim Xpander.
 
+use crate::modules::mod_matrix::FilterModulation;
 use crate::modules::oscillator::constants::DEFAULT_NOTE_FREQUENCY;
+use crate::modules::tuning::{TuningParameters, VoiceTuning};
 use accsyn_core::defaults::Defaults;
 use accsyn_core::parameter_types::{FilterPoles, Hertz, NormalizedValue};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::AtomicU8;
 use std::sync::atomic::Ordering::Relaxed;
+use strum::EnumCount as StrumEnumCount;
+use strum_macros::{EnumCount, EnumIter, FromRepr};
 
 /// Number of filter poles in the ladder topology.
 pub const NUMBER_OF_FILER_POLES: f32 = 4.0;
@@ -13,14 +18,144 @@ const DEFAULT_RESONANCE: f32 = 0.0;
 const NATURAL_LOG_OF_4: f32 = 1.386_294_3;
 const DENORMAL_GUARD: f32 = 1e-25_f32;
 const MIDI_CENTER_NOTE_NUMBER: u8 = 64;
-const NOTES_PER_OCTAVE: u8 = 12;
 /// Default key tracking amount (0.5 = center, no tracking offset).
 pub const DEFAULT_KEY_TRACKING_AMOUNT: f32 = 0.5;
 /// Default frequency offset multiplier from key tracking.
 pub const DEFAULT_KEY_TRACKING_FREQUENCY_OFFSET: f32 = 1.0;
 const DEFAULT_FILTER_POLES: u8 = 4;
+const MIN_FILTER_POLES: u8 = 1;
+const MAX_FILTER_POLES: u8 = 4;
+// COUNT is capped by the number of possible filter types which will always be less than u8::MAX
+#[allow(clippy::cast_possible_truncation)]
+const LAST_FILTER_TYPE_STEP: u8 = (FilterType::COUNT - 1) as u8;
 const DEFAULT_FILTER_FREQUENCY: f32 = 16_800.0;
+const DEFAULT_FILTER_CROSSFADE: f32 = 0.5;
 const MAX_FILTER_PERCENT_OF_NYQUIST: f32 = 0.35;
+const MAX_CUTOFF_MODULATION_OCTAVES: f32 = 5.0;
+const MAX_DRIVE_GAIN: f32 = 4.0;
+// Share of the resonance feedback gain restored to the passband at full drive
+const DRIVE_PASSBAND_COMPENSATION: f32 = 0.5;
+/// Index of the last filter type variant.
+// COUNT is capped by the number of possible filter types which will always be less than u32::MAX
+#[allow(clippy::cast_possible_truncation)]
+pub const LAST_FILTER_TYPE_INDEX: u32 = (FilterType::COUNT - 1) as u32;
+/// Index of the last filter routing variant.
+// COUNT is capped by the number of possible filter routings which will always be less than u32::MAX
+#[allow(clippy::cast_possible_truncation)]
+pub const LAST_FILTER_ROUTING_INDEX: u32 = (FilterRouting::COUNT - 1) as u32;
+const LADDER_TAP_COUNT: usize = 5;
+
+// Weights for the ladder input and the four stage outputs, indexed by pole count - 1.
+const LOWPASS_TAP_WEIGHTS: [[f32; LADDER_TAP_COUNT]; 4] = [
+    [0.0, 1.0, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 1.0],
+];
+const HIGHPASS_TAP_WEIGHTS: [[f32; LADDER_TAP_COUNT]; 4] = [
+    [1.0, -1.0, 0.0, 0.0, 0.0],
+    [1.0, -2.0, 1.0, 0.0, 0.0],
+    [1.0, -3.0, 3.0, -1.0, 0.0],
+    [1.0, -4.0, 6.0, -4.0, 1.0],
+];
+// Bandpass, notch and peak responses are 2 pole below 3 poles and 4 pole from 3 poles up.
+const BANDPASS_TAP_WEIGHTS: [[f32; LADDER_TAP_COUNT]; 2] =
+    [[0.0, 2.0, -2.0, 0.0, 0.0], [0.0, 0.0, 4.0, -8.0, 4.0]];
+const NOTCH_TAP_WEIGHTS: [[f32; LADDER_TAP_COUNT]; 2] =
+    [[1.0, -2.0, 2.0, 0.0, 0.0], [1.0, 0.0, -4.0, 8.0, -4.0]];
+const PEAK_TAP_WEIGHTS: [[f32; LADDER_TAP_COUNT]; 2] =
+    [[1.0, 2.0, -2.0, 0.0, 0.0], [1.0, 0.0, 4.0, -8.0, 4.0]];
+
+/// Filter response selecting how the ladder stage outputs are mixed.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
+#[repr(u8)]
+pub enum FilterType {
+    /// Lowpass with a slope of 6 dB/oct per pole.
+    #[default]
+    LowPass,
+    /// Highpass with a slope of 6 dB/oct per pole.
+    HighPass,
+    /// Bandpass centered on the cutoff frequency.
+    BandPass,
+    /// Band reject notch centered on the cutoff frequency.
+    Notch,
+    /// Peak boosting the band around the cutoff frequency while passing the rest.
+    Peak,
+}
+
+impl FilterType {
+    /// Converts a numeric index to the corresponding filter type, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
+
+    fn tap_weights(self, poles: u8) -> &'static [f32; LADDER_TAP_COUNT] {
+        let pole_index = usize::from(poles.clamp(1, 4) - 1);
+        let pair_index = pole_index / 2;
+
+        match self {
+            FilterType::LowPass => &LOWPASS_TAP_WEIGHTS[pole_index],
+            FilterType::HighPass => &HIGHPASS_TAP_WEIGHTS[pole_index],
+            FilterType::BandPass => &BANDPASS_TAP_WEIGHTS[pair_index],
+            FilterType::Notch => &NOTCH_TAP_WEIGHTS[pair_index],
+            FilterType::Peak => &PEAK_TAP_WEIGHTS[pair_index],
+        }
+    }
+}
+
+/// How the two filters of a voice are connected.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
+#[repr(u8)]
+pub enum FilterRouting {
+    /// Only filter 1 is in the signal path.
+    #[default]
+    Single,
+    /// Filter 1 feeds filter 2.
+    Serial,
+    /// Both filters process the same input and their outputs are crossfaded.
+    Parallel,
+    /// Filter 1 processes the left channel and filter 2 processes the right channel.
+    Split,
+}
+
+impl FilterRouting {
+    /// Converts a numeric index to the corresponding filter routing, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
+}
+
+/// Shared atomic parameters for connecting the two filters.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterRoutingParameters {
+    /// Index of the selected `FilterRouting`.
+    pub routing: AtomicU8,
+    /// Parallel routing mix from filter 1 (0.0) to filter 2 (1.0).
+    pub crossfade: NormalizedValue,
+}
+
+impl FilterRoutingParameters {
+    /// Replace all the values in this `FilterRoutingParameters` with the values from the provided `FilterRoutingParameters`.
+    pub fn assign_from(&self, parameters: &FilterRoutingParameters) {
+        self.routing.store(
+            FilterRouting::from_index(parameters.routing.load(Relaxed)) as u8,
+            Relaxed,
+        );
+        self.crossfade.store(parameters.crossfade.load());
+    }
+}
+
+impl Default for FilterRoutingParameters {
+    fn default() -> Self {
+        Self {
+            routing: AtomicU8::new(FilterRouting::default() as u8),
+            crossfade: NormalizedValue::new(DEFAULT_FILTER_CROSSFADE),
+        }
+    }
+}
 
 /// Shared atomic parameters for controlling the filter from the UI thread.
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,6 +171,10 @@ pub struct FilterParameters {
     pub key_tracking_amount: NormalizedValue,
     /// Current MIDI note number used for key tracking calculation.
     pub current_note_number: AtomicU8,
+    /// Index of the selected `FilterType` response.
+    pub filter_type: AtomicU8,
+    /// Drive into the saturating ladder stages (0.0 keeps the ladder linear).
+    pub drive: NormalizedValue,
 }
 
 impl FilterParameters {
@@ -47,6 +186,11 @@ impl FilterParameters {
         self.filter_poles.store(parameters.filter_poles.load());
         self.key_tracking_amount
             .store(parameters.key_tracking_amount.load());
+        self.filter_type.store(
+            FilterType::from_index(parameters.filter_type.load(Relaxed)) as u8,
+            Relaxed,
+        );
+        self.drive.store(parameters.drive.load());
     }
 }
 
@@ -58,6 +202,8 @@ impl Default for FilterParameters {
             filter_poles: FilterPoles::new(DEFAULT_FILTER_POLES),
             key_tracking_amount: NormalizedValue::new(DEFAULT_KEY_TRACKING_AMOUNT),
             current_note_number: AtomicU8::new(0),
+            filter_type: AtomicU8::new(FilterType::default() as u8),
+            drive: NormalizedValue::default(),
         }
     }
 }
@@ -70,6 +216,10 @@ struct Coefficients {
     resonance_factor: f32,
     adjusted_resonance_factor: f32,
     feedback_gain: f32,
+    drive: f32,
+    drive_gain: f32,
+    passband_compensation: f32,
+    output_gain: f32,
 }
 
 #[derive(Default, Copy, Clone, Debug, PartialEq)]
@@ -84,18 +234,35 @@ struct LadderState {
     stage3_unit_delay: f32,
 }
 
-/// Resonant lowpass ladder filter with configurable pole count and key tracking.
-#[derive(Default, Debug)]
+/// The filter settings from the shared parameters, before the modulation matrix offsets.
+#[derive(Default, Debug, Clone, Copy)]
+struct FilterSettings {
+    cutoff_frequency: f32,
+    resonance: f32,
+    drive: f32,
+    poles: u8,
+    filter_type: FilterType,
+    key_tracking_amount: f32,
+    // Tuned distance of the key tracked note from the centre note
+    octaves_from_center_note: f32,
+}
+
+/// Resonant ladder filter with selectable response, configurable pole count and key tracking.
+#[derive(Default, Debug, Clone)]
 pub struct Filter {
     sample_rate: u32,
+    settings: FilterSettings,
+    filter_type: FilterType,
     max_frequency: f32,
     cutoff_frequency: f32,
     base_cutoff_frequency: f32,
     resonance: f32,
+    drive: f32,
     poles: u8,
     key_tracking_amount: f32,
     key_tracking_frequency_offset: f32,
     current_note_frequency: f32,
+    microtuning: VoiceTuning,
     cutoff_modulation_amount: f32,
     cutoff_envelope_amount: f32,
     coefficients: Coefficients,
@@ -141,6 +308,10 @@ impl Filter {
                 resonance_factor,
                 adjusted_resonance_factor,
                 feedback_gain,
+                drive: 0.0,
+                drive_gain: 1.0,
+                passband_compensation: 1.0,
+                output_gain: 1.0,
             },
             ..Default::default()
         }
@@ -148,10 +319,74 @@ impl Filter {
 
     /// Updates all filter settings from the shared parameter block and recalculates coefficients.
     pub fn set_parameters(&mut self, filter_parameters: &FilterParameters) {
-        self.store_filter_parameters(filter_parameters);
+        let current_note_number = filter_parameters.current_note_number.load(Relaxed);
+        self.set_parameters_with_note_number(filter_parameters, current_note_number);
+    }
+
+    /// Updates all filter settings, key tracking the given note instead of the shared current note.
+    pub fn set_parameters_with_note_number(
+        &mut self,
+        filter_parameters: &FilterParameters,
+        current_note_number: u8,
+    ) {
+        self.set_modulated_parameters(
+            filter_parameters,
+            current_note_number,
+            &FilterModulation::default(),
+        );
+    }
+
+    /// Updates all filter settings for the given note, then applies the modulation matrix offsets.
+    pub fn set_modulated_parameters(
+        &mut self,
+        filter_parameters: &FilterParameters,
+        current_note_number: u8,
+        modulation: &FilterModulation,
+    ) {
+        self.store_filter_parameters(filter_parameters, current_note_number);
+        self.apply_modulation(modulation);
+    }
+
+    /// Applies the modulation matrix offsets to the settings stored by the last parameter update.
+    ///
+    /// A cutoff offset of 1.0 raises the cutoff by `MAX_CUTOFF_MODULATION_OCTAVES`, resonance, drive and
+    /// key tracking offsets span their full ranges, and pole and type offsets of 1.0 step across every
+    /// pole count and filter type.
+    fn apply_modulation(&mut self, modulation: &FilterModulation) {
+        let settings = self.settings;
+
+        self.base_cutoff_frequency = settings.cutoff_frequency
+            * 2.0_f32.powf(modulation.cutoff * MAX_CUTOFF_MODULATION_OCTAVES);
+        self.resonance =
+            (settings.resonance + modulation.resonance * Defaults::MAX_FILTER_RESONANCE).clamp(
+                Defaults::MIN_FILTER_RESONANCE,
+                Defaults::MAX_FILTER_RESONANCE,
+            );
+        self.drive = (settings.drive + modulation.drive).clamp(0.0, 1.0);
+        self.poles = offset_step(
+            settings.poles.clamp(MIN_FILTER_POLES, MAX_FILTER_POLES) - MIN_FILTER_POLES,
+            modulation.poles,
+            MAX_FILTER_POLES - MIN_FILTER_POLES,
+        ) + MIN_FILTER_POLES;
+        self.filter_type = FilterType::from_index(offset_step(
+            settings.filter_type as u8,
+            modulation.filter_type,
+            LAST_FILTER_TYPE_STEP,
+        ));
+        self.key_tracking_amount =
+            (settings.key_tracking_amount + modulation.key_tracking).clamp(0.0, 1.0);
+        self.key_tracking_frequency_offset =
+            self.tracking_offset_from_octaves(settings.octaves_from_center_note);
+
         self.calculate_coefficients();
     }
 
+    /// Clears the ladder stages so no signal from a previous note rings on, keeping the filter settings.
+    pub fn reset(&mut self) {
+        self.left_ladder_state = LadderState::default();
+        self.right_ladder_state = LadderState::default();
+    }
+
     /// Processes a stereo sample pair through the ladder filter with envelope and modulation.
     pub fn process(
         &mut self,
@@ -168,12 +403,14 @@ impl Filter {
             left_sample,
             &mut self.left_ladder_state,
             &self.coefficients,
+            self.filter_type,
             self.poles,
         );
         let right_output = apply_ladder_filter(
             right_sample,
             &mut self.right_ladder_state,
             &self.coefficients,
+            self.filter_type,
             self.poles,
         );
 
@@ -223,48 +460,235 @@ impl Filter {
             self.coefficients.resonance_factor,
             self.coefficients.adjusted_resonance_factor,
         );
+        self.coefficients.drive = self.drive;
+        self.coefficients.drive_gain = calculate_drive_gain(self.drive);
+        self.coefficients.passband_compensation =
+            calculate_passband_compensation(self.drive, self.coefficients.feedback_gain);
+        self.coefficients.output_gain = calculate_drive_output_gain(self.coefficients.drive_gain);
     }
 
-    fn store_filter_parameters(&mut self, parameters: &FilterParameters) {
-        self.base_cutoff_frequency = parameters.cutoff_frequency.load();
-        self.resonance = parameters.resonance.load();
-        self.poles = parameters.filter_poles.load();
-        self.key_tracking_amount = parameters.key_tracking_amount.load();
-        let current_note_number = parameters.current_note_number.load(Relaxed);
-        self.current_note_frequency = Defaults::midi_note_frequency(current_note_number);
-        self.key_tracking_frequency_offset = get_tracking_offset_from_midi_note_number(
-            current_note_number,
-            self.key_tracking_amount,
-        );
+    fn store_filter_parameters(&mut self, parameters: &FilterParameters, current_note_number: u8) {
+        self.current_note_frequency = self.microtuning.frequency(current_note_number);
+        // Tracks the tuned interval between the note and the centre note, so the cutoff follows the selected scale
+        let octaves_from_center_note = (self.current_note_frequency
+            / self.microtuning.frequency(MIDI_CENTER_NOTE_NUMBER))
+        .log2();
+
+        self.settings = FilterSettings {
+            cutoff_frequency: parameters.cutoff_frequency.load(),
+            resonance: parameters.resonance.load(),
+            drive: parameters.drive.load(),
+            poles: parameters.filter_poles.load(),
+            filter_type: FilterType::from_index(parameters.filter_type.load(Relaxed)),
+            key_tracking_amount: parameters.key_tracking_amount.load(),
+            octaves_from_center_note,
+        };
+    }
+
+    fn tracking_offset_from_octaves(&self, octaves_from_center_note: f32) -> f32 {
+        let key_tracking_bipolar = (self.key_tracking_amount - DEFAULT_KEY_TRACKING_AMOUNT) * 2.0;
+        2.0_f32.powf(key_tracking_bipolar * octaves_from_center_note)
+    }
+
+    /// Picks up the selected scale and keyboard mapping and the global master tuning for key tracking.
+    pub(crate) fn set_microtuning(&mut self, parameters: &TuningParameters) {
+        self.microtuning.update(parameters);
+    }
+}
+
+/// The two filters of a voice, connected according to the filter routing.
+#[derive(Debug)]
+pub struct DualFilter {
+    first_filter: Filter,
+    second_filter: Filter,
+    crossfade_modulation: f32,
+}
+
+impl DualFilter {
+    /// Creates both filters initialized with the maximum cutoff for the given sample rate.
+    pub(crate) fn new(sample_rate: u32) -> Self {
+        Self {
+            first_filter: Filter::new(sample_rate),
+            second_filter: Filter::new(sample_rate),
+            crossfade_modulation: 0.0,
+        }
+    }
+
+    /// Updates both filters for the given note, before any modulation matrix offsets are applied.
+    pub fn set_parameters(
+        &mut self,
+        first_filter_parameters: &FilterParameters,
+        second_filter_parameters: &FilterParameters,
+        current_note_number: u8,
+    ) {
+        self.first_filter
+            .store_filter_parameters(first_filter_parameters, current_note_number);
+        self.second_filter
+            .store_filter_parameters(second_filter_parameters, current_note_number);
+    }
+
+    /// Applies each filter's modulation matrix offsets and the parallel crossfade offset.
+    pub fn apply_modulation(
+        &mut self,
+        first_modulation: &FilterModulation,
+        second_modulation: &FilterModulation,
+        crossfade_modulation: f32,
+    ) {
+        self.first_filter.apply_modulation(first_modulation);
+        self.second_filter.apply_modulation(second_modulation);
+        self.crossfade_modulation = crossfade_modulation;
+    }
+
+    /// Picks up the selected tuning for both filters' key tracking.
+    pub(crate) fn set_microtuning(&mut self, parameters: &TuningParameters) {
+        self.first_filter.set_microtuning(parameters);
+        self.second_filter.set_microtuning(parameters);
+    }
+
+    /// Clears both filters' ladder stages and the crossfade offset, keeping the filter settings.
+    pub fn reset(&mut self) {
+        self.first_filter.reset();
+        self.second_filter.reset();
+        self.crossfade_modulation = 0.0;
+    }
+
+    /// Processes a stereo sample pair through the filters selected by the routing parameters.
+    ///
+    /// Both filters follow the same envelope and LFO modulation. Filter 2 is skipped entirely in single routing.
+    pub fn process(
+        &mut self,
+        routing_parameters: &FilterRoutingParameters,
+        left_sample: f32,
+        right_sample: f32,
+        envelope_value: f32,
+        modulation_amount: f32,
+    ) -> (f32, f32) {
+        match FilterRouting::from_index(routing_parameters.routing.load(Relaxed)) {
+            FilterRouting::Single => self.first_filter.process(
+                left_sample,
+                right_sample,
+                envelope_value,
+                modulation_amount,
+            ),
+            FilterRouting::Serial => {
+                let (first_left, first_right) = self.first_filter.process(
+                    left_sample,
+                    right_sample,
+                    envelope_value,
+                    modulation_amount,
+                );
+                self.second_filter.process(
+                    first_left,
+                    first_right,
+                    envelope_value,
+                    modulation_amount,
+                )
+            }
+            FilterRouting::Parallel => {
+                let crossfade = (routing_parameters.crossfade.load() + self.crossfade_modulation)
+                    .clamp(0.0, 1.0);
+                let (first_left, first_right) = self.first_filter.process(
+                    left_sample,
+                    right_sample,
+                    envelope_value,
+                    modulation_amount,
+                );
+                let (second_left, second_right) = self.second_filter.process(
+                    left_sample,
+                    right_sample,
+                    envelope_value,
+                    modulation_amount,
+                );
+                (
+                    first_left + (second_left - first_left) * crossfade,
+                    first_right + (second_right - first_right) * crossfade,
+                )
+            }
+            FilterRouting::Split => {
+                let (first_left, _) = self.first_filter.process(
+                    left_sample,
+                    right_sample,
+                    envelope_value,
+                    modulation_amount,
+                );
+                let (_, second_right) = self.second_filter.process(
+                    left_sample,
+                    right_sample,
+                    envelope_value,
+                    modulation_amount,
+                );
+                (first_left, second_right)
+            }
+        }
     }
 }
 
+// Steps a discrete setting by the offset, where an offset of 1.0 spans every step from the first to the last
+fn offset_step(step: u8, offset: f32, last_step: u8) -> u8 {
+    let last_step_f32 = f32::from(last_step);
+    // Clamped to 0..=last_step, which came from a u8
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let offset_step =
+        (f32::from(step) + (offset * last_step_f32).round()).clamp(0.0, last_step_f32) as u8;
+    offset_step
+}
+
 fn apply_ladder_filter(
     sample: f32,
     ladder_state: &mut LadderState,
     coefficients: &Coefficients,
+    filter_type: FilterType,
     poles: u8,
 ) -> f32 {
-    let input = sample - coefficients.feedback_gain * ladder_state.stage4_output;
-
-    ladder_state.stage1_output = calculate_ladder_stage1(input, ladder_state, coefficients);
-    ladder_state.stage2_output = calculate_ladder_stage2(ladder_state, coefficients);
-    ladder_state.stage3_output = calculate_ladder_stage3(ladder_state, coefficients);
-    ladder_state.stage4_output = calculate_ladder_stage4(ladder_state, coefficients);
-
-    ladder_state.stage4_output = calculate_non_linear_saturation(ladder_state.stage4_output);
+    let driven_sample = sample * coefficients.drive_gain * coefficients.passband_compensation;
+    let input = saturate(
+        driven_sample - coefficients.feedback_gain * ladder_state.stage4_output,
+        coefficients.drive,
+    );
+
+    ladder_state.stage1_output = saturate(
+        calculate_ladder_stage1(input, ladder_state, coefficients),
+        coefficients.drive,
+    );
+    ladder_state.stage2_output = saturate(
+        calculate_ladder_stage2(ladder_state, coefficients),
+        coefficients.drive,
+    );
+    ladder_state.stage3_output = saturate(
+        calculate_ladder_stage3(ladder_state, coefficients),
+        coefficients.drive,
+    );
+    ladder_state.stage4_output = saturate(
+        calculate_ladder_stage4(ladder_state, coefficients),
+        coefficients.drive,
+    );
+
+    // The stage 4 saturation is part of the lowpass voicing. The mixed responses need linear
+    // stages so their tap sums cancel outside the passband, which only drive deliberately breaks.
+    if filter_type == FilterType::LowPass {
+        ladder_state.stage4_output = calculate_non_linear_saturation(ladder_state.stage4_output);
+    }
 
     ladder_state.input_unit_delay = input;
     ladder_state.stage1_unit_delay = ladder_state.stage1_output + DENORMAL_GUARD;
     ladder_state.stage2_unit_delay = ladder_state.stage2_output + DENORMAL_GUARD;
     ladder_state.stage3_unit_delay = ladder_state.stage3_output + DENORMAL_GUARD;
 
-    match poles {
-        1 => ladder_state.stage1_output,
-        2 => ladder_state.stage2_output,
-        3 => ladder_state.stage3_output,
-        _ => ladder_state.stage4_output,
-    }
+    let taps = [
+        input,
+        ladder_state.stage1_output,
+        ladder_state.stage2_output,
+        ladder_state.stage3_output,
+        ladder_state.stage4_output,
+    ];
+
+    let output: f32 = taps
+        .iter()
+        .zip(filter_type.tap_weights(poles))
+        .map(|(tap, weight)| tap * weight)
+        .sum();
+
+    output * coefficients.output_gain
 }
 
 fn calculate_ladder_stage4(ladder_state: &LadderState, coefficients: &Coefficients) -> f32 {
@@ -295,13 +719,27 @@ fn calculate_ladder_stage1(
         - coefficients.pole_coefficient * ladder_state.stage1_output
 }
 
-fn get_tracking_offset_from_midi_note_number(midi_note: u8, key_tracking_amount: f32) -> f32 {
-    let delta_from_tracking_reference_note =
-        f32::from(midi_note) - f32::from(MIDI_CENTER_NOTE_NUMBER);
-    let key_tracking_bipolar = (key_tracking_amount - DEFAULT_KEY_TRACKING_AMOUNT) * 2.0;
-    2.0_f32.powf(
-        key_tracking_bipolar * delta_from_tracking_reference_note / f32::from(NOTES_PER_OCTAVE),
-    )
+// Blends from linear at no drive to a full tanh curve, so a drive of 0.0 leaves the ladder untouched.
+fn saturate(value: f32, drive: f32) -> f32 {
+    if drive <= 0.0 {
+        return value;
+    }
+
+    value + (value.tanh() - value) * drive
+}
+
+fn calculate_drive_gain(drive: f32) -> f32 {
+    1.0 + (MAX_DRIVE_GAIN - 1.0) * drive
+}
+
+// Splits the drive gain between louder quiet signals and quieter saturated loud signals
+fn calculate_drive_output_gain(drive_gain: f32) -> f32 {
+    1.0 / drive_gain.sqrt()
+}
+
+// The ladder passband drops by 1 / (1 + feedback gain) as resonance rises
+fn calculate_passband_compensation(drive: f32, feedback_gain: f32) -> f32 {
+    1.0 + feedback_gain * DRIVE_PASSBAND_COMPENSATION * drive
 }
 
 fn calculate_non_linear_saturation(stage4_output: f32) -> f32 {
@@ -344,6 +782,20 @@ mod tests {
     use super::*;
     use accsyn_core::math::f32s_are_equal;
 
+    #[test]
+    fn reset_clears_the_ladder_stages() {
+        let mut filter = Filter::new(48000);
+        for _ in 0..64 {
+            filter.process(1.0, -1.0, 0.0, 0.0);
+        }
+        assert_ne!(filter.left_ladder_state, LadderState::default());
+
+        filter.reset();
+
+        assert_eq!(filter.left_ladder_state, LadderState::default());
+        assert_eq!(filter.right_ladder_state, LadderState::default());
+    }
+
     #[test]
     fn new_returns_filter_with_correct_default_values() {
         let sample_rate = 48000;
@@ -627,6 +1079,458 @@ mod tests {
         );
     }
 
+    #[test]
+    fn set_modulated_parameters_offsets_cutoff_by_octaves_and_clamps_resonance() {
+        let mut filter = Filter::new(48000);
+        let parameters = FilterParameters::default();
+        parameters.cutoff_frequency.store(1000.0);
+        parameters.resonance.store(0.5);
+        let modulation = FilterModulation {
+            cutoff: 0.2,
+            resonance: 1.0,
+            ..FilterModulation::default()
+        };
+
+        filter.set_modulated_parameters(&parameters, MIDI_CENTER_NOTE_NUMBER, &modulation);
+
+        // 0.2 of the five octave range is one octave above the set cutoff.
+        assert!(f32s_are_equal(filter.base_cutoff_frequency, 2000.0));
+        assert!(f32s_are_equal(
+            filter.resonance,
+            Defaults::MAX_FILTER_RESONANCE
+        ));
+    }
+
+    #[test]
+    fn type_offset_steps_across_every_filter_type() {
+        let mut filter = Filter::new(48000);
+        let parameters = FilterParameters::default();
+        parameters
+            .filter_type
+            .store(FilterType::HighPass as u8, Relaxed);
+
+        filter.set_modulated_parameters(
+            &parameters,
+            MIDI_CENTER_NOTE_NUMBER,
+            &FilterModulation {
+                filter_type: 0.5,
+                ..FilterModulation::default()
+            },
+        );
+        assert_eq!(filter.filter_type, FilterType::Notch);
+
+        filter.set_modulated_parameters(
+            &parameters,
+            MIDI_CENTER_NOTE_NUMBER,
+            &FilterModulation {
+                filter_type: -1.0,
+                ..FilterModulation::default()
+            },
+        );
+        assert_eq!(filter.filter_type, FilterType::LowPass);
+    }
+
+    #[test]
+    fn drive_offset_is_clamped_to_the_drive_range() {
+        let mut filter = Filter::new(48000);
+        let parameters = FilterParameters::default();
+        parameters.drive.store(0.5);
+
+        filter.set_modulated_parameters(
+            &parameters,
+            MIDI_CENTER_NOTE_NUMBER,
+            &FilterModulation {
+                drive: 0.25,
+                ..FilterModulation::default()
+            },
+        );
+        assert!(f32s_are_equal(filter.drive, 0.75));
+
+        filter.set_modulated_parameters(
+            &parameters,
+            MIDI_CENTER_NOTE_NUMBER,
+            &FilterModulation {
+                drive: -2.0,
+                ..FilterModulation::default()
+            },
+        );
+        assert!(f32s_are_equal(filter.drive, 0.0));
+    }
+
+    #[test]
+    fn pole_offset_steps_across_the_pole_range_from_the_stored_setting() {
+        let mut filter = Filter::new(48000);
+        let parameters = FilterParameters::default();
+        parameters.filter_poles.store(2);
+
+        filter.set_modulated_parameters(
+            &parameters,
+            MIDI_CENTER_NOTE_NUMBER,
+            &FilterModulation {
+                poles: 1.0 / 3.0,
+                ..FilterModulation::default()
+            },
+        );
+        assert_eq!(filter.poles, 3);
+
+        filter.apply_modulation(&FilterModulation {
+            poles: -1.0,
+            ..FilterModulation::default()
+        });
+        assert_eq!(filter.poles, 1);
+    }
+
+    fn sine_response_amplitude(
+        filter_parameters: &FilterParameters,
+        frequency: f32,
+        envelope_value: f32,
+    ) -> f32 {
+        sine_response_amplitude_at_level(filter_parameters, frequency, envelope_value, 1.0)
+    }
+
+    fn sine_response_amplitude_at_level(
+        filter_parameters: &FilterParameters,
+        frequency: f32,
+        envelope_value: f32,
+        level: f32,
+    ) -> f32 {
+        let sample_rate = 48000;
+        let mut filter = Filter::new(sample_rate);
+        let mut peak_amplitude = 0.0_f32;
+
+        for sample_index in 0..sample_rate {
+            filter.set_parameters_with_note_number(filter_parameters, MIDI_CENTER_NOTE_NUMBER);
+            // Test sample indexes stay well below f32 precision limits
+            #[allow(clippy::cast_precision_loss)]
+            let phase = std::f32::consts::TAU * frequency * sample_index as f32 / 48000.0;
+            let (output, _) = filter.process(phase.sin() * level, 0.0, envelope_value, 0.0);
+
+            if sample_index > sample_rate / 2 {
+                peak_amplitude = peak_amplitude.max(output.abs());
+            }
+        }
+
+        peak_amplitude
+    }
+
+    // The musicdsp coefficient tuning puts the single stage corner, and so the center of the
+    // band responses, a little above the set cutoff frequency.
+    const BAND_CENTER_FREQUENCY: f32 = 1200.0;
+
+    fn filter_parameters_with_type(filter_type: FilterType) -> FilterParameters {
+        let parameters = FilterParameters::default();
+        parameters.cutoff_frequency.store(1000.0);
+        parameters.filter_type.store(filter_type as u8, Relaxed);
+        parameters
+    }
+
+    #[test]
+    fn filter_type_from_index_defaults_to_lowpass_on_invalid_index() {
+        assert_eq!(FilterType::from_index(1), FilterType::HighPass);
+        assert_eq!(FilterType::from_index(4), FilterType::Peak);
+        assert_eq!(FilterType::from_index(u8::MAX), FilterType::LowPass);
+    }
+
+    #[test]
+    fn assign_from_copies_and_sanitizes_filter_type() {
+        let parameters = FilterParameters::default();
+        let source = FilterParameters::default();
+
+        source.filter_type.store(FilterType::Notch as u8, Relaxed);
+        parameters.assign_from(&source);
+        assert_eq!(
+            parameters.filter_type.load(Relaxed),
+            FilterType::Notch as u8
+        );
+
+        source.filter_type.store(u8::MAX, Relaxed);
+        parameters.assign_from(&source);
+        assert_eq!(
+            parameters.filter_type.load(Relaxed),
+            FilterType::LowPass as u8
+        );
+    }
+
+    #[test]
+    fn lowpass_tap_weights_select_the_stage_for_the_pole_count() {
+        for poles in 1..=4_u8 {
+            let weights = FilterType::LowPass.tap_weights(poles);
+            assert!(f32s_are_equal(weights[usize::from(poles)], 1.0));
+            assert!(f32s_are_equal(weights.iter().sum(), 1.0));
+        }
+    }
+
+    #[test]
+    fn highpass_passes_high_frequencies_and_attenuates_low_frequencies() {
+        let parameters = filter_parameters_with_type(FilterType::HighPass);
+
+        let low = sine_response_amplitude(&parameters, 100.0, 0.0);
+        let high = sine_response_amplitude(&parameters, 8000.0, 0.0);
+
+        assert!(low < 0.01, "low: {low}");
+        assert!(high > 0.9, "high: {high}");
+    }
+
+    #[test]
+    fn bandpass_passes_the_cutoff_band_and_attenuates_both_sides() {
+        let parameters = filter_parameters_with_type(FilterType::BandPass);
+
+        let low = sine_response_amplitude(&parameters, 100.0, 0.0);
+        let center = sine_response_amplitude(&parameters, BAND_CENTER_FREQUENCY, 0.0);
+        let high = sine_response_amplitude(&parameters, 8000.0, 0.0);
+
+        assert!(center > 0.95, "center: {center}");
+        assert!(low < center * 0.2, "low: {low}");
+        assert!(high < center * 0.2, "high: {high}");
+    }
+
+    #[test]
+    fn notch_rejects_the_cutoff_band_and_passes_both_sides() {
+        let parameters = filter_parameters_with_type(FilterType::Notch);
+
+        let low = sine_response_amplitude(&parameters, 100.0, 0.0);
+        let center = sine_response_amplitude(&parameters, BAND_CENTER_FREQUENCY, 0.0);
+        let high = sine_response_amplitude(&parameters, 8000.0, 0.0);
+
+        assert!(center < 0.1, "center: {center}");
+        assert!(low > 0.9, "low: {low}");
+        assert!(high > 0.9, "high: {high}");
+    }
+
+    #[test]
+    fn peak_boosts_the_cutoff_band_and_passes_both_sides() {
+        let parameters = filter_parameters_with_type(FilterType::Peak);
+
+        let low = sine_response_amplitude(&parameters, 100.0, 0.0);
+        let center = sine_response_amplitude(&parameters, BAND_CENTER_FREQUENCY, 0.0);
+        let high = sine_response_amplitude(&parameters, 8000.0, 0.0);
+
+        assert!(center > 1.9, "center: {center}");
+        assert!(low > 0.8 && low < 1.2, "low: {low}");
+        assert!(high > 0.8 && high < 1.2, "high: {high}");
+    }
+
+    #[test]
+    fn envelope_moves_the_cutoff_for_every_filter_type() {
+        let parameters = filter_parameters_with_type(FilterType::BandPass);
+        parameters.filter_poles.store(2);
+
+        // Opening the envelope moves the band up and away from the test tone.
+        let closed = sine_response_amplitude(&parameters, BAND_CENTER_FREQUENCY, 0.0);
+        let opened = sine_response_amplitude(&parameters, BAND_CENTER_FREQUENCY, 0.5);
+        assert!(opened < closed * 0.5, "closed: {closed}, opened: {opened}");
+
+        parameters
+            .filter_type
+            .store(FilterType::HighPass as u8, Relaxed);
+        let closed = sine_response_amplitude(&parameters, 2000.0, 0.0);
+        let opened = sine_response_amplitude(&parameters, 2000.0, 0.5);
+        assert!(opened < closed * 0.5, "closed: {closed}, opened: {opened}");
+    }
+
+    fn process_tone_through_dual_filter(
+        dual_filter: &mut DualFilter,
+        routing_parameters: &FilterRoutingParameters,
+        sample_index: u32,
+    ) -> (f32, f32) {
+        // Test sample indexes stay well below f32 precision limits
+        #[allow(clippy::cast_precision_loss)]
+        let phase = std::f32::consts::TAU * 440.0 * sample_index as f32 / 48000.0;
+        dual_filter.process(routing_parameters, phase.sin(), phase.cos(), 0.0, 0.0)
+    }
+
+    fn dual_filter_with_lowpass_and_highpass() -> DualFilter {
+        let first_parameters = filter_parameters_with_type(FilterType::LowPass);
+        let second_parameters = filter_parameters_with_type(FilterType::HighPass);
+        let mut dual_filter = DualFilter::new(48000);
+        dual_filter.set_parameters(
+            &first_parameters,
+            &second_parameters,
+            MIDI_CENTER_NOTE_NUMBER,
+        );
+        dual_filter.apply_modulation(
+            &FilterModulation::default(),
+            &FilterModulation::default(),
+            0.0,
+        );
+        dual_filter
+    }
+
+    #[test]
+    fn filter_routing_from_index_defaults_to_single_on_invalid_index() {
+        assert_eq!(FilterRouting::from_index(1), FilterRouting::Serial);
+        assert_eq!(FilterRouting::from_index(3), FilterRouting::Split);
+        assert_eq!(FilterRouting::from_index(u8::MAX), FilterRouting::Single);
+    }
+
+    #[test]
+    fn filter_routing_assign_from_copies_and_sanitizes_values() {
+        let parameters = FilterRoutingParameters::default();
+        let source = FilterRoutingParameters::default();
+
+        source.routing.store(FilterRouting::Split as u8, Relaxed);
+        source.crossfade.store(0.75);
+        parameters.assign_from(&source);
+        assert_eq!(parameters.routing.load(Relaxed), FilterRouting::Split as u8);
+        assert!(f32s_are_equal(parameters.crossfade.load(), 0.75));
+
+        source.routing.store(u8::MAX, Relaxed);
+        parameters.assign_from(&source);
+        assert_eq!(
+            parameters.routing.load(Relaxed),
+            FilterRouting::Single as u8
+        );
+    }
+
+    #[test]
+    fn single_and_serial_routing_match_the_filters_run_by_hand() {
+        let routing_parameters = FilterRoutingParameters::default();
+        let mut dual_filter = dual_filter_with_lowpass_and_highpass();
+        let mut first_filter = dual_filter.first_filter.clone();
+        let mut second_filter = dual_filter.second_filter.clone();
+
+        for sample_index in 0..64 {
+            let output = process_tone_through_dual_filter(
+                &mut dual_filter,
+                &routing_parameters,
+                sample_index,
+            );
+            #[allow(clippy::cast_precision_loss)]
+            let phase = std::f32::consts::TAU * 440.0 * sample_index as f32 / 48000.0;
+            let expected = first_filter.process(phase.sin(), phase.cos(), 0.0, 0.0);
+            assert_eq!(output, expected);
+        }
+
+        routing_parameters
+            .routing
+            .store(FilterRouting::Serial as u8, Relaxed);
+        let mut dual_filter = dual_filter_with_lowpass_and_highpass();
+        let mut first_filter = dual_filter.first_filter.clone();
+
+        for sample_index in 0..64 {
+            let output = process_tone_through_dual_filter(
+                &mut dual_filter,
+                &routing_parameters,
+                sample_index,
+            );
+            #[allow(clippy::cast_precision_loss)]
+            let phase = std::f32::consts::TAU * 440.0 * sample_index as f32 / 48000.0;
+            let (left, right) = first_filter.process(phase.sin(), phase.cos(), 0.0, 0.0);
+            let expected = second_filter.process(left, right, 0.0, 0.0);
+            assert_eq!(output, expected);
+        }
+    }
+
+    #[test]
+    fn parallel_routing_crossfades_between_the_filter_outputs() {
+        let routing_parameters = FilterRoutingParameters::default();
+        routing_parameters
+            .routing
+            .store(FilterRouting::Parallel as u8, Relaxed);
+        let mut dual_filter = dual_filter_with_lowpass_and_highpass();
+        let mut first_filter = dual_filter.first_filter.clone();
+        let mut second_filter = dual_filter.second_filter.clone();
+
+        for sample_index in 0..64 {
+            let (left, right) = process_tone_through_dual_filter(
+                &mut dual_filter,
+                &routing_parameters,
+                sample_index,
+            );
+            #[allow(clippy::cast_precision_loss)]
+            let phase = std::f32::consts::TAU * 440.0 * sample_index as f32 / 48000.0;
+            let (first_left, first_right) =
+                first_filter.process(phase.sin(), phase.cos(), 0.0, 0.0);
+            let (second_left, second_right) =
+                second_filter.process(phase.sin(), phase.cos(), 0.0, 0.0);
+            assert!(f32s_are_equal(left, (first_left + second_left) * 0.5));
+            assert!(f32s_are_equal(right, (first_right + second_right) * 0.5));
+        }
+    }
+
+    #[test]
+    fn crossfade_offset_moves_the_parallel_mix() {
+        let routing_parameters = FilterRoutingParameters::default();
+        routing_parameters
+            .routing
+            .store(FilterRouting::Parallel as u8, Relaxed);
+        let mut dual_filter = dual_filter_with_lowpass_and_highpass();
+        dual_filter.apply_modulation(
+            &FilterModulation::default(),
+            &FilterModulation::default(),
+            -1.0,
+        );
+        let mut first_filter = dual_filter.first_filter.clone();
+
+        for sample_index in 0..64 {
+            let (left, right) = process_tone_through_dual_filter(
+                &mut dual_filter,
+                &routing_parameters,
+                sample_index,
+            );
+            #[allow(clippy::cast_precision_loss)]
+            let phase = std::f32::consts::TAU * 440.0 * sample_index as f32 / 48000.0;
+            let (first_left, first_right) =
+                first_filter.process(phase.sin(), phase.cos(), 0.0, 0.0);
+            assert!(f32s_are_equal(left, first_left));
+            assert!(f32s_are_equal(right, first_right));
+        }
+    }
+
+    #[test]
+    fn each_filter_takes_its_own_modulation_offsets() {
+        let parameters = FilterParameters::default();
+        parameters.cutoff_frequency.store(1000.0);
+        let mut dual_filter = DualFilter::new(48000);
+
+        dual_filter.set_parameters(&parameters, &parameters, MIDI_CENTER_NOTE_NUMBER);
+        dual_filter.apply_modulation(
+            &FilterModulation::default(),
+            &FilterModulation {
+                cutoff: 0.2,
+                filter_type: 0.25,
+                ..FilterModulation::default()
+            },
+            0.0,
+        );
+
+        assert!(f32s_are_equal(
+            dual_filter.first_filter.base_cutoff_frequency,
+            1000.0
+        ));
+        assert_eq!(dual_filter.first_filter.filter_type, FilterType::LowPass);
+        assert!(f32s_are_equal(
+            dual_filter.second_filter.base_cutoff_frequency,
+            2000.0
+        ));
+        assert_eq!(dual_filter.second_filter.filter_type, FilterType::HighPass);
+    }
+
+    #[test]
+    fn split_routing_filters_each_channel_with_its_own_filter() {
+        let routing_parameters = FilterRoutingParameters::default();
+        routing_parameters
+            .routing
+            .store(FilterRouting::Split as u8, Relaxed);
+        let mut dual_filter = dual_filter_with_lowpass_and_highpass();
+        let mut first_filter = dual_filter.first_filter.clone();
+        let mut second_filter = dual_filter.second_filter.clone();
+
+        for sample_index in 0..64 {
+            let (left, right) = process_tone_through_dual_filter(
+                &mut dual_filter,
+                &routing_parameters,
+                sample_index,
+            );
+            #[allow(clippy::cast_precision_loss)]
+            let phase = std::f32::consts::TAU * 440.0 * sample_index as f32 / 48000.0;
+            let (first_left, _) = first_filter.process(phase.sin(), phase.cos(), 0.0, 0.0);
+            let (_, second_right) = second_filter.process(phase.sin(), phase.cos(), 0.0, 0.0);
+            assert_eq!(left, first_left);
+            assert_eq!(right, second_right);
+        }
+    }
+
     #[test]
     fn calculate_non_linear_saturation_returns_expected_values() {
         let expected_result = 0.0;
@@ -641,4 +1545,90 @@ mod tests {
         let result = calculate_non_linear_saturation(-0.5);
         assert!(f32s_are_equal(result, expected_result));
     }
+
+    fn filter_parameters_with_drive(drive: f32, resonance: f32) -> FilterParameters {
+        let parameters = FilterParameters::default();
+        parameters.cutoff_frequency.store(1000.0);
+        parameters.resonance.store(resonance);
+        parameters.drive.store(drive);
+        parameters
+    }
+
+    #[test]
+    fn zero_drive_leaves_the_ladder_linear() {
+        assert!(f32s_are_equal(saturate(0.9, 0.0), 0.9));
+        assert!(f32s_are_equal(calculate_drive_gain(0.0), 1.0));
+        assert!(f32s_are_equal(
+            calculate_passband_compensation(0.0, 3.5),
+            1.0
+        ));
+        assert!(f32s_are_equal(calculate_drive_output_gain(1.0), 1.0));
+    }
+
+    #[test]
+    fn full_drive_saturates_with_tanh() {
+        assert!(f32s_are_equal(saturate(0.9, 1.0), 0.9_f32.tanh()));
+        assert!(saturate(0.9, 0.5) < 0.9);
+        assert!(saturate(0.9, 0.5) > 0.9_f32.tanh());
+    }
+
+    #[test]
+    fn drive_compresses_loud_signals_more_than_quiet_signals() {
+        let clean_parameters = filter_parameters_with_drive(0.0, 0.0);
+        let driven_parameters = filter_parameters_with_drive(1.0, 0.0);
+
+        let clean_ratio = sine_response_amplitude_at_level(&clean_parameters, 100.0, 0.0, 1.0)
+            / sine_response_amplitude_at_level(&clean_parameters, 100.0, 0.0, 0.1);
+        let driven_ratio = sine_response_amplitude_at_level(&driven_parameters, 100.0, 0.0, 1.0)
+            / sine_response_amplitude_at_level(&driven_parameters, 100.0, 0.0, 0.1);
+
+        assert!(
+            driven_ratio < clean_ratio * 0.5,
+            "clean ratio {clean_ratio}, driven ratio {driven_ratio}"
+        );
+    }
+
+    #[test]
+    fn drive_restores_passband_level_lost_to_resonance() {
+        let clean_amplitude = sine_response_amplitude_at_level(
+            &filter_parameters_with_drive(0.0, 0.7),
+            100.0,
+            0.0,
+            0.05,
+        );
+        let driven_amplitude = sine_response_amplitude_at_level(
+            &filter_parameters_with_drive(1.0, 0.7),
+            100.0,
+            0.0,
+            0.05,
+        );
+
+        assert!(
+            driven_amplitude > clean_amplitude * 3.0,
+            "clean {clean_amplitude}, driven {driven_amplitude}"
+        );
+    }
+
+    #[test]
+    fn driven_resonant_filter_stays_bounded() {
+        let amplitude = sine_response_amplitude(
+            &filter_parameters_with_drive(1.0, 1.0),
+            BAND_CENTER_FREQUENCY,
+            0.0,
+        );
+
+        assert!(amplitude.is_finite());
+        assert!(amplitude < 2.0, "amplitude was {amplitude}");
+    }
+
+    #[test]
+    fn filter_parameters_assign_from_copies_drive() {
+        let source = FilterParameters::default();
+        source.drive.store(0.75);
+        let target = FilterParameters::default();
+
+        target.assign_from(&source);
+
+        assert!(f32s_are_equal(target.drive.load(), 0.75));
+    }
 }
@@ -3,10 +3,19 @@ use crate::modules::oscillator::{Oscillator, WaveShape};
 use accsyn_core::defaults::Defaults;
 use accsyn_core::math::f32s_are_equal;
 use accsyn_core::parameter_types::{Balance, Hertz, LfoRange, NormalizedValue};
-use serde::{Deserialize, Serialize};
+use accsyn_core::synth_events::LFOIndex;
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::default::Default;
+use std::ops::Index;
 use std::sync::atomic::Ordering::Relaxed;
 use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU16};
+use strum::EnumCount;
+
+/// Maximum number of LFOs in the LFO bank.
+pub const MAX_LFO_COUNT: usize = 8;
+/// Number of LFOs in a new patch: the mod wheel and filter LFOs, which are always present.
+pub const DEFAULT_LFO_COUNT: usize = LFOIndex::COUNT;
 
 /// Shared atomic parameters for controlling an LFO from the UI thread.
 #[derive(Debug, Serialize, Deserialize)]
@@ -76,6 +85,175 @@ impl Default for LfoParameters {
     }
 }
 
+/// The bank of LFO parameters.
+///
+/// Storage is fixed at `MAX_LFO_COUNT` so the audio thread never sees it resize; `lfo_count` sets
+/// how many of them run. Patches store only the running LFOs, so a patch with the original mod wheel and
+/// filter pair loads as a two LFO bank.
+#[derive(Debug)]
+pub struct LfoBank {
+    lfos: [LfoParameters; MAX_LFO_COUNT],
+    lfo_count: AtomicU8,
+}
+
+impl LfoBank {
+    /// Replace all the values in this `LfoBank` with the values from the provided `LfoBank`.
+    pub fn assign_from(&self, bank: &LfoBank) {
+        self.lfos
+            .iter()
+            .zip(bank.lfos.iter())
+            .for_each(|(lfo, preset_lfo)| lfo.assign_from(preset_lfo));
+        self.set_lfo_count(bank.lfo_count());
+    }
+
+    /// Returns the number of running LFOs.
+    #[must_use]
+    pub fn lfo_count(&self) -> usize {
+        clamp_lfo_count(usize::from(self.lfo_count.load(Relaxed)))
+    }
+
+    /// Sets the number of running LFOs, clamped to `DEFAULT_LFO_COUNT`–`MAX_LFO_COUNT`.
+    pub fn set_lfo_count(&self, lfo_count: usize) {
+        // The clamped count is at most MAX_LFO_COUNT, well within u8 range
+        #[allow(clippy::cast_possible_truncation)]
+        self.lfo_count
+            .store(clamp_lfo_count(lfo_count) as u8, Relaxed);
+    }
+
+    /// Returns an iterator over every LFO in the bank, running or not.
+    pub fn iter(&self) -> std::slice::Iter<'_, LfoParameters> {
+        self.lfos.iter()
+    }
+
+    /// Returns the running LFOs.
+    #[must_use]
+    pub fn active_lfos(&self) -> &[LfoParameters] {
+        &self.lfos[..self.lfo_count()]
+    }
+}
+
+impl Default for LfoBank {
+    fn default() -> Self {
+        let bank = Self {
+            lfos: Default::default(),
+            lfo_count: AtomicU8::new(0),
+        };
+        bank.set_lfo_count(DEFAULT_LFO_COUNT);
+        bank
+    }
+}
+
+impl Index<usize> for LfoBank {
+    type Output = LfoParameters;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.lfos[index]
+    }
+}
+
+impl<'a> IntoIterator for &'a LfoBank {
+    type Item = &'a LfoParameters;
+    type IntoIter = std::slice::Iter<'a, LfoParameters>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Serialize for LfoBank {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let active_lfos = self.active_lfos();
+        let mut sequence = serializer.serialize_seq(Some(active_lfos.len()))?;
+        for lfo in active_lfos {
+            sequence.serialize_element(lfo)?;
+        }
+        sequence.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for LfoBank {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let preset_lfos = Vec::<LfoParameters>::deserialize(deserializer)?;
+        let lfo_count = preset_lfos.len();
+        let mut preset_lfos = preset_lfos.into_iter();
+
+        let bank = Self {
+            lfos: std::array::from_fn(|_| preset_lfos.next().unwrap_or_default()),
+            lfo_count: AtomicU8::new(0),
+        };
+        bank.set_lfo_count(lfo_count);
+        Ok(bank)
+    }
+}
+
+fn clamp_lfo_count(lfo_count: usize) -> usize {
+    lfo_count.clamp(DEFAULT_LFO_COUNT, MAX_LFO_COUNT)
+}
+
+/// Shared atomic parameters choosing the LFOs behind the two fixed routes: mod wheel vibrato and filter cutoff.
+///
+/// They default to the mod wheel and filter LFOs, so patches saved before the routes could be changed play the same.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LfoRoutingParameters {
+    /// Index of the LFO in the bank whose output the mod wheel adds to the oscillator pitch.
+    pub vibrato_lfo: AtomicU8,
+    /// Index of the LFO in the bank whose output moves the filter cutoff by the filter LFO amount.
+    pub filter_lfo: AtomicU8,
+}
+
+impl LfoRoutingParameters {
+    /// Replace all the values in these `LfoRoutingParameters` with the values from the provided `LfoRoutingParameters`.
+    pub fn assign_from(&self, parameters: &LfoRoutingParameters) {
+        self.set_vibrato_lfo(parameters.vibrato_lfo());
+        self.set_filter_lfo(parameters.filter_lfo());
+    }
+
+    /// Returns the index of the LFO driving the vibrato.
+    #[must_use]
+    pub fn vibrato_lfo(&self) -> usize {
+        clamp_lfo_index(usize::from(self.vibrato_lfo.load(Relaxed)))
+    }
+
+    /// Sets the index of the LFO driving the vibrato, clamped to the bank.
+    pub fn set_vibrato_lfo(&self, lfo_index: usize) {
+        // The clamped index is below MAX_LFO_COUNT, well within u8 range
+        #[allow(clippy::cast_possible_truncation)]
+        self.vibrato_lfo
+            .store(clamp_lfo_index(lfo_index) as u8, Relaxed);
+    }
+
+    /// Returns the index of the LFO driving the filter cutoff.
+    #[must_use]
+    pub fn filter_lfo(&self) -> usize {
+        clamp_lfo_index(usize::from(self.filter_lfo.load(Relaxed)))
+    }
+
+    /// Sets the index of the LFO driving the filter cutoff, clamped to the bank.
+    pub fn set_filter_lfo(&self, lfo_index: usize) {
+        // The clamped index is below MAX_LFO_COUNT, well within u8 range
+        #[allow(clippy::cast_possible_truncation)]
+        self.filter_lfo
+            .store(clamp_lfo_index(lfo_index) as u8, Relaxed);
+    }
+}
+
+impl Default for LfoRoutingParameters {
+    fn default() -> Self {
+        let routing = Self {
+            vibrato_lfo: AtomicU8::new(0),
+            filter_lfo: AtomicU8::new(0),
+        };
+        routing.set_vibrato_lfo(LFOIndex::ModWheel as usize);
+        routing.set_filter_lfo(LFOIndex::Filter as usize);
+        routing
+    }
+}
+
+fn clamp_lfo_index(lfo_index: usize) -> usize {
+    lfo_index.min(MAX_LFO_COUNT - 1)
+}
+
 /// Low-frequency oscillator for modulating synthesis parameters.
 pub struct Lfo {
     oscillator: Oscillator,
@@ -196,7 +374,8 @@ impl Lfo {
     /// Sets the LFO wave shape by numeric index, updating the oscillator if changed.
     pub fn set_wave_shape(&mut self, wave_shape_index: u8) {
         if wave_shape_index != self.wave_shape_index {
-            let wave_shape = WaveShape::from_index(wave_shape_index);
+            // Wave shapes after Noise need sample data or oscillator parameters, so LFOs fall back to Noise
+            let wave_shape = WaveShape::from_index(wave_shape_index.min(WaveShape::Noise as u8));
             self.oscillator.set_wave_shape(wave_shape);
             self.wave_shape_index = wave_shape_index;
         }
@@ -224,6 +403,61 @@ mod tests {
 
     const SAMPLE_RATE: u32 = 48000;
 
+    #[test]
+    fn lfo_routing_defaults_to_the_mod_wheel_and_filter_lfos_and_clamps_to_the_bank() {
+        let routing: LfoRoutingParameters = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(routing.vibrato_lfo(), LFOIndex::ModWheel as usize);
+        assert_eq!(routing.filter_lfo(), LFOIndex::Filter as usize);
+
+        routing.set_vibrato_lfo(4);
+        routing.set_filter_lfo(MAX_LFO_COUNT + 3);
+
+        assert_eq!(routing.vibrato_lfo(), 4);
+        assert_eq!(routing.filter_lfo(), MAX_LFO_COUNT - 1);
+    }
+
+    #[test]
+    fn legacy_two_lfo_patch_loads_as_a_two_lfo_bank() {
+        let legacy_json = r#"[{"frequency": 3.0}, {"frequency": 5.0}]"#;
+
+        let bank: LfoBank = serde_json::from_str(legacy_json).unwrap();
+
+        assert_eq!(bank.lfo_count(), 2);
+        assert!(f32s_are_equal(
+            bank[LFOIndex::ModWheel as usize].frequency.load(),
+            3.0
+        ));
+        assert!(f32s_are_equal(
+            bank[LFOIndex::Filter as usize].frequency.load(),
+            5.0
+        ));
+    }
+
+    #[test]
+    fn bank_serializes_only_the_running_lfos() {
+        let bank = LfoBank::default();
+        bank.set_lfo_count(4);
+        bank[3].frequency.store(7.0);
+
+        let json = serde_json::to_string(&bank).unwrap();
+        let loaded: LfoBank = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.lfo_count(), 4);
+        assert!(f32s_are_equal(loaded[3].frequency.load(), 7.0));
+    }
+
+    #[test]
+    fn lfo_count_is_clamped_to_the_bank_size() {
+        let bank = LfoBank::default();
+
+        bank.set_lfo_count(0);
+        assert_eq!(bank.lfo_count(), DEFAULT_LFO_COUNT);
+
+        bank.set_lfo_count(MAX_LFO_COUNT + 1);
+        assert_eq!(bank.lfo_count(), MAX_LFO_COUNT);
+    }
+
     // Tests for generate() - early return branching
     #[test]
     fn test_generate_returns_zero_when_range_is_zero() {
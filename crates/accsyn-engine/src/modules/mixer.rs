@@ -3,8 +3,8 @@ const QUAD_MIX_DEFAULT_LEVEL_SUM: f32 = 4.0;
 /// Input to the oscillator mixer representing one oscillator's contribution.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct MixerInput {
-    /// Current audio sample value from the oscillator.
-    pub sample: f32,
+    /// Current left and right audio sample values from the oscillator.
+    pub sample: (f32, f32),
     /// Oscillator volume level (0.0–1.0).
     pub level: f32,
     /// Stereo pan position (-1.0 left, 0.0 center, 1.0 right).
@@ -16,7 +16,10 @@ pub struct MixerInput {
 pub(crate) fn quad_mix(inputs: [MixerInput; 4]) -> (f32, f32) {
     let (mut left_input_sum, mut right_input_sum): (f32, f32) = inputs
         .iter()
-        .map(|input| apply_quad_balance(apply_quad_level(input), input.balance))
+        .map(|input| {
+            let (left_input, right_input) = apply_quad_level(input);
+            apply_balance(left_input, right_input, input.balance)
+        })
         .fold(
             (0.0, 0.0),
             |(left_sum, right_sum), (left_input, right_input)| {
@@ -45,16 +48,12 @@ pub(crate) fn output_mix(
     apply_balance(leveled_left_input, leveled_right_input, balance)
 }
 
-fn apply_quad_level(input: &MixerInput) -> f32 {
+fn apply_quad_level(input: &MixerInput) -> (f32, f32) {
     if input.mute {
-        return 0.0;
+        return (0.0, 0.0);
     }
 
-    input.sample * input.level
-}
-
-fn apply_quad_balance(input: f32, balance: f32) -> (f32, f32) {
-    apply_balance(input, input, balance)
+    (input.sample.0 * input.level, input.sample.1 * input.level)
 }
 
 fn apply_balance(mut input_left: f32, mut input_right: f32, balance: f32) -> (f32, f32) {
@@ -91,19 +90,16 @@ mod tests {
     #[test]
     fn test_apply_quad_level_muted() {
         let input = MixerInput {
-            sample: 0.5,
+            sample: (0.5, 0.5),
             level: 0.8,
             balance: 0.0,
             mute: true,
         };
 
         let actual = apply_quad_level(&input);
-        let expected = 0.0;
+        let expected = (0.0, 0.0);
 
-        assert!(
-            f32s_are_equal(actual, expected),
-            "Expected {expected}, got {actual}"
-        );
+        assert_stereo_eq(actual, expected);
     }
 
     // Tests for apply_balance - test branching and edge cases
@@ -148,25 +144,25 @@ mod tests {
     fn test_quad_mix_all_muted() {
         let inputs = [
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: 0.0,
                 mute: true,
             },
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: 0.0,
                 mute: true,
             },
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: 0.0,
                 mute: true,
             },
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: 0.0,
                 mute: true,
@@ -183,25 +179,25 @@ mod tests {
     fn test_quad_mix_mixed_panning() {
         let inputs = [
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: -1.0, // Full left
                 mute: false,
             },
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: 1.0, // Full right
                 mute: false,
             },
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: 0.0, // Center
                 mute: false,
             },
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: 0.0, // Center
                 mute: false,
@@ -218,25 +214,25 @@ mod tests {
     fn test_quad_mix_partial_mute() {
         let inputs = [
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: 0.0,
                 mute: false,
             },
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: 0.0,
                 mute: true,
             },
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: 0.0,
                 mute: false,
             },
             MixerInput {
-                sample: 1.0,
+                sample: (1.0, 1.0),
                 level: 1.0,
                 balance: 0.0,
                 mute: true,
@@ -249,6 +245,32 @@ mod tests {
         assert_stereo_eq(actual, expected);
     }
 
+    #[test]
+    fn test_quad_mix_keeps_stereo_inputs_apart() {
+        let silent = MixerInput {
+            sample: (0.0, 0.0),
+            level: 1.0,
+            balance: 0.0,
+            mute: false,
+        };
+        let inputs = [
+            MixerInput {
+                sample: (1.0, -1.0),
+                level: 0.5,
+                balance: 0.0,
+                mute: false,
+            },
+            silent,
+            silent,
+            silent,
+        ];
+
+        let actual = quad_mix(inputs);
+        let expected = (0.125, -0.125); // (0.5, -0.5) / 4.0
+
+        assert_stereo_eq(actual, expected);
+    }
+
     // Tests for output_mix - test branching and integration
     #[test]
     fn test_output_mix_muted() {
@@ -10,7 +10,13 @@ pub mod filter;
 pub mod lfo;
 /// Level and stereo balance mixing for oscillator and output stages.
 pub mod mixer;
+/// Modulation matrix routing any modulation source to any destination parameter.
+pub mod mod_matrix;
 /// Waveform generation oscillators supporting multiple wave shapes.
 pub mod oscillator;
 /// All pass filter for use in various other effects
 pub mod phase_shift_all_pass;
+/// Holding shared data replaced off the audio thread so the audio thread never frees it.
+pub mod retired_arcs;
+/// Scala scale and keyboard mapping microtuning with the global master tuning.
+pub mod tuning;
@@ -0,0 +1,821 @@
+use accsyn_core::effects::{EffectIndex, PARAMETERS_PER_EFFECT};
+use accsyn_core::parameter_types::BiPolarNormalizedValue;
+use accsyn_core::synth_events::OscillatorIndex;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering::Relaxed;
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, FromRepr};
+
+/// Number of routing slots in the modulation matrix.
+pub const MOD_MATRIX_SLOT_COUNT: usize = 8;
+const OSCILLATOR_DESTINATION_COUNT: usize = 4;
+const FIRST_OSCILLATOR_DESTINATION: usize = 1;
+const FIRST_FILTER_DESTINATION: usize =
+    FIRST_OSCILLATOR_DESTINATION + OscillatorIndex::COUNT * OSCILLATOR_DESTINATION_COUNT;
+const FILTER_DESTINATIONS: [ModulationDestination; 14] = [
+    ModulationDestination::FilterCutoff,
+    ModulationDestination::FilterResonance,
+    ModulationDestination::FilterKeyTracking,
+    ModulationDestination::FilterPoles,
+    ModulationDestination::FilterType,
+    ModulationDestination::FilterDrive,
+    ModulationDestination::SecondFilterCutoff,
+    ModulationDestination::SecondFilterResonance,
+    ModulationDestination::SecondFilterKeyTracking,
+    ModulationDestination::SecondFilterPoles,
+    ModulationDestination::SecondFilterType,
+    ModulationDestination::SecondFilterDrive,
+    ModulationDestination::FilterCrossfade,
+    ModulationDestination::FilterEnvelopeAmount,
+];
+const FIRST_EFFECT_DESTINATION: usize = FIRST_FILTER_DESTINATION + FILTER_DESTINATIONS.len();
+const PITCH_DESTINATION: usize =
+    FIRST_EFFECT_DESTINATION + EffectIndex::COUNT * PARAMETERS_PER_EFFECT;
+
+/// Pitch offset in cents for a pitch destination offset of 1.0, one octave.
+pub const PITCH_MODULATION_RANGE_CENTS: f32 = 1200.0;
+
+/// A signal that can drive a modulation matrix slot.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumCount, EnumIter, FromRepr)]
+#[repr(u8)]
+pub enum ModulationSource {
+    /// No source; the slot is inactive.
+    #[default]
+    None,
+    /// The mod wheel (vibrato) LFO output.
+    ModWheelLfo,
+    /// The filter LFO output.
+    FilterLfo,
+    /// The amp envelope level.
+    AmpEnvelope,
+    /// The filter envelope level.
+    FilterEnvelope,
+    /// The pitch envelope level.
+    PitchEnvelope,
+    /// The scaled note-on velocity.
+    Velocity,
+    /// The MIDI note number scaled to 0.0–1.0.
+    Key,
+    /// Channel aftertouch.
+    Aftertouch,
+    /// The mod wheel position.
+    ModWheel,
+    /// The pitch bend wheel position (-1.0–1.0).
+    PitchBend,
+    /// The third LFO in the LFO bank.
+    Lfo3,
+    /// The fourth LFO in the LFO bank.
+    Lfo4,
+    /// The fifth LFO in the LFO bank.
+    Lfo5,
+    /// The sixth LFO in the LFO bank.
+    Lfo6,
+    /// The seventh LFO in the LFO bank.
+    Lfo7,
+    /// The eighth LFO in the LFO bank.
+    Lfo8,
+    /// The MPE per-note pitch bend (-1.0–1.0), at rest for notes outside an MPE zone.
+    NotePitchBend,
+    /// The per-note pressure from MPE or polyphonic key pressure.
+    NotePressure,
+    /// The MPE per-note timbre from CC 74 (-1.0–1.0).
+    NoteTimbre,
+    /// The release velocity of the voice's most recent note off.
+    ReleaseVelocity,
+}
+
+impl ModulationSource {
+    /// Converts a numeric index to the corresponding source, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
+
+    /// Returns the source for the LFO at the given position in the LFO bank, or `None` past the last one.
+    #[must_use]
+    pub fn lfo(lfo_index: usize) -> Self {
+        match lfo_index {
+            0 => Self::ModWheelLfo,
+            1 => Self::FilterLfo,
+            2 => Self::Lfo3,
+            3 => Self::Lfo4,
+            4 => Self::Lfo5,
+            5 => Self::Lfo6,
+            6 => Self::Lfo7,
+            7 => Self::Lfo8,
+            _ => Self::None,
+        }
+    }
+}
+
+/// A parameter that a modulation matrix slot can offset.
+///
+/// Destinations are stored in presets as a flat index: 0 is `None`, then four destinations per
+/// oscillator, then the filter destinations in `FILTER_DESTINATIONS` order, then `PARAMETERS_PER_EFFECT`
+/// destinations per effect, then pitch.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModulationDestination {
+    /// No destination; the slot is inactive.
+    #[default]
+    None,
+    /// An oscillator's first wave shape parameter.
+    OscillatorShapeParameter1(OscillatorIndex),
+    /// An oscillator's second wave shape parameter.
+    OscillatorShapeParameter2(OscillatorIndex),
+    /// An oscillator's quad mixer level.
+    OscillatorLevel(OscillatorIndex),
+    /// An oscillator's quad mixer balance.
+    OscillatorBalance(OscillatorIndex),
+    /// The filter cutoff frequency.
+    FilterCutoff,
+    /// The filter resonance.
+    FilterResonance,
+    /// The filter key tracking amount.
+    FilterKeyTracking,
+    /// The filter pole count, where 1.0 spans the full 1 to 4 pole range.
+    FilterPoles,
+    /// The filter type, where 1.0 spans every filter type.
+    FilterType,
+    /// The filter drive.
+    FilterDrive,
+    /// The second filter cutoff frequency.
+    SecondFilterCutoff,
+    /// The second filter resonance.
+    SecondFilterResonance,
+    /// The second filter key tracking amount.
+    SecondFilterKeyTracking,
+    /// The second filter pole count, where 1.0 spans the full 1 to 4 pole range.
+    SecondFilterPoles,
+    /// The second filter type, where 1.0 spans every filter type.
+    SecondFilterType,
+    /// The second filter drive.
+    SecondFilterDrive,
+    /// The parallel routing crossfade between the two filters.
+    FilterCrossfade,
+    /// The filter envelope amount, shared by both filters. An envelope with its amount at zero stays off.
+    FilterEnvelopeAmount,
+    /// One of an effect's parameters (effect, parameter index).
+    EffectParameter(EffectIndex, usize),
+    /// The pitch of every oscillator in the voice, where 1.0 transposes up an octave.
+    Pitch,
+}
+
+impl ModulationDestination {
+    /// Converts a flat destination index to the corresponding destination, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        let index = usize::from(index);
+        match index {
+            FIRST_OSCILLATOR_DESTINATION..FIRST_FILTER_DESTINATION => {
+                let offset = index - FIRST_OSCILLATOR_DESTINATION;
+                let Some(oscillator) = i32::try_from(offset / OSCILLATOR_DESTINATION_COUNT)
+                    .ok()
+                    .and_then(OscillatorIndex::from_i32)
+                else {
+                    return Self::None;
+                };
+                match offset % OSCILLATOR_DESTINATION_COUNT {
+                    0 => Self::OscillatorShapeParameter1(oscillator),
+                    1 => Self::OscillatorShapeParameter2(oscillator),
+                    2 => Self::OscillatorLevel(oscillator),
+                    _ => Self::OscillatorBalance(oscillator),
+                }
+            }
+            FIRST_FILTER_DESTINATION..FIRST_EFFECT_DESTINATION => {
+                FILTER_DESTINATIONS[index - FIRST_FILTER_DESTINATION]
+            }
+            FIRST_EFFECT_DESTINATION..PITCH_DESTINATION => {
+                let offset = index - FIRST_EFFECT_DESTINATION;
+                i32::try_from(offset / PARAMETERS_PER_EFFECT)
+                    .ok()
+                    .and_then(EffectIndex::from_i32)
+                    .map_or(Self::None, |effect| {
+                        Self::EffectParameter(effect, offset % PARAMETERS_PER_EFFECT)
+                    })
+            }
+            PITCH_DESTINATION => Self::Pitch,
+            _ => Self::None,
+        }
+    }
+
+    /// Returns the flat destination index stored in presets.
+    #[must_use]
+    pub fn index(self) -> u8 {
+        let oscillator_index = |oscillator: OscillatorIndex, parameter: usize| {
+            FIRST_OSCILLATOR_DESTINATION
+                + oscillator as usize * OSCILLATOR_DESTINATION_COUNT
+                + parameter
+        };
+
+        let index = match self {
+            Self::None => 0,
+            Self::OscillatorShapeParameter1(oscillator) => oscillator_index(oscillator, 0),
+            Self::OscillatorShapeParameter2(oscillator) => oscillator_index(oscillator, 1),
+            Self::OscillatorLevel(oscillator) => oscillator_index(oscillator, 2),
+            Self::OscillatorBalance(oscillator) => oscillator_index(oscillator, 3),
+            Self::EffectParameter(effect, parameter) => {
+                FIRST_EFFECT_DESTINATION
+                    + effect as usize * PARAMETERS_PER_EFFECT
+                    + parameter.min(PARAMETERS_PER_EFFECT - 1)
+            }
+            Self::Pitch => PITCH_DESTINATION,
+            filter_destination => FILTER_DESTINATIONS
+                .iter()
+                .position(|destination| *destination == filter_destination)
+                .map_or(0, |position| FIRST_FILTER_DESTINATION + position),
+        };
+
+        u8::try_from(index).unwrap_or_default()
+    }
+}
+
+/// Shared atomic parameters for a single modulation matrix slot.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModulationSlotParameters {
+    /// Index selecting the slot's `ModulationSource`.
+    pub source: AtomicU8,
+    /// Flat index selecting the slot's `ModulationDestination`.
+    pub destination: AtomicU8,
+    /// Modulation depth and direction (-1.0–1.0).
+    pub amount: BiPolarNormalizedValue,
+    /// Index selecting an optional `ModulationSource` that scales the slot's output.
+    pub via_source: AtomicU8,
+}
+
+impl ModulationSlotParameters {
+    /// Replace all the values in this `ModulationSlotParameters` with the values from the provided `ModulationSlotParameters`.
+    pub fn assign_from(&self, parameters: &ModulationSlotParameters) {
+        self.source.store(parameters.source.load(Relaxed), Relaxed);
+        self.destination
+            .store(parameters.destination.load(Relaxed), Relaxed);
+        self.amount.store(parameters.amount.load());
+        self.via_source
+            .store(parameters.via_source.load(Relaxed), Relaxed);
+    }
+
+    /// Returns the slot's source.
+    #[must_use]
+    pub fn source(&self) -> ModulationSource {
+        ModulationSource::from_index(self.source.load(Relaxed))
+    }
+
+    /// Returns the slot's destination.
+    #[must_use]
+    pub fn destination(&self) -> ModulationDestination {
+        ModulationDestination::from_index(self.destination.load(Relaxed))
+    }
+
+    /// Returns the slot's via source.
+    #[must_use]
+    pub fn via_source(&self) -> ModulationSource {
+        ModulationSource::from_index(self.via_source.load(Relaxed))
+    }
+}
+
+/// Deserializes the matrix slots stored in a patch, padding a shorter list with empty slots and ignoring any
+/// slots past `MOD_MATRIX_SLOT_COUNT`.
+///
+/// # Errors
+///
+/// Returns an error if the stored slots are not a list of modulation slots.
+pub fn deserialize_modulation_slots<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<[ModulationSlotParameters; MOD_MATRIX_SLOT_COUNT], D::Error> {
+    let mut preset_slots = Vec::<ModulationSlotParameters>::deserialize(deserializer)?.into_iter();
+    Ok(std::array::from_fn(|_| {
+        preset_slots.next().unwrap_or_default()
+    }))
+}
+
+impl Default for ModulationSlotParameters {
+    fn default() -> Self {
+        Self {
+            source: AtomicU8::new(ModulationSource::default() as u8),
+            destination: AtomicU8::new(ModulationDestination::default().index()),
+            amount: BiPolarNormalizedValue::default(),
+            via_source: AtomicU8::new(ModulationSource::default() as u8),
+        }
+    }
+}
+
+/// The current value of every modulation source, sampled once per buffer.
+///
+/// The offsets calculated from them are interpolated across the buffer, see `ModulationOffsets::interpolate`.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct ModulationSourceValues {
+    values: [f32; ModulationSource::COUNT],
+}
+
+impl ModulationSourceValues {
+    /// Sets the current value of a source.
+    pub fn set(&mut self, source: ModulationSource, value: f32) {
+        if source != ModulationSource::None {
+            self.values[source as usize] = value;
+        }
+    }
+
+    /// Returns the current value of a source, or 0.0 for `ModulationSource::None`.
+    #[must_use]
+    pub fn get(&self, source: ModulationSource) -> f32 {
+        self.values[source as usize]
+    }
+}
+
+/// Summed matrix offsets for one oscillator.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct OscillatorModulation {
+    /// Offset added to the first wave shape parameter.
+    pub shape_parameter1: f32,
+    /// Offset added to the second wave shape parameter.
+    pub shape_parameter2: f32,
+    /// Offset added to the quad mixer level.
+    pub level: f32,
+    /// Offset added to the quad mixer balance.
+    pub balance: f32,
+}
+
+impl OscillatorModulation {
+    fn interpolate(&self, target: &Self, fraction: f32) -> Self {
+        Self {
+            shape_parameter1: lerp(self.shape_parameter1, target.shape_parameter1, fraction),
+            shape_parameter2: lerp(self.shape_parameter2, target.shape_parameter2, fraction),
+            level: lerp(self.level, target.level, fraction),
+            balance: lerp(self.balance, target.balance, fraction),
+        }
+    }
+}
+
+/// Summed matrix offsets for one filter.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct FilterModulation {
+    /// Cutoff offset, where 1.0 is the full modulation range above the set cutoff.
+    pub cutoff: f32,
+    /// Offset added to the normalized resonance.
+    pub resonance: f32,
+    /// Offset added to the normalized key tracking amount.
+    pub key_tracking: f32,
+    /// Offset added to the normalized drive.
+    pub drive: f32,
+    /// Pole count offset, where 1.0 adds the full 1 to 4 pole range.
+    pub poles: f32,
+    /// Filter type offset, where 1.0 steps through every filter type.
+    pub filter_type: f32,
+}
+
+impl FilterModulation {
+    fn interpolate(&self, target: &Self, fraction: f32) -> Self {
+        Self {
+            cutoff: lerp(self.cutoff, target.cutoff, fraction),
+            resonance: lerp(self.resonance, target.resonance, fraction),
+            key_tracking: lerp(self.key_tracking, target.key_tracking, fraction),
+            drive: lerp(self.drive, target.drive, fraction),
+            poles: lerp(self.poles, target.poles, fraction),
+            filter_type: lerp(self.filter_type, target.filter_type, fraction),
+        }
+    }
+}
+
+/// Summed matrix offsets for every destination.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct ModulationOffsets {
+    /// Offsets for each oscillator, in `OscillatorIndex` order.
+    pub oscillators: [OscillatorModulation; OscillatorIndex::COUNT],
+    /// Offsets for the first filter.
+    pub filter: FilterModulation,
+    /// Offsets for the second filter.
+    pub second_filter: FilterModulation,
+    /// Offset added to the filter envelope amount.
+    pub filter_envelope_amount: f32,
+    /// Offset added to the parallel routing crossfade.
+    pub filter_crossfade: f32,
+    /// Offsets for each effect parameter, in `EffectIndex` order.
+    pub effects: EffectOffsets,
+    /// Pitch offset, where 1.0 is `PITCH_MODULATION_RANGE_CENTS` up.
+    pub pitch: f32,
+}
+
+/// Matrix offsets for each effect parameter, in `EffectIndex` order.
+pub type EffectOffsets = [[f32; PARAMETERS_PER_EFFECT]; EffectIndex::COUNT];
+
+/// Returns the effect offsets `fraction` of the way from `start` to `target`.
+#[must_use]
+pub fn interpolate_effect_offsets(
+    start: &EffectOffsets,
+    target: &EffectOffsets,
+    fraction: f32,
+) -> EffectOffsets {
+    let mut effects = *start;
+    for (effect_offsets, target_offsets) in effects.iter_mut().zip(target) {
+        for (offset, target_offset) in effect_offsets.iter_mut().zip(target_offsets) {
+            *offset = lerp(*offset, *target_offset, fraction);
+        }
+    }
+    effects
+}
+
+impl ModulationOffsets {
+    /// Returns the offsets `fraction` of the way from these offsets to the `target` offsets.
+    ///
+    /// The matrix is evaluated once per buffer, so moving from one buffer's offsets to the next a little every
+    /// sample keeps modulated parameters from stepping at the buffer rate.
+    #[must_use]
+    pub fn interpolate(&self, target: &Self, fraction: f32) -> Self {
+        Self {
+            oscillators: std::array::from_fn(|index| {
+                self.oscillators[index].interpolate(&target.oscillators[index], fraction)
+            }),
+            filter: self.filter.interpolate(&target.filter, fraction),
+            second_filter: self
+                .second_filter
+                .interpolate(&target.second_filter, fraction),
+            filter_envelope_amount: lerp(
+                self.filter_envelope_amount,
+                target.filter_envelope_amount,
+                fraction,
+            ),
+            filter_crossfade: lerp(self.filter_crossfade, target.filter_crossfade, fraction),
+            effects: interpolate_effect_offsets(&self.effects, &target.effects, fraction),
+            pitch: lerp(self.pitch, target.pitch, fraction),
+        }
+    }
+
+    /// Returns the pitch offset in cents.
+    #[must_use]
+    pub fn pitch_cents(&self) -> i16 {
+        // Pitch offsets from a full matrix stay within a few octaves, well within i16 range
+        #[allow(clippy::cast_possible_truncation)]
+        let cents = (self.pitch * PITCH_MODULATION_RANGE_CENTS) as i16;
+        cents
+    }
+}
+
+fn lerp(start: f32, target: f32, fraction: f32) -> f32 {
+    start + (target - start) * fraction
+}
+
+/// Sums the output of every active slot into offsets for each destination.
+///
+/// A slot outputs its source value times its amount, scaled by its via source when one is set.
+#[must_use]
+pub fn calculate_modulation_offsets(
+    slots: &[ModulationSlotParameters],
+    source_values: &ModulationSourceValues,
+) -> ModulationOffsets {
+    let mut offsets = ModulationOffsets::default();
+
+    for slot in slots {
+        let source = slot.source();
+        let destination = slot.destination();
+        if source == ModulationSource::None || destination == ModulationDestination::None {
+            continue;
+        }
+
+        let via_value = match slot.via_source() {
+            ModulationSource::None => 1.0,
+            via_source => source_values.get(via_source),
+        };
+        let offset = source_values.get(source) * slot.amount.load() * via_value;
+
+        match destination {
+            ModulationDestination::None => {}
+            ModulationDestination::OscillatorShapeParameter1(oscillator) => {
+                offsets.oscillators[oscillator as usize].shape_parameter1 += offset;
+            }
+            ModulationDestination::OscillatorShapeParameter2(oscillator) => {
+                offsets.oscillators[oscillator as usize].shape_parameter2 += offset;
+            }
+            ModulationDestination::OscillatorLevel(oscillator) => {
+                offsets.oscillators[oscillator as usize].level += offset;
+            }
+            ModulationDestination::OscillatorBalance(oscillator) => {
+                offsets.oscillators[oscillator as usize].balance += offset;
+            }
+            ModulationDestination::FilterCutoff => offsets.filter.cutoff += offset,
+            ModulationDestination::FilterResonance => offsets.filter.resonance += offset,
+            ModulationDestination::FilterKeyTracking => offsets.filter.key_tracking += offset,
+            ModulationDestination::FilterPoles => offsets.filter.poles += offset,
+            ModulationDestination::FilterType => offsets.filter.filter_type += offset,
+            ModulationDestination::FilterDrive => offsets.filter.drive += offset,
+            ModulationDestination::SecondFilterCutoff => offsets.second_filter.cutoff += offset,
+            ModulationDestination::SecondFilterResonance => {
+                offsets.second_filter.resonance += offset;
+            }
+            ModulationDestination::SecondFilterKeyTracking => {
+                offsets.second_filter.key_tracking += offset;
+            }
+            ModulationDestination::SecondFilterPoles => offsets.second_filter.poles += offset,
+            ModulationDestination::SecondFilterType => {
+                offsets.second_filter.filter_type += offset;
+            }
+            ModulationDestination::SecondFilterDrive => offsets.second_filter.drive += offset,
+            ModulationDestination::FilterCrossfade => offsets.filter_crossfade += offset,
+            ModulationDestination::FilterEnvelopeAmount => {
+                offsets.filter_envelope_amount += offset;
+            }
+            ModulationDestination::EffectParameter(effect, parameter) => {
+                offsets.effects[effect as usize][parameter] += offset;
+            }
+            ModulationDestination::Pitch => offsets.pitch += offset,
+        }
+    }
+
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::lfo::MAX_LFO_COUNT;
+    use accsyn_core::math::f32s_are_equal;
+    use std::collections::HashSet;
+
+    const DESTINATION_COUNT: usize = PITCH_DESTINATION + 1;
+
+    fn slot(
+        source: ModulationSource,
+        destination: ModulationDestination,
+        amount: f32,
+        via_source: ModulationSource,
+    ) -> ModulationSlotParameters {
+        let slot = ModulationSlotParameters::default();
+        slot.source.store(source as u8, Relaxed);
+        slot.destination.store(destination.index(), Relaxed);
+        slot.amount.store(amount);
+        slot.via_source.store(via_source as u8, Relaxed);
+        slot
+    }
+
+    #[test]
+    fn destination_index_round_trips_for_every_destination() {
+        for index in 0..u8::try_from(DESTINATION_COUNT).unwrap() {
+            let destination = ModulationDestination::from_index(index);
+            assert_eq!(destination.index(), index);
+        }
+    }
+
+    #[test]
+    fn out_of_range_destination_index_is_none() {
+        assert_eq!(
+            ModulationDestination::from_index(u8::try_from(DESTINATION_COUNT).unwrap()),
+            ModulationDestination::None
+        );
+        assert_eq!(
+            ModulationDestination::from_index(u8::MAX),
+            ModulationDestination::None
+        );
+    }
+
+    #[test]
+    fn filter_destinations_are_contiguous_before_the_effects() {
+        for (position, destination) in FILTER_DESTINATIONS.iter().enumerate() {
+            assert_eq!(
+                usize::from(destination.index()),
+                FIRST_FILTER_DESTINATION + position
+            );
+        }
+        assert_eq!(
+            ModulationDestination::from_index(u8::try_from(FIRST_EFFECT_DESTINATION).unwrap()),
+            ModulationDestination::EffectParameter(EffectIndex::Saturation, 0)
+        );
+    }
+
+    #[test]
+    fn pitch_destination_is_stored_after_the_effects() {
+        assert_eq!(
+            ModulationDestination::from_index(u8::try_from(PITCH_DESTINATION).unwrap()),
+            ModulationDestination::Pitch
+        );
+        assert!(matches!(
+            ModulationDestination::from_index(u8::try_from(PITCH_DESTINATION - 1).unwrap()),
+            ModulationDestination::EffectParameter(_, parameter) if parameter == PARAMETERS_PER_EFFECT - 1
+        ));
+    }
+
+    #[test]
+    fn note_expression_routes_to_pitch_in_cents() {
+        let slots = [
+            slot(
+                ModulationSource::NotePressure,
+                ModulationDestination::Pitch,
+                0.5,
+                ModulationSource::None,
+            ),
+            slot(
+                ModulationSource::NoteTimbre,
+                ModulationDestination::Pitch,
+                -0.25,
+                ModulationSource::None,
+            ),
+        ];
+        let mut source_values = ModulationSourceValues::default();
+        source_values.set(ModulationSource::NotePressure, 1.0);
+        source_values.set(ModulationSource::NoteTimbre, 1.0);
+
+        let offsets = calculate_modulation_offsets(&slots, &source_values);
+
+        assert!(f32s_are_equal(offsets.pitch, 0.25));
+        assert_eq!(offsets.pitch_cents(), 300);
+    }
+
+    #[test]
+    fn filter_destinations_offset_their_own_filter() {
+        let slots = [
+            slot(
+                ModulationSource::Velocity,
+                ModulationDestination::FilterDrive,
+                0.5,
+                ModulationSource::None,
+            ),
+            slot(
+                ModulationSource::Velocity,
+                ModulationDestination::SecondFilterType,
+                -1.0,
+                ModulationSource::None,
+            ),
+            slot(
+                ModulationSource::Velocity,
+                ModulationDestination::FilterCrossfade,
+                0.25,
+                ModulationSource::None,
+            ),
+        ];
+        let mut source_values = ModulationSourceValues::default();
+        source_values.set(ModulationSource::Velocity, 1.0);
+
+        let offsets = calculate_modulation_offsets(&slots, &source_values);
+
+        assert!(f32s_are_equal(offsets.filter.drive, 0.5));
+        assert!(f32s_are_equal(offsets.second_filter.filter_type, -1.0));
+        assert!(f32s_are_equal(offsets.second_filter.drive, 0.0));
+        assert!(f32s_are_equal(offsets.filter_crossfade, 0.25));
+    }
+
+    #[test]
+    fn interpolate_moves_every_offset_towards_the_target() {
+        let mut target = ModulationOffsets::default();
+        target.oscillators[OscillatorIndex::Two as usize].level = 1.0;
+        target.second_filter.cutoff = -0.5;
+        target.filter_envelope_amount = 0.5;
+        target.effects[EffectIndex::Delay as usize][1] = 1.0;
+
+        let halfway = ModulationOffsets::default().interpolate(&target, 0.5);
+
+        assert!(f32s_are_equal(
+            halfway.oscillators[OscillatorIndex::Two as usize].level,
+            0.5
+        ));
+        assert!(f32s_are_equal(halfway.second_filter.cutoff, -0.25));
+        assert!(f32s_are_equal(halfway.filter_envelope_amount, 0.25));
+        assert!(f32s_are_equal(
+            halfway.effects[EffectIndex::Delay as usize][1],
+            0.5
+        ));
+        assert_eq!(
+            ModulationOffsets::default().interpolate(&target, 1.0),
+            target
+        );
+    }
+
+    #[test]
+    fn mpe_sources_are_stored_after_the_existing_sources() {
+        let first_mpe_source = ModulationSource::Lfo8 as u8 + 1;
+
+        assert_eq!(
+            ModulationSource::from_index(first_mpe_source),
+            ModulationSource::NotePitchBend
+        );
+        assert_eq!(
+            ModulationSource::from_index(first_mpe_source + 2),
+            ModulationSource::NoteTimbre
+        );
+        assert_eq!(
+            ModulationSource::from_index(first_mpe_source + 3),
+            ModulationSource::ReleaseVelocity
+        );
+    }
+
+    #[test]
+    fn every_lfo_in_the_bank_has_its_own_source() {
+        let sources = (0..MAX_LFO_COUNT)
+            .map(ModulationSource::lfo)
+            .collect::<HashSet<ModulationSource>>();
+
+        assert_eq!(sources.len(), MAX_LFO_COUNT);
+        assert!(!sources.contains(&ModulationSource::None));
+        assert_eq!(ModulationSource::lfo(MAX_LFO_COUNT), ModulationSource::None);
+    }
+
+    #[test]
+    fn default_slots_produce_no_offsets() {
+        let slots: [ModulationSlotParameters; MOD_MATRIX_SLOT_COUNT] = Default::default();
+        let mut source_values = ModulationSourceValues::default();
+        source_values.set(ModulationSource::Velocity, 1.0);
+
+        let offsets = calculate_modulation_offsets(&slots, &source_values);
+
+        assert_eq!(offsets, ModulationOffsets::default());
+    }
+
+    #[test]
+    fn slot_scales_source_value_by_amount() {
+        let slots = [slot(
+            ModulationSource::Velocity,
+            ModulationDestination::FilterCutoff,
+            0.5,
+            ModulationSource::None,
+        )];
+        let mut source_values = ModulationSourceValues::default();
+        source_values.set(ModulationSource::Velocity, 0.8);
+
+        let offsets = calculate_modulation_offsets(&slots, &source_values);
+
+        assert!(f32s_are_equal(offsets.filter.cutoff, 0.4));
+    }
+
+    #[test]
+    fn via_source_scales_the_slot_output() {
+        let slots = [slot(
+            ModulationSource::ModWheelLfo,
+            ModulationDestination::OscillatorShapeParameter1(OscillatorIndex::One),
+            1.0,
+            ModulationSource::ModWheel,
+        )];
+        let mut source_values = ModulationSourceValues::default();
+        source_values.set(ModulationSource::ModWheelLfo, -0.5);
+        source_values.set(ModulationSource::ModWheel, 0.5);
+
+        let offsets = calculate_modulation_offsets(&slots, &source_values);
+
+        assert!(f32s_are_equal(
+            offsets.oscillators[OscillatorIndex::One as usize].shape_parameter1,
+            -0.25
+        ));
+    }
+
+    #[test]
+    fn slots_sharing_a_destination_are_summed() {
+        let slots = [
+            slot(
+                ModulationSource::Key,
+                ModulationDestination::EffectParameter(EffectIndex::Delay, 2),
+                0.5,
+                ModulationSource::None,
+            ),
+            slot(
+                ModulationSource::Aftertouch,
+                ModulationDestination::EffectParameter(EffectIndex::Delay, 2),
+                -0.25,
+                ModulationSource::None,
+            ),
+        ];
+        let mut source_values = ModulationSourceValues::default();
+        source_values.set(ModulationSource::Key, 1.0);
+        source_values.set(ModulationSource::Aftertouch, 1.0);
+
+        let offsets = calculate_modulation_offsets(&slots, &source_values);
+
+        assert!(f32s_are_equal(
+            offsets.effects[EffectIndex::Delay as usize][2],
+            0.25
+        ));
+    }
+
+    #[test]
+    fn assign_from_copies_every_slot_value() {
+        let preset = slot(
+            ModulationSource::PitchBend,
+            ModulationDestination::OscillatorBalance(OscillatorIndex::Three),
+            -0.75,
+            ModulationSource::Velocity,
+        );
+        let parameters = ModulationSlotParameters::default();
+
+        parameters.assign_from(&preset);
+
+        assert_eq!(parameters.source(), ModulationSource::PitchBend);
+        assert_eq!(
+            parameters.destination(),
+            ModulationDestination::OscillatorBalance(OscillatorIndex::Three)
+        );
+        assert!(f32s_are_equal(parameters.amount.load(), -0.75));
+        assert_eq!(parameters.via_source(), ModulationSource::Velocity);
+    }
+
+    #[test]
+    fn deserialize_modulation_slots_pads_a_short_list_with_empty_slots() {
+        let json = r#"[{"source": 1, "destination": 17, "amount": 0.5, "via_source": 0}]"#;
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+
+        let slots = deserialize_modulation_slots(&mut deserializer).unwrap();
+
+        assert_eq!(slots[0].source(), ModulationSource::ModWheelLfo);
+        assert_eq!(slots[0].destination(), ModulationDestination::FilterCutoff);
+        assert!(f32s_are_equal(slots[0].amount.load(), 0.5));
+        for slot in &slots[1..] {
+            assert_eq!(slot.source(), ModulationSource::None);
+            assert_eq!(slot.destination(), ModulationDestination::None);
+        }
+    }
+}
@@ -1,12 +1,19 @@
+/// Additive oscillator built from editable sine partials.
+pub mod additive;
 /// Amplitude modulation oscillator.
 pub mod am;
 mod broken;
 /// Shared oscillator constants for tuning, phase, and shape parameters.
 pub mod constants;
+/// Analog style random pitch drift and note start phase.
+pub mod drift;
 /// Frequency modulation oscillator.
 pub mod fm;
+/// Four-operator FM oscillator with selectable routing algorithms.
+pub mod four_op_fm;
 mod generate_wave_trait;
-/// White noise generator.
+mod min_blep;
+/// Coloured noise generator with sample and hold and a resonant bandpass.
 pub mod noise;
 /// Phase modulation oscillator
 pub mod pm;
@@ -14,6 +21,8 @@ pub mod pm;
 pub mod pulse;
 /// Ramp (reverse sawtooth) wave oscillator.
 pub mod ramp;
+/// Sample playback oscillator with one-shot and looped playback.
+pub mod sampler;
 /// Sawtooth wave oscillator.
 pub mod saw;
 /// Sine wave oscillator.
@@ -24,36 +33,48 @@ pub mod square;
 pub mod supersaw;
 /// Triangle wave oscillator.
 pub mod triangle;
+/// WAV file reading for the wavetable oscillator.
+pub mod wav_file;
+/// Wavetable oscillator with user-loadable WAV wavetables.
+pub mod wavetable;
 
+use self::additive::{Additive, AdditiveParameters};
 use self::am::AM;
 use self::broken::Broken;
 use self::constants::{
     DEFAULT_KEY_SYNC_ENABLED, DEFAULT_NOTE_FREQUENCY, DEFAULT_PORTAMENTO_TIME_IN_BUFFERS,
-    MAX_MIDI_NOTE_NUMBER, MAX_NOTE_FREQUENCY, MIN_MIDI_NOTE_NUMBER, MIN_NOTE_FREQUENCY,
+    MAX_CROSS_FM_INDEX, MAX_CROSS_PM_INDEX, MAX_NOTE_FREQUENCY, MIN_NOTE_FREQUENCY,
+    SEMITONES_PER_OCTAVE,
 };
+use self::drift::{Drift, DriftParameters};
 use self::fm::FM;
+use self::four_op_fm::{FourOpFM, FourOpFmParameters};
+use self::min_blep::MinBlep;
 use self::noise::Noise;
 use self::pm::PM;
 use self::pulse::Pulse;
 use self::ramp::Ramp;
+use self::sampler::{DEFAULT_SAMPLE_ROOT_NOTE, LoopMode, Sampler, SharedSample};
 use self::saw::Saw;
 use self::sine::Sine;
 use self::square::Square;
-use self::supersaw::Supersaw;
+use self::supersaw::{Supersaw, SupersawParameters};
 use self::triangle::Triangle;
+use self::wavetable::{SharedWavetable, WavetableOscillator};
+use crate::modules::mod_matrix::OscillatorModulation;
 use crate::modules::oscillator::constants::{
     DEFAULT_HARD_SYNC_ENABLED, DEFAULT_PORTAMENTO_ENABLED, MAX_CLIP_BOOST,
 };
-use accsyn_core::defaults::Defaults;
+use crate::modules::tuning::{TuningParameters, VoiceTuning, equal_temperament_frequency};
 use accsyn_core::math;
 use accsyn_core::math::{MIDI_DATA_BYTE_7BIT_MASK, dbfs_to_f32_sample, f32s_are_equal};
 use accsyn_core::parameter_types::{
     BiPolarNormalizedValue, Cents, NormalizedValue, PitchBend, PortamentoBuffers, Semitones,
 };
+use accsyn_core::synth_events::OscillatorIndex;
 use generate_wave_trait::GenerateWave;
-use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use std::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::sync::atomic::Ordering::{Acquire, Relaxed};
 use std::sync::atomic::{AtomicBool, AtomicU8};
 use strum::EnumCount as StrumEnumCount;
 use strum_macros::{EnumCount, EnumIter, FromRepr};
@@ -64,6 +85,15 @@ pub const FIRST_WAVE_SHAPE_INDEX: u32 = 0;
 // COUNT is capped by the number of possible wave shapes which will always less than u32::MAX
 #[allow(clippy::cast_possible_truncation)]
 pub const LAST_WAVE_SHAPE_INDEX: u32 = (WaveShape::COUNT - 1) as u32;
+/// Index of the last wave shape an LFO can use. Wavetable and Sampler need loaded sample data, and
+/// FourOpFM and Additive read their operators and partials from an oscillator's parameters, none of
+/// which LFOs or the tremolo and autopan effects have, so they stop at Noise.
+pub const LAST_LFO_WAVE_SHAPE_INDEX: u32 = WaveShape::Noise as u32;
+/// Index of the last cross modulation routing, Off followed by every other mode paired with each source oscillator.
+// Both counts are tiny, so the product is always far below u32::MAX
+#[allow(clippy::cast_possible_truncation)]
+pub const LAST_CROSS_MODULATION_ROUTING_INDEX: u32 =
+    ((CrossModulationMode::COUNT - 1) * OscillatorIndex::COUNT) as u32;
 
 /// Available waveform shapes for oscillator generation.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
@@ -92,8 +122,16 @@ pub enum WaveShape {
     PM,
     /// Honestly, I have no idea what this is
     Broken,
-    /// White noise.
+    /// Coloured noise with optional sample and hold or resonant bandpass.
     Noise,
+    /// Wavetable loaded from a WAV file, scanned by shape parameter 1.
+    Wavetable,
+    /// Sample played from a WAV file, pitched relative to its root note.
+    Sampler,
+    /// Four-operator FM synthesis with selectable algorithms and per-operator envelopes.
+    FourOpFM,
+    /// Additive synthesis from up to 64 sine partials with spectral macros.
+    Additive,
 }
 
 impl WaveShape {
@@ -102,18 +140,114 @@ impl WaveShape {
     pub fn from_index(index: u8) -> Self {
         Self::from_repr(index).unwrap_or_default()
     }
+
+    /// Returns whether the shape repeats a cycle, so a start phase is a point in that cycle. Noise has no
+    /// cycle and the sampler's phase is a position in the whole sample.
+    #[must_use]
+    pub fn is_periodic(self) -> bool {
+        !matches!(self, Self::Noise | Self::Sampler)
+    }
+}
+
+/// How another oscillator's output modulates an oscillator at audio rate.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
+#[repr(u8)]
+pub enum CrossModulationMode {
+    /// No cross modulation.
+    #[default]
+    Off,
+    /// Linear frequency modulation that can push the frequency through zero.
+    LinearFM,
+    /// Phase modulation.
+    PhaseModulation,
+    /// Ring modulation, multiplying the two oscillator outputs.
+    RingModulation,
+}
+
+impl CrossModulationMode {
+    /// Converts a numeric index to the corresponding cross modulation mode, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
+}
+
+/// Which oscillator restarts an oscillator's cycle when hard sync is enabled.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
+#[repr(u8)]
+pub enum HardSyncSource {
+    /// Oscillator 2 follows Oscillator 1 and the others run free, the pairing before sync sources were selectable.
+    #[default]
+    Classic,
+    /// Never synced.
+    Off,
+    /// Synced to the sub oscillator.
+    Sub,
+    /// Synced to Oscillator 1.
+    One,
+    /// Synced to Oscillator 2.
+    Two,
+    /// Synced to Oscillator 3.
+    Three,
+}
+
+impl HardSyncSource {
+    /// Converts a numeric index to the corresponding hard sync source, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
+
+    /// Returns the index of the oscillator that syncs the oscillator at `oscillator_index`, if any. An
+    /// oscillator never syncs to itself.
+    #[must_use]
+    pub fn source_oscillator(self, oscillator_index: usize) -> Option<usize> {
+        let source = match self {
+            Self::Classic => {
+                (oscillator_index == OscillatorIndex::Two as usize).then_some(OscillatorIndex::One)
+            }
+            Self::Off => None,
+            Self::Sub => Some(OscillatorIndex::Sub),
+            Self::One => Some(OscillatorIndex::One),
+            Self::Two => Some(OscillatorIndex::Two),
+            Self::Three => Some(OscillatorIndex::Three),
+        };
+
+        source
+            .map(|source| source as usize)
+            .filter(|source| *source != oscillator_index)
+    }
 }
 
-/// Role an oscillator plays in hard sync (source, synced, or none).
-#[derive(Default, Debug)]
-pub enum HardSyncRole {
-    /// No hard sync role assigned.
+/// How far below the played note the sub oscillator sounds.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
+#[repr(u8)]
+pub enum SubOctave {
+    /// One octave down.
     #[default]
-    None,
-    /// Source oscillator that triggers sync resets via the shared flag.
-    Source(Arc<AtomicBool>),
-    /// Synced oscillator that resets its phase when the shared flag is set.
-    Synced(Arc<AtomicBool>),
+    OneDown,
+    /// Two octaves down.
+    TwoDown,
+    /// No octave shift, so the sub plays as a fourth full oscillator.
+    Off,
+}
+
+impl SubOctave {
+    /// Converts a numeric index to the corresponding sub octave, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
+
+    /// Returns the pitch offset of the sub octave in semitones.
+    #[must_use]
+    pub fn semitones(self) -> i8 {
+        match self {
+            Self::OneDown => -12,
+            Self::TwoDown => -24,
+            Self::Off => 0,
+        }
+    }
 }
 
 /// Shared atomic parameters for controlling an oscillator from the UI thread.
@@ -134,10 +268,14 @@ pub struct OscillatorParameters {
     pub wave_shape_index: AtomicU8,
     /// Flag indicating a new note gate event has occurred.
     pub gate_flag: AtomicBool,
+    /// Flag indicating a legato note change that glides to the new pitch without a retrigger.
+    pub glide_flag: AtomicBool,
     /// Whether key sync (phase reset on note-on) is enabled.
     pub key_sync_enabled: AtomicBool,
     /// Whether hard sync between oscillators is enabled.
     pub hard_sync_enabled: AtomicBool,
+    /// Index selecting the oscillator that restarts this one's cycle when hard sync is enabled.
+    pub hard_sync_source: AtomicU8,
     /// Whether portamento (pitch glide) is enabled.
     pub portamento_enabled: AtomicBool,
     /// Portamento glide time in buffer increments.
@@ -146,6 +284,32 @@ pub struct OscillatorParameters {
     pub clipper_boost: AtomicU8,
     /// Pitch Envelope Amount
     pub pitch_envelope_amount: BiPolarNormalizedValue,
+    /// Wavetable played by the wavetable wave shape, stored in patches by name.
+    pub wavetable: SharedWavetable,
+    /// Sample played by the sampler wave shape, stored in patches by name.
+    pub sample: SharedSample,
+    /// MIDI note that plays the sample back at its recorded pitch.
+    pub sample_root_note: AtomicU8,
+    /// Index selecting the sampler loop mode.
+    pub sample_loop_mode: AtomicU8,
+    /// Operator settings and algorithm for the four-operator FM wave shape.
+    pub four_op_fm: FourOpFmParameters,
+    /// Voice count, stereo spread and phase settings for the supersaw wave shape.
+    pub supersaw: SupersawParameters,
+    /// Partial amplitudes for the additive wave shape.
+    pub additive: AdditiveParameters,
+    /// Index selecting how the cross modulation source modulates this oscillator.
+    pub cross_modulation_mode: AtomicU8,
+    /// Index of the oscillator whose output modulates this one.
+    pub cross_modulation_source: AtomicU8,
+    /// Depth of the cross modulation.
+    pub cross_modulation_depth: NormalizedValue,
+    /// Index selecting the octave the sub oscillator plays in, ignored by the other oscillators.
+    pub sub_octave: AtomicU8,
+    /// Whether the shape parameters colour and process the noise wave shape, missing from patches saved when
+    /// noise was always white and ignored them.
+    #[serde(default)]
+    pub shaped_noise: AtomicBool,
 }
 
 impl OscillatorParameters {
@@ -162,10 +326,16 @@ impl OscillatorParameters {
             .store(parameters.wave_shape_index.load(Relaxed), Relaxed);
         self.gate_flag
             .store(parameters.gate_flag.load(Relaxed), Relaxed);
+        self.glide_flag
+            .store(parameters.glide_flag.load(Relaxed), Relaxed);
         self.key_sync_enabled
             .store(parameters.key_sync_enabled.load(Relaxed), Relaxed);
         self.hard_sync_enabled
             .store(parameters.hard_sync_enabled.load(Relaxed), Relaxed);
+        self.hard_sync_source.store(
+            HardSyncSource::from_index(parameters.hard_sync_source.load(Relaxed)) as u8,
+            Relaxed,
+        );
         self.portamento_enabled
             .store(parameters.portamento_enabled.load(Relaxed), Relaxed);
         self.portamento_time
@@ -174,9 +344,69 @@ impl OscillatorParameters {
             .store(parameters.clipper_boost.load(Relaxed), Relaxed);
         self.pitch_envelope_amount
             .store(parameters.pitch_envelope_amount.load());
+        self.wavetable.assign_from(&parameters.wavetable);
+        self.sample.assign_from(&parameters.sample);
+        self.sample_root_note.store(
+            parameters
+                .sample_root_note
+                .load(Relaxed)
+                .min(MIDI_DATA_BYTE_7BIT_MASK),
+            Relaxed,
+        );
+        self.sample_loop_mode.store(
+            LoopMode::from_index(parameters.sample_loop_mode.load(Relaxed)) as u8,
+            Relaxed,
+        );
+        self.four_op_fm.assign_from(&parameters.four_op_fm);
+        self.supersaw.assign_from(&parameters.supersaw);
+        self.additive.assign_from(&parameters.additive);
+        self.cross_modulation_mode.store(
+            CrossModulationMode::from_index(parameters.cross_modulation_mode.load(Relaxed)) as u8,
+            Relaxed,
+        );
+        self.cross_modulation_source.store(
+            cross_modulation_source_index(parameters.cross_modulation_source.load(Relaxed)),
+            Relaxed,
+        );
+        self.cross_modulation_depth
+            .store(parameters.cross_modulation_depth.load());
+        self.sub_octave.store(
+            SubOctave::from_index(parameters.sub_octave.load(Relaxed)) as u8,
+            Relaxed,
+        );
+        self.shaped_noise
+            .store(parameters.shaped_noise.load(Relaxed), Relaxed);
+    }
+
+    // A patch saved while the noise ignored the shape parameters may hold any values in them, so they are cleared
+    // to keep its noise white
+    fn migrate_unshaped_noise(&self) {
+        if self.shaped_noise.swap(true, Relaxed) {
+            return;
+        }
+        if WaveShape::from_index(self.wave_shape_index.load(Relaxed)) == WaveShape::Noise {
+            self.shape_parameter1.store(0.0);
+            self.shape_parameter2.store(0.0);
+        }
     }
 }
 
+/// Deserializes the oscillators stored in a patch, clearing the shape parameters of noise oscillators saved
+/// before the noise wave shape read them.
+///
+/// # Errors
+///
+/// Returns an error if the stored oscillators are not a list of every oscillator's parameters.
+pub fn deserialize_oscillators<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<[OscillatorParameters; OscillatorIndex::COUNT], D::Error> {
+    let oscillators = <[OscillatorParameters; OscillatorIndex::COUNT]>::deserialize(deserializer)?;
+    for oscillator in &oscillators {
+        oscillator.migrate_unshaped_noise();
+    }
+    Ok(oscillators)
+}
+
 impl Default for OscillatorParameters {
     fn default() -> Self {
         Self {
@@ -187,12 +417,26 @@ impl Default for OscillatorParameters {
             shape_parameter2: NormalizedValue::default(),
             wave_shape_index: AtomicU8::new(WaveShape::default() as u8),
             gate_flag: AtomicBool::new(false),
+            glide_flag: AtomicBool::new(false),
             key_sync_enabled: AtomicBool::new(DEFAULT_KEY_SYNC_ENABLED),
             hard_sync_enabled: AtomicBool::new(DEFAULT_HARD_SYNC_ENABLED),
+            hard_sync_source: AtomicU8::new(HardSyncSource::default() as u8),
             portamento_enabled: AtomicBool::new(DEFAULT_PORTAMENTO_ENABLED),
             portamento_time: PortamentoBuffers::new(DEFAULT_PORTAMENTO_TIME_IN_BUFFERS),
             clipper_boost: AtomicU8::new(0),
             pitch_envelope_amount: BiPolarNormalizedValue::default(),
+            wavetable: SharedWavetable::default(),
+            sample: SharedSample::default(),
+            sample_root_note: AtomicU8::new(DEFAULT_SAMPLE_ROOT_NOTE),
+            sample_loop_mode: AtomicU8::new(LoopMode::default() as u8),
+            four_op_fm: FourOpFmParameters::default(),
+            supersaw: SupersawParameters::default(),
+            additive: AdditiveParameters::default(),
+            cross_modulation_mode: AtomicU8::new(CrossModulationMode::default() as u8),
+            cross_modulation_source: AtomicU8::new(OscillatorIndex::Sub as u8),
+            cross_modulation_depth: NormalizedValue::default(),
+            sub_octave: AtomicU8::new(SubOctave::default() as u8),
+            shaped_noise: AtomicBool::new(true),
         }
     }
 }
@@ -204,6 +448,7 @@ struct Portamento {
     target_frequency: f32,
     increment: f32,
     recalculate_increment: bool,
+    is_legato_glide: bool,
 }
 
 impl Default for Portamento {
@@ -214,6 +459,7 @@ impl Default for Portamento {
             target_frequency: DEFAULT_NOTE_FREQUENCY,
             increment: 0.0,
             recalculate_increment: false,
+            is_legato_glide: false,
         }
     }
 }
@@ -222,17 +468,85 @@ impl Default for Portamento {
 #[derive(Debug)]
 pub struct HardSync {
     is_enabled: bool,
+    source: HardSyncSource,
     last_sample: f32,
-    sync_role: HardSyncRole,
+    // Samples since the source started a new cycle, when it did so during the current sample
+    trigger: Option<f32>,
+    // Samples since this oscillator's own output started a new cycle, passed on to the oscillators it syncs
+    cycle_start: Option<f32>,
+    min_blep: MinBlep,
 }
 
 impl Default for HardSync {
     fn default() -> Self {
         Self {
-            sync_role: HardSyncRole::None,
             is_enabled: false,
+            source: HardSyncSource::default(),
             last_sample: 0.0,
+            trigger: None,
+            cycle_start: None,
+            min_blep: MinBlep::new(),
+        }
+    }
+}
+
+impl HardSync {
+    // A new cycle starts where the output crosses zero going up, interpolated between the two samples
+    fn detect_cycle_start(&mut self, next_sample: f32) {
+        self.cycle_start = (self.last_sample < 0.0 && next_sample >= 0.0)
+            .then(|| next_sample / (next_sample - self.last_sample));
+        self.last_sample = next_sample;
+    }
+}
+
+/// Tracks the audio rate modulation an oscillator receives from another oscillator.
+#[derive(Debug, Default, Copy, Clone)]
+struct CrossModulation {
+    mode: CrossModulationMode,
+    source: usize,
+    depth: f32,
+    input: f32,
+    previous_input: f32,
+}
+
+impl CrossModulation {
+    // Both FM and PM are applied through the frequency multiplier every wave shape already accepts, so the
+    // modulation works the same whichever shape is selected
+    fn frequency_modulation(
+        &self,
+        modulation: Option<f32>,
+        frequency: f32,
+        sample_rate: u32,
+    ) -> Option<f32> {
+        let frequency_multiplier = modulation.unwrap_or(1.0);
+        let deviation = match self.mode {
+            CrossModulationMode::LinearFM => self.depth * MAX_CROSS_FM_INDEX * self.input,
+            CrossModulationMode::PhaseModulation if frequency * frequency_multiplier > 0.0 => {
+                // Moving the phase by the change in the modulator is the same as briefly changing the frequency
+                let phase_offset =
+                    self.depth * MAX_CROSS_PM_INDEX * (self.input - self.previous_input);
+                // Sample rate is always ≤ 192_000, within f32 precision (2²³ = 8_388_608)
+                #[allow(clippy::cast_precision_loss)]
+                let sample_rate = sample_rate as f32;
+                phase_offset / std::f32::consts::TAU * sample_rate
+                    / (frequency * frequency_multiplier)
+            }
+            _ => return modulation,
+        };
+
+        if deviation == 0.0 {
+            return modulation;
         }
+
+        Some(frequency_multiplier * (1.0 + deviation))
+    }
+
+    fn ring_modulate(&self, sample: f32) -> f32 {
+        if self.mode != CrossModulationMode::RingModulation {
+            return sample;
+        }
+
+        sample * (1.0 - self.depth + self.depth * self.input)
     }
 }
 
@@ -241,9 +555,12 @@ impl Default for HardSync {
 pub struct Tuning {
     frequency: f32,
     pitch_bend: i16,
+    note_pitch_bend: i16,
     course: i8,
     fine: i8,
     is_sub: bool,
+    sub_octave: SubOctave,
+    note_number: u8,
 }
 
 impl Default for Tuning {
@@ -251,9 +568,12 @@ impl Default for Tuning {
         Self {
             frequency: DEFAULT_NOTE_FREQUENCY,
             pitch_bend: 0,
+            note_pitch_bend: 0,
             course: 0,
             fine: 0,
             is_sub: false,
+            sub_octave: SubOctave::default(),
+            note_number: 0,
         }
     }
 }
@@ -270,6 +590,12 @@ pub struct Oscillator {
     hard_sync: HardSync,
     portamento: Portamento,
     aftertouch: f32,
+    wavetable_version: Option<u32>,
+    sample_version: Option<u32>,
+    cross_modulation: CrossModulation,
+    drift: Drift,
+    microtuning: VoiceTuning,
+    stereo_sample: (f32, f32),
 }
 
 impl Oscillator {
@@ -290,6 +616,12 @@ impl Oscillator {
             pitch_envelope_amount: 0.0,
             clipper_boost: 0,
             aftertouch: 0.0,
+            wavetable_version: None,
+            sample_version: None,
+            cross_modulation: CrossModulation::default(),
+            drift: Drift::new(sample_rate),
+            microtuning: VoiceTuning::default(),
+            stereo_sample: (0.0, 0.0),
         }
     }
 
@@ -298,18 +630,37 @@ impl Oscillator {
         self.set_shape_parameter1(parameters.shape_parameter1.load());
         self.set_shape_parameter2(parameters.shape_parameter2.load());
         self.set_wave_shape_index(parameters.wave_shape_index.load(Relaxed));
+        self.set_wavetable(&parameters.wavetable);
+        self.set_sample(&parameters.sample);
+        self.wave_generator.set_sample_playback(
+            parameters.sample_root_note.load(Relaxed),
+            LoopMode::from_index(parameters.sample_loop_mode.load(Relaxed)),
+        );
+        self.wave_generator.set_four_op_fm(&parameters.four_op_fm);
+        self.wave_generator.set_supersaw(&parameters.supersaw);
+        self.wave_generator.set_additive(&parameters.additive);
         self.set_pitch_bend(parameters.pitch_bend.load());
         self.set_course_tune(parameters.course_tune.load());
         self.set_fine_tune(parameters.fine_tune.load());
+        self.set_sub_octave(SubOctave::from_index(parameters.sub_octave.load(Relaxed)));
         self.set_key_sync_enabled(parameters.key_sync_enabled.load(Relaxed));
         self.set_hard_sync_enabled(parameters.hard_sync_enabled.load(Relaxed));
+        self.set_hard_sync_source(HardSyncSource::from_index(
+            parameters.hard_sync_source.load(Relaxed),
+        ));
         self.set_portamento(
             parameters.portamento_enabled.load(Relaxed),
             parameters.portamento_time.load(),
         );
         self.set_gate(&parameters.gate_flag);
+        self.set_glide(&parameters.glide_flag);
         self.set_clipper_boost(parameters.clipper_boost.load(Relaxed));
         self.set_pitch_envelope_amount(parameters.pitch_envelope_amount.load());
+        self.set_cross_modulation(
+            CrossModulationMode::from_index(parameters.cross_modulation_mode.load(Relaxed)),
+            parameters.cross_modulation_source.load(Relaxed),
+            parameters.cross_modulation_depth.load(),
+        );
     }
 
     /// Generates the next audio sample with optional modulation input.
@@ -328,40 +679,105 @@ impl Oscillator {
             0.0
         };
 
-        let adjusted_frequency =
-            (self.tuning.frequency + pitch_envelope_offset).min(MAX_NOTE_FREQUENCY);
+        let adjusted_frequency = ((self.tuning.frequency + pitch_envelope_offset)
+            * self.drift.next_frequency_multiplier())
+        .min(MAX_NOTE_FREQUENCY);
+        let modulation = self.cross_modulation.frequency_modulation(
+            modulation,
+            adjusted_frequency,
+            self.sample_rate,
+        );
         let mut next_sample = self
             .wave_generator
             .next_sample(adjusted_frequency, modulation);
 
-        next_sample = self.clip_signal(next_sample);
-
-        if !self.hard_sync.is_enabled {
-            return next_sample;
+        if let Some(delay) = self
+            .hard_sync
+            .trigger
+            .take()
+            .filter(|_| self.hard_sync.is_enabled)
+        {
+            next_sample = self.hard_sync_reset(next_sample, adjusted_frequency, modulation, delay);
         }
 
-        self.perform_sync_role(next_sample);
-        self.hard_sync.last_sample = next_sample;
+        next_sample += self.hard_sync.min_blep.next_correction();
+        let side_sample = self.wave_generator.side_sample();
+        let clipped_sample = self.clip_signal(next_sample);
+        self.hard_sync.detect_cycle_start(clipped_sample);
 
-        next_sample
+        let output_sample = self.cross_modulation.ring_modulate(clipped_sample);
+        self.stereo_sample = if side_sample == 0.0 {
+            (output_sample, output_sample)
+        } else {
+            // Each channel is clipped on its own so a spread signal saturates the same way as a centred one
+            let left_sample = self.clip_signal(next_sample + side_sample);
+            let right_sample = self.clip_signal(next_sample - side_sample);
+            (
+                self.cross_modulation.ring_modulate(left_sample),
+                self.cross_modulation.ring_modulate(right_sample),
+            )
+        };
+
+        output_sample
     }
 
-    fn perform_sync_role(&mut self, next_sample: f32) {
-        match &self.hard_sync.sync_role {
-            HardSyncRole::None => {}
-            HardSyncRole::Source(buffer) => {
-                if self.hard_sync.last_sample.is_sign_negative() && next_sample.is_sign_positive() {
-                    buffer.store(true, Relaxed);
-                }
-            }
-            HardSyncRole::Synced(buffer) => {
-                let sync_state = buffer.load(Acquire);
-                if sync_state {
-                    self.wave_generator.reset();
-                    buffer.store(false, Release);
-                }
-            }
+    /// Returns the left and right output of the last `generate`, both equal to its mono output unless the wave
+    /// shape spreads across the stereo field.
+    #[must_use]
+    pub fn stereo_sample(&self) -> (f32, f32) {
+        self.stereo_sample
+    }
+
+    // Restarts the cycle and smooths the jump with a band-limited step, as a plain reset aliases badly when the
+    // synced oscillator runs far above its source
+    fn hard_sync_reset(
+        &mut self,
+        continuing_sample: f32,
+        frequency: f32,
+        modulation: Option<f32>,
+        delay: f32,
+    ) -> f32 {
+        self.wave_generator.reset();
+        // The source restarted its cycle `delay` samples ago, so the new cycle has already run for that long
+        if delay > 0.0 {
+            self.wave_generator.advance(frequency, modulation, delay);
         }
+        let restarted_sample = self.wave_generator.next_sample(frequency, modulation);
+        self.hard_sync
+            .min_blep
+            .add_step(restarted_sample - continuing_sample, delay);
+        restarted_sample
+    }
+
+    /// Returns the hard sync source selected for this oscillator.
+    #[must_use]
+    pub fn hard_sync_source(&self) -> HardSyncSource {
+        self.hard_sync.source
+    }
+
+    /// Sets how many samples ago the hard sync source started a new cycle, or `None` if it did not, called
+    /// before each `generate`.
+    pub fn set_hard_sync_trigger(&mut self, trigger: Option<f32>) {
+        self.hard_sync.trigger = trigger;
+    }
+
+    /// Returns how many samples ago this oscillator's output started a new cycle during the last `generate`,
+    /// or `None` if it did not.
+    #[must_use]
+    pub fn hard_sync_trigger(&self) -> Option<f32> {
+        self.hard_sync.cycle_start
+    }
+
+    /// Returns the index of the oscillator whose output modulates this one.
+    #[must_use]
+    pub fn cross_modulation_source(&self) -> usize {
+        self.cross_modulation.source
+    }
+
+    /// Sets the latest output of the cross modulation source, called before each `generate`.
+    pub fn set_cross_modulation_input(&mut self, input: f32) {
+        self.cross_modulation.previous_input = self.cross_modulation.input;
+        self.cross_modulation.input = input;
     }
 
     /// Changes the oscillator's waveform shape, replacing the wave generator if different.
@@ -372,6 +788,8 @@ impl Oscillator {
 
         log::info!(target: "synthesizer::modules::oscillator", "Setting Oscillator Shape to {wave_shape:#?}");
         self.wave_generator = get_wave_generator_from_wave_shape(self.sample_rate, wave_shape);
+        self.wavetable_version = None;
+        self.sample_version = None;
     }
 
     /// Sets the wave shape by numeric index, updating the generator if changed.
@@ -395,16 +813,35 @@ impl Oscillator {
         self.wave_generator.set_phase(phase);
     }
 
-    /// Assigns a hard sync role (source, synced, or none) to this oscillator.
-    pub fn set_hard_sync_role(&mut self, sync_role: HardSyncRole) {
-        self.hard_sync.sync_role = sync_role;
-    }
-
     /// Resets the oscillator's wave generator phase to the initial position.
     pub fn reset(&mut self) {
         self.wave_generator.reset();
     }
 
+    /// Clears everything a played note leaves behind (phase, glide, hard sync, cross modulation and drift) while
+    /// keeping the wave generator and settings, so a voice can be reused without allocating on the audio thread.
+    pub(crate) fn reset_voice_state(&mut self) {
+        self.wave_generator.reset();
+        self.portamento = Portamento {
+            is_enabled: self.portamento.is_enabled,
+            time: self.portamento.time,
+            ..Portamento::default()
+        };
+        self.hard_sync = HardSync {
+            is_enabled: self.hard_sync.is_enabled,
+            source: self.hard_sync.source,
+            ..HardSync::default()
+        };
+        self.cross_modulation = CrossModulation {
+            input: 0.0,
+            previous_input: 0.0,
+            ..self.cross_modulation
+        };
+        self.drift.reseed();
+        self.tuning.note_number = 0;
+        self.stereo_sample = (0.0, 0.0);
+    }
+
     /// Sets the MIDI aftertouch pressure value for clipper modulation.
     pub fn set_aftertouch(&mut self, aftertouch: f32) {
         self.aftertouch = aftertouch;
@@ -421,39 +858,48 @@ impl Oscillator {
         boosted_signal.clamp(-1.0, 1.0)
     }
 
-    /// Calculates and sets the oscillator frequency from a MIDI note number with tuning offsets.
-    pub fn tune(&mut self, mut note_number: u8) {
-        if self.tuning.course != 0 {
-            // Post-clamp to [MIN_MIDI_NOTE_NUMBER, MAX_MIDI_NOTE_NUMBER] (0–127), within u8 range
-            #[allow(clippy::cast_sign_loss)]
-            let clamped = i16::from(note_number)
-                .saturating_add(i16::from(self.tuning.course))
-                .clamp(MIN_MIDI_NOTE_NUMBER, MAX_MIDI_NOTE_NUMBER) as u8;
-            note_number = clamped;
+    /// Calculates and sets the oscillator frequency from a MIDI note number through the selected tuning, with
+    /// the tuning offsets applied.
+    pub fn tune(&mut self, note_number: u8) {
+        // A note change without a new gate (paraphonic note reassignment) still needs a fresh glide
+        if note_number != self.tuning.note_number {
+            self.tuning.note_number = note_number;
+            self.portamento.recalculate_increment = true;
         }
 
-        if self.tuning.is_sub {
-            // Post-clamp to [MIN_MIDI_NOTE_NUMBER, MAX_MIDI_NOTE_NUMBER] (0–127), within u8 range
-            #[allow(clippy::cast_sign_loss)]
-            let clamped = i16::from(note_number)
-                .saturating_sub(12)
-                .clamp(MIN_MIDI_NOTE_NUMBER, MAX_MIDI_NOTE_NUMBER) as u8;
-            note_number = clamped;
-        }
+        let mut note_frequency = self.microtuning.frequency(note_number);
+
+        // The course tune and sub octave transpose the played note's tuned frequency by equal tempered
+        // semitones, so a sub octave stays an octave below in any scale. Fine tune, portamento, pitch bend,
+        // and the pitch envelope all work from the shifted pitch exactly as they do for the other oscillators
+        let sub_octave = if self.tuning.is_sub {
+            self.tuning.sub_octave.semitones()
+        } else {
+            0
+        };
+        let transposition = i16::from(self.tuning.course) + i16::from(sub_octave);
 
-        let mut note_frequency = midi_note_to_frequency(note_number);
+        if transposition != 0 {
+            note_frequency = (note_frequency
+                * 2.0_f32.powf(f32::from(transposition) / SEMITONES_PER_OCTAVE))
+            .clamp(MIN_NOTE_FREQUENCY, MAX_NOTE_FREQUENCY);
+        }
 
         if self.tuning.fine != 0 {
             note_frequency =
                 math::frequency_from_cents(note_frequency, i16::from(self.tuning.fine));
         }
 
-        if self.portamento.is_enabled {
+        if self.portamento.is_enabled || self.portamento.is_legato_glide {
             note_frequency = self.run_portamento(note_frequency);
         }
 
-        if self.tuning.pitch_bend != 0 {
-            note_frequency = math::frequency_from_cents(note_frequency, self.tuning.pitch_bend)
+        let pitch_bend = self
+            .tuning
+            .pitch_bend
+            .saturating_add(self.tuning.note_pitch_bend);
+        if pitch_bend != 0 {
+            note_frequency = math::frequency_from_cents(note_frequency, pitch_bend)
                 .clamp(MIN_NOTE_FREQUENCY, MAX_NOTE_FREQUENCY);
         }
 
@@ -492,16 +938,37 @@ impl Oscillator {
         self.portamento.recalculate_increment = false;
     }
 
-    fn set_gate(&mut self, gate_flag: &AtomicBool) {
+    /// Consumes the gate flag, resetting the phase on key sync and restarting the portamento glide.
+    pub(crate) fn set_gate(&mut self, gate_flag: &AtomicBool) {
         let gate_on = gate_flag.swap(false, Acquire);
         if !gate_on {
             return;
         }
 
         self.portamento.recalculate_increment = true;
+        self.portamento.is_legato_glide = false;
 
         if self.key_sync_enabled {
             self.reset();
+
+            let start_phase = self
+                .wave_generator
+                .shape()
+                .is_periodic()
+                .then(|| self.drift.start_phase())
+                .flatten();
+            if let Some(phase) = start_phase {
+                self.set_phase(phase);
+            }
+        }
+
+        self.wave_generator.retrigger();
+    }
+
+    fn set_glide(&mut self, glide_flag: &AtomicBool) {
+        if glide_flag.swap(false, Acquire) {
+            self.portamento.recalculate_increment = true;
+            self.portamento.is_legato_glide = true;
         }
     }
 
@@ -518,6 +985,10 @@ impl Oscillator {
         self.hard_sync.is_enabled = hard_sync_enabled;
     }
 
+    fn set_hard_sync_source(&mut self, source: HardSyncSource) {
+        self.hard_sync.source = source;
+    }
+
     fn set_pitch_bend(&mut self, pitch_bend: i16) {
         self.tuning.pitch_bend = pitch_bend;
     }
@@ -530,11 +1001,50 @@ impl Oscillator {
         self.tuning.fine = fine_tune;
     }
 
-    /// Enables or disables sub-oscillator mode, which tunes down one octave.
+    fn set_sub_octave(&mut self, sub_octave: SubOctave) {
+        self.tuning.sub_octave = sub_octave;
+    }
+
+    /// Updates the analog drift depth and seed from the shared drift parameters.
+    pub(crate) fn set_drift(&mut self, parameters: &DriftParameters) {
+        self.drift
+            .set_parameters(parameters.depth.load(), parameters.seed.load(Relaxed));
+    }
+
+    /// Sets the MPE per-note pitch bend in cents, added on top of the pitch bend wheel.
+    pub(crate) fn set_note_pitch_bend(&mut self, cents: i16) {
+        self.tuning.note_pitch_bend = cents;
+    }
+
+    /// Picks up the selected scale and keyboard mapping and the global master tuning.
+    pub(crate) fn set_microtuning(&mut self, parameters: &TuningParameters) {
+        self.microtuning.update(parameters);
+    }
+
+    /// Selects this oscillator's own random drift sequence, so oscillators sharing a seed drift independently.
+    pub(crate) fn set_drift_stream(&mut self, stream: u32) {
+        self.drift.set_stream(stream);
+    }
+
+    /// Enables or disables sub-oscillator mode, which tunes down by the selected sub octave.
     pub fn set_is_sub_oscillator(&mut self, is_sub_oscillator: bool) {
         self.tuning.is_sub = is_sub_oscillator;
     }
 
+    /// Offsets the wave shape parameters by the modulation matrix, clamped to the normalized range.
+    pub(crate) fn set_shape_modulation(
+        &mut self,
+        parameters: &OscillatorParameters,
+        modulation: &OscillatorModulation,
+    ) {
+        self.set_shape_parameter1(
+            (parameters.shape_parameter1.load() + modulation.shape_parameter1).clamp(0.0, 1.0),
+        );
+        self.set_shape_parameter2(
+            (parameters.shape_parameter2.load() + modulation.shape_parameter2).clamp(0.0, 1.0),
+        );
+    }
+
     fn set_shape_parameter1(&mut self, parameter: f32) {
         self.wave_generator.set_shape_parameter1(parameter);
     }
@@ -543,6 +1053,32 @@ impl Oscillator {
         self.wave_generator.set_shape_parameter2(parameter);
     }
 
+    // The lock is only tried, never waited on, so a wavetable being loaded is picked up on a later buffer
+    fn set_wavetable(&mut self, shared_wavetable: &SharedWavetable) {
+        let version = shared_wavetable.version();
+        if self.wavetable_version == Some(version) {
+            return;
+        }
+
+        if let Some(wavetable) = shared_wavetable.try_wavetable() {
+            self.wave_generator.set_wavetable(wavetable);
+            self.wavetable_version = Some(version);
+        }
+    }
+
+    // Same as set_wavetable, a sample still loading is picked up on a later buffer
+    fn set_sample(&mut self, shared_sample: &SharedSample) {
+        let version = shared_sample.version();
+        if self.sample_version == Some(version) {
+            return;
+        }
+
+        if let Some(sample) = shared_sample.try_sample() {
+            self.wave_generator.set_sample(sample);
+            self.sample_version = Some(version);
+        }
+    }
+
     fn set_clipper_boost(&mut self, clipper_boost: u8) {
         self.clipper_boost = clipper_boost;
     }
@@ -550,6 +1086,16 @@ impl Oscillator {
     fn set_pitch_envelope_amount(&mut self, pitch_envelope_amount: f32) {
         self.pitch_envelope_amount = pitch_envelope_amount;
     }
+
+    fn set_cross_modulation(&mut self, mode: CrossModulationMode, source: u8, depth: f32) {
+        self.cross_modulation.mode = mode;
+        self.cross_modulation.source = usize::from(cross_modulation_source_index(source));
+        self.cross_modulation.depth = depth.clamp(0.0, 1.0);
+    }
+}
+
+fn cross_modulation_source_index(index: u8) -> u8 {
+    index.min(OscillatorIndex::Three as u8)
 }
 
 fn get_wave_generator_from_wave_shape(
@@ -568,16 +1114,23 @@ fn get_wave_generator_from_wave_shape(
         WaveShape::FM => Box::new(FM::new(sample_rate)),
         WaveShape::PM => Box::new(PM::new(sample_rate)),
         WaveShape::Broken => Box::new(Broken::new(sample_rate)),
-        WaveShape::Noise => Box::new(Noise::new()),
+        WaveShape::Noise => Box::new(Noise::new(sample_rate)),
+        WaveShape::Wavetable => Box::new(WavetableOscillator::new(sample_rate)),
+        WaveShape::Sampler => Box::new(Sampler::new(sample_rate)),
+        WaveShape::FourOpFM => Box::new(FourOpFM::new(sample_rate)),
+        WaveShape::Additive => Box::new(Additive::new(sample_rate)),
     }
 }
 
+// Samples are recorded at concert pitch, so their root notes are always placed in equal temperament
 fn midi_note_to_frequency(note_number: u8) -> f32 {
-    Defaults::MIDI_NOTE_FREQUENCIES[(note_number & MIDI_DATA_BYTE_7BIT_MASK) as usize].0
+    equal_temperament_frequency(note_number)
 }
 
 fn poly_blep(mut normalized_phase: f64, phase_increment: f64) -> f64 {
-    if phase_increment <= 0.0 {
+    // Through-zero FM runs the phase backwards, the discontinuity needs the same smoothing either way
+    let phase_increment = phase_increment.abs();
+    if phase_increment == 0.0 {
         return 0.0;
     }
 
@@ -596,8 +1149,10 @@ fn poly_blep(mut normalized_phase: f64, phase_increment: f64) -> f64 {
 
 #[cfg(test)]
 mod tests {
+    use super::constants::RADS_PER_CYCLE;
     use super::*;
     use accsyn_core::math::f32s_are_equal;
+    use strum::IntoEnumIterator;
 
     #[test]
     fn new_returns_oscillator_with_correct_default_values() {
@@ -659,6 +1214,29 @@ mod tests {
         assert!(f32s_are_equal(oscillator.generate(None, None), first_value));
     }
 
+    #[test]
+    fn reset_voice_state_plays_like_a_new_oscillator() {
+        let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
+        let mut new_oscillator = Oscillator::new(44100, WaveShape::Sine);
+        oscillator.set_frequency(100.0);
+        new_oscillator.set_frequency(100.0);
+        oscillator.set_hard_sync_enabled(true);
+        oscillator.set_hard_sync_trigger(Some(0.5));
+        oscillator.set_cross_modulation_input(0.75);
+        for _ in 0..5 {
+            oscillator.generate(None, None);
+        }
+
+        oscillator.reset_voice_state();
+
+        assert_eq!(oscillator.stereo_sample(), (0.0, 0.0));
+        assert!(oscillator.hard_sync.is_enabled);
+        assert!(f32s_are_equal(
+            oscillator.generate(None, None),
+            new_oscillator.generate(None, None)
+        ));
+    }
+
     #[test]
     fn set_wave_shape_returns_oscillator_with_correct_wave_shape() {
         let sample_rate = 44100;
@@ -719,64 +1297,396 @@ mod tests {
     }
 
     #[test]
-    fn set_synced_buffer_correctly_sets_sync_role() {
-        let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
-        assert!(matches!(oscillator.hard_sync.sync_role, HardSyncRole::None));
-        oscillator.set_hard_sync_role(HardSyncRole::Synced(Arc::new(AtomicBool::new(false))));
-        assert!(matches!(
-            oscillator.hard_sync.sync_role,
-            HardSyncRole::Synced(_)
-        ));
+    fn only_noise_and_the_sampler_are_not_periodic() {
+        let non_periodic: Vec<WaveShape> = WaveShape::iter()
+            .filter(|shape| !shape.is_periodic())
+            .collect();
+
+        assert_eq!(non_periodic, vec![WaveShape::Noise, WaveShape::Sampler]);
     }
 
     #[test]
-    fn generate_sets_hard_sync_buffer_to_true_when_enabled_and_sync_role_is_source_and_wave_phase_roles_over()
-     {
-        let sync_buffer = Arc::new(AtomicBool::new(false));
+    fn hard_sync_source_resolves_the_oscillator_that_syncs_each_oscillator() {
+        let one = OscillatorIndex::One as usize;
+        let two = OscillatorIndex::Two as usize;
+        let three = OscillatorIndex::Three as usize;
+        let sub = OscillatorIndex::Sub as usize;
+
+        assert_eq!(HardSyncSource::Classic.source_oscillator(two), Some(one));
+        assert_eq!(HardSyncSource::Classic.source_oscillator(three), None);
+        assert_eq!(HardSyncSource::Off.source_oscillator(two), None);
+        assert_eq!(HardSyncSource::One.source_oscillator(three), Some(one));
+        assert_eq!(HardSyncSource::Sub.source_oscillator(one), Some(sub));
+        assert_eq!(HardSyncSource::Sub.source_oscillator(sub), None);
+    }
+
+    #[test]
+    fn out_of_range_hard_sync_source_loads_as_classic() {
+        let parameters = OscillatorParameters::default();
+        parameters.hard_sync_source.store(99, Relaxed);
+        let loaded = OscillatorParameters::default();
+
+        loaded.assign_from(&parameters);
+
+        assert_eq!(
+            loaded.hard_sync_source.load(Relaxed),
+            HardSyncSource::Classic as u8
+        );
+    }
+
+    #[test]
+    fn generate_reports_a_cycle_start_when_the_output_crosses_zero_going_up() {
         let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
-        oscillator.set_hard_sync_enabled(true);
-        oscillator.set_hard_sync_role(HardSyncRole::Source(sync_buffer.clone()));
-        assert!(!sync_buffer.load(Relaxed));
 
-        let negative_sample = -0.01;
-        oscillator.hard_sync.last_sample = negative_sample;
-        let _ = oscillator.generate(None, None);
+        oscillator.hard_sync.last_sample = -0.01;
+        let sample = oscillator.generate(None, None);
+        let delay = oscillator.hard_sync_trigger().expect("cycle start");
+        assert!(f32s_are_equal(delay, sample / (sample + 0.01)));
 
-        assert!(sync_buffer.load(Relaxed));
+        let _ = oscillator.generate(None, None);
+        assert_eq!(oscillator.hard_sync_trigger(), None);
     }
 
     #[test]
-    fn generate_syncs_wave_generator_when_sync_enabled_and_sync_role_is_synced_and_sync_buffer_is_true()
-     {
-        let sync_buffer = Arc::new(AtomicBool::new(false));
+    fn generate_restarts_the_cycle_when_triggered_and_sync_enabled() {
         let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
-        oscillator.set_hard_sync_role(HardSyncRole::Synced(sync_buffer.clone()));
+        let mut reference = Oscillator::new(44100, WaveShape::Sine);
         oscillator.set_hard_sync_enabled(true);
 
-        let expected_first_sample = 0.0;
+        for _i in 0..5 {
+            let _ = oscillator.generate(None, None);
+        }
+
+        oscillator.set_hard_sync_trigger(Some(0.0));
+        let mut synced = oscillator.generate(None, None);
+        let mut expected = reference.generate(None, None);
+
+        // The band-limited step eases into the restarted cycle before matching it exactly
+        for _i in 0..20 {
+            synced = oscillator.generate(None, None);
+            expected = reference.generate(None, None);
+        }
 
-        let sample = oscillator.generate(None, None);
         assert!(
-            f32s_are_equal(sample, expected_first_sample),
-            "Expected {expected_first_sample:?}, but got {sample:?}"
+            (synced - expected).abs() < 1e-4,
+            "Expected {expected:?}, but got {synced:?}"
         );
+    }
+
+    #[test]
+    fn hard_sync_reset_advances_the_restarted_cycle_by_the_trigger_delay() {
+        for shape in [WaveShape::Sine, WaveShape::Pulse] {
+            let mut oscillator = Oscillator::new(44100, shape);
+            let mut reference = Oscillator::new(44100, shape);
+            oscillator.set_hard_sync_enabled(true);
+
+            for _i in 0..5 {
+                let _ = oscillator.generate(None, None);
+            }
 
-        for _i in 0..3 {
+            // A source that restarted one sample ago leaves the synced cycle one sample ahead of a fresh one
+            oscillator.set_hard_sync_trigger(Some(1.0));
+            let _ = reference.generate(None, None);
+            let mut synced = oscillator.generate(None, None);
+            let mut expected = reference.generate(None, None);
+
+            for _i in 0..20 {
+                synced = oscillator.generate(None, None);
+                expected = reference.generate(None, None);
+            }
+
+            assert!(
+                (synced - expected).abs() < 1e-4,
+                "{shape:?}: Expected {expected:?}, but got {synced:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn generate_ignores_the_hard_sync_trigger_when_sync_is_disabled() {
+        let mut oscillator = Oscillator::new(44100, WaveShape::Saw);
+        let mut reference = Oscillator::new(44100, WaveShape::Saw);
+
+        for _i in 0..5 {
             let _ = oscillator.generate(None, None);
+            let _ = reference.generate(None, None);
         }
 
-        sync_buffer.store(true, Release);
+        oscillator.set_hard_sync_trigger(Some(0.5));
+        let sample = oscillator.generate(None, None);
+        let expected = reference.generate(None, None);
 
-        let not_yet_synced = oscillator.generate(None, None);
-        assert!(
-            !f32s_are_equal(not_yet_synced, expected_first_sample),
-            "Expected {expected_first_sample:?}, but got {not_yet_synced:?}"
-        );
+        assert!(f32s_are_equal(sample, expected));
+    }
 
-        let first_synced_sample = oscillator.generate(None, None);
-        assert!(
-            f32s_are_equal(first_synced_sample, expected_first_sample),
-            "Expected {expected_first_sample:?}, but got {first_synced_sample:?}"
+    #[test]
+    fn tune_shifts_the_sub_oscillator_by_the_selected_sub_octave() {
+        let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
+        oscillator.set_is_sub_oscillator(true);
+
+        for (sub_octave, expected_note) in [
+            (SubOctave::OneDown, 48),
+            (SubOctave::TwoDown, 36),
+            (SubOctave::Off, 60),
+        ] {
+            oscillator.set_sub_octave(sub_octave);
+            oscillator.tune(60);
+            assert!(f32s_are_equal(
+                oscillator.tuning.frequency,
+                midi_note_to_frequency(expected_note)
+            ));
+        }
+    }
+
+    #[test]
+    fn tune_plays_the_note_through_the_selected_tuning() {
+        let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
+        let tuning = TuningParameters::default();
+        tuning.master_tune.store(100);
+
+        oscillator.set_microtuning(&tuning);
+        oscillator.tune(69);
+
+        assert!((oscillator.tuning.frequency - midi_note_to_frequency(70)).abs() < 0.01);
+    }
+
+    #[test]
+    fn tune_transposes_the_tuned_note_by_equal_tempered_octaves_in_a_19_tone_scale() {
+        let directory =
+            std::env::temp_dir().join(format!("accsyn_sub_octave_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let steps: String = (1..=19)
+            .map(|step| format!("{:.6}\n", f64::from(step) * 1200.0 / 19.0))
+            .collect();
+        std::fs::write(directory.join("19edo.scl"), format!("19 EDO\n19\n{steps}")).unwrap();
+        let tuning = TuningParameters::default();
+        tuning.load("19edo", "", &directory);
+        let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
+        oscillator.set_microtuning(&tuning);
+        oscillator.set_is_sub_oscillator(true);
+        oscillator.set_sub_octave(SubOctave::Off);
+        oscillator.tune(62);
+        let played_frequency = oscillator.tuning.frequency;
+
+        oscillator.set_sub_octave(SubOctave::OneDown);
+        oscillator.tune(62);
+        assert!(f32s_are_equal(
+            oscillator.tuning.frequency,
+            played_frequency / 2.0
+        ));
+
+        oscillator.set_sub_octave(SubOctave::Off);
+        oscillator.set_course_tune(12);
+        oscillator.tune(62);
+        assert!(f32s_are_equal(
+            oscillator.tuning.frequency,
+            played_frequency * 2.0
+        ));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn tune_ignores_the_sub_octave_on_other_oscillators() {
+        let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
+        oscillator.set_sub_octave(SubOctave::TwoDown);
+        oscillator.tune(60);
+
+        assert!(f32s_are_equal(
+            oscillator.tuning.frequency,
+            midi_note_to_frequency(60)
+        ));
+    }
+
+    #[test]
+    fn tune_applies_course_tune_and_sub_octave_together_before_clamping() {
+        let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
+        oscillator.set_is_sub_oscillator(true);
+        oscillator.set_course_tune(12);
+        oscillator.tune(125);
+
+        assert!(f32s_are_equal(
+            oscillator.tuning.frequency,
+            midi_note_to_frequency(125)
+        ));
+    }
+
+    #[test]
+    fn glide_flag_glides_to_the_new_note_without_resetting_phase_when_portamento_is_off() {
+        let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
+        oscillator.set_key_sync_enabled(true);
+        oscillator.set_portamento(false, 4);
+        oscillator.tune(60);
+        let start_frequency = oscillator.tuning.frequency;
+        let _ = oscillator.generate(None, None);
+
+        oscillator.set_glide(&AtomicBool::new(true));
+        oscillator.tune(72);
+
+        assert!(oscillator.tuning.frequency > start_frequency);
+        assert!(oscillator.tuning.frequency < midi_note_to_frequency(72));
+        assert!(!f32s_are_equal(oscillator.generate(None, None), 0.0));
+    }
+
+    #[test]
+    fn gate_after_a_legato_glide_jumps_straight_to_the_note_when_portamento_is_off() {
+        let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
+        oscillator.set_portamento(false, 4);
+        oscillator.tune(60);
+        oscillator.set_glide(&AtomicBool::new(true));
+        oscillator.tune(72);
+
+        oscillator.set_gate(&AtomicBool::new(true));
+        oscillator.tune(48);
+
+        assert!(f32s_are_equal(
+            oscillator.tuning.frequency,
+            midi_note_to_frequency(48)
+        ));
+    }
+
+    #[test]
+    fn full_depth_ring_modulation_multiplies_by_the_source() {
+        let mut oscillator = Oscillator::new(44100, WaveShape::Sine);
+        oscillator.set_frequency(100.0);
+        oscillator.set_cross_modulation(CrossModulationMode::RingModulation, 3, 1.0);
+        let _ = oscillator.generate(None, None);
+
+        oscillator.set_cross_modulation_input(0.0);
+        assert!(f32s_are_equal(oscillator.generate(None, None), 0.0));
+
+        oscillator.set_cross_modulation_input(-1.0);
+        let inverted = oscillator.generate(None, None);
+        assert!(inverted < 0.0);
+    }
+
+    #[test]
+    fn linear_fm_runs_the_oscillator_backwards_through_zero() {
+        let mut carrier = Oscillator::new(44100, WaveShape::Sine);
+        carrier.set_frequency(100.0);
+        let mut modulated = Oscillator::new(44100, WaveShape::Sine);
+        modulated.set_frequency(100.0);
+        modulated.set_cross_modulation(CrossModulationMode::LinearFM, 3, 1.0);
+
+        for _ in 0..32 {
+            // A frequency multiplier of 1 + 4 * -0.5 = -1 plays the same pitch in reverse
+            modulated.set_cross_modulation_input(-0.5);
+            let reversed = modulated.generate(None, None);
+            let forwards = carrier.generate(None, None);
+            assert!((reversed + forwards).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn through_zero_fm_keeps_the_poly_blep_shapes_band_limited() {
+        fn largest_step(shape: WaveShape, frequency_multiplier: f32) -> f32 {
+            let mut wave_generator = get_wave_generator_from_wave_shape(44100, shape);
+            let mut previous = wave_generator.next_sample(1000.0, Some(frequency_multiplier));
+            (0..44100).fold(0.0_f32, |largest, _| {
+                let sample = wave_generator.next_sample(1000.0, Some(frequency_multiplier));
+                let step = (sample - previous).abs();
+                previous = sample;
+                largest.max(step)
+            })
+        }
+
+        for shape in [WaveShape::Saw, WaveShape::Ramp, WaveShape::Square] {
+            let forwards = largest_step(shape, 1.0);
+            let backwards = largest_step(shape, -1.0);
+
+            // An unsmoothed edge jumps the full 2.0 between samples
+            assert!(backwards < 1.9, "{shape:?} backwards step {backwards}");
+            assert!(
+                (backwards - forwards).abs() < 0.1,
+                "{shape:?} {forwards} {backwards}"
+            );
+        }
+    }
+
+    #[test]
+    fn phase_modulation_shifts_the_phase_by_the_change_in_the_source() {
+        let sample_rate = 44100;
+        let frequency = 100.0;
+        let mut oscillator = Oscillator::new(sample_rate, WaveShape::Sine);
+        oscillator.set_frequency(frequency);
+        oscillator.set_cross_modulation(CrossModulationMode::PhaseModulation, 3, 1.0);
+        // A step of 0.25 in the source moves the phase once by 0.25 * MAX_CROSS_PM_INDEX radians
+        let phase_offset = 0.25 * f64::from(MAX_CROSS_PM_INDEX);
+        let phase_increment = RADS_PER_CYCLE * f64::from(frequency) / f64::from(sample_rate);
+
+        for step in 0..32 {
+            oscillator.set_cross_modulation_input(0.25);
+            let sample = oscillator.generate(None, None);
+            let expected = if step == 0 {
+                0.0
+            } else {
+                (f64::from(step) * phase_increment + phase_offset).sin()
+            };
+            assert!((f64::from(sample) - expected).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn cross_modulation_source_is_clamped_to_the_last_oscillator() {
+        let parameters = OscillatorParameters::default();
+        parameters.cross_modulation_source.store(9, Relaxed);
+        let loaded = OscillatorParameters::default();
+
+        loaded.assign_from(&parameters);
+
+        assert_eq!(
+            loaded.cross_modulation_source.load(Relaxed),
+            OscillatorIndex::Three as u8
         );
     }
+
+    #[test]
+    fn cross_modulation_off_leaves_the_output_unchanged() {
+        let mut carrier = Oscillator::new(44100, WaveShape::Saw);
+        carrier.set_frequency(100.0);
+        let mut unmodulated = Oscillator::new(44100, WaveShape::Saw);
+        unmodulated.set_frequency(100.0);
+        unmodulated.set_cross_modulation(CrossModulationMode::Off, 3, 1.0);
+
+        for _ in 0..32 {
+            unmodulated.set_cross_modulation_input(0.7);
+            assert!(f32s_are_equal(
+                unmodulated.generate(None, None),
+                carrier.generate(None, None)
+            ));
+        }
+    }
+
+    #[test]
+    fn stereo_sample_matches_the_mono_output_without_spread() {
+        let mut oscillator = Oscillator::new(44100, WaveShape::Saw);
+        oscillator.set_frequency(100.0);
+
+        for _ in 0..32 {
+            let sample = oscillator.generate(None, None);
+            assert_eq!(oscillator.stereo_sample(), (sample, sample));
+        }
+    }
+
+    #[test]
+    fn spread_supersaw_averages_back_to_the_mono_output() {
+        let parameters = OscillatorParameters::default();
+        parameters
+            .wave_shape_index
+            .store(WaveShape::Supersaw as u8, Relaxed);
+        parameters.shape_parameter1.store(1.0);
+        parameters.shape_parameter2.store(1.0);
+        parameters.supersaw.stereo_spread.store(1.0);
+        let mut oscillator = Oscillator::new(44100, WaveShape::Supersaw);
+        oscillator.set_parameters(&parameters);
+        oscillator.set_frequency(100.0);
+
+        let mut channels_differ = false;
+        for _ in 0..441 {
+            let sample = oscillator.generate(None, None);
+            let (left, right) = oscillator.stereo_sample();
+            channels_differ |= !f32s_are_equal(left, right);
+            assert!(((left + right) / 2.0 - sample).abs() < 0.0001);
+        }
+        assert!(channels_differ);
+    }
 }
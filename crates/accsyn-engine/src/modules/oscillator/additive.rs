@@ -0,0 +1,411 @@
+use super::WaveShape;
+use super::constants::RADS_PER_CYCLE;
+use crate::modules::oscillator::generate_wave_trait::GenerateWave;
+use accsyn_core::casting::f64_to_f32_clamped;
+use accsyn_core::parameter_types::NormalizedValue;
+use serde::de::{SeqAccess, Visitor};
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+const SHAPE: WaveShape = WaveShape::Additive;
+
+/// Most sine partials the additive oscillator can play.
+pub const MAX_PARTIALS: usize = 64;
+// Partials are scaled by their number raised to up to this power either way, 12 dB per octave at the ends
+const MAX_BRIGHTNESS_TILT: f64 = 2.0;
+// Partial frequencies follow the partial number raised to 1.0 plus up to this much stretch
+const MAX_PARTIAL_STRETCH: f64 = 0.5;
+// Shape parameter 2 balances odd and even partials below this point and stretches the partials above it
+const SPECTRAL_MACRO_SPLIT: f32 = 0.5;
+
+/// Amplitudes of the additive oscillator's partials, the fundamental first.
+///
+/// Stored in patches as a list of numbers with the silent partials at the top left out, missing partials load silent.
+#[derive(Debug)]
+pub struct PartialAmplitudes {
+    amplitudes: [NormalizedValue; MAX_PARTIALS],
+}
+
+impl PartialAmplitudes {
+    /// Loads the amplitude of a partial, 0 is the fundamental.
+    #[must_use]
+    pub fn load(&self, partial: usize) -> f32 {
+        self.amplitudes
+            .get(partial)
+            .map_or(0.0, NormalizedValue::load)
+    }
+
+    /// Stores the amplitude of a partial, 0 is the fundamental. Partials past the last are ignored.
+    pub fn store(&self, partial: usize, amplitude: f32) {
+        if let Some(value) = self.amplitudes.get(partial) {
+            value.store(amplitude);
+        }
+    }
+
+    fn from_amplitudes(amplitudes: &[f32]) -> Self {
+        let partial_amplitudes = Self {
+            amplitudes: std::array::from_fn(|_| NormalizedValue::new(0.0)),
+        };
+        for (partial, amplitude) in amplitudes.iter().take(MAX_PARTIALS).enumerate() {
+            partial_amplitudes.store(partial, *amplitude);
+        }
+        partial_amplitudes
+    }
+}
+
+impl Default for PartialAmplitudes {
+    // A sawtooth spectrum, every harmonic at one over its number
+    fn default() -> Self {
+        Self {
+            amplitudes: std::array::from_fn(|partial| {
+                // Partial numbers are at most 64, exactly representable in f32
+                #[allow(clippy::cast_precision_loss)]
+                let harmonic = (partial + 1) as f32;
+                NormalizedValue::new(1.0 / harmonic)
+            }),
+        }
+    }
+}
+
+impl Serialize for PartialAmplitudes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let audible_partials = self
+            .amplitudes
+            .iter()
+            .rposition(|amplitude| amplitude.load() > 0.0)
+            .map_or(0, |last| last + 1);
+        let mut sequence = serializer.serialize_seq(Some(audible_partials))?;
+        for amplitude in self.amplitudes.iter().take(audible_partials) {
+            sequence.serialize_element(amplitude)?;
+        }
+        sequence.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for PartialAmplitudes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PartialAmplitudesVisitor;
+
+        impl<'de> Visitor<'de> for PartialAmplitudesVisitor {
+            type Value = PartialAmplitudes;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a list of partial amplitudes")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut sequence: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut amplitudes = Vec::with_capacity(MAX_PARTIALS);
+                while let Some(amplitude) = sequence.next_element::<f32>()? {
+                    if amplitudes.len() < MAX_PARTIALS {
+                        amplitudes.push(amplitude);
+                    }
+                }
+                Ok(PartialAmplitudes::from_amplitudes(&amplitudes))
+            }
+        }
+
+        deserializer.deserialize_seq(PartialAmplitudesVisitor)
+    }
+}
+
+/// Shared atomic parameters for the additive wave shape.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdditiveParameters {
+    /// Amplitude of each partial before the spectral macros are applied.
+    pub partials: PartialAmplitudes,
+}
+
+impl AdditiveParameters {
+    /// Replace all the values in this `AdditiveParameters` with the values from the provided `AdditiveParameters`.
+    pub fn assign_from(&self, parameters: &AdditiveParameters) {
+        for partial in 0..MAX_PARTIALS {
+            self.partials
+                .store(partial, parameters.partials.load(partial));
+        }
+    }
+}
+
+/// Additive oscillator summing up to 64 sine partials, shaped by brightness, odd/even and stretch macros.
+pub struct Additive {
+    shape: WaveShape,
+    sample_rate: f64,
+    partial_amplitudes: [f64; MAX_PARTIALS],
+    brightness: f64,
+    odd_even_balance: f64,
+    stretch: f64,
+    // Partial amplitudes and frequency ratios after the macros, recalculated only when something changes
+    amplitudes: [f64; MAX_PARTIALS],
+    ratios: [f64; MAX_PARTIALS],
+    gain: f64,
+    needs_update: bool,
+    phases: [f64; MAX_PARTIALS],
+}
+
+impl Additive {
+    pub(crate) fn new(sample_rate: u32) -> Self {
+        log::debug!(target: "synth::oscillator", shape = "Additive"; "Constructing wave generator");
+        let default_partials = PartialAmplitudes::default();
+
+        Self {
+            shape: SHAPE,
+            sample_rate: f64::from(sample_rate),
+            partial_amplitudes: std::array::from_fn(|partial| {
+                f64::from(default_partials.load(partial))
+            }),
+            brightness: 0.0,
+            odd_even_balance: 1.0,
+            stretch: 0.0,
+            amplitudes: [0.0; MAX_PARTIALS],
+            ratios: [1.0; MAX_PARTIALS],
+            gain: 1.0,
+            needs_update: true,
+            phases: [0.0; MAX_PARTIALS],
+        }
+    }
+
+    fn update_partials(&mut self) {
+        let mut amplitude_sum = 0.0;
+        for partial in 0..MAX_PARTIALS {
+            // Partial numbers are at most 64, exactly representable in f64
+            #[allow(clippy::cast_precision_loss)]
+            let harmonic = (partial + 1) as f64;
+            // The fundamental counts as odd, so an odd only spectrum keeps the note's pitch
+            let odd_even_level = if partial % 2 == 0 {
+                1.0
+            } else {
+                self.odd_even_balance
+            };
+            let amplitude =
+                self.partial_amplitudes[partial] * harmonic.powf(self.brightness) * odd_even_level;
+
+            self.amplitudes[partial] = amplitude;
+            self.ratios[partial] = harmonic.powf(1.0 + self.stretch);
+            amplitude_sum += amplitude;
+        }
+
+        // The partials can only ever peak at the sum of their amplitudes, so dividing by it keeps every spectrum
+        // within full scale however its partials line up
+        self.gain = if amplitude_sum > 0.0 {
+            amplitude_sum.recip()
+        } else {
+            0.0
+        };
+        self.needs_update = false;
+    }
+}
+
+impl GenerateWave for Additive {
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        if self.needs_update {
+            self.update_partials();
+        }
+
+        let frequency = f64::from(tone_frequency) * f64::from(modulation.unwrap_or(1.0));
+        let nyquist = self.sample_rate / 2.0;
+
+        let mut sample = 0.0;
+        for partial in 0..MAX_PARTIALS {
+            let partial_frequency = frequency * self.ratios[partial];
+            // Ratios rise with the partial number, so every partial from here up would alias
+            if partial_frequency.abs() >= nyquist {
+                break;
+            }
+
+            if self.amplitudes[partial] > 0.0 {
+                sample += self.amplitudes[partial] * (RADS_PER_CYCLE * self.phases[partial]).sin();
+            }
+            self.phases[partial] =
+                (self.phases[partial] + partial_frequency / self.sample_rate).rem_euclid(1.0);
+        }
+
+        f64_to_f32_clamped(sample * self.gain)
+    }
+
+    fn set_shape_parameter1(&mut self, parameter: f32) {
+        let brightness = f64::from(parameter.clamp(0.0, 1.0) * 2.0 - 1.0) * MAX_BRIGHTNESS_TILT;
+        if brightness != self.brightness {
+            self.brightness = brightness;
+            self.needs_update = true;
+        }
+    }
+
+    fn set_shape_parameter2(&mut self, parameter: f32) {
+        let parameter = parameter.clamp(0.0, 1.0);
+        let (odd_even_balance, stretch) = if parameter < SPECTRAL_MACRO_SPLIT {
+            (f64::from(parameter / SPECTRAL_MACRO_SPLIT), 0.0)
+        } else {
+            let stretch_amount = (parameter - SPECTRAL_MACRO_SPLIT) / (1.0 - SPECTRAL_MACRO_SPLIT);
+            (1.0, f64::from(stretch_amount) * MAX_PARTIAL_STRETCH)
+        };
+
+        if odd_even_balance != self.odd_even_balance || stretch != self.stretch {
+            self.odd_even_balance = odd_even_balance;
+            self.stretch = stretch;
+            self.needs_update = true;
+        }
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        let phase = f64::from(phase);
+        for (partial_phase, ratio) in self.phases.iter_mut().zip(self.ratios.iter()) {
+            *partial_phase = (phase * ratio).rem_euclid(1.0);
+        }
+    }
+
+    fn shape(&self) -> WaveShape {
+        self.shape
+    }
+
+    fn reset(&mut self) {
+        self.phases = [0.0; MAX_PARTIALS];
+    }
+
+    fn set_additive(&mut self, parameters: &AdditiveParameters) {
+        for (partial, amplitude) in self.partial_amplitudes.iter_mut().enumerate() {
+            let new_amplitude = f64::from(parameters.partials.load(partial));
+            if new_amplitude != *amplitude {
+                *amplitude = new_amplitude;
+                self.needs_update = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48000;
+
+    fn parameters_with(amplitudes: &[f32]) -> AdditiveParameters {
+        AdditiveParameters {
+            partials: PartialAmplitudes::from_amplitudes(amplitudes),
+        }
+    }
+
+    fn additive_with(amplitudes: &[f32], parameter1: f32, parameter2: f32) -> Additive {
+        let mut additive = Additive::new(SAMPLE_RATE);
+        additive.set_additive(&parameters_with(amplitudes));
+        additive.set_shape_parameter1(parameter1);
+        additive.set_shape_parameter2(parameter2);
+        additive
+    }
+
+    #[test]
+    fn single_partial_plays_a_full_scale_sine() {
+        let mut additive = additive_with(&[1.0], 0.5, 0.5);
+        let frequency = 480.0;
+
+        for step in 0..100 {
+            let expected = (RADS_PER_CYCLE * f64::from(step) * f64::from(frequency)
+                / f64::from(SAMPLE_RATE))
+            .sin();
+            let sample = additive.next_sample(frequency, None);
+            assert!((f64::from(sample) - expected).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn dense_spectrum_never_exceeds_full_scale() {
+        let mut additive = additive_with(&[1.0; MAX_PARTIALS], 0.5, 0.5);
+        let frequency = 50.0;
+
+        // Every partial starts at phase zero, so a full cycle passes the point where they line up
+        let peak = (0..SAMPLE_RATE / 50)
+            .map(|_| additive.next_sample(frequency, None).abs())
+            .fold(0.0, f32::max);
+
+        assert!(peak <= 1.0, "Peak was {peak}");
+        assert!(peak > 0.5, "Peak was {peak}");
+    }
+
+    #[test]
+    fn partials_above_nyquist_are_culled() {
+        // Only the third partial, at 3 × 9 kHz, is above the 24 kHz Nyquist frequency
+        let mut culled = additive_with(&[0.0, 0.0, 1.0], 0.5, 0.5);
+        let mut audible = additive_with(&[0.0, 0.0, 1.0], 0.5, 0.5);
+
+        assert!((0..100).all(|_| culled.next_sample(9000.0, None) == 0.0));
+        assert!((0..100).any(|_| audible.next_sample(1000.0, None) != 0.0));
+    }
+
+    #[test]
+    fn brightness_tilts_the_spectrum() {
+        let mut dark = additive_with(&[1.0, 1.0], 0.0, 0.5);
+        let mut bright = additive_with(&[1.0, 1.0], 1.0, 0.5);
+        dark.next_sample(100.0, None);
+        bright.next_sample(100.0, None);
+
+        assert!(dark.amplitudes[1] < dark.amplitudes[0]);
+        assert!(bright.amplitudes[1] > bright.amplitudes[0]);
+    }
+
+    #[test]
+    fn odd_even_balance_removes_the_even_partials_at_zero() {
+        let mut additive = additive_with(&[1.0, 1.0, 1.0, 1.0], 0.5, 0.0);
+        additive.next_sample(100.0, None);
+
+        assert!(additive.amplitudes[0] > 0.0 && additive.amplitudes[2] > 0.0);
+        assert!(additive.amplitudes[1] == 0.0 && additive.amplitudes[3] == 0.0);
+    }
+
+    #[test]
+    fn stretch_makes_the_partials_inharmonic() {
+        let mut harmonic = additive_with(&[1.0, 1.0, 1.0], 0.5, 0.5);
+        let mut stretched = additive_with(&[1.0, 1.0, 1.0], 0.5, 1.0);
+        harmonic.next_sample(100.0, None);
+        stretched.next_sample(100.0, None);
+
+        assert!((harmonic.ratios[2] - 3.0).abs() < f64::EPSILON);
+        assert!(stretched.ratios[2] > 3.0);
+        assert!((stretched.ratios[0] - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn silent_partial_table_is_silent() {
+        let mut additive = additive_with(&[], 0.5, 0.5);
+
+        assert!((0..100).all(|_| additive.next_sample(220.0, None) == 0.0));
+    }
+
+    #[test]
+    fn partial_amplitudes_round_trip_without_trailing_silent_partials() {
+        let partials = PartialAmplitudes::from_amplitudes(&[1.0, 0.0, 0.25]);
+
+        let json = serde_json::to_string(&partials).expect("partials should serialize");
+        let loaded: PartialAmplitudes =
+            serde_json::from_str(&json).expect("partials should deserialize");
+
+        assert_eq!(json, "[1.0,0.0,0.25]");
+        assert!((loaded.load(2) - 0.25).abs() < f32::EPSILON);
+        assert!(loaded.load(3) == 0.0);
+    }
+
+    #[test]
+    fn short_partial_list_silences_the_default_partials_on_load() {
+        let parameters: AdditiveParameters = serde_json::from_str(r#"{"partials":[1.0,2.0,0.25]}"#)
+            .expect("additive parameters should deserialize");
+        let loaded = AdditiveParameters::default();
+
+        loaded.assign_from(&parameters);
+
+        assert!((loaded.partials.load(1) - 1.0).abs() < f32::EPSILON);
+        assert!((loaded.partials.load(2) - 0.25).abs() < f32::EPSILON);
+        assert!((3..MAX_PARTIALS).all(|partial| loaded.partials.load(partial) == 0.0));
+    }
+
+    #[test]
+    fn extra_partials_in_a_patch_are_ignored() {
+        let loaded: PartialAmplitudes =
+            serde_json::from_str(&format!("[{}]", vec!["2.0"; 80].join(",")))
+                .expect("partials should deserialize");
+
+        assert!((loaded.load(MAX_PARTIALS - 1) - 1.0).abs() < f32::EPSILON);
+        assert!(loaded.load(MAX_PARTIALS) == 0.0);
+    }
+}
@@ -34,15 +34,19 @@ pub const DEFAULT_PULSE_WIDTH_ADJUSTMENT: f64 = 0.5;
 /// Scaling factor for converting oscillator modulation to pulse width modulation.
 pub const OSCILLATOR_MOD_TO_PWM_ADJUSTMENT_FACTOR: f64 = 0.5;
 
+// Cross Modulation Constants
+/// Peak frequency deviation of full depth linear FM as a multiple of the note frequency.
+pub const MAX_CROSS_FM_INDEX: f32 = 4.0;
+/// Peak phase deviation in radians of full depth cross phase modulation.
+pub const MAX_CROSS_PM_INDEX: f32 = 8.0;
+
 // Oscillator Tuning Constants
-/// Maximum MIDI note number (127).
-pub const MAX_MIDI_NOTE_NUMBER: i16 = 127;
-/// Minimum MIDI note number (0).
-pub const MIN_MIDI_NOTE_NUMBER: i16 = 0;
 /// Maximum oscillator frequency in Hz (MIDI note 127).
 pub const MAX_NOTE_FREQUENCY: f32 = 12543.854;
 /// Minimum oscillator frequency in Hz (MIDI note 0).
 pub const MIN_NOTE_FREQUENCY: f32 = 8.175;
+/// Equal tempered semitones in an octave, the unit of the course tune and sub octave transpositions.
+pub const SEMITONES_PER_OCTAVE: f32 = 12.0;
 /// Default oscillator frequency in Hz (middle C).
 pub const DEFAULT_NOTE_FREQUENCY: f32 = 261.625;
 /// Default portamento glide time measured in buffer increments.
@@ -51,7 +55,7 @@ pub const DEFAULT_PORTAMENTO_TIME_IN_BUFFERS: u16 = 7;
 pub const DEFAULT_PORTAMENTO_ENABLED: bool = false;
 
 /// Oscillator `WaveShape` Specific Parameter Defaults
-pub const OSCILLATOR_WAVESHAPE_PARAMETER_DEFAULTS: [(f32, f32); 12] = [
+pub const OSCILLATOR_WAVESHAPE_PARAMETER_DEFAULTS: [(f32, f32); 16] = [
     (0.0, 0.0), // Sine
     (0.0, 0.0), // Triangle
     (0.0, 0.0), // Square
@@ -64,4 +68,8 @@ pub const OSCILLATOR_WAVESHAPE_PARAMETER_DEFAULTS: [(f32, f32); 12] = [
     (0.5, 0.0), // PM
     (0.5, 0.0), // Broken
     (0.0, 0.0), // Noise
+    (0.0, 1.0), // Wavetable
+    (0.0, 1.0), // Sampler
+    (0.5, 0.5), // FourOpFM
+    (0.5, 0.5), // Additive
 ];
@@ -0,0 +1,221 @@
+use accsyn_core::parameter_types::NormalizedValue;
+use rand::rngs::Xoshiro128PlusPlus;
+use rand::{RngExt, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering::Relaxed;
+
+/// Widest pitch wander at full drift depth, in cents either side of the note.
+pub const MAX_DRIFT_CENTS: f32 = 20.0;
+/// Seed used when a patch doesn't set one.
+pub const DEFAULT_DRIFT_SEED: u32 = 0;
+// Average time between new random pitch targets, each interval is varied by up to half either way
+const TARGET_INTERVAL_SECONDS: f32 = 0.6;
+// Time constant of each of the two smoothing stages between the random targets and the pitch
+const SMOOTHING_SECONDS: f32 = 0.3;
+
+/// Shared analog drift settings applied to every oscillator in every voice.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DriftParameters {
+    /// Drift depth, scaling both the pitch wander and the random start phase.
+    pub depth: NormalizedValue,
+    /// Seed for the random drift, the same seed drifts the same way on every render.
+    pub seed: AtomicU32,
+}
+
+impl DriftParameters {
+    /// Replace all the values in this `DriftParameters` with the values from the provided `DriftParameters`.
+    pub fn assign_from(&self, parameters: &DriftParameters) {
+        self.depth.store(parameters.depth.load());
+        self.seed.store(parameters.seed.load(Relaxed), Relaxed);
+    }
+}
+
+impl Default for DriftParameters {
+    fn default() -> Self {
+        Self {
+            depth: NormalizedValue::new(0.0),
+            seed: AtomicU32::new(DEFAULT_DRIFT_SEED),
+        }
+    }
+}
+
+/// Slow, smoothed random pitch wander and random note start phase for one oscillator.
+#[derive(Debug)]
+pub(super) struct Drift {
+    depth: f32,
+    seed: Option<u32>,
+    // Separates the random sequences of every oscillator in every voice sharing one seed
+    stream: u32,
+    random: Xoshiro128PlusPlus,
+    target_interval_samples: f32,
+    samples_until_target: u32,
+    target: f32,
+    smoothed_target: f32,
+    wander: f32,
+    smoothing_coefficient: f32,
+}
+
+impl Drift {
+    pub(super) fn new(sample_rate: u32) -> Self {
+        // Sample rate is always ≤ 192_000, within f32 precision (2²³ = 8_388_608)
+        #[allow(clippy::cast_precision_loss)]
+        let sample_rate = sample_rate as f32;
+
+        Self {
+            depth: 0.0,
+            seed: None,
+            stream: 0,
+            random: Xoshiro128PlusPlus::seed_from_u64(0),
+            target_interval_samples: TARGET_INTERVAL_SECONDS * sample_rate,
+            samples_until_target: 0,
+            target: 0.0,
+            smoothed_target: 0.0,
+            wander: 0.0,
+            smoothing_coefficient: 1.0 - (-1.0 / (SMOOTHING_SECONDS * sample_rate)).exp(),
+        }
+    }
+
+    pub(super) fn set_parameters(&mut self, depth: f32, seed: u32) {
+        self.depth = depth.clamp(0.0, 1.0);
+        if self.seed != Some(seed) {
+            self.seed = Some(seed);
+            self.reseed();
+        }
+    }
+
+    pub(super) fn set_stream(&mut self, stream: u32) {
+        if stream != self.stream {
+            self.stream = stream;
+            self.reseed();
+        }
+    }
+
+    /// Returns the frequency multiplier for the next sample, 1.0 when drift is off.
+    pub(super) fn next_frequency_multiplier(&mut self) -> f32 {
+        if self.depth == 0.0 {
+            return 1.0;
+        }
+
+        if self.samples_until_target == 0 {
+            self.target = self.random.random_range(-1.0..=1.0);
+            let interval = self.target_interval_samples * self.random.random_range(0.5..1.5);
+            // Interval is positive and a few seconds of samples at most, within u32 range
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let interval = interval.max(1.0) as u32;
+            self.samples_until_target = interval;
+        }
+        self.samples_until_target -= 1;
+
+        // Two one-pole stages turn the stepped targets into a smooth, band-limited wander
+        self.smoothed_target += self.smoothing_coefficient * (self.target - self.smoothed_target);
+        self.wander += self.smoothing_coefficient * (self.smoothed_target - self.wander);
+
+        (self.wander * self.depth * MAX_DRIFT_CENTS / 1200.0).exp2()
+    }
+
+    /// Returns a random start phase for a new note, scaled by the depth, or `None` when drift is off.
+    pub(super) fn start_phase(&mut self) -> Option<f32> {
+        if self.depth == 0.0 {
+            return None;
+        }
+
+        Some(self.random.random_range(0.0..1.0) * self.depth)
+    }
+
+    /// Restarts the random sequence from the seed and stream, as a new oscillator would.
+    pub(super) fn reseed(&mut self) {
+        let seed =
+            u64::from(self.seed.unwrap_or(DEFAULT_DRIFT_SEED)) << 32 | u64::from(self.stream);
+        self.random = Xoshiro128PlusPlus::seed_from_u64(seed);
+        self.samples_until_target = 0;
+        self.target = 0.0;
+        self.smoothed_target = 0.0;
+        self.wander = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(drift: &mut Drift, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|_| drift.next_frequency_multiplier())
+            .collect()
+    }
+
+    #[test]
+    fn drift_is_off_at_zero_depth() {
+        let mut drift = Drift::new(48000);
+        drift.set_parameters(0.0, 7);
+
+        assert!(
+            render(&mut drift, 1000)
+                .iter()
+                .all(|multiplier| *multiplier == 1.0)
+        );
+        assert_eq!(drift.start_phase(), None);
+    }
+
+    #[test]
+    fn drift_stays_within_the_maximum_depth() {
+        let mut drift = Drift::new(48000);
+        drift.set_parameters(1.0, 7);
+        let limit = (MAX_DRIFT_CENTS / 1200.0).exp2();
+
+        let multipliers = render(&mut drift, 48000 * 4);
+
+        assert!(multipliers.iter().any(|multiplier| *multiplier != 1.0));
+        assert!(
+            multipliers
+                .iter()
+                .all(|multiplier| *multiplier <= limit && *multiplier >= 1.0 / limit)
+        );
+    }
+
+    #[test]
+    fn drift_moves_slowly_between_samples() {
+        let mut drift = Drift::new(48000);
+        drift.set_parameters(1.0, 7);
+
+        let multipliers = render(&mut drift, 48000);
+
+        assert!(
+            multipliers
+                .windows(2)
+                .all(|pair| (pair[1] - pair[0]).abs() < 1.0e-5)
+        );
+    }
+
+    #[test]
+    fn same_seed_and_stream_drift_the_same_way() {
+        let mut first = Drift::new(48000);
+        let mut second = Drift::new(48000);
+        first.set_parameters(0.5, 42);
+        second.set_parameters(0.5, 42);
+
+        assert_eq!(render(&mut first, 10_000), render(&mut second, 10_000));
+        assert_eq!(first.start_phase(), second.start_phase());
+    }
+
+    #[test]
+    fn different_streams_drift_independently() {
+        let mut first = Drift::new(48000);
+        let mut second = Drift::new(48000);
+        first.set_parameters(0.5, 42);
+        second.set_parameters(0.5, 42);
+        second.set_stream(1);
+
+        assert_ne!(render(&mut first, 10_000), render(&mut second, 10_000));
+    }
+
+    #[test]
+    fn start_phase_is_scaled_by_depth() {
+        let mut drift = Drift::new(48000);
+        drift.set_parameters(0.25, 3);
+
+        assert!((0..100).all(|_| drift.start_phase().is_some_and(|phase| phase < 0.25)));
+    }
+}
@@ -0,0 +1,625 @@
+use super::WaveShape;
+use crate::modules::oscillator::constants::RADS_PER_CYCLE;
+use crate::modules::oscillator::generate_wave_trait::GenerateWave;
+use accsyn_core::casting::f64_to_f32_clamped;
+use accsyn_core::defaults::Defaults;
+use accsyn_core::parameter_types::{FrequencyRatio, Milliseconds, NormalizedValue};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::{AtomicBool, AtomicU8};
+
+const SHAPE: WaveShape = WaveShape::FourOpFM;
+
+/// Number of operators in the four operator FM oscillator.
+pub const FM_OPERATOR_COUNT: usize = 4;
+/// Number of selectable operator routing algorithms.
+pub const FM_ALGORITHM_COUNT: usize = FM_ALGORITHMS.len();
+/// Longest operator envelope attack or decay time in milliseconds.
+pub const MAX_OPERATOR_ENVELOPE_MILLISECONDS: u32 = 10_000;
+// Phase offset in radians a full level modulator adds at the default index
+const MAX_MODULATION_INDEX: f64 = 8.0;
+// Phase offset in radians full feedback adds at the default brightness
+const MAX_FEEDBACK: f64 = PI;
+// Shape parameters of 0.5 play the operators exactly as the patch sets them
+const MACRO_SCALE: f64 = 2.0;
+const DEFAULT_MACRO: f64 = 1.0;
+const DEFAULT_OPERATOR_LEVELS: [f32; FM_OPERATOR_COUNT] = [1.0, 0.5, 0.0, 0.0];
+const DEFAULT_OPERATOR_DECAY_MILLISECONDS: u32 = 500;
+const DEFAULT_OPERATOR_SUSTAIN: f32 = 0.5;
+
+/// Routing of the four operators: which operators modulate each operator and which are heard.
+///
+/// Operators are numbered 1 to 4 in the docs and 0 to 3 here. Modulators always have a higher index
+/// than the operator they modulate, so operators are processed from the last to the first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FmAlgorithm {
+    /// Operators whose output modulates each operator's phase.
+    pub modulators: [&'static [usize]; FM_OPERATOR_COUNT],
+    /// Operators mixed into the output.
+    pub carriers: [bool; FM_OPERATOR_COUNT],
+}
+
+/// The eight classic four operator algorithms.
+pub const FM_ALGORITHMS: [FmAlgorithm; 8] = [
+    // 4 > 3 > 2 > 1
+    FmAlgorithm {
+        modulators: [&[1], &[2], &[3], &[]],
+        carriers: [true, false, false, false],
+    },
+    // (3 + 4) > 2 > 1
+    FmAlgorithm {
+        modulators: [&[1], &[2, 3], &[], &[]],
+        carriers: [true, false, false, false],
+    },
+    // (4 + (3 > 2)) > 1
+    FmAlgorithm {
+        modulators: [&[1, 3], &[2], &[], &[]],
+        carriers: [true, false, false, false],
+    },
+    // (2 + (4 > 3)) > 1
+    FmAlgorithm {
+        modulators: [&[1, 2], &[], &[3], &[]],
+        carriers: [true, false, false, false],
+    },
+    // 2 > 1, 4 > 3
+    FmAlgorithm {
+        modulators: [&[1], &[], &[3], &[]],
+        carriers: [true, false, true, false],
+    },
+    // 4 > 1, 4 > 2, 4 > 3
+    FmAlgorithm {
+        modulators: [&[3], &[3], &[3], &[]],
+        carriers: [true, true, true, false],
+    },
+    // 1, 2, 4 > 3
+    FmAlgorithm {
+        modulators: [&[], &[], &[3], &[]],
+        carriers: [true, true, true, false],
+    },
+    // 1, 2, 3, 4
+    FmAlgorithm {
+        modulators: [&[], &[], &[], &[]],
+        carriers: [true, true, true, true],
+    },
+];
+
+/// Shared atomic parameters for one operator of the four operator FM oscillator.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FmOperatorParameters {
+    /// Operator frequency as a multiple of the note frequency.
+    pub ratio: FrequencyRatio,
+    /// Output level of a carrier, or modulation depth of a modulator.
+    pub level: NormalizedValue,
+    /// Amount of the operator's own output fed back into its phase.
+    pub feedback: NormalizedValue,
+    /// Whether the operator envelope shapes the level, otherwise the level is constant.
+    pub envelope_enabled: AtomicBool,
+    /// Operator envelope attack time.
+    pub attack: Milliseconds,
+    /// Operator envelope decay time to the sustain level.
+    pub decay: Milliseconds,
+    /// Operator envelope level held after the decay until the next note.
+    pub sustain: NormalizedValue,
+}
+
+impl FmOperatorParameters {
+    fn new(level: f32) -> Self {
+        Self {
+            ratio: FrequencyRatio::default(),
+            level: NormalizedValue::new(level),
+            feedback: NormalizedValue::default(),
+            envelope_enabled: AtomicBool::new(false),
+            attack: Milliseconds::new(0),
+            decay: Milliseconds::new(DEFAULT_OPERATOR_DECAY_MILLISECONDS),
+            sustain: NormalizedValue::new(DEFAULT_OPERATOR_SUSTAIN),
+        }
+    }
+
+    /// Replace all the values in this `FmOperatorParameters` with the values from the provided `FmOperatorParameters`.
+    pub fn assign_from(&self, parameters: &FmOperatorParameters) {
+        self.ratio.store(parameters.ratio.load());
+        self.level.store(parameters.level.load());
+        self.feedback.store(parameters.feedback.load());
+        self.envelope_enabled
+            .store(parameters.envelope_enabled.load(Relaxed), Relaxed);
+        self.attack.store(
+            parameters
+                .attack
+                .load()
+                .min(MAX_OPERATOR_ENVELOPE_MILLISECONDS),
+        );
+        self.decay.store(
+            parameters
+                .decay
+                .load()
+                .min(MAX_OPERATOR_ENVELOPE_MILLISECONDS),
+        );
+        self.sustain.store(parameters.sustain.load());
+    }
+}
+
+impl Default for FmOperatorParameters {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+/// Shared atomic parameters for the four operator FM oscillator.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FourOpFmParameters {
+    /// Index into `FM_ALGORITHMS` selecting the operator routing.
+    pub algorithm: AtomicU8,
+    /// Operators 1 to 4.
+    pub operators: [FmOperatorParameters; FM_OPERATOR_COUNT],
+}
+
+impl FourOpFmParameters {
+    /// Replace all the values in this `FourOpFmParameters` with the values from the provided `FourOpFmParameters`.
+    pub fn assign_from(&self, parameters: &FourOpFmParameters) {
+        self.algorithm
+            .store(algorithm_index(parameters.algorithm.load(Relaxed)), Relaxed);
+        self.operators
+            .iter()
+            .zip(parameters.operators.iter())
+            .for_each(|(operator, source)| operator.assign_from(source));
+    }
+}
+
+impl Default for FourOpFmParameters {
+    fn default() -> Self {
+        Self {
+            algorithm: AtomicU8::new(0),
+            operators: DEFAULT_OPERATOR_LEVELS.map(FmOperatorParameters::new),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EnvelopeStage {
+    Attack,
+    Decay,
+    Sustain,
+}
+
+// Linear attack and decay to a held sustain level, the amp envelope takes care of the release
+#[derive(Debug, Clone, Copy)]
+struct OperatorEnvelope {
+    is_enabled: bool,
+    stage: EnvelopeStage,
+    level: f64,
+    attack_increment: f64,
+    decay_increment: f64,
+    sustain: f64,
+}
+
+impl OperatorEnvelope {
+    fn new() -> Self {
+        Self {
+            is_enabled: false,
+            stage: EnvelopeStage::Attack,
+            level: 0.0,
+            attack_increment: 1.0,
+            decay_increment: 1.0,
+            sustain: f64::from(DEFAULT_OPERATOR_SUSTAIN),
+        }
+    }
+
+    fn set_parameters(&mut self, parameters: &FmOperatorParameters, sample_rate: u32) {
+        self.is_enabled = parameters.envelope_enabled.load(Relaxed);
+        self.attack_increment = segment_increment(parameters.attack.load(), sample_rate);
+        self.decay_increment = segment_increment(parameters.decay.load(), sample_rate);
+        self.sustain = f64::from(parameters.sustain.load());
+    }
+
+    fn retrigger(&mut self) {
+        self.stage = EnvelopeStage::Attack;
+        self.level = 0.0;
+    }
+
+    fn next_level(&mut self) -> f64 {
+        if !self.is_enabled {
+            return 1.0;
+        }
+
+        match self.stage {
+            EnvelopeStage::Attack => {
+                self.level += self.attack_increment;
+                if self.level >= 1.0 {
+                    self.level = 1.0;
+                    self.stage = EnvelopeStage::Decay;
+                }
+            }
+            EnvelopeStage::Decay => {
+                self.level -= self.decay_increment;
+                if self.level <= self.sustain {
+                    self.level = self.sustain;
+                    self.stage = EnvelopeStage::Sustain;
+                }
+            }
+            EnvelopeStage::Sustain => self.level = self.sustain,
+        }
+
+        self.level
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Operator {
+    phase: f64,
+    ratio: f64,
+    level: f64,
+    feedback: f64,
+    // The last two outputs are averaged for feedback to keep it from oscillating at Nyquist
+    previous_outputs: [f64; 2],
+    envelope: OperatorEnvelope,
+}
+
+impl Operator {
+    fn new(level: f32) -> Self {
+        Self {
+            phase: 0.0,
+            ratio: f64::from(Defaults::FREQUENCY_RATIO),
+            level: f64::from(level),
+            feedback: 0.0,
+            previous_outputs: [0.0; 2],
+            envelope: OperatorEnvelope::new(),
+        }
+    }
+}
+
+/// Four operator FM oscillator with selectable routing algorithms and operator feedback.
+pub struct FourOpFM {
+    shape: WaveShape,
+    sample_rate: u32,
+    phase_coefficient: f64,
+    algorithm: FmAlgorithm,
+    operators: [Operator; FM_OPERATOR_COUNT],
+    index: f64,
+    brightness: f64,
+}
+
+impl FourOpFM {
+    pub(crate) fn new(sample_rate: u32) -> Self {
+        log::debug!(target: "synth::oscillator", shape = "FourOpFM"; "Constructing wave generator");
+
+        Self {
+            shape: SHAPE,
+            sample_rate,
+            phase_coefficient: RADS_PER_CYCLE / f64::from(sample_rate),
+            algorithm: FM_ALGORITHMS[0],
+            operators: DEFAULT_OPERATOR_LEVELS.map(Operator::new),
+            index: DEFAULT_MACRO,
+            brightness: DEFAULT_MACRO,
+        }
+    }
+}
+
+impl GenerateWave for FourOpFM {
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        let frequency = f64::from(tone_frequency * modulation.unwrap_or(1.0));
+        let mut outputs = [0.0; FM_OPERATOR_COUNT];
+        let mut carrier_sum = 0.0;
+        let mut carrier_level_sum = 0.0;
+
+        for operator_index in (0..FM_OPERATOR_COUNT).rev() {
+            let phase_modulation: f64 = self.algorithm.modulators[operator_index]
+                .iter()
+                .map(|modulator_index| outputs[*modulator_index])
+                .sum();
+            let operator = &mut self.operators[operator_index];
+            let feedback = operator.feedback
+                * self.brightness
+                * MAX_FEEDBACK
+                * (operator.previous_outputs[0] + operator.previous_outputs[1])
+                / 2.0;
+
+            let sine = (operator.phase + phase_modulation + feedback).sin();
+            let output = sine * operator.level * operator.envelope.next_level();
+            operator.previous_outputs = [output, operator.previous_outputs[0]];
+
+            operator.phase += self.phase_coefficient * frequency * operator.ratio;
+            operator.phase = operator.phase.rem_euclid(RADS_PER_CYCLE);
+
+            if self.algorithm.carriers[operator_index] {
+                carrier_sum += output;
+                carrier_level_sum += operator.level;
+            } else {
+                outputs[operator_index] = output * self.index * MAX_MODULATION_INDEX;
+            }
+        }
+
+        // Scaled by the summed carrier levels only when they could exceed full scale, so a silent carrier
+        // doesn't turn the others down and a single carrier keeps its own level
+        f64_to_f32_clamped(carrier_sum / carrier_level_sum.max(1.0))
+    }
+
+    fn set_shape_parameter1(&mut self, parameter: f32) {
+        self.index = f64::from(parameter.clamp(0.0, 1.0)) * MACRO_SCALE;
+    }
+
+    fn set_shape_parameter2(&mut self, parameter: f32) {
+        self.brightness = f64::from(parameter.clamp(0.0, 1.0)) * MACRO_SCALE;
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        let phase = RADS_PER_CYCLE * f64::from(phase.clamp(0.0, 1.0));
+        self.operators
+            .iter_mut()
+            .for_each(|operator| operator.phase = phase);
+    }
+
+    fn shape(&self) -> WaveShape {
+        self.shape
+    }
+
+    fn reset(&mut self) {
+        self.operators.iter_mut().for_each(|operator| {
+            operator.phase = 0.0;
+            operator.previous_outputs = [0.0; 2];
+        });
+    }
+
+    fn retrigger(&mut self) {
+        self.operators
+            .iter_mut()
+            .for_each(|operator| operator.envelope.retrigger());
+    }
+
+    fn set_four_op_fm(&mut self, parameters: &FourOpFmParameters) {
+        self.algorithm =
+            FM_ALGORITHMS[usize::from(algorithm_index(parameters.algorithm.load(Relaxed)))];
+
+        for (operator, operator_parameters) in
+            self.operators.iter_mut().zip(parameters.operators.iter())
+        {
+            operator.ratio = f64::from(operator_parameters.ratio.load());
+            operator.level = f64::from(operator_parameters.level.load());
+            operator.feedback = f64::from(operator_parameters.feedback.load());
+            operator
+                .envelope
+                .set_parameters(operator_parameters, self.sample_rate);
+        }
+    }
+}
+
+fn algorithm_index(index: u8) -> u8 {
+    // FM_ALGORITHM_COUNT is 8
+    #[allow(clippy::cast_possible_truncation)]
+    let last_algorithm = (FM_ALGORITHM_COUNT - 1) as u8;
+    index.min(last_algorithm)
+}
+
+fn segment_increment(milliseconds: u32, sample_rate: u32) -> f64 {
+    let samples = f64::from(milliseconds.min(MAX_OPERATOR_ENVELOPE_MILLISECONDS))
+        * f64::from(sample_rate)
+        / 1000.0;
+
+    if samples < 1.0 { 1.0 } else { 1.0 / samples }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use accsyn_core::math::f32s_are_equal;
+
+    const TEST_SAMPLE_RATE: u32 = 48_000;
+    const TEST_FREQUENCY: f32 = 440.0;
+
+    fn render(oscillator: &mut FourOpFM, count: usize) -> Vec<f32> {
+        (0..count)
+            .map(|_| oscillator.next_sample(TEST_FREQUENCY, None))
+            .collect()
+    }
+
+    fn parameters_with_levels(levels: [f32; FM_OPERATOR_COUNT]) -> FourOpFmParameters {
+        let parameters = FourOpFmParameters::default();
+        parameters
+            .operators
+            .iter()
+            .zip(levels)
+            .for_each(|(operator, level)| operator.level.store(level));
+        parameters
+    }
+
+    #[test]
+    fn unmodulated_carrier_is_a_sine_at_the_note_frequency() {
+        let mut oscillator = FourOpFM::new(TEST_SAMPLE_RATE);
+        oscillator.set_four_op_fm(&parameters_with_levels([1.0, 0.0, 0.0, 0.0]));
+
+        let output = render(&mut oscillator, 64);
+
+        for (index, sample) in output.iter().enumerate() {
+            // Test indexes are tiny
+            #[allow(clippy::cast_precision_loss)]
+            let expected = (RADS_PER_CYCLE * f64::from(TEST_FREQUENCY) * index as f64
+                / f64::from(TEST_SAMPLE_RATE))
+            .sin();
+            #[allow(clippy::cast_possible_truncation)]
+            let expected = expected as f32;
+            assert!((sample - expected).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn modulator_changes_the_carrier_output() {
+        let mut clean = FourOpFM::new(TEST_SAMPLE_RATE);
+        clean.set_four_op_fm(&parameters_with_levels([1.0, 0.0, 0.0, 0.0]));
+        let mut modulated = FourOpFM::new(TEST_SAMPLE_RATE);
+        modulated.set_four_op_fm(&parameters_with_levels([1.0, 1.0, 0.0, 0.0]));
+
+        let clean_output = render(&mut clean, 256);
+        let modulated_output = render(&mut modulated, 256);
+
+        assert!(
+            clean_output
+                .iter()
+                .zip(modulated_output.iter())
+                .any(|(clean, modulated)| (clean - modulated).abs() > 0.1)
+        );
+    }
+
+    #[test]
+    fn index_macro_of_zero_removes_all_modulation() {
+        let mut clean = FourOpFM::new(TEST_SAMPLE_RATE);
+        clean.set_four_op_fm(&parameters_with_levels([1.0, 0.0, 0.0, 0.0]));
+        let mut modulated = FourOpFM::new(TEST_SAMPLE_RATE);
+        modulated.set_four_op_fm(&parameters_with_levels([1.0, 1.0, 1.0, 1.0]));
+        modulated.set_shape_parameter1(0.0);
+
+        let clean_output = render(&mut clean, 256);
+        let modulated_output = render(&mut modulated, 256);
+
+        assert!(
+            clean_output
+                .iter()
+                .zip(modulated_output.iter())
+                .all(|(clean, modulated)| f32s_are_equal(*clean, *modulated))
+        );
+    }
+
+    #[test]
+    fn additive_algorithm_mixes_every_operator_as_a_carrier() {
+        let parameters = parameters_with_levels([1.0, 1.0, 1.0, 1.0]);
+        parameters.algorithm.store(7, Relaxed);
+        let mut oscillator = FourOpFM::new(TEST_SAMPLE_RATE);
+        oscillator.set_four_op_fm(&parameters);
+
+        let output = render(&mut oscillator, 64);
+        let mut single = FourOpFM::new(TEST_SAMPLE_RATE);
+        single.set_four_op_fm(&parameters_with_levels([1.0, 0.0, 0.0, 0.0]));
+        let single_output = render(&mut single, 64);
+
+        // Every operator is a sine at ratio 1, so the averaged mix matches one sine
+        assert!(
+            output
+                .iter()
+                .zip(single_output.iter())
+                .all(|(mixed, single)| (mixed - single).abs() < 0.0001)
+        );
+    }
+
+    #[test]
+    fn silent_carriers_do_not_turn_down_the_mix() {
+        let parameters = parameters_with_levels([1.0, 0.0, 0.0, 0.0]);
+        parameters.algorithm.store(7, Relaxed);
+        let mut oscillator = FourOpFM::new(TEST_SAMPLE_RATE);
+        oscillator.set_four_op_fm(&parameters);
+
+        let output = render(&mut oscillator, 64);
+        let mut single = FourOpFM::new(TEST_SAMPLE_RATE);
+        single.set_four_op_fm(&parameters_with_levels([1.0, 0.0, 0.0, 0.0]));
+        let single_output = render(&mut single, 64);
+
+        assert!(
+            output
+                .iter()
+                .zip(single_output.iter())
+                .all(|(mixed, single)| (mixed - single).abs() < 0.0001)
+        );
+    }
+
+    #[test]
+    fn feedback_adds_harmonics_to_a_single_operator() {
+        let parameters = parameters_with_levels([1.0, 0.0, 0.0, 0.0]);
+        let mut clean = FourOpFM::new(TEST_SAMPLE_RATE);
+        clean.set_four_op_fm(&parameters);
+        parameters.operators[0].feedback.store(1.0);
+        let mut fed_back = FourOpFM::new(TEST_SAMPLE_RATE);
+        fed_back.set_four_op_fm(&parameters);
+
+        let clean_output = render(&mut clean, 256);
+        let fed_back_output = render(&mut fed_back, 256);
+
+        assert!(
+            clean_output
+                .iter()
+                .zip(fed_back_output.iter())
+                .any(|(clean, fed_back)| (clean - fed_back).abs() > 0.1)
+        );
+        assert!(fed_back_output.iter().all(|sample| sample.abs() <= 1.0));
+    }
+
+    #[test]
+    fn operator_envelope_attacks_then_decays_to_sustain() {
+        let parameters = FmOperatorParameters::new(1.0);
+        parameters.envelope_enabled.store(true, Relaxed);
+        parameters.attack.store(1);
+        parameters.decay.store(1);
+        parameters.sustain.store(0.25);
+        let mut envelope = OperatorEnvelope::new();
+        envelope.set_parameters(&parameters, TEST_SAMPLE_RATE);
+        envelope.retrigger();
+
+        let levels: Vec<f64> = (0..200).map(|_| envelope.next_level()).collect();
+
+        assert!(levels[0] < 0.1);
+        assert!(
+            levels
+                .iter()
+                .any(|level| (level - 1.0).abs() < f64::EPSILON)
+        );
+        assert!((levels[199] - 0.25).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn disabled_operator_envelope_holds_full_level() {
+        let mut envelope = OperatorEnvelope::new();
+
+        assert!((envelope.next_level() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn out_of_range_algorithm_uses_the_last_algorithm() {
+        let parameters = FourOpFmParameters::default();
+        parameters.algorithm.store(200, Relaxed);
+        let loaded = FourOpFmParameters::default();
+
+        loaded.assign_from(&parameters);
+
+        assert_eq!(
+            usize::from(loaded.algorithm.load(Relaxed)),
+            FM_ALGORITHM_COUNT - 1
+        );
+    }
+
+    #[test]
+    fn algorithms_only_route_higher_operators_into_lower_ones() {
+        for algorithm in FM_ALGORITHMS {
+            for (operator_index, modulators) in algorithm.modulators.iter().enumerate() {
+                assert!(
+                    modulators
+                        .iter()
+                        .all(|modulator| *modulator > operator_index)
+                );
+                assert!(
+                    modulators
+                        .iter()
+                        .all(|modulator| !algorithm.carriers[*modulator])
+                );
+            }
+            assert!(algorithm.carriers.iter().any(|is_carrier| *is_carrier));
+        }
+    }
+
+    #[test]
+    fn parameters_round_trip_through_json() {
+        let parameters = FourOpFmParameters::default();
+        parameters.algorithm.store(4, Relaxed);
+        parameters.operators[3].ratio.store(3.5);
+        parameters.operators[3].feedback.store(0.75);
+
+        let json = serde_json::to_string(&parameters).unwrap();
+        let restored: FourOpFmParameters = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.algorithm.load(Relaxed), 4);
+        assert!(f32s_are_equal(restored.operators[3].ratio.load(), 3.5));
+        assert!(f32s_are_equal(restored.operators[3].feedback.load(), 0.75));
+        assert!(f32s_are_equal(
+            restored.operators[1].level.load(),
+            DEFAULT_OPERATOR_LEVELS[1]
+        ));
+    }
+}
@@ -1,8 +1,20 @@
 use crate::modules::oscillator::WaveShape;
+use crate::modules::oscillator::additive::AdditiveParameters;
+use crate::modules::oscillator::four_op_fm::FourOpFmParameters;
+use crate::modules::oscillator::sampler::{LoopMode, Sample};
+use crate::modules::oscillator::supersaw::SupersawParameters;
+use crate::modules::oscillator::wavetable::Wavetable;
+use std::sync::Arc;
 
 pub trait GenerateWave {
     fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32;
 
+    // Half the difference between the left and right channels of the last sample, the left channel is the
+    // sample plus this and the right the sample minus it
+    fn side_sample(&self) -> f32 {
+        0.0
+    }
+
     fn set_shape_parameter1(&mut self, parameter: f32);
     fn set_shape_parameter2(&mut self, parameter: f32);
 
@@ -11,4 +23,24 @@ pub trait GenerateWave {
     fn shape(&self) -> WaveShape;
 
     fn reset(&mut self);
+
+    // Moves the cycle on by `samples` worth of phase increment without using the output. Any shape that takes
+    // the modulation as a frequency multiplier can do this with one scaled sample
+    fn advance(&mut self, tone_frequency: f32, modulation: Option<f32>, samples: f32) {
+        let _ = self.next_sample(tone_frequency, Some(modulation.unwrap_or(1.0) * samples));
+    }
+
+    fn retrigger(&mut self) {}
+
+    fn set_wavetable(&mut self, _wavetable: Arc<Wavetable>) {}
+
+    fn set_sample(&mut self, _sample: Arc<Sample>) {}
+
+    fn set_sample_playback(&mut self, _root_note: u8, _loop_mode: LoopMode) {}
+
+    fn set_four_op_fm(&mut self, _parameters: &FourOpFmParameters) {}
+
+    fn set_supersaw(&mut self, _parameters: &SupersawParameters) {}
+
+    fn set_additive(&mut self, _parameters: &AdditiveParameters) {}
 }
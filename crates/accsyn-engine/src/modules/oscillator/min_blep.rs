@@ -0,0 +1,172 @@
+use super::wavetable::fft;
+use std::f64::consts::{PI, TAU};
+use std::sync::LazyLock;
+
+// Length of the band-limited step in samples, the correction is finished after this many samples
+const ZERO_CROSSINGS: usize = 16;
+// Table entries per sample, lookups between entries are linearly interpolated
+const OVERSAMPLING: usize = 64;
+const TABLE_LENGTH: usize = ZERO_CROSSINGS * OVERSAMPLING;
+const FFT_SIZE: usize = 8192;
+// Keeps the log of the spectrum finite in the stop band
+const MAGNITUDE_FLOOR: f64 = 1.0e-9;
+
+// Minimum phase band-limited step, rising from 0 to 1 with nothing before the step itself
+static MIN_BLEP_TABLE: LazyLock<Vec<f32>> = LazyLock::new(build_min_blep_table);
+
+/// Accumulates the corrections that turn the hard steps of a phase reset into band-limited steps.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct MinBlep {
+    residual: [f32; ZERO_CROSSINGS],
+    position: usize,
+}
+
+impl MinBlep {
+    pub(super) fn new() -> Self {
+        // Build the table now rather than on the audio thread at the first sync
+        LazyLock::force(&MIN_BLEP_TABLE);
+
+        Self {
+            residual: [0.0; ZERO_CROSSINGS],
+            position: 0,
+        }
+    }
+
+    /// Adds a step of `height` that happened `delay` samples (0 to 2) before the current sample, which
+    /// already contains the whole step.
+    pub(super) fn add_step(&mut self, height: f32, delay: f32) {
+        let delay = delay.clamp(0.0, 2.0);
+
+        for offset in 0..ZERO_CROSSINGS {
+            // Offsets and oversampling are small constants, exactly representable in f32
+            #[allow(clippy::cast_precision_loss)]
+            let table_position = (offset as f32 + delay) * OVERSAMPLING as f32;
+            // Position is non-negative and bounded by the table length
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let index = table_position as usize;
+            if index >= TABLE_LENGTH {
+                break;
+            }
+
+            // Index is bounded by the table length
+            #[allow(clippy::cast_precision_loss)]
+            let fraction = table_position - index as f32;
+            let band_limited_step = MIN_BLEP_TABLE[index]
+                + (MIN_BLEP_TABLE[index + 1] - MIN_BLEP_TABLE[index]) * fraction;
+            self.residual[(self.position + offset) % ZERO_CROSSINGS] +=
+                height * (band_limited_step - 1.0);
+        }
+    }
+
+    /// Returns the correction for the current sample and moves on to the next one.
+    pub(super) fn next_correction(&mut self) -> f32 {
+        let correction = self.residual[self.position];
+        self.residual[self.position] = 0.0;
+        self.position = (self.position + 1) % ZERO_CROSSINGS;
+        correction
+    }
+}
+
+fn build_min_blep_table() -> Vec<f32> {
+    let impulse_length = 2 * TABLE_LENGTH + 1;
+    let mut real = vec![0.0; FFT_SIZE];
+    let mut imaginary = vec![0.0; FFT_SIZE];
+
+    // Blackman windowed sinc with a zero crossing every sample
+    for (index, sample) in real.iter_mut().take(impulse_length).enumerate() {
+        // Table sizes are small constants, exactly representable in f64
+        #[allow(clippy::cast_precision_loss)]
+        let (time, window_phase) = (
+            (index as f64 - TABLE_LENGTH as f64) / OVERSAMPLING as f64,
+            TAU * index as f64 / (impulse_length - 1) as f64,
+        );
+        let sinc = if time == 0.0 {
+            1.0
+        } else {
+            (PI * time).sin() / (PI * time)
+        };
+        let window = 0.42 - 0.5 * window_phase.cos() + 0.08 * (2.0 * window_phase).cos();
+        *sample = sinc * window;
+    }
+
+    make_minimum_phase(&mut real, &mut imaginary);
+
+    // Normalizing over the table rather than the whole impulse makes the step end exactly at 1, so no step
+    // is left behind once the correction runs out
+    let total: f64 = real.iter().take(TABLE_LENGTH + 1).sum();
+    let mut running_sum = 0.0;
+    real.iter()
+        .take(TABLE_LENGTH + 1)
+        .map(|sample| {
+            running_sum += sample;
+            // The table is only ever interpolated in f32
+            #[allow(clippy::cast_possible_truncation)]
+            let step = (running_sum / total) as f32;
+            step
+        })
+        .collect()
+}
+
+// Folds the real cepstrum so every zero sits inside the unit circle, moving the energy of the impulse to its
+// start while keeping its magnitude response
+fn make_minimum_phase(real: &mut [f64], imaginary: &mut [f64]) {
+    let size = real.len();
+
+    fft(real, imaginary, false);
+    for (real, imaginary) in real.iter_mut().zip(imaginary.iter_mut()) {
+        *real = real.hypot(*imaginary).max(MAGNITUDE_FLOOR).ln();
+        *imaginary = 0.0;
+    }
+
+    fft(real, imaginary, true);
+    for (index, (real, imaginary)) in real.iter_mut().zip(imaginary.iter_mut()).enumerate() {
+        if index > size / 2 {
+            *real = 0.0;
+        } else if index > 0 && index < size / 2 {
+            *real *= 2.0;
+        }
+        *imaginary = 0.0;
+    }
+
+    fft(real, imaginary, false);
+    for (real, imaginary) in real.iter_mut().zip(imaginary.iter_mut()) {
+        let magnitude = real.exp();
+        let (sin, cos) = imaginary.sin_cos();
+        *real = magnitude * cos;
+        *imaginary = magnitude * sin;
+    }
+
+    fft(real, imaginary, true);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_rises_from_zero_to_one() {
+        let table = &*MIN_BLEP_TABLE;
+
+        assert_eq!(table.len(), TABLE_LENGTH + 1);
+        assert!(table[0].abs() < 0.01, "Table starts at {}", table[0]);
+        assert!(
+            (table[TABLE_LENGTH] - 1.0).abs() < f32::EPSILON,
+            "Table ends at {}",
+            table[TABLE_LENGTH]
+        );
+    }
+
+    #[test]
+    fn corrected_step_settles_on_the_step_height() {
+        let mut min_blep = MinBlep::new();
+        min_blep.add_step(2.0, 0.5);
+
+        let corrected: Vec<f32> = (0..ZERO_CROSSINGS + 1)
+            .map(|_| 2.0 + min_blep.next_correction())
+            .collect();
+
+        assert!(corrected[0] < 1.0, "Step starts at {}", corrected[0]);
+        assert!((corrected[ZERO_CROSSINGS] - 2.0).abs() < f32::EPSILON);
+        assert!((corrected[ZERO_CROSSINGS - 1] - 2.0).abs() < 0.02);
+    }
+}
@@ -1,24 +1,179 @@
 use super::WaveShape;
 use crate::modules::oscillator::generate_wave_trait::GenerateWave;
+use std::f32::consts::PI;
 
-/// White noise generator producing random samples in the [-1, 1] range.
-pub struct Noise {
-    shape: WaveShape,
+// Output gains that bring each colour's peaks back to roughly [-1, 1]
+const PINK_GAIN: f32 = 0.11;
+const BROWN_GAIN: f32 = 3.5;
+const BLUE_GAIN: f32 = 0.35;
+// Leak of the brown noise integrator, keeping it from wandering off to DC
+const BROWN_LEAK: f32 = 1.02;
+const BROWN_STEP: f32 = 0.02;
+// Shape parameter 2 holds samples below this point and band-passes above it
+const PROCESSING_SPLIT: f32 = 0.5;
+const MIN_BANDPASS_Q: f32 = 0.7;
+const MAX_BANDPASS_Q: f32 = 40.0;
+// Keeps the bandpass centre safely below Nyquist
+const MAX_BANDPASS_CENTRE_RATIO: f32 = 0.45;
+const BANDPASS_LEVEL: f32 = 0.5;
+
+/// Spectral colour of the noise, selected by shape parameter 1.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum NoiseColour {
+    /// Equal energy at every frequency.
+    #[default]
+    White,
+    /// Equal energy per octave, falling 3 dB per octave.
+    Pink,
+    /// Falling 6 dB per octave.
+    Brown,
+    /// Rising 3 dB per octave.
+    Blue,
 }
 
-impl Default for Noise {
-    fn default() -> Self {
-        Self::new()
+impl NoiseColour {
+    const COLOURS: [NoiseColour; 4] = [Self::White, Self::Pink, Self::Brown, Self::Blue];
+
+    fn from_parameter(parameter: f32) -> Self {
+        // The parameter is clamped to [0, 1] so the index is within the colour count
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let index = (parameter.clamp(0.0, 1.0) * Self::COLOURS.len() as f32) as usize;
+        Self::COLOURS[index.min(Self::COLOURS.len() - 1)]
     }
 }
 
+/// Processing applied after the colour, selected by shape parameter 2.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NoiseProcessing {
+    /// Plain coloured noise.
+    None,
+    /// Sample and hold, from the sample rate (0.0) down to the played note (1.0).
+    SampleAndHold(f32),
+    /// Resonant bandpass centred on the played note, from gentle (0.0) to narrow (1.0).
+    BandPass(f32),
+}
+
+impl NoiseProcessing {
+    fn from_parameter(parameter: f32) -> Self {
+        let parameter = parameter.clamp(0.0, 1.0);
+        if parameter == 0.0 {
+            Self::None
+        } else if parameter <= PROCESSING_SPLIT {
+            Self::SampleAndHold(parameter / PROCESSING_SPLIT)
+        } else {
+            Self::BandPass((parameter - PROCESSING_SPLIT) / (1.0 - PROCESSING_SPLIT))
+        }
+    }
+}
+
+/// Coloured noise generator with an optional sample and hold or resonant bandpass that follows the played note.
+pub struct Noise {
+    shape: WaveShape,
+    sample_rate: f32,
+    colour: NoiseColour,
+    processing: NoiseProcessing,
+    // Paul Kellet's pink noise filter states
+    pink_states: [f32; 7],
+    brown_state: f32,
+    previous_pink: f32,
+    hold_phase: f32,
+    held_sample: f32,
+    // Trapezoidal state variable filter integrator states
+    bandpass_states: [f32; 2],
+}
+
 impl Noise {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(sample_rate: u32) -> Self {
         log::debug!(target: "synth::oscillator", shape = "Noise"; "Constructing wave generator");
+
+        // Sample rate is always ≤ 192_000, within f32 precision (2²³ = 8_388_608)
+        #[allow(clippy::cast_precision_loss)]
+        let sample_rate = sample_rate as f32;
+
         Self {
             shape: WaveShape::Noise,
+            sample_rate,
+            colour: NoiseColour::default(),
+            processing: NoiseProcessing::None,
+            pink_states: [0.0; 7],
+            brown_state: 0.0,
+            previous_pink: 0.0,
+            hold_phase: 0.0,
+            held_sample: 0.0,
+            bandpass_states: [0.0; 2],
+        }
+    }
+
+    fn coloured_sample(&mut self) -> f32 {
+        let white: f32 = rand::random_range(-1.0..=1.0);
+
+        match self.colour {
+            NoiseColour::White => white,
+            NoiseColour::Pink => self.pink(white) * PINK_GAIN,
+            NoiseColour::Brown => {
+                self.brown_state = (self.brown_state + BROWN_STEP * white) / BROWN_LEAK;
+                self.brown_state * BROWN_GAIN
+            }
+            NoiseColour::Blue => {
+                // Differentiating pink noise turns its 3 dB per octave fall into a 3 dB per octave rise
+                let pink = self.pink(white);
+                let blue = (pink - self.previous_pink) * BLUE_GAIN;
+                self.previous_pink = pink;
+                blue
+            }
         }
     }
+
+    fn pink(&mut self, white: f32) -> f32 {
+        let states = &mut self.pink_states;
+        states[0] = 0.998_86 * states[0] + white * 0.055_517_9;
+        states[1] = 0.993_32 * states[1] + white * 0.075_075_9;
+        states[2] = 0.969 * states[2] + white * 0.153_852;
+        states[3] = 0.866_5 * states[3] + white * 0.310_485_6;
+        states[4] = 0.55 * states[4] + white * 0.532_952_2;
+        states[5] = -0.761_6 * states[5] - white * 0.016_898;
+        let pink = states.iter().sum::<f32>() + white * 0.536_2;
+        states[6] = white * 0.115_926;
+        pink
+    }
+
+    fn sample_and_hold(&mut self, amount: f32, frequency: f32) -> f32 {
+        // Exponential sweep from the sample rate down to the note frequency
+        let hold_frequency = self.sample_rate * (frequency / self.sample_rate).powf(amount);
+        self.hold_phase += hold_frequency / self.sample_rate;
+
+        if self.hold_phase >= 1.0 {
+            self.hold_phase = self.hold_phase.fract();
+            self.held_sample = self.coloured_sample();
+        }
+
+        self.held_sample
+    }
+
+    fn band_pass(&mut self, amount: f32, frequency: f32, input: f32) -> f32 {
+        let centre = frequency.min(self.sample_rate * MAX_BANDPASS_CENTRE_RATIO);
+        let q = MIN_BANDPASS_Q * (MAX_BANDPASS_Q / MIN_BANDPASS_Q).powf(amount);
+
+        let g = (PI * centre / self.sample_rate).tan();
+        let k = 1.0 / q;
+        let a1 = 1.0 / (1.0 + g * (g + k));
+        let a2 = g * a1;
+        let a3 = g * a2;
+
+        let [band_state, low_state] = self.bandpass_states;
+        let v3 = input - low_state;
+        let band = a1 * band_state + a2 * v3;
+        let low = low_state + a2 * band_state + a3 * v3;
+        self.bandpass_states = [2.0 * band - band_state, 2.0 * low - low_state];
+
+        // A narrower band passes less of the noise, so the level is raised to keep the power roughly even
+        let power_compensation = (self.sample_rate * q / (PI * centre)).sqrt();
+        band * k * power_compensation * BANDPASS_LEVEL
+    }
 }
 
 impl GenerateWave for Noise {
@@ -26,12 +181,35 @@ impl GenerateWave for Noise {
         if tone_frequency == 0.0 {
             return 0.0;
         }
-        rand::random_range(-1.0..=1.0) * modulation.unwrap_or(1.0)
+
+        let frequency = (tone_frequency * modulation.unwrap_or(1.0)).abs().max(1.0);
+
+        let sample = match self.processing {
+            NoiseProcessing::None => self.coloured_sample(),
+            NoiseProcessing::SampleAndHold(amount) => self.sample_and_hold(amount, frequency),
+            NoiseProcessing::BandPass(amount) => {
+                let input = self.coloured_sample();
+                self.band_pass(amount, frequency, input)
+            }
+        };
+
+        sample.clamp(-1.0, 1.0)
     }
 
-    fn set_shape_parameter1(&mut self, _parameter: f32) {}
+    fn set_shape_parameter1(&mut self, parameter: f32) {
+        self.colour = NoiseColour::from_parameter(parameter);
+    }
 
-    fn set_shape_parameter2(&mut self, _parameter: f32) {}
+    fn set_shape_parameter2(&mut self, parameter: f32) {
+        let processing = NoiseProcessing::from_parameter(parameter);
+        if !matches!(
+            (self.processing, processing),
+            (NoiseProcessing::BandPass(_), NoiseProcessing::BandPass(_))
+        ) {
+            self.bandpass_states = [0.0; 2];
+        }
+        self.processing = processing;
+    }
 
     fn set_phase(&mut self, _phase: f32) {}
 
@@ -40,4 +218,111 @@ impl GenerateWave for Noise {
     }
 
     fn reset(&mut self) {}
+
+    fn advance(&mut self, _tone_frequency: f32, _modulation: Option<f32>, _samples: f32) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48000;
+    const SAMPLE_COUNT: usize = 48000;
+
+    fn render(noise: &mut Noise, frequency: f32) -> Vec<f32> {
+        (0..SAMPLE_COUNT)
+            .map(|_| noise.next_sample(frequency, None))
+            .collect()
+    }
+
+    // Mean absolute difference between neighbouring samples, high for bright noise and low for dark
+    fn roughness(samples: &[f32]) -> f32 {
+        let total: f32 = samples
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .sum();
+        // Sample count is a small test constant, exactly representable in f32
+        #[allow(clippy::cast_precision_loss)]
+        let count = (samples.len() - 1) as f32;
+        total / count
+    }
+
+    fn noise_with(parameter1: f32, parameter2: f32) -> Noise {
+        let mut noise = Noise::new(SAMPLE_RATE);
+        noise.set_shape_parameter1(parameter1);
+        noise.set_shape_parameter2(parameter2);
+        noise
+    }
+
+    #[test]
+    fn shape_parameter1_selects_the_colour() {
+        assert_eq!(NoiseColour::from_parameter(0.0), NoiseColour::White);
+        assert_eq!(NoiseColour::from_parameter(0.3), NoiseColour::Pink);
+        assert_eq!(NoiseColour::from_parameter(0.6), NoiseColour::Brown);
+        assert_eq!(NoiseColour::from_parameter(1.0), NoiseColour::Blue);
+    }
+
+    #[test]
+    fn shape_parameter2_selects_sample_and_hold_below_the_middle_and_bandpass_above() {
+        assert_eq!(NoiseProcessing::from_parameter(0.0), NoiseProcessing::None);
+        assert_eq!(
+            NoiseProcessing::from_parameter(0.25),
+            NoiseProcessing::SampleAndHold(0.5)
+        );
+        assert_eq!(
+            NoiseProcessing::from_parameter(0.75),
+            NoiseProcessing::BandPass(0.5)
+        );
+    }
+
+    #[test]
+    fn every_colour_stays_within_range_and_is_not_silent() {
+        for parameter1 in [0.0, 0.3, 0.6, 1.0] {
+            let samples = render(&mut noise_with(parameter1, 0.0), 440.0);
+
+            assert!(samples.iter().all(|sample| (-1.0..=1.0).contains(sample)));
+            assert!(samples.iter().any(|sample| sample.abs() > 0.05));
+        }
+    }
+
+    #[test]
+    fn darker_colours_change_more_slowly_than_brighter_ones() {
+        let white = roughness(&render(&mut noise_with(0.0, 0.0), 440.0));
+        let pink = roughness(&render(&mut noise_with(0.3, 0.0), 440.0));
+        let brown = roughness(&render(&mut noise_with(0.6, 0.0), 440.0));
+
+        assert!(
+            brown < pink,
+            "Brown {brown} should be smoother than pink {pink}"
+        );
+        assert!(
+            pink < white,
+            "Pink {pink} should be smoother than white {white}"
+        );
+    }
+
+    #[test]
+    fn sample_and_hold_at_full_amount_holds_for_a_note_period() {
+        let samples = render(&mut noise_with(0.0, 0.5), 480.0);
+
+        // 480 Hz at 48 kHz changes value at most once every 100 samples
+        let changes = samples.windows(2).filter(|pair| pair[0] != pair[1]).count();
+        assert!(changes <= SAMPLE_COUNT / 100 + 1, "{changes} changes");
+    }
+
+    #[test]
+    fn narrow_bandpass_is_smoother_than_the_white_noise_it_filters() {
+        let white = roughness(&render(&mut noise_with(0.0, 0.0), 220.0));
+        let filtered = render(&mut noise_with(0.0, 1.0), 220.0);
+
+        assert!(roughness(&filtered) < white / 4.0);
+        assert!(filtered.iter().any(|sample| sample.abs() > 0.05));
+    }
+
+    #[test]
+    fn silent_at_zero_frequency() {
+        let mut noise = noise_with(0.3, 0.75);
+
+        assert!(noise.next_sample(0.0, None) == 0.0);
+    }
 }
@@ -93,4 +93,9 @@ impl GenerateWave for Pulse {
     fn reset(&mut self) {
         self.x_coordinate = DEFAULT_X_COORDINATE;
     }
+
+    // The modulation sets the pulse width rather than the frequency, so the cycle is moved on directly
+    fn advance(&mut self, _tone_frequency: f32, _modulation: Option<f32>, samples: f32) {
+        self.x_coordinate += DEFAULT_X_INCREMENT * f64::from(samples);
+    }
 }
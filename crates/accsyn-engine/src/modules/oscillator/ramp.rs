@@ -49,6 +49,8 @@ impl GenerateWave for Ramp {
 
         if tone_frequency > 0.0 && self.x_coordinate >= period {
             self.x_coordinate -= period;
+        } else if tone_frequency > 0.0 && self.x_coordinate < 0.0 {
+            self.x_coordinate += period;
         }
 
         f64_to_f32_clamped(y_coordinate)
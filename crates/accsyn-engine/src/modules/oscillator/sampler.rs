@@ -0,0 +1,581 @@
+use super::wav_file::{WavFileError, read_wav_file};
+use super::{WaveShape, midi_note_to_frequency};
+use crate::modules::oscillator::generate_wave_trait::GenerateWave;
+use crate::modules::retired_arcs::RetiredArcs;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::path::Path;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering::{Acquire, Release};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use strum_macros::{EnumCount, EnumIter, FromRepr};
+
+const SHAPE: WaveShape = WaveShape::Sampler;
+
+/// File extension of the sample files in the user sample directory.
+pub const SAMPLE_FILE_EXTENSION: &str = "wav";
+/// Default root note of a sample, the note that plays it back at its recorded pitch (middle C).
+pub const DEFAULT_SAMPLE_ROOT_NOTE: u8 = 60;
+const DEFAULT_LOOP_START: f32 = 0.0;
+const DEFAULT_LOOP_END: f32 = 1.0;
+
+// Silence, used until a sample is selected or when the selected file can't be read
+static EMPTY_SAMPLE: LazyLock<Arc<Sample>> = LazyLock::new(|| Arc::new(Sample::empty()));
+
+/// How the sampler continues once playback reaches the end of the loop.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
+#[repr(u8)]
+pub enum LoopMode {
+    /// Play the sample once from the top and stop at the end.
+    #[default]
+    OneShot,
+    /// Jump back to the loop start when playback reaches the loop end.
+    Forward,
+    /// Reverse direction at each loop point, bouncing back and forth.
+    PingPong,
+}
+
+impl LoopMode {
+    /// Converts a numeric index to the corresponding loop mode, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
+}
+
+/// Mono audio loaded from a sample file.
+pub struct Sample {
+    name: String,
+    samples: Vec<f32>,
+    sample_rate: u32,
+}
+
+impl Sample {
+    /// Loads a sample from a WAV file, mixing it down to mono.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read as a WAV file.
+    pub fn load(name: &str, path: &Path) -> Result<Self, WavFileError> {
+        let wav_file = read_wav_file(path)?;
+        Ok(Self {
+            name: name.to_string(),
+            samples: wav_file.samples,
+            sample_rate: wav_file.sample_rate,
+        })
+    }
+
+    /// Returns the name the sample was loaded with.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of frames in the sample.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns true when the sample holds no audio.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    fn empty() -> Self {
+        Self {
+            name: String::new(),
+            samples: Vec::new(),
+            sample_rate: 0,
+        }
+    }
+
+    fn read(&self, position: f64) -> f32 {
+        let last_index = self.samples.len() - 1;
+        // Positions are kept within the sample, far below f64 precision limits
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let index = (position.max(0.0) as usize).min(last_index);
+        #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+        let fraction = (position - index as f64).clamp(0.0, 1.0) as f32;
+        let current = self.samples[index];
+        let next = self.samples[(index + 1).min(last_index)];
+
+        current + (next - current) * fraction
+    }
+}
+
+impl fmt::Debug for Sample {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Sample")
+            .field("name", &self.name)
+            .field("length", &self.samples.len())
+            .field("sample_rate", &self.sample_rate)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The sample selected for an oscillator, shared between the patch and the audio thread.
+///
+/// Patches store only the sample name. Files are loaded off the audio thread with `load`, and the audio
+/// thread picks up the new sample when the version changes. Replaced samples are retired rather than dropped,
+/// so the audio thread never frees one.
+pub struct SharedSample {
+    name: Mutex<String>,
+    sample: Mutex<Arc<Sample>>,
+    retired_samples: RetiredArcs<Sample>,
+    version: AtomicU32,
+}
+
+impl SharedSample {
+    /// Copies the selected sample from another `SharedSample` without reloading the file.
+    pub fn assign_from(&self, shared_sample: &SharedSample) {
+        self.store(
+            shared_sample.name(),
+            shared_sample
+                .sample
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+        );
+    }
+
+    /// Loads the named sample from the sample directory.
+    ///
+    /// An empty name clears the sample. A missing or unreadable file logs a warning and leaves the
+    /// oscillator silent, keeping the name so the patch still saves it.
+    pub fn load(&self, name: &str, sample_directory: &Path) {
+        if name.is_empty() {
+            self.store(String::new(), EMPTY_SAMPLE.clone());
+            return;
+        }
+
+        let mut path = sample_directory.join(sanitize_filename::sanitize(name));
+        path.set_extension(SAMPLE_FILE_EXTENSION);
+
+        let sample = match Sample::load(name, &path) {
+            Ok(sample) => {
+                log::info!(target: "synthesizer::modules::oscillator", "Loaded sample '{name}' with {} frames at {} Hz", sample.len(), sample.sample_rate);
+                Arc::new(sample)
+            }
+            Err(err) => {
+                log::warn!(target: "synthesizer::modules::oscillator", "Failed to load sample {}, the oscillator will be silent: {err}", path.display());
+                EMPTY_SAMPLE.clone()
+            }
+        };
+
+        self.store(name.to_string(), sample);
+    }
+
+    /// Returns the name of the selected sample, empty when no sample is selected.
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns a counter that changes every time a new sample is selected.
+    #[must_use]
+    pub fn version(&self) -> u32 {
+        self.version.load(Acquire)
+    }
+
+    /// Returns the selected sample without waiting, or `None` while another thread is changing it.
+    #[must_use]
+    pub fn try_sample(&self) -> Option<Arc<Sample>> {
+        self.sample.try_lock().ok().map(|sample| sample.clone())
+    }
+
+    fn store(&self, name: String, sample: Arc<Sample>) {
+        *self.name.lock().unwrap_or_else(PoisonError::into_inner) = name;
+        let replaced_sample = std::mem::replace(
+            &mut *self.sample.lock().unwrap_or_else(PoisonError::into_inner),
+            sample,
+        );
+        self.version.fetch_add(1, Release);
+        self.retired_samples.retire(replaced_sample);
+    }
+}
+
+impl Default for SharedSample {
+    fn default() -> Self {
+        Self {
+            name: Mutex::new(String::new()),
+            sample: Mutex::new(EMPTY_SAMPLE.clone()),
+            retired_samples: RetiredArcs::default(),
+            version: AtomicU32::new(0),
+        }
+    }
+}
+
+impl fmt::Debug for SharedSample {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SharedSample")
+            .field("name", &self.name())
+            .field("version", &self.version())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Serialize for SharedSample {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for SharedSample {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        let shared_sample = SharedSample::default();
+        *shared_sample
+            .name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = name;
+        Ok(shared_sample)
+    }
+}
+
+/// Sample playback oscillator, pitching a sample relative to its root note.
+pub struct Sampler {
+    shape: WaveShape,
+    sample_rate: u32,
+    sample: Arc<Sample>,
+    root_note: u8,
+    loop_mode: LoopMode,
+    loop_start: f32,
+    loop_end: f32,
+    position: f64,
+    is_reversed: bool,
+    is_playing: bool,
+}
+
+impl Sampler {
+    pub(crate) fn new(sample_rate: u32) -> Self {
+        log::debug!(target: "synth::oscillator", shape = "Sampler"; "Constructing wave generator");
+
+        Self {
+            shape: SHAPE,
+            sample_rate,
+            sample: EMPTY_SAMPLE.clone(),
+            root_note: DEFAULT_SAMPLE_ROOT_NOTE,
+            loop_mode: LoopMode::default(),
+            loop_start: DEFAULT_LOOP_START,
+            loop_end: DEFAULT_LOOP_END,
+            position: 0.0,
+            is_reversed: false,
+            is_playing: true,
+        }
+    }
+
+    // Loop points are whole frames, always at least one frame apart and inside the sample
+    fn loop_frames(&self) -> (f64, f64) {
+        // Sample lengths are capped well below f64 precision limits
+        #[allow(clippy::cast_precision_loss)]
+        let last_frame = (self.sample.len() - 1) as f64;
+        let start = f64::from(self.loop_start.min(self.loop_end)) * last_frame;
+        let end = f64::from(self.loop_start.max(self.loop_end)) * last_frame;
+        let start = start.floor().min((last_frame - 1.0).max(0.0));
+        let end = end.ceil().max(start + 1.0).min(last_frame.max(1.0));
+
+        (start, end)
+    }
+
+    fn advance(&mut self, increment: f64) {
+        // Sample lengths are capped well below f64 precision limits
+        #[allow(clippy::cast_precision_loss)]
+        let last_frame = (self.sample.len() - 1) as f64;
+        let (loop_start, loop_end) = self.loop_frames();
+
+        match self.loop_mode {
+            LoopMode::OneShot => {
+                self.position += increment;
+                if self.position > last_frame {
+                    self.is_playing = false;
+                }
+            }
+            LoopMode::Forward => {
+                self.position += increment;
+                if self.position >= loop_end {
+                    self.position =
+                        loop_start + (self.position - loop_start).rem_euclid(loop_end - loop_start);
+                }
+            }
+            LoopMode::PingPong => {
+                if self.is_reversed {
+                    self.position -= increment;
+                    if self.position <= loop_start {
+                        self.position = (loop_start + (loop_start - self.position)).min(loop_end);
+                        self.is_reversed = false;
+                    }
+                } else {
+                    self.position += increment;
+                    if self.position >= loop_end {
+                        self.position = (loop_end - (self.position - loop_end)).max(loop_start);
+                        self.is_reversed = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl GenerateWave for Sampler {
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        if !self.is_playing || self.sample.is_empty() {
+            return 0.0;
+        }
+
+        let output_sample = self.sample.read(self.position);
+
+        let frequency = tone_frequency * modulation.unwrap_or(1.0);
+        let pitch_ratio = f64::from(frequency / midi_note_to_frequency(self.root_note));
+        let rate_ratio = f64::from(self.sample.sample_rate) / f64::from(self.sample_rate);
+        let increment = (pitch_ratio * rate_ratio).abs();
+        if increment.is_finite() {
+            self.advance(increment);
+        }
+
+        output_sample
+    }
+
+    fn set_shape_parameter1(&mut self, parameter: f32) {
+        self.loop_start = parameter.clamp(0.0, 1.0);
+    }
+
+    fn set_shape_parameter2(&mut self, parameter: f32) {
+        self.loop_end = parameter.clamp(0.0, 1.0);
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        if self.sample.is_empty() {
+            return;
+        }
+
+        // Sample lengths are capped well below f64 precision limits
+        #[allow(clippy::cast_precision_loss)]
+        let last_frame = (self.sample.len() - 1) as f64;
+        self.position = f64::from(phase.clamp(0.0, 1.0)) * last_frame;
+    }
+
+    fn shape(&self) -> WaveShape {
+        self.shape
+    }
+
+    fn reset(&mut self) {
+        self.position = 0.0;
+        self.is_reversed = false;
+        self.is_playing = true;
+    }
+
+    fn retrigger(&mut self) {
+        self.reset();
+    }
+
+    fn set_sample(&mut self, sample: Arc<Sample>) {
+        self.sample = sample;
+        self.reset();
+    }
+
+    fn set_sample_playback(&mut self, root_note: u8, loop_mode: LoopMode) {
+        self.root_note = root_note;
+        self.loop_mode = loop_mode;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use accsyn_core::math::f32s_are_equal;
+
+    const TEST_SAMPLE_RATE: u32 = 48_000;
+
+    fn ramp_sample(length: usize, sample_rate: u32) -> Arc<Sample> {
+        // Test sample lengths are tiny
+        #[allow(clippy::cast_precision_loss)]
+        let samples = (0..length).map(|index| index as f32).collect();
+        Arc::new(Sample {
+            name: String::from("Ramp"),
+            samples,
+            sample_rate,
+        })
+    }
+
+    fn root_frequency() -> f32 {
+        midi_note_to_frequency(DEFAULT_SAMPLE_ROOT_NOTE)
+    }
+
+    fn play(sampler: &mut Sampler, frequency: f32, count: usize) -> Vec<f32> {
+        (0..count)
+            .map(|_| sampler.next_sample(frequency, None))
+            .collect()
+    }
+
+    #[test]
+    fn root_note_plays_the_sample_at_its_recorded_speed() {
+        let mut sampler = Sampler::new(TEST_SAMPLE_RATE);
+        sampler.set_sample(ramp_sample(8, TEST_SAMPLE_RATE));
+
+        let output = play(&mut sampler, root_frequency(), 4);
+
+        assert_eq!(output, vec![0.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn an_octave_up_plays_twice_as_fast() {
+        let mut sampler = Sampler::new(TEST_SAMPLE_RATE);
+        sampler.set_sample(ramp_sample(16, TEST_SAMPLE_RATE));
+
+        let output = play(&mut sampler, root_frequency() * 2.0, 4);
+
+        for (output_sample, expected) in output.iter().zip([0.0, 2.0, 4.0, 6.0]) {
+            assert!((output_sample - expected).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn sample_rate_difference_is_compensated() {
+        let mut sampler = Sampler::new(TEST_SAMPLE_RATE);
+        sampler.set_sample(ramp_sample(16, TEST_SAMPLE_RATE / 2));
+
+        let output = play(&mut sampler, root_frequency(), 3);
+
+        for (output_sample, expected) in output.iter().zip([0.0, 0.5, 1.0]) {
+            assert!((output_sample - expected).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn one_shot_stops_at_the_end_of_the_sample() {
+        let mut sampler = Sampler::new(TEST_SAMPLE_RATE);
+        sampler.set_sample(ramp_sample(4, TEST_SAMPLE_RATE));
+
+        let output = play(&mut sampler, root_frequency(), 6);
+
+        assert_eq!(output, vec![0.0, 1.0, 2.0, 3.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn forward_loop_jumps_back_to_the_loop_start() {
+        let mut sampler = Sampler::new(TEST_SAMPLE_RATE);
+        sampler.set_sample(ramp_sample(9, TEST_SAMPLE_RATE));
+        sampler.set_sample_playback(DEFAULT_SAMPLE_ROOT_NOTE, LoopMode::Forward);
+        sampler.set_shape_parameter1(0.25);
+        sampler.set_shape_parameter2(0.75);
+
+        let output = play(&mut sampler, root_frequency(), 10);
+
+        assert_eq!(
+            output,
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 2.0, 3.0, 4.0, 5.0]
+        );
+    }
+
+    #[test]
+    fn ping_pong_loop_reverses_at_each_loop_point() {
+        let mut sampler = Sampler::new(TEST_SAMPLE_RATE);
+        sampler.set_sample(ramp_sample(9, TEST_SAMPLE_RATE));
+        sampler.set_sample_playback(DEFAULT_SAMPLE_ROOT_NOTE, LoopMode::PingPong);
+        sampler.set_shape_parameter1(0.25);
+        sampler.set_shape_parameter2(0.75);
+
+        let output = play(&mut sampler, root_frequency(), 12);
+
+        assert_eq!(
+            output,
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 5.0, 4.0, 3.0, 2.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn retrigger_restarts_a_finished_one_shot_from_the_top() {
+        let mut sampler = Sampler::new(TEST_SAMPLE_RATE);
+        sampler.set_sample(ramp_sample(4, TEST_SAMPLE_RATE));
+        play(&mut sampler, root_frequency(), 8);
+
+        sampler.retrigger();
+
+        assert!(f32s_are_equal(
+            sampler.next_sample(root_frequency(), None),
+            0.0
+        ));
+        assert!(f32s_are_equal(
+            sampler.next_sample(root_frequency(), None),
+            1.0
+        ));
+    }
+
+    #[test]
+    fn empty_sample_is_silent() {
+        let mut sampler = Sampler::new(TEST_SAMPLE_RATE);
+
+        assert!(
+            play(&mut sampler, root_frequency(), 16)
+                .iter()
+                .all(|sample| f32s_are_equal(*sample, 0.0))
+        );
+    }
+
+    #[test]
+    fn loop_points_are_ordered_and_at_least_one_frame_apart() {
+        let mut sampler = Sampler::new(TEST_SAMPLE_RATE);
+        sampler.set_sample(ramp_sample(9, TEST_SAMPLE_RATE));
+        sampler.set_shape_parameter1(0.8);
+        sampler.set_shape_parameter2(0.8);
+
+        let (loop_start, loop_end) = sampler.loop_frames();
+
+        assert!(loop_start < loop_end);
+        assert!(loop_end <= 8.0);
+    }
+
+    #[test]
+    fn load_leaves_the_sample_empty_when_file_is_missing() {
+        let shared_sample = SharedSample::default();
+        let starting_version = shared_sample.version();
+
+        shared_sample.load("Does Not Exist", &std::env::temp_dir());
+
+        assert_eq!(shared_sample.name(), "Does Not Exist");
+        assert_ne!(shared_sample.version(), starting_version);
+        assert!(shared_sample.try_sample().unwrap().is_empty());
+    }
+
+    #[test]
+    fn replaced_sample_outlives_the_oscillator_reference() {
+        let shared_sample = SharedSample::default();
+        shared_sample.store("Ramp".to_string(), ramp_sample(8, TEST_SAMPLE_RATE));
+        let mut sampler = Sampler::new(TEST_SAMPLE_RATE);
+        sampler.set_sample(shared_sample.try_sample().unwrap());
+        let first_sample = Arc::downgrade(&sampler.sample);
+
+        shared_sample.store(String::new(), EMPTY_SAMPLE.clone());
+        sampler.set_sample(shared_sample.try_sample().unwrap());
+
+        // The oscillator swap is not the last reference, the next load frees it
+        assert!(first_sample.upgrade().is_some());
+        shared_sample.store(String::new(), EMPTY_SAMPLE.clone());
+        assert!(first_sample.upgrade().is_none());
+    }
+
+    #[test]
+    fn shared_sample_serializes_as_its_name() {
+        let shared_sample = SharedSample::default();
+        shared_sample.load("Missing Kick", &std::env::temp_dir());
+
+        let json = serde_json::to_string(&shared_sample).unwrap();
+        let restored: SharedSample = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(json, "\"Missing Kick\"");
+        assert_eq!(restored.name(), "Missing Kick");
+    }
+
+    #[test]
+    fn loop_mode_from_index_defaults_to_one_shot_on_invalid_values() {
+        assert_eq!(LoopMode::from_index(2), LoopMode::PingPong);
+        assert_eq!(LoopMode::from_index(200), LoopMode::OneShot);
+    }
+}
@@ -59,6 +59,8 @@ impl GenerateWave for Square {
 
         if tone_frequency_f64 > 0.0 && self.x_coordinate >= period {
             self.x_coordinate -= period;
+        } else if tone_frequency_f64 > 0.0 && self.x_coordinate < 0.0 {
+            self.x_coordinate += period;
         }
 
         f64_to_f32_clamped(y_coordinate)
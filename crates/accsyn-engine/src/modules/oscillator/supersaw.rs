@@ -1,92 +1,167 @@
-use super::constants::{DEFAULT_X_COORDINATE, DEFAULT_X_INCREMENT};
 use super::{WaveShape, poly_blep};
 use crate::modules::oscillator::generate_wave_trait::GenerateWave;
 use accsyn_core::casting::f64_to_f32_clamped;
+use accsyn_core::parameter_types::NormalizedValue;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::{AtomicBool, AtomicU8};
 
 const SHAPE: WaveShape = WaveShape::Supersaw;
 const DEFAULT_DETUNE: f64 = 0.1;
 const DEFAULT_BLEND: f64 = 1.0;
 
-const VOICE_FREQUENCY_OFFSETS: [(f64, f64); 6] = [
-    (0.893, 0.85),
-    (0.939, 0.90),
-    (0.98, 0.95),
-    (1.02, 0.95),
-    (1.064, 0.9),
-    (1.11, 0.85),
-];
+/// Fewest saw voices the supersaw can play.
+pub const MIN_SUPERSAW_VOICES: u8 = 3;
+/// Most saw voices the supersaw can play.
+pub const MAX_SUPERSAW_VOICES: u8 = 15;
+/// Saw voices played when a patch doesn't set a count.
+pub const DEFAULT_SUPERSAW_VOICES: u8 = 7;
+// Frequency offset of the outermost voices at full detune, the inner voices follow a curve that bunches them
+// towards the centre
+const MAX_VOICE_FREQUENCY_OFFSET: f64 = 0.11;
+const VOICE_FREQUENCY_OFFSET_CURVE: f64 = 1.5;
+// Level drop of the outermost voices against the centre voice
+const OUTER_VOICE_LEVEL_DROP: f64 = 0.15;
 
-/// Multi-voice detuned supersaw oscillator blending seven saw waves.
+/// Shared atomic parameters for the supersaw wave shape.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SupersawParameters {
+    /// Number of detuned saw voices.
+    pub voice_count: AtomicU8,
+    /// How far the voices are panned across the stereo field, 0.0 plays them all in the centre.
+    pub stereo_spread: NormalizedValue,
+    /// Whether each voice starts every note at a random phase.
+    pub random_phase: AtomicBool,
+}
+
+impl SupersawParameters {
+    /// Replace all the values in this `SupersawParameters` with the values from the provided `SupersawParameters`.
+    pub fn assign_from(&self, parameters: &SupersawParameters) {
+        self.voice_count.store(
+            clamp_supersaw_voice_count(parameters.voice_count.load(Relaxed)),
+            Relaxed,
+        );
+        self.stereo_spread.store(parameters.stereo_spread.load());
+        self.random_phase
+            .store(parameters.random_phase.load(Relaxed), Relaxed);
+    }
+}
+
+impl Default for SupersawParameters {
+    fn default() -> Self {
+        Self {
+            voice_count: AtomicU8::new(DEFAULT_SUPERSAW_VOICES),
+            stereo_spread: NormalizedValue::new(0.0),
+            random_phase: AtomicBool::new(false),
+        }
+    }
+}
+
+/// Clamps a voice count into the range the supersaw can play.
+#[must_use]
+pub fn clamp_supersaw_voice_count(voice_count: u8) -> u8 {
+    voice_count.clamp(MIN_SUPERSAW_VOICES, MAX_SUPERSAW_VOICES)
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct SawVoice {
+    phase: f64,
+    // Position from the flattest voice (-1.0) to the sharpest (1.0)
+    position: f64,
+    frequency_offset: f64,
+    level: f64,
+}
+
+/// Multi-voice detuned supersaw oscillator with stereo spread and random voice phases.
 pub struct Supersaw {
     shape: WaveShape,
-    center_x_coordinate: f64,
-    x_coordinates: [f64; 6],
+    voices: [SawVoice; MAX_SUPERSAW_VOICES as usize],
+    voice_count: usize,
     detune: f64,
     blend: f64,
+    stereo_spread: f64,
+    random_phase: bool,
+    side_sample: f32,
     sample_rate: u32,
 }
 
 impl Supersaw {
     pub(crate) fn new(sample_rate: u32) -> Self {
         log::debug!(target: "synth::oscillator", shape = "Supersaw"; "Constructing wave generator");
-        let center_x_coordinate = DEFAULT_X_COORDINATE;
-        let x_coordinates = [3.0, 11.0, 15.0, 13.0, 21.0, 35.0];
 
-        Self {
+        let mut supersaw = Self {
             shape: SHAPE,
-            center_x_coordinate,
-            x_coordinates,
+            voices: [SawVoice::default(); MAX_SUPERSAW_VOICES as usize],
+            voice_count: 0,
             detune: DEFAULT_DETUNE,
             blend: DEFAULT_BLEND,
+            stereo_spread: 0.0,
+            random_phase: false,
+            side_sample: 0.0,
             sample_rate,
+        };
+        supersaw.arrange_voices(DEFAULT_SUPERSAW_VOICES);
+        supersaw
+    }
+
+    // Spreads the voices evenly from the flattest to the sharpest, an odd count keeps an undetuned centre voice
+    fn arrange_voices(&mut self, voice_count: u8) {
+        let voice_count = usize::from(clamp_supersaw_voice_count(voice_count));
+        if voice_count == self.voice_count {
+            return;
         }
+
+        // Voice counts are at most 15, exactly representable in f64
+        #[allow(clippy::cast_precision_loss)]
+        let last_voice = (voice_count - 1) as f64;
+        for (index, voice) in self.voices.iter_mut().take(voice_count).enumerate() {
+            // Voice indexes are at most 14, exactly representable in f64
+            #[allow(clippy::cast_precision_loss)]
+            let position = 2.0 * index as f64 / last_voice - 1.0;
+            voice.position = position;
+            voice.frequency_offset = position.signum()
+                * MAX_VOICE_FREQUENCY_OFFSET
+                * position.abs().powf(VOICE_FREQUENCY_OFFSET_CURVE);
+            voice.level = 1.0 - OUTER_VOICE_LEVEL_DROP * position.abs();
+        }
+        self.voice_count = voice_count;
     }
 }
 
 impl GenerateWave for Supersaw {
     fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
         let sample_rate_f64 = f64::from(self.sample_rate);
-        let tone_frequency_f64 = f64::from(tone_frequency);
-        let new_x_increment = DEFAULT_X_INCREMENT * f64::from(modulation.unwrap_or(1.0));
-
-        let center_period = sample_rate_f64 / tone_frequency_f64;
-        let normalized_center_x_increment = new_x_increment / center_period;
-        let normalized_center_x_coordinate =
-            (self.center_x_coordinate / center_period).rem_euclid(1.0);
-        let mut voice_mix = single_saw_sample(normalized_center_x_coordinate)
-            - poly_blep(
-                normalized_center_x_coordinate,
-                normalized_center_x_increment,
-            );
-        self.center_x_coordinate += new_x_increment;
-
-        if tone_frequency_f64 > 0.0 && self.center_x_coordinate >= center_period {
-            self.center_x_coordinate -= center_period;
-        }
-
-        for (voice, (frequency_offset, level_offset)) in VOICE_FREQUENCY_OFFSETS.iter().enumerate()
-        {
-            let voice_frequency =
-                tone_frequency_f64 * (1.0 + self.detune * (frequency_offset - 1.0));
+        let tone_frequency_f64 = f64::from(tone_frequency) * f64::from(modulation.unwrap_or(1.0));
 
-            let voice_period = sample_rate_f64 / voice_frequency;
-            let normalized_voice_x_increment = new_x_increment / voice_period;
-            let normalized_voice_x_coordinate =
-                (self.x_coordinates[voice] / voice_period).rem_euclid(1.0);
-            let sample = single_saw_sample(normalized_voice_x_coordinate)
-                - poly_blep(normalized_voice_x_coordinate, normalized_voice_x_increment);
-            self.x_coordinates[voice] += new_x_increment;
+        let mut voice_mix = 0.0;
+        let mut side_mix = 0.0;
+        for voice in self.voices.iter_mut().take(self.voice_count) {
+            let voice_frequency = tone_frequency_f64 * (1.0 + self.detune * voice.frequency_offset);
+            let phase_increment = voice_frequency / sample_rate_f64;
 
-            voice_mix += sample * level_offset * self.blend;
+            let sample =
+                single_saw_sample(voice.phase) - poly_blep(voice.phase, phase_increment.abs());
+            voice.phase = (voice.phase + phase_increment).rem_euclid(1.0);
 
-            if tone_frequency_f64 > 0.0 && self.x_coordinates[voice] >= voice_period {
-                self.x_coordinates[voice] -= voice_period;
-            }
+            // The undetuned centre voice always plays at full level, blend only fades the detuned voices
+            let level = if voice.position == 0.0 {
+                1.0
+            } else {
+                voice.level * self.blend
+            };
+            voice_mix += sample * level;
+            side_mix += sample * level * voice.position * self.stereo_spread;
         }
 
+        self.side_sample = f64_to_f32_clamped(side_mix);
         f64_to_f32_clamped(voice_mix)
     }
 
+    fn side_sample(&self) -> f32 {
+        self.side_sample
+    }
+
     fn set_shape_parameter1(&mut self, parameter: f32) {
         self.detune = f64::from(parameter);
     }
@@ -95,18 +170,139 @@ impl GenerateWave for Supersaw {
         self.blend = f64::from(parameter);
     }
 
-    fn set_phase(&mut self, _phase: f32) {}
+    fn set_phase(&mut self, phase: f32) {
+        for voice in &mut self.voices {
+            voice.phase = f64::from(phase).rem_euclid(1.0);
+        }
+    }
 
     fn shape(&self) -> WaveShape {
         self.shape
     }
 
     fn reset(&mut self) {
-        self.x_coordinates = [DEFAULT_X_COORDINATE; 6];
-        self.center_x_coordinate = DEFAULT_X_COORDINATE;
+        for voice in &mut self.voices {
+            voice.phase = 0.0;
+        }
+        self.side_sample = 0.0;
+    }
+
+    fn retrigger(&mut self) {
+        if !self.random_phase {
+            return;
+        }
+
+        for voice in &mut self.voices {
+            voice.phase = rand::random_range(0.0..1.0);
+        }
+    }
+
+    fn set_supersaw(&mut self, parameters: &SupersawParameters) {
+        self.arrange_voices(parameters.voice_count.load(Relaxed));
+        self.stereo_spread = f64::from(parameters.stereo_spread.load());
+        self.random_phase = parameters.random_phase.load(Relaxed);
     }
 }
 
-fn single_saw_sample(normalized_x_coordinate: f64) -> f64 {
-    2.0 * normalized_x_coordinate - 1.0
+fn single_saw_sample(phase: f64) -> f64 {
+    2.0 * phase - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48000;
+
+    fn supersaw_with(voice_count: u8, stereo_spread: f32, random_phase: bool) -> Supersaw {
+        let parameters = SupersawParameters::default();
+        parameters.voice_count.store(voice_count, Relaxed);
+        parameters.stereo_spread.store(stereo_spread);
+        parameters.random_phase.store(random_phase, Relaxed);
+
+        let mut supersaw = Supersaw::new(SAMPLE_RATE);
+        supersaw.set_shape_parameter1(1.0);
+        supersaw.set_supersaw(&parameters);
+        supersaw
+    }
+
+    #[test]
+    fn voice_count_is_clamped_to_the_playable_range() {
+        assert_eq!(clamp_supersaw_voice_count(0), MIN_SUPERSAW_VOICES);
+        assert_eq!(clamp_supersaw_voice_count(9), 9);
+        assert_eq!(clamp_supersaw_voice_count(200), MAX_SUPERSAW_VOICES);
+        assert_eq!(supersaw_with(40, 0.0, false).voice_count, 15);
+    }
+
+    #[test]
+    fn out_of_range_voice_count_is_clamped_on_load() {
+        let parameters = SupersawParameters::default();
+        parameters.voice_count.store(99, Relaxed);
+        let loaded = SupersawParameters::default();
+
+        loaded.assign_from(&parameters);
+
+        assert_eq!(loaded.voice_count.load(Relaxed), MAX_SUPERSAW_VOICES);
+    }
+
+    #[test]
+    fn voices_are_spread_symmetrically_around_the_note() {
+        let supersaw = supersaw_with(7, 0.0, false);
+        let voices = &supersaw.voices[..supersaw.voice_count];
+
+        assert!(voices[3].position == 0.0 && voices[3].frequency_offset == 0.0);
+        assert!((voices[0].frequency_offset + MAX_VOICE_FREQUENCY_OFFSET).abs() < f64::EPSILON);
+        assert!((voices[6].frequency_offset - MAX_VOICE_FREQUENCY_OFFSET).abs() < f64::EPSILON);
+        for (flat, sharp) in voices.iter().zip(voices.iter().rev()) {
+            assert!((flat.frequency_offset + sharp.frequency_offset).abs() < f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn no_spread_plays_in_mono() {
+        let mut supersaw = supersaw_with(9, 0.0, false);
+
+        for _ in 0..1000 {
+            supersaw.next_sample(220.0, None);
+            assert!(supersaw.side_sample() == 0.0);
+        }
+    }
+
+    #[test]
+    fn spread_separates_the_left_and_right_channels() {
+        let mut supersaw = supersaw_with(9, 1.0, false);
+
+        let side_energy: f32 = (0..4800)
+            .map(|_| {
+                supersaw.next_sample(220.0, None);
+                supersaw.side_sample().abs()
+            })
+            .sum();
+
+        assert!(side_energy > 1.0, "Side energy {side_energy}");
+    }
+
+    #[test]
+    fn random_phase_starts_each_voice_somewhere_different() {
+        let mut supersaw = supersaw_with(7, 0.0, true);
+
+        supersaw.reset();
+        supersaw.retrigger();
+
+        let phases: Vec<f64> = supersaw.voices[..supersaw.voice_count]
+            .iter()
+            .map(|voice| voice.phase)
+            .collect();
+        assert!(phases.iter().any(|phase| *phase != phases[0]));
+    }
+
+    #[test]
+    fn retrigger_keeps_the_phases_without_random_phase() {
+        let mut supersaw = supersaw_with(7, 0.0, false);
+
+        supersaw.reset();
+        supersaw.retrigger();
+
+        assert!(supersaw.voices.iter().all(|voice| voice.phase == 0.0));
+    }
 }
@@ -0,0 +1,199 @@
+use hound::{SampleFormat, WavReader};
+use std::io::Cursor;
+use std::path::Path;
+use thiserror::Error;
+
+// Ten minutes of mono audio at 48 kHz, far beyond any wavetable or one-shot sample
+const MAX_WAV_FILE_FRAMES: u32 = 28_800_000;
+const RIFF_HEADER_LENGTH: usize = 12;
+const CHUNK_HEADER_LENGTH: usize = 8;
+// Wavetable editors such as Serum write the frame size into a "clm " chunk as text, e.g. "<!>2048 ..."
+const CYCLE_LENGTH_CHUNK_ID: &[u8; 4] = b"clm ";
+const CYCLE_LENGTH_PREFIX: &str = "<!>";
+
+/// Errors that can occur while reading a WAV file for an oscillator.
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum WavFileError {
+    /// The file could not be opened or decoded as a WAV file.
+    #[error("Failed to read WAV file: {0}")]
+    FailedToRead(String),
+
+    /// The WAV file contains no audio frames.
+    #[error("WAV file contains no audio")]
+    Empty,
+
+    /// The WAV file is longer than the oscillators will load.
+    #[error("WAV file is too long")]
+    TooLong,
+}
+
+/// Audio read from a WAV file, mixed down to mono.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WavFile {
+    /// Mono samples in the [-1, 1] range.
+    pub samples: Vec<f32>,
+    /// Sample rate the file was recorded at in Hz.
+    pub sample_rate: u32,
+    /// Samples per wavetable frame stored in the file metadata, if the file has any.
+    pub cycle_length: Option<usize>,
+}
+
+/// Reads a PCM or float WAV file, averaging all channels into a single mono channel.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be decoded, contains no audio, or exceeds the maximum length.
+pub fn read_wav_file(path: &Path) -> Result<WavFile, WavFileError> {
+    let file_bytes =
+        std::fs::read(path).map_err(|err| WavFileError::FailedToRead(err.to_string()))?;
+    let mut reader = WavReader::new(Cursor::new(file_bytes.as_slice()))
+        .map_err(|err| WavFileError::FailedToRead(err.to_string()))?;
+    let spec = reader.spec();
+
+    if reader.duration() == 0 {
+        return Err(WavFileError::Empty);
+    }
+
+    if reader.duration() > MAX_WAV_FILE_FRAMES {
+        return Err(WavFileError::TooLong);
+    }
+
+    let interleaved_samples = match spec.sample_format {
+        SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<Vec<f32>, _>>()
+            .map_err(|err| WavFileError::FailedToRead(err.to_string()))?,
+        SampleFormat::Int => {
+            let full_scale = 2.0_f32.powi(i32::from(spec.bits_per_sample) - 1);
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|value| integer_sample_to_f32(value, full_scale)))
+                .collect::<Result<Vec<f32>, _>>()
+                .map_err(|err| WavFileError::FailedToRead(err.to_string()))?
+        }
+    };
+
+    let channel_count = usize::from(spec.channels.max(1));
+    let samples = interleaved_samples
+        .chunks(channel_count)
+        .map(|frame| frame.iter().sum::<f32>() / f32::from(spec.channels.max(1)))
+        .collect();
+
+    Ok(WavFile {
+        samples,
+        sample_rate: spec.sample_rate,
+        cycle_length: cycle_length_from_chunks(&file_bytes),
+    })
+}
+
+// Walks the RIFF chunks looking for the wavetable frame size, ignoring anything malformed.
+fn cycle_length_from_chunks(file_bytes: &[u8]) -> Option<usize> {
+    let mut offset = RIFF_HEADER_LENGTH;
+
+    while offset + CHUNK_HEADER_LENGTH <= file_bytes.len() {
+        let chunk_id = &file_bytes[offset..offset + 4];
+        let chunk_size_bytes: [u8; 4] = file_bytes[offset + 4..offset + 8].try_into().ok()?;
+        let chunk_size = usize::try_from(u32::from_le_bytes(chunk_size_bytes)).ok()?;
+        let data_start = offset + CHUNK_HEADER_LENGTH;
+        let data_end = data_start.checked_add(chunk_size)?.min(file_bytes.len());
+
+        if chunk_id == CYCLE_LENGTH_CHUNK_ID {
+            let text = String::from_utf8_lossy(&file_bytes[data_start..data_end]);
+            let digits: String = text
+                .strip_prefix(CYCLE_LENGTH_PREFIX)?
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect();
+            return digits.parse().ok().filter(|cycle_length| *cycle_length > 0);
+        }
+
+        // Chunks are padded to an even number of bytes
+        offset = data_start + chunk_size + chunk_size % 2;
+    }
+
+    None
+}
+
+fn integer_sample_to_f32(value: i32, full_scale: f32) -> f32 {
+    // Integer samples are at most 32 bits, and the scaled sample only needs f32 precision
+    #[allow(clippy::cast_precision_loss)]
+    let sample = value as f32;
+    sample / full_scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use accsyn_core::math::f32s_are_equal;
+    use hound::{WavSpec, WavWriter};
+    use std::path::PathBuf;
+
+    fn temporary_wav_path(label: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "accsyn_wav_file_{label}_{}.wav",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn read_wav_file_mixes_stereo_integer_samples_to_mono() {
+        let path = temporary_wav_path("stereo");
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 44_100,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        writer.write_sample(i16::MAX).unwrap();
+        writer.write_sample(0_i16).unwrap();
+        writer.write_sample(i16::MIN).unwrap();
+        writer.write_sample(i16::MIN).unwrap();
+        writer.finalize().unwrap();
+
+        let wav_file = read_wav_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(wav_file.sample_rate, 44_100);
+        assert_eq!(wav_file.samples.len(), 2);
+        assert!((wav_file.samples[0] - 0.5).abs() < 0.001);
+        assert!(f32s_are_equal(wav_file.samples[1], -1.0));
+    }
+
+    #[test]
+    fn read_wav_file_reads_float_samples() {
+        let path = temporary_wav_path("float");
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: 48_000,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+        let mut writer = WavWriter::create(&path, spec).unwrap();
+        writer.write_sample(0.25_f32).unwrap();
+        writer.write_sample(-0.75_f32).unwrap();
+        writer.finalize().unwrap();
+
+        let wav_file = read_wav_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(wav_file.samples, vec![0.25, -0.75]);
+    }
+
+    #[test]
+    fn cycle_length_is_read_from_the_clm_chunk() {
+        let mut file_bytes = b"RIFF\0\0\0\0WAVE".to_vec();
+        file_bytes.extend(b"fmt \x02\0\0\0\0\0");
+        file_bytes.extend(b"clm \x0b\0\0\0<!>256 1000\0");
+
+        assert_eq!(cycle_length_from_chunks(&file_bytes), Some(256));
+        assert_eq!(cycle_length_from_chunks(b"RIFF\0\0\0\0WAVE"), None);
+    }
+
+    #[test]
+    fn read_wav_file_returns_error_for_missing_file() {
+        let result = read_wav_file(&temporary_wav_path("missing"));
+
+        assert!(matches!(result, Err(WavFileError::FailedToRead(_))));
+    }
+}
@@ -0,0 +1,782 @@
+use super::WaveShape;
+use super::wav_file::{WavFileError, read_wav_file};
+use crate::modules::oscillator::generate_wave_trait::GenerateWave;
+use crate::modules::retired_arcs::RetiredArcs;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::path::Path;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering::{Acquire, Release};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+
+const SHAPE: WaveShape = WaveShape::Wavetable;
+
+/// Number of samples in one band-limited wavetable frame. Frames of any other size are resampled to it.
+pub const WAVETABLE_FRAME_SIZE: usize = 2048;
+/// Frame size assumed for files that don't store one in their metadata.
+pub const DEFAULT_FILE_FRAME_SIZE: usize = 2048;
+/// Maximum number of frames loaded from a wavetable file.
+pub const MAX_WAVETABLE_FRAMES: usize = 256;
+/// File extension of the wavetable files in the user wavetable directory.
+pub const WAVETABLE_FILE_EXTENSION: &str = "wav";
+const HIGHEST_HARMONIC: usize = WAVETABLE_FRAME_SIZE / 2;
+// One mipmap per octave, from every harmonic down to the fundamental alone
+const MIPMAP_LEVEL_COUNT: usize = HIGHEST_HARMONIC.ilog2() as usize + 1;
+const BUILT_IN_WAVETABLE_NAME: &str = "Built In";
+const BUILT_IN_HARMONIC_COUNT: usize = 64;
+const DEFAULT_POSITION: f32 = 0.0;
+const DEFAULT_MORPH: f32 = 1.0;
+
+// Sine, triangle, saw, and square frames, so the wavetable shape sounds without a file
+static BUILT_IN_WAVETABLE: LazyLock<Arc<Wavetable>> =
+    LazyLock::new(|| Arc::new(Wavetable::built_in()));
+
+/// Band-limited frames loaded from a single-cycle or multi-frame wavetable.
+pub struct Wavetable {
+    name: String,
+    frame_count: usize,
+    // One buffer per mipmap level holding every frame back to back
+    mipmaps: Vec<Vec<f32>>,
+}
+
+impl Wavetable {
+    /// Builds a wavetable from mono samples.
+    ///
+    /// `file_frame_size` is the frame size from the file metadata, `DEFAULT_FILE_FRAME_SIZE` is used without
+    /// one. Sample counts that divide into whole frames are split into frames, anything else is treated as one
+    /// single-cycle frame. Every frame is resampled to `WAVETABLE_FRAME_SIZE`.
+    #[must_use]
+    pub fn from_samples(name: &str, samples: &[f32], file_frame_size: Option<usize>) -> Self {
+        let frame_size = file_frame_size.unwrap_or(DEFAULT_FILE_FRAME_SIZE);
+        let frames: Vec<Vec<f32>> =
+            if frame_size > 0 && !samples.is_empty() && samples.len().is_multiple_of(frame_size) {
+                samples
+                    .chunks(frame_size)
+                    .take(MAX_WAVETABLE_FRAMES)
+                    .map(resample_single_cycle)
+                    .collect()
+            } else {
+                vec![resample_single_cycle(samples)]
+            };
+
+        Self {
+            name: name.to_string(),
+            frame_count: frames.len(),
+            mipmaps: build_mipmaps(&frames),
+        }
+    }
+
+    /// Loads a wavetable from a WAV file, using the frame size stored in the file when it has one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the WAV file cannot be read.
+    pub fn load(name: &str, path: &Path) -> Result<Self, WavFileError> {
+        let wav_file = read_wav_file(path)?;
+        Ok(Self::from_samples(
+            name,
+            &wav_file.samples,
+            wav_file.cycle_length,
+        ))
+    }
+
+    /// Returns the name the wavetable was loaded with.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of frames in the wavetable.
+    #[must_use]
+    pub fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    fn built_in() -> Self {
+        let frames = [
+            sine_harmonic,
+            triangle_harmonic,
+            saw_harmonic,
+            square_harmonic,
+        ]
+        .iter()
+        .map(|harmonic_amplitude| additive_frame(*harmonic_amplitude))
+        .collect::<Vec<Vec<f32>>>();
+
+        Self {
+            name: BUILT_IN_WAVETABLE_NAME.to_string(),
+            frame_count: frames.len(),
+            mipmaps: build_mipmaps(&frames),
+        }
+    }
+
+    fn read(&self, mipmap_level: usize, frame: usize, phase: f32) -> f32 {
+        let frame_start = frame * WAVETABLE_FRAME_SIZE;
+        let frame_samples =
+            &self.mipmaps[mipmap_level][frame_start..frame_start + WAVETABLE_FRAME_SIZE];
+
+        // Phase is kept in [0, 1), so the position is always within the frame
+        #[allow(clippy::cast_precision_loss)]
+        let position = phase * WAVETABLE_FRAME_SIZE as f32;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let index = (position as usize).min(WAVETABLE_FRAME_SIZE - 1);
+        #[allow(clippy::cast_precision_loss)]
+        let fraction = position - index as f32;
+        let current = frame_samples[index];
+        let next = frame_samples[(index + 1) % WAVETABLE_FRAME_SIZE];
+
+        current + (next - current) * fraction
+    }
+}
+
+impl fmt::Debug for Wavetable {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Wavetable")
+            .field("name", &self.name)
+            .field("frame_count", &self.frame_count)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The wavetable selected for an oscillator, shared between the patch and the audio thread.
+///
+/// Patches store only the wavetable name. Files are loaded off the audio thread with `load`, and the audio
+/// thread picks up the new wavetable when the version changes. Replaced wavetables are retired rather than
+/// dropped, so the audio thread never frees one.
+pub struct SharedWavetable {
+    name: Mutex<String>,
+    wavetable: Mutex<Arc<Wavetable>>,
+    retired_wavetables: RetiredArcs<Wavetable>,
+    version: AtomicU32,
+}
+
+impl SharedWavetable {
+    /// Replace the selected wavetable with the one selected in the provided `SharedWavetable`.
+    pub fn assign_from(&self, shared_wavetable: &SharedWavetable) {
+        self.store(
+            shared_wavetable.name(),
+            shared_wavetable
+                .wavetable
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+        );
+    }
+
+    /// Loads the named wavetable from the wavetable directory.
+    ///
+    /// An empty name selects the built-in wavetable. A missing or unreadable file logs a warning and
+    /// falls back to the built-in wavetable, keeping the name so the patch still saves it.
+    pub fn load(&self, name: &str, wavetable_directory: &Path) {
+        if name.is_empty() {
+            self.store(String::new(), BUILT_IN_WAVETABLE.clone());
+            return;
+        }
+
+        let mut path = wavetable_directory.join(sanitize_filename::sanitize(name));
+        path.set_extension(WAVETABLE_FILE_EXTENSION);
+
+        let wavetable = match Wavetable::load(name, &path) {
+            Ok(wavetable) => {
+                log::info!(target: "synthesizer::modules::oscillator", "Loaded wavetable '{name}' with {} frames", wavetable.frame_count());
+                Arc::new(wavetable)
+            }
+            Err(err) => {
+                log::warn!(target: "synthesizer::modules::oscillator", "Failed to load wavetable {}, using the built-in wavetable: {err}", path.display());
+                BUILT_IN_WAVETABLE.clone()
+            }
+        };
+
+        self.store(name.to_string(), wavetable);
+    }
+
+    /// Returns the name of the selected wavetable, empty for the built-in wavetable.
+    #[must_use]
+    pub fn name(&self) -> String {
+        self.name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns a counter that changes every time a new wavetable is selected.
+    #[must_use]
+    pub fn version(&self) -> u32 {
+        self.version.load(Acquire)
+    }
+
+    /// Returns the selected wavetable without waiting, or `None` while another thread is changing it.
+    #[must_use]
+    pub fn try_wavetable(&self) -> Option<Arc<Wavetable>> {
+        self.wavetable
+            .try_lock()
+            .ok()
+            .map(|wavetable| wavetable.clone())
+    }
+
+    fn store(&self, name: String, wavetable: Arc<Wavetable>) {
+        *self.name.lock().unwrap_or_else(PoisonError::into_inner) = name;
+        let replaced_wavetable = std::mem::replace(
+            &mut *self
+                .wavetable
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+            wavetable,
+        );
+        self.version.fetch_add(1, Release);
+        self.retired_wavetables.retire(replaced_wavetable);
+    }
+}
+
+impl Default for SharedWavetable {
+    fn default() -> Self {
+        Self {
+            name: Mutex::new(String::new()),
+            wavetable: Mutex::new(BUILT_IN_WAVETABLE.clone()),
+            retired_wavetables: RetiredArcs::default(),
+            version: AtomicU32::new(0),
+        }
+    }
+}
+
+impl fmt::Debug for SharedWavetable {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SharedWavetable")
+            .field("name", &self.name())
+            .field("version", &self.version())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Serialize for SharedWavetable {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for SharedWavetable {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        let shared_wavetable = SharedWavetable::default();
+        *shared_wavetable
+            .name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = name;
+        Ok(shared_wavetable)
+    }
+}
+
+/// Wavetable oscillator scanning through the frames of a band-limited wavetable.
+pub struct WavetableOscillator {
+    shape: WaveShape,
+    sample_rate: u32,
+    wavetable: Arc<Wavetable>,
+    phase: f32,
+    position: f32,
+    morph: f32,
+}
+
+impl WavetableOscillator {
+    pub(crate) fn new(sample_rate: u32) -> Self {
+        log::debug!(target: "synth::oscillator", shape = "Wavetable"; "Constructing wave generator");
+
+        Self {
+            shape: SHAPE,
+            sample_rate,
+            wavetable: BUILT_IN_WAVETABLE.clone(),
+            phase: 0.0,
+            position: DEFAULT_POSITION,
+            morph: DEFAULT_MORPH,
+        }
+    }
+
+    fn mipmap_level(&self, frequency: f32) -> usize {
+        // Sample rate is always ≤ 192_000, within f32 precision (2²³ = 8_388_608)
+        #[allow(clippy::cast_precision_loss)]
+        let nyquist = self.sample_rate as f32 / 2.0;
+        // HIGHEST_HARMONIC is 1024, exactly representable in f32
+        #[allow(clippy::cast_precision_loss)]
+        let octaves_above_full_table = (HIGHEST_HARMONIC as f32 * frequency.abs() / nyquist).log2();
+
+        if !octaves_above_full_table.is_finite() || octaves_above_full_table <= 0.0 {
+            return 0;
+        }
+
+        // The ceiling of a positive log2 is a small whole number
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let mipmap_level = octaves_above_full_table.ceil() as usize;
+        mipmap_level.min(MIPMAP_LEVEL_COUNT - 1)
+    }
+}
+
+impl GenerateWave for WavetableOscillator {
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        let frequency = tone_frequency * modulation.unwrap_or(1.0);
+        let mipmap_level = self.mipmap_level(frequency);
+        let last_frame = self.wavetable.frame_count() - 1;
+
+        // Frame counts are capped at MAX_WAVETABLE_FRAMES, exactly representable in f32
+        #[allow(clippy::cast_precision_loss)]
+        let frame_position = self.position * last_frame as f32;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let lower_frame = (frame_position as usize).min(last_frame);
+        let upper_frame = (lower_frame + 1).min(last_frame);
+        #[allow(clippy::cast_precision_loss)]
+        let blend = morph_blend(frame_position - lower_frame as f32, self.morph);
+
+        let lower_sample = self.wavetable.read(mipmap_level, lower_frame, self.phase);
+        let sample = if blend > 0.0 && upper_frame != lower_frame {
+            let upper_sample = self.wavetable.read(mipmap_level, upper_frame, self.phase);
+            lower_sample + (upper_sample - lower_sample) * blend
+        } else {
+            lower_sample
+        };
+
+        // Sample rate is always ≤ 192_000, within f32 precision (2²³ = 8_388_608)
+        #[allow(clippy::cast_precision_loss)]
+        let phase_increment = frequency / self.sample_rate as f32;
+        self.phase = (self.phase + phase_increment).rem_euclid(1.0);
+
+        sample
+    }
+
+    fn set_shape_parameter1(&mut self, parameter: f32) {
+        self.position = parameter.clamp(0.0, 1.0);
+    }
+
+    fn set_shape_parameter2(&mut self, parameter: f32) {
+        self.morph = parameter.clamp(0.0, 1.0);
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        self.phase = phase.clamp(0.0, 1.0).rem_euclid(1.0);
+    }
+
+    fn shape(&self) -> WaveShape {
+        self.shape
+    }
+
+    fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+
+    fn set_wavetable(&mut self, wavetable: Arc<Wavetable>) {
+        self.wavetable = wavetable;
+    }
+}
+
+// A morph of 0.0 steps between frames, 1.0 crossfades across the whole distance between them, and
+// values in between crossfade over a narrower window centered between the two frames.
+fn morph_blend(frame_fraction: f32, morph: f32) -> f32 {
+    if morph <= 0.0 {
+        return if frame_fraction < 0.5 { 0.0 } else { 1.0 };
+    }
+
+    ((frame_fraction - 0.5) / morph + 0.5).clamp(0.0, 1.0)
+}
+
+fn resample_single_cycle(samples: &[f32]) -> Vec<f32> {
+    if samples.is_empty() {
+        return vec![0.0; WAVETABLE_FRAME_SIZE];
+    }
+
+    (0..WAVETABLE_FRAME_SIZE)
+        .map(|index| {
+            // Frame indexes and sample counts stay far below f64 precision limits
+            #[allow(clippy::cast_precision_loss)]
+            let position = index as f64 * samples.len() as f64 / WAVETABLE_FRAME_SIZE as f64;
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let sample_index = position as usize;
+            #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+            let fraction = (position - sample_index as f64) as f32;
+            let current = samples[sample_index];
+            let next = samples[(sample_index + 1) % samples.len()];
+            current + (next - current) * fraction
+        })
+        .collect()
+}
+
+fn additive_frame(harmonic_amplitude: fn(usize) -> f64) -> Vec<f32> {
+    (0..WAVETABLE_FRAME_SIZE)
+        .map(|index| {
+            // Frame indexes stay far below f64 precision limits
+            #[allow(clippy::cast_precision_loss)]
+            let phase = std::f64::consts::TAU * index as f64 / WAVETABLE_FRAME_SIZE as f64;
+            let sample: f64 = (1..=BUILT_IN_HARMONIC_COUNT)
+                .map(|harmonic| {
+                    #[allow(clippy::cast_precision_loss)]
+                    let harmonic_phase = phase * harmonic as f64;
+                    harmonic_amplitude(harmonic) * harmonic_phase.sin()
+                })
+                .sum();
+            #[allow(clippy::cast_possible_truncation)]
+            let sample = sample as f32;
+            sample
+        })
+        .collect()
+}
+
+fn sine_harmonic(harmonic: usize) -> f64 {
+    if harmonic == 1 { 1.0 } else { 0.0 }
+}
+
+fn triangle_harmonic(harmonic: usize) -> f64 {
+    if harmonic.is_multiple_of(2) {
+        return 0.0;
+    }
+    let sign = if (harmonic / 2).is_multiple_of(2) {
+        1.0
+    } else {
+        -1.0
+    };
+    // Harmonic numbers are at most BUILT_IN_HARMONIC_COUNT
+    #[allow(clippy::cast_precision_loss)]
+    let harmonic = harmonic as f64;
+    sign / (harmonic * harmonic)
+}
+
+fn saw_harmonic(harmonic: usize) -> f64 {
+    // Harmonic numbers are at most BUILT_IN_HARMONIC_COUNT
+    #[allow(clippy::cast_precision_loss)]
+    let harmonic = harmonic as f64;
+    1.0 / harmonic
+}
+
+fn square_harmonic(harmonic: usize) -> f64 {
+    if harmonic.is_multiple_of(2) {
+        0.0
+    } else {
+        saw_harmonic(harmonic)
+    }
+}
+
+// Each level keeps half the harmonics of the one before it, so reading the level matching the note's
+// octave never produces harmonics above Nyquist. The whole table is normalized to a peak of 1.0.
+fn build_mipmaps(frames: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let mut mipmaps = (0..MIPMAP_LEVEL_COUNT)
+        .map(|_| Vec::with_capacity(frames.len() * WAVETABLE_FRAME_SIZE))
+        .collect::<Vec<Vec<f32>>>();
+
+    for frame in frames {
+        let mut real: Vec<f64> = frame.iter().copied().map(f64::from).collect();
+        let mut imaginary = vec![0.0; WAVETABLE_FRAME_SIZE];
+        fft(&mut real, &mut imaginary, false);
+
+        for (mipmap_level, mipmap) in mipmaps.iter_mut().enumerate() {
+            let highest_harmonic = HIGHEST_HARMONIC >> mipmap_level;
+            let mut level_real = real.clone();
+            let mut level_imaginary = imaginary.clone();
+
+            for bin in 0..WAVETABLE_FRAME_SIZE {
+                let harmonic = bin.min(WAVETABLE_FRAME_SIZE - bin);
+                if harmonic == 0 || harmonic > highest_harmonic {
+                    level_real[bin] = 0.0;
+                    level_imaginary[bin] = 0.0;
+                }
+            }
+
+            fft(&mut level_real, &mut level_imaginary, true);
+            #[allow(clippy::cast_possible_truncation)]
+            mipmap.extend(level_real.iter().map(|sample| *sample as f32));
+        }
+    }
+
+    let peak = mipmaps[0]
+        .iter()
+        .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+
+    if peak > 0.0 {
+        mipmaps
+            .iter_mut()
+            .flatten()
+            .for_each(|sample| *sample /= peak);
+    }
+
+    mipmaps
+}
+
+// In place iterative radix 2 FFT. The inverse transform is scaled by 1/N.
+pub(super) fn fft(real: &mut [f64], imaginary: &mut [f64], inverse: bool) {
+    let size = real.len();
+    let mut reversed_index = 0;
+
+    for index in 1..size {
+        let mut bit = size >> 1;
+        while reversed_index & bit != 0 {
+            reversed_index ^= bit;
+            bit >>= 1;
+        }
+        reversed_index |= bit;
+
+        if index < reversed_index {
+            real.swap(index, reversed_index);
+            imaginary.swap(index, reversed_index);
+        }
+    }
+
+    let direction = if inverse { 1.0 } else { -1.0 };
+    let mut length = 2;
+
+    while length <= size {
+        // FFT sizes are small powers of two, exactly representable in f64
+        #[allow(clippy::cast_precision_loss)]
+        let angle = direction * std::f64::consts::TAU / length as f64;
+        let (step_imaginary, step_real) = angle.sin_cos();
+
+        for start in (0..size).step_by(length) {
+            let mut twiddle_real = 1.0;
+            let mut twiddle_imaginary = 0.0;
+
+            for offset in 0..length / 2 {
+                let even = start + offset;
+                let odd = even + length / 2;
+                let odd_real = real[odd] * twiddle_real - imaginary[odd] * twiddle_imaginary;
+                let odd_imaginary = real[odd] * twiddle_imaginary + imaginary[odd] * twiddle_real;
+
+                real[odd] = real[even] - odd_real;
+                imaginary[odd] = imaginary[even] - odd_imaginary;
+                real[even] += odd_real;
+                imaginary[even] += odd_imaginary;
+
+                let next_twiddle_real =
+                    twiddle_real * step_real - twiddle_imaginary * step_imaginary;
+                twiddle_imaginary = twiddle_real * step_imaginary + twiddle_imaginary * step_real;
+                twiddle_real = next_twiddle_real;
+            }
+        }
+
+        length <<= 1;
+    }
+
+    if inverse {
+        #[allow(clippy::cast_precision_loss)]
+        let scale = 1.0 / size as f64;
+        real.iter_mut().for_each(|value| *value *= scale);
+        imaginary.iter_mut().for_each(|value| *value *= scale);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use accsyn_core::math::f32s_are_equal;
+
+    fn sine_frame(harmonic: usize) -> Vec<f32> {
+        (0..WAVETABLE_FRAME_SIZE)
+            .map(|index| {
+                #[allow(clippy::cast_precision_loss)]
+                let phase =
+                    std::f32::consts::TAU * (index * harmonic) as f32 / WAVETABLE_FRAME_SIZE as f32;
+                phase.sin()
+            })
+            .collect()
+    }
+
+    fn peak_output(oscillator: &mut WavetableOscillator, frequency: f32) -> f32 {
+        (0..4800).fold(0.0_f32, |peak, _| {
+            peak.max(oscillator.next_sample(frequency, None).abs())
+        })
+    }
+
+    #[test]
+    fn from_samples_splits_whole_frames() {
+        let mut samples = sine_frame(1);
+        samples.extend(sine_frame(2));
+        samples.extend(sine_frame(3));
+
+        let wavetable = Wavetable::from_samples("three", &samples, None);
+
+        assert_eq!(wavetable.frame_count(), 3);
+        assert_eq!(wavetable.name(), "three");
+    }
+
+    #[test]
+    fn from_samples_resamples_a_single_cycle_of_any_length() {
+        let samples: Vec<f32> = (0..600)
+            .map(|index| {
+                #[allow(clippy::cast_precision_loss)]
+                let phase = std::f32::consts::TAU * index as f32 / 600.0;
+                phase.sin()
+            })
+            .collect();
+
+        let wavetable = Wavetable::from_samples("single", &samples, None);
+
+        assert_eq!(wavetable.frame_count(), 1);
+        assert!((wavetable.read(0, 0, 0.25) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn from_samples_uses_the_file_frame_size() {
+        let samples: Vec<f32> = (0..256 * 4)
+            .map(|index| {
+                #[allow(clippy::cast_precision_loss)]
+                let phase = std::f32::consts::TAU * index as f32 / 256.0;
+                phase.sin()
+            })
+            .collect();
+
+        let wavetable = Wavetable::from_samples("small frames", &samples, Some(256));
+
+        assert_eq!(wavetable.frame_count(), 4);
+        assert!((wavetable.read(0, 3, 0.25) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn from_samples_caps_the_frame_count() {
+        let samples = vec![0.0; WAVETABLE_FRAME_SIZE * (MAX_WAVETABLE_FRAMES + 2)];
+
+        let wavetable = Wavetable::from_samples("long", &samples, None);
+
+        assert_eq!(wavetable.frame_count(), MAX_WAVETABLE_FRAMES);
+    }
+
+    #[test]
+    fn mipmaps_remove_harmonics_above_each_level() {
+        let mut samples = sine_frame(1);
+        samples
+            .iter_mut()
+            .zip(sine_frame(600))
+            .for_each(|(sample, high)| *sample += high);
+
+        let wavetable = Wavetable::from_samples("bright", &samples, None);
+        let top_level = MIPMAP_LEVEL_COUNT - 1;
+        let expected_fundamental = sine_frame(1);
+
+        // Only the fundamental survives the top level, scaled by the full table normalization
+        let peak = (0..WAVETABLE_FRAME_SIZE)
+            .map(|index| wavetable.mipmaps[top_level][index].abs())
+            .fold(0.0_f32, f32::max);
+        for (index, expected) in expected_fundamental.iter().enumerate() {
+            assert!((wavetable.mipmaps[top_level][index] - expected * peak).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn mipmap_level_rises_one_level_per_octave() {
+        let oscillator = WavetableOscillator::new(48_000);
+
+        assert_eq!(oscillator.mipmap_level(20.0), 0);
+        assert_eq!(oscillator.mipmap_level(100.0), 3);
+        assert_eq!(oscillator.mipmap_level(200.0), 4);
+        assert_eq!(oscillator.mipmap_level(24_000.0), MIPMAP_LEVEL_COUNT - 1);
+    }
+
+    #[test]
+    fn built_in_wavetable_has_four_normalized_frames() {
+        let wavetable = Wavetable::built_in();
+
+        assert_eq!(wavetable.frame_count(), 4);
+        let peak = wavetable.mipmaps[0]
+            .iter()
+            .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+        assert!(f32s_are_equal(peak, 1.0));
+    }
+
+    #[test]
+    fn position_scans_through_the_frames() {
+        let mut samples = sine_frame(1);
+        samples.extend(vec![0.0; WAVETABLE_FRAME_SIZE]);
+        let mut oscillator = WavetableOscillator::new(48_000);
+        oscillator.set_wavetable(Arc::new(Wavetable::from_samples("scan", &samples, None)));
+
+        oscillator.set_shape_parameter1(0.0);
+        let first_frame_peak = peak_output(&mut oscillator, 100.0);
+        oscillator.set_shape_parameter1(1.0);
+        let last_frame_peak = peak_output(&mut oscillator, 100.0);
+        oscillator.set_shape_parameter1(0.5);
+        let halfway_peak = peak_output(&mut oscillator, 100.0);
+
+        assert!(first_frame_peak > 0.99);
+        assert!(last_frame_peak < 0.001);
+        assert!((halfway_peak - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn morph_of_zero_steps_between_frames() {
+        assert!(f32s_are_equal(morph_blend(0.4, 0.0), 0.0));
+        assert!(f32s_are_equal(morph_blend(0.6, 0.0), 1.0));
+        assert!(f32s_are_equal(morph_blend(0.25, 1.0), 0.25));
+        assert!(f32s_are_equal(morph_blend(0.25, 0.5), 0.0));
+        assert!(f32s_are_equal(morph_blend(0.6, 0.5), 0.7));
+    }
+
+    #[test]
+    fn reset_restarts_the_cycle() {
+        let mut oscillator = WavetableOscillator::new(48_000);
+        let first_sample = oscillator.next_sample(220.0, None);
+        for _ in 0..10 {
+            oscillator.next_sample(220.0, None);
+        }
+
+        oscillator.reset();
+
+        assert!(f32s_are_equal(
+            oscillator.next_sample(220.0, None),
+            first_sample
+        ));
+    }
+
+    #[test]
+    fn replaced_wavetable_outlives_the_oscillator_reference() {
+        let shared_wavetable = SharedWavetable::default();
+        shared_wavetable.store(
+            "first".to_string(),
+            Arc::new(Wavetable::from_samples("first", &sine_frame(1), None)),
+        );
+        let mut oscillator = WavetableOscillator::new(48_000);
+        oscillator.set_wavetable(shared_wavetable.try_wavetable().unwrap());
+        let first_wavetable = Arc::downgrade(&oscillator.wavetable);
+
+        shared_wavetable.store(String::new(), BUILT_IN_WAVETABLE.clone());
+        oscillator.set_wavetable(shared_wavetable.try_wavetable().unwrap());
+
+        // The oscillator swap is not the last reference, the next load frees it
+        assert!(first_wavetable.upgrade().is_some());
+        shared_wavetable.store(String::new(), BUILT_IN_WAVETABLE.clone());
+        assert!(first_wavetable.upgrade().is_none());
+    }
+
+    #[test]
+    fn fft_round_trip_restores_the_signal() {
+        let original: Vec<f64> = (0..64).map(|index| f64::from(index % 7) - 3.0).collect();
+        let mut real = original.clone();
+        let mut imaginary = vec![0.0; 64];
+
+        fft(&mut real, &mut imaginary, false);
+        fft(&mut real, &mut imaginary, true);
+
+        for (restored, expected) in real.iter().zip(original.iter()) {
+            assert!((restored - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn load_falls_back_to_built_in_wavetable_when_file_is_missing() {
+        let shared_wavetable = SharedWavetable::default();
+        let starting_version = shared_wavetable.version();
+
+        shared_wavetable.load("Does Not Exist", &std::env::temp_dir());
+
+        assert_eq!(shared_wavetable.name(), "Does Not Exist");
+        assert_ne!(shared_wavetable.version(), starting_version);
+        assert_eq!(
+            shared_wavetable.try_wavetable().unwrap().name(),
+            BUILT_IN_WAVETABLE_NAME
+        );
+    }
+
+    #[test]
+    fn shared_wavetable_serializes_as_its_name() {
+        let shared_wavetable = SharedWavetable::default();
+        shared_wavetable.load("Missing Table", &std::env::temp_dir());
+
+        let json = serde_json::to_string(&shared_wavetable).unwrap();
+        let restored: SharedWavetable = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(json, "\"Missing Table\"");
+        assert_eq!(restored.name(), "Missing Table");
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Shared data replaced off the audio thread, held until the audio thread lets go of it.
+///
+/// The audio thread only clones the current `Arc` from the shared parameters, so once a replaced `Arc` is held
+/// here its last reference, and the deallocation that goes with it, is dropped by a later `retire` and never
+/// by the audio thread.
+pub struct RetiredArcs<T> {
+    arcs: Mutex<Vec<Arc<T>>>,
+}
+
+impl<T> RetiredArcs<T> {
+    /// Drops the retired values nothing else holds any more, then holds on to the replaced one.
+    ///
+    /// A value that is already held, like a built-in default that a static keeps alive, is only held once.
+    pub fn retire(&self, replaced: Arc<T>) {
+        let mut arcs = self.arcs.lock().unwrap_or_else(PoisonError::into_inner);
+        arcs.retain(|arc| Arc::strong_count(arc) > 1);
+        if !arcs.iter().any(|arc| Arc::ptr_eq(arc, &replaced)) {
+            arcs.push(replaced);
+        }
+    }
+}
+
+impl<T> Default for RetiredArcs<T> {
+    fn default() -> Self {
+        Self {
+            arcs: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_retired_value_is_dropped_by_the_next_retire_once_nothing_else_holds_it() {
+        let retired_arcs = RetiredArcs::default();
+        let audio_thread_copy = Arc::new(1);
+        let replaced = Arc::downgrade(&audio_thread_copy);
+
+        retired_arcs.retire(audio_thread_copy.clone());
+        drop(audio_thread_copy);
+        assert!(replaced.upgrade().is_some());
+
+        retired_arcs.retire(Arc::new(2));
+        assert!(replaced.upgrade().is_none());
+    }
+
+    #[test]
+    fn a_value_retired_twice_is_held_once() {
+        let retired_arcs = RetiredArcs::default();
+        let built_in = Arc::new(0);
+
+        retired_arcs.retire(built_in.clone());
+        retired_arcs.retire(built_in.clone());
+
+        assert_eq!(Arc::strong_count(&built_in), 2);
+    }
+}
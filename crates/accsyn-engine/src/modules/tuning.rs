@@ -0,0 +1,923 @@
+use crate::modules::oscillator::constants::{MAX_NOTE_FREQUENCY, MIN_NOTE_FREQUENCY};
+use crate::modules::retired_arcs::RetiredArcs;
+use accsyn_core::casting::f64_to_f32_clamped;
+use accsyn_core::defaults::Defaults;
+use accsyn_core::math::MIDI_DATA_BYTE_7BIT_MASK;
+use accsyn_core::parameter_types::{Cents, Hertz};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering::{Acquire, Release};
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use thiserror::Error;
+
+/// File extension of the Scala scale files in the user tunings directory.
+pub const SCALE_FILE_EXTENSION: &str = "scl";
+/// File extension of the Scala keyboard mapping files in the user tunings directory.
+pub const KEYBOARD_MAPPING_FILE_EXTENSION: &str = "kbm";
+/// Most pitches a scale or keys a keyboard mapping pattern can hold.
+pub const MAX_TUNING_FILE_ENTRIES: usize = 1024;
+const MIDI_NOTE_COUNT: usize = 128;
+const MAX_MIDI_NOTE: u8 = 127;
+const CENTS_PER_OCTAVE: f64 = 1200.0;
+const EQUAL_TEMPERAMENT_STEPS: u8 = 12;
+const EQUAL_TEMPERAMENT_STEP_CENTS: f64 = 100.0;
+const EQUAL_TEMPERAMENT_DESCRIPTION: &str = "12 tone equal temperament";
+// The default keyboard mapping starts the scale on middle C, tuned as it is in equal temperament at A4 = 440 Hz
+const MIDDLE_C_NOTE: u8 = 60;
+const MIDDLE_C_FREQUENCY: f64 = 261.625_565_300_598_6;
+const A4_NOTE: u8 = 69;
+const COMMENT_PREFIX: char = '!';
+const UNMAPPED_KEY: &str = "x";
+
+static EQUAL_TEMPERAMENT: LazyLock<Arc<TuningTable>> =
+    LazyLock::new(|| Arc::new(TuningTable::default()));
+
+/// Errors that can occur while reading a Scala scale or keyboard mapping file.
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum TuningFileError {
+    /// The file could not be read as text.
+    #[error("Failed to read tuning file: {0}")]
+    FailedToRead(String),
+
+    /// The file ended before a line the format requires.
+    #[error("Tuning file is missing the {0}")]
+    MissingLine(&'static str),
+
+    /// A line holds a value that is out of range or can't be parsed.
+    #[error("Invalid {0} in tuning file: '{1}'")]
+    InvalidValue(&'static str, String),
+}
+
+/// The pitches of a Scala `.scl` scale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scale {
+    description: String,
+    // Cents above the root of each degree, the last is the interval the scale repeats at
+    degrees: Vec<f64>,
+}
+
+impl Scale {
+    /// Parses the text of a Scala scale file.
+    ///
+    /// Pitches containing a period are read as cents, anything else as a ratio such as `3/2` or a whole
+    /// number such as `2`. Text after the pitch on each line is ignored, as the format allows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line is missing or a pitch or the note count is invalid.
+    pub fn parse(text: &str) -> Result<Self, TuningFileError> {
+        let mut lines = content_lines(text);
+        let description = lines
+            .next()
+            .ok_or(TuningFileError::MissingLine("description"))?
+            .to_string();
+
+        // Only the description may be blank
+        let mut lines = lines.filter(|line| !line.is_empty());
+        let note_count = parse_value::<usize>(&mut lines, "note count", |count| {
+            (1..=MAX_TUNING_FILE_ENTRIES).contains(count)
+        })?;
+        let degrees = (0..note_count)
+            .map(|_| {
+                let line = lines.next().ok_or(TuningFileError::MissingLine("pitch"))?;
+                parse_pitch(line)
+            })
+            .collect::<Result<Vec<f64>, TuningFileError>>()?;
+
+        Ok(Self {
+            description,
+            degrees,
+        })
+    }
+
+    /// Reads and parses a Scala scale file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, TuningFileError> {
+        Self::parse(&read_tuning_file(path)?)
+    }
+
+    /// Returns the description line of the scale.
+    #[must_use]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the number of degrees in one repeat of the scale.
+    #[must_use]
+    pub fn degree_count(&self) -> usize {
+        self.degrees.len()
+    }
+
+    // Cents above the root of any degree, counting on through the repeats of the scale in both directions
+    fn degree_cents(&self, degree: i64) -> f64 {
+        // Scales hold at most MAX_TUNING_FILE_ENTRIES degrees, well within i64 range
+        #[allow(clippy::cast_possible_wrap)]
+        let degree_count = self.degrees.len() as i64;
+        let period = self.degrees[self.degrees.len() - 1];
+
+        // rem_euclid keeps the step within [0, degree_count)
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let step = degree.rem_euclid(degree_count) as usize;
+        let step_cents = if step == 0 {
+            0.0
+        } else {
+            self.degrees[step - 1]
+        };
+
+        // Repeats are bounded by the MIDI note range divided by the scale size, exactly representable in f64
+        #[allow(clippy::cast_precision_loss)]
+        let repeats = degree.div_euclid(degree_count) as f64;
+        repeats * period + step_cents
+    }
+}
+
+impl Default for Scale {
+    fn default() -> Self {
+        Self {
+            description: EQUAL_TEMPERAMENT_DESCRIPTION.to_string(),
+            degrees: (1..=EQUAL_TEMPERAMENT_STEPS)
+                .map(|step| f64::from(step) * EQUAL_TEMPERAMENT_STEP_CENTS)
+                .collect(),
+        }
+    }
+}
+
+/// How the degrees of a scale are laid out across the MIDI keys, read from a Scala `.kbm` keyboard mapping.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardMapping {
+    first_note: u8,
+    last_note: u8,
+    middle_note: u8,
+    reference_note: u8,
+    reference_frequency: f64,
+    // Degree the mapping pattern repeats at, 0 repeats at the scale's own period
+    octave_degree: usize,
+    // Scale degree of each key in the repeating pattern, None for an unmapped key. Empty maps each key to
+    // the next degree in turn
+    keys: Vec<Option<usize>>,
+}
+
+impl KeyboardMapping {
+    /// Parses the text of a Scala keyboard mapping file.
+    ///
+    /// A pattern with fewer key lines than its size leaves the remaining keys unmapped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a header line is missing or holds an invalid value.
+    pub fn parse(text: &str) -> Result<Self, TuningFileError> {
+        let mut lines = content_lines(text).filter(|line| !line.is_empty());
+        let pattern_size = parse_value::<usize>(&mut lines, "map size", |size| {
+            *size <= MAX_TUNING_FILE_ENTRIES
+        })?;
+        let first_note = parse_note(&mut lines, "first note")?;
+        let last_note = parse_note(&mut lines, "last note")?;
+        let middle_note = parse_note(&mut lines, "middle note")?;
+        let reference_note = parse_note(&mut lines, "reference note")?;
+        let reference_frequency =
+            parse_value::<f64>(&mut lines, "reference frequency", |frequency| {
+                frequency.is_finite() && *frequency > 0.0
+            })?;
+        let octave_degree = parse_value::<usize>(&mut lines, "octave degree", |_| true)?;
+        let keys = (0..pattern_size)
+            .map(|_| match lines.next().map(first_token) {
+                None => Ok(None),
+                Some(token) if token.eq_ignore_ascii_case(UNMAPPED_KEY) => Ok(None),
+                Some(token) => token
+                    .parse::<usize>()
+                    .map(Some)
+                    .map_err(|_| TuningFileError::InvalidValue("key degree", token.to_string())),
+            })
+            .collect::<Result<Vec<Option<usize>>, TuningFileError>>()?;
+
+        Ok(Self {
+            first_note,
+            last_note,
+            middle_note,
+            reference_note,
+            reference_frequency,
+            octave_degree,
+            keys,
+        })
+    }
+
+    /// Reads and parses a Scala keyboard mapping file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, TuningFileError> {
+        Self::parse(&read_tuning_file(path)?)
+    }
+
+    // Cents of the key above the scale root on the middle note, None for keys left unmapped
+    fn key_cents(&self, scale: &Scale, note: u8) -> Option<f64> {
+        if note < self.first_note || note > self.last_note {
+            return None;
+        }
+
+        let offset = i64::from(note) - i64::from(self.middle_note);
+        if self.keys.is_empty() {
+            return Some(scale.degree_cents(offset));
+        }
+
+        let pattern_size = i64::try_from(self.keys.len()).ok()?;
+        let key = usize::try_from(offset.rem_euclid(pattern_size)).ok()?;
+        let degree = i64::try_from(self.keys[key]?).ok()?;
+        let octave_degree = if self.octave_degree == 0 {
+            scale.degree_count()
+        } else {
+            self.octave_degree
+        };
+        let octave_cents = scale.degree_cents(i64::try_from(octave_degree).ok()?);
+
+        // Repeats are bounded by the MIDI note range, exactly representable in f64
+        #[allow(clippy::cast_precision_loss)]
+        let repeats = offset.div_euclid(pattern_size) as f64;
+        Some(repeats * octave_cents + scale.degree_cents(degree))
+    }
+}
+
+impl Default for KeyboardMapping {
+    fn default() -> Self {
+        Self {
+            first_note: 0,
+            last_note: MAX_MIDI_NOTE,
+            middle_note: MIDDLE_C_NOTE,
+            reference_note: MIDDLE_C_NOTE,
+            reference_frequency: MIDDLE_C_FREQUENCY,
+            octave_degree: 0,
+            keys: Vec::new(),
+        }
+    }
+}
+
+/// Frequency of every MIDI note for a scale and keyboard mapping, before the master tuning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningTable {
+    frequencies: [f32; MIDI_NOTE_COUNT],
+}
+
+impl TuningTable {
+    /// Builds the note frequencies for a scale laid out by a keyboard mapping.
+    ///
+    /// Unmapped keys play the nearest mapped key below them, or above them at the bottom of the keyboard.
+    #[must_use]
+    pub fn new(scale: &Scale, keyboard_mapping: &KeyboardMapping) -> Self {
+        let mut mapped_cents = [None; MIDI_NOTE_COUNT];
+        for (note, cents) in (0..=MAX_MIDI_NOTE).zip(mapped_cents.iter_mut()) {
+            *cents = keyboard_mapping.key_cents(scale, note);
+        }
+
+        let mut key_cents = [0.0; MIDI_NOTE_COUNT];
+        for (note, cents) in key_cents.iter_mut().enumerate() {
+            *cents = mapped_cents[note]
+                .or_else(|| mapped_cents[..note].iter().rev().find_map(|cents| *cents))
+                .or_else(|| mapped_cents[note..].iter().find_map(|cents| *cents))
+                .unwrap_or_default();
+        }
+
+        let reference_cents = key_cents[usize::from(keyboard_mapping.reference_note)];
+        let mut frequencies = [0.0; MIDI_NOTE_COUNT];
+        for (frequency, cents) in frequencies.iter_mut().zip(key_cents) {
+            *frequency = f64_to_f32_clamped(
+                keyboard_mapping.reference_frequency
+                    * 2.0_f64.powf((cents - reference_cents) / CENTS_PER_OCTAVE),
+            );
+        }
+
+        Self { frequencies }
+    }
+
+    /// Builds 12 tone equal temperament with each pitch class, starting from C, offset by its cents.
+    #[must_use]
+    pub fn from_pitch_class_offsets(offsets: &[f32; EQUAL_TEMPERAMENT_STEPS as usize]) -> Self {
+        let mut frequencies = [0.0; MIDI_NOTE_COUNT];
+        for (note, frequency) in (0..=MAX_MIDI_NOTE).zip(frequencies.iter_mut()) {
+            let offset = offsets[usize::from(note % EQUAL_TEMPERAMENT_STEPS)];
+            *frequency =
+                pitch_frequency(f64::from(note) + f64::from(offset) / EQUAL_TEMPERAMENT_STEP_CENTS);
+        }
+
+        Self { frequencies }
+    }
+
+    /// Returns a copy of this table with the listed notes (note number, pitch) retuned, each pitch a
+    /// fractional MIDI note number in equal temperament at A4 = 440 Hz.
+    #[must_use]
+    pub fn with_note_pitches(&self, note_pitches: &[(u8, f32)]) -> Self {
+        let mut table = self.clone();
+        for &(note, pitch) in note_pitches {
+            table.frequencies[usize::from(note & MIDI_DATA_BYTE_7BIT_MASK)] =
+                pitch_frequency(f64::from(pitch));
+        }
+        table
+    }
+
+    /// Returns the frequency of a MIDI note in Hz.
+    #[must_use]
+    pub fn frequency(&self, note: u8) -> f32 {
+        self.frequencies[(note & MIDI_DATA_BYTE_7BIT_MASK) as usize]
+    }
+}
+
+impl Default for TuningTable {
+    fn default() -> Self {
+        Self::new(&Scale::default(), &KeyboardMapping::default())
+    }
+}
+
+/// The tuning shared between the patch and the audio thread, along with the global master tuning.
+///
+/// Patches store only the scale and keyboard mapping file names. Files are loaded off the audio thread with
+/// `load`, and the audio thread picks up the new tuning table when the version changes. Replaced tables are
+/// retired rather than dropped, so the audio thread never frees one. The master tune and
+/// reference pitch are global settings, so they are neither saved in patches nor changed by loading one.
+pub struct TuningParameters {
+    scale_name: Mutex<String>,
+    keyboard_mapping_name: Mutex<String>,
+    table: Mutex<Arc<TuningTable>>,
+    retired_tables: RetiredArcs<TuningTable>,
+    version: AtomicU32,
+    /// Offset in cents applied to every note on top of the tuning.
+    pub master_tune: Cents,
+    /// Frequency of A4 in Hz, every tuning is scaled by its ratio to 440 Hz.
+    pub reference_pitch: Hertz,
+}
+
+impl TuningParameters {
+    /// Replace the selected scale and keyboard mapping with the ones selected in the provided
+    /// `TuningParameters`, leaving the global master tune and reference pitch untouched.
+    pub fn assign_from(&self, parameters: &TuningParameters) {
+        self.store(
+            parameters.scale_name(),
+            parameters.keyboard_mapping_name(),
+            parameters
+                .table
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+        );
+    }
+
+    /// Loads the named scale and keyboard mapping from the tuning directory.
+    ///
+    /// An empty scale name selects 12 tone equal temperament and an empty keyboard mapping name maps the
+    /// scale root to middle C. A missing or unreadable file logs a warning and falls back to the default,
+    /// keeping the name so the patch still saves it.
+    pub fn load(&self, scale_name: &str, keyboard_mapping_name: &str, tuning_directory: &Path) {
+        let scale = load_tuning_file(
+            scale_name,
+            SCALE_FILE_EXTENSION,
+            tuning_directory,
+            Scale::load,
+        );
+        let keyboard_mapping = load_tuning_file(
+            keyboard_mapping_name,
+            KEYBOARD_MAPPING_FILE_EXTENSION,
+            tuning_directory,
+            KeyboardMapping::load,
+        );
+
+        self.store(
+            scale_name.to_string(),
+            keyboard_mapping_name.to_string(),
+            Arc::new(TuningTable::new(&scale, &keyboard_mapping)),
+        );
+    }
+
+    /// Retunes individual notes live from a MIDI Tuning Standard message, keeping the rest of the tuning.
+    ///
+    /// Retuning isn't saved, loading a patch or selecting a scale or keyboard mapping replaces it.
+    pub fn retune_notes(&self, note_pitches: &[(u8, f32)]) {
+        self.replace_table(|table| Arc::new(table.with_note_pitches(note_pitches)));
+    }
+
+    /// Retunes every note live to equal temperament with a cents offset for each pitch class, from a MIDI
+    /// Tuning Standard scale/octave message.
+    pub fn retune_pitch_classes(&self, offsets: &[f32; EQUAL_TEMPERAMENT_STEPS as usize]) {
+        self.replace_table(|_| Arc::new(TuningTable::from_pitch_class_offsets(offsets)));
+    }
+
+    /// Returns the name of the selected scale, empty for 12 tone equal temperament.
+    #[must_use]
+    pub fn scale_name(&self) -> String {
+        self.scale_name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns the name of the selected keyboard mapping, empty for the default mapping.
+    #[must_use]
+    pub fn keyboard_mapping_name(&self) -> String {
+        self.keyboard_mapping_name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns a counter that changes every time a new tuning is selected.
+    #[must_use]
+    pub fn version(&self) -> u32 {
+        self.version.load(Acquire)
+    }
+
+    /// Returns the selected tuning table without waiting, or `None` while another thread is changing it.
+    #[must_use]
+    pub fn try_table(&self) -> Option<Arc<TuningTable>> {
+        self.table.try_lock().ok().map(|table| table.clone())
+    }
+
+    /// Returns the frequency ratio the master tune and reference pitch apply to every note.
+    #[must_use]
+    pub fn master_tuning_ratio(&self) -> f32 {
+        let reference_ratio = self.reference_pitch.load() / Defaults::REFERENCE_PITCH;
+        // Cents per octave is an exactly representable constant
+        #[allow(clippy::cast_possible_truncation)]
+        let cents_per_octave = CENTS_PER_OCTAVE as f32;
+        reference_ratio * 2.0_f32.powf(f32::from(self.master_tune.load()) / cents_per_octave)
+    }
+
+    fn store(&self, scale_name: String, keyboard_mapping_name: String, table: Arc<TuningTable>) {
+        *self
+            .scale_name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = scale_name;
+        *self
+            .keyboard_mapping_name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = keyboard_mapping_name;
+        self.replace_table(|_| table);
+    }
+
+    // Replaced tables are retired rather than dropped, so the audio thread never frees one
+    fn replace_table(&self, new_table: impl FnOnce(&TuningTable) -> Arc<TuningTable>) {
+        let mut table = self.table.lock().unwrap_or_else(PoisonError::into_inner);
+        let replacement = new_table(&table);
+        let replaced_table = std::mem::replace(&mut *table, replacement);
+        drop(table);
+        self.version.fetch_add(1, Release);
+        self.retired_tables.retire(replaced_table);
+    }
+}
+
+impl Default for TuningParameters {
+    fn default() -> Self {
+        Self {
+            scale_name: Mutex::new(String::new()),
+            keyboard_mapping_name: Mutex::new(String::new()),
+            table: Mutex::new(EQUAL_TEMPERAMENT.clone()),
+            retired_tables: RetiredArcs::default(),
+            version: AtomicU32::new(0),
+            master_tune: Cents::new(Defaults::MASTER_TUNE_CENTS),
+            reference_pitch: Hertz::new(Defaults::REFERENCE_PITCH),
+        }
+    }
+}
+
+impl fmt::Debug for TuningParameters {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("TuningParameters")
+            .field("scale_name", &self.scale_name())
+            .field("keyboard_mapping_name", &self.keyboard_mapping_name())
+            .field("version", &self.version())
+            .field("master_tune", &self.master_tune)
+            .field("reference_pitch", &self.reference_pitch)
+            .finish_non_exhaustive()
+    }
+}
+
+// The part of the tuning saved in patches
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct TuningFileNames {
+    scale: String,
+    keyboard_mapping: String,
+}
+
+impl Serialize for TuningParameters {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TuningFileNames {
+            scale: self.scale_name(),
+            keyboard_mapping: self.keyboard_mapping_name(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TuningParameters {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let names = TuningFileNames::deserialize(deserializer)?;
+        let parameters = TuningParameters::default();
+        *parameters
+            .scale_name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = names.scale;
+        *parameters
+            .keyboard_mapping_name
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = names.keyboard_mapping;
+        Ok(parameters)
+    }
+}
+
+/// The tuning a voice plays, refreshed from the shared `TuningParameters` once per buffer.
+#[derive(Debug, Clone)]
+pub struct VoiceTuning {
+    table: Arc<TuningTable>,
+    version: Option<u32>,
+    master_tuning_ratio: f32,
+}
+
+impl VoiceTuning {
+    /// Picks up a newly selected tuning table and the current master tuning.
+    ///
+    /// The table lock is only tried, never waited on, so a tuning being loaded is picked up on a later buffer.
+    pub fn update(&mut self, parameters: &TuningParameters) {
+        self.master_tuning_ratio = parameters.master_tuning_ratio();
+
+        let version = parameters.version();
+        if self.version == Some(version) {
+            return;
+        }
+
+        if let Some(table) = parameters.try_table() {
+            self.table = table;
+            self.version = Some(version);
+        }
+    }
+
+    /// Returns the tuned frequency of a MIDI note in Hz, including the master tuning.
+    #[must_use]
+    pub fn frequency(&self, note: u8) -> f32 {
+        (self.table.frequency(note) * self.master_tuning_ratio)
+            .clamp(MIN_NOTE_FREQUENCY, MAX_NOTE_FREQUENCY)
+    }
+}
+
+impl Default for VoiceTuning {
+    fn default() -> Self {
+        Self {
+            table: EQUAL_TEMPERAMENT.clone(),
+            version: None,
+            master_tuning_ratio: 1.0,
+        }
+    }
+}
+
+/// Returns the frequency of a MIDI note in Hz in 12 tone equal temperament at A4 = 440 Hz, ignoring the
+/// selected tuning.
+#[must_use]
+pub fn equal_temperament_frequency(note: u8) -> f32 {
+    EQUAL_TEMPERAMENT.frequency(note)
+}
+
+// Frequency of a fractional MIDI note number in equal temperament at A4 = 440 Hz
+fn pitch_frequency(pitch: f64) -> f32 {
+    let octaves_from_a4 = (pitch - f64::from(A4_NOTE)) / f64::from(EQUAL_TEMPERAMENT_STEPS);
+    f64_to_f32_clamped(f64::from(Defaults::REFERENCE_PITCH) * 2.0_f64.powf(octaves_from_a4))
+}
+
+// Non-comment lines with surrounding whitespace removed, Scala files may use either line ending
+fn content_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .filter(|line| !line.starts_with(COMMENT_PREFIX))
+        .map(str::trim)
+}
+
+fn first_token(line: &str) -> &str {
+    line.split_whitespace().next().unwrap_or_default()
+}
+
+fn parse_value<'a, T: FromStr>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    name: &'static str,
+    is_valid: impl Fn(&T) -> bool,
+) -> Result<T, TuningFileError> {
+    let line = lines.next().ok_or(TuningFileError::MissingLine(name))?;
+    first_token(line)
+        .parse::<T>()
+        .ok()
+        .filter(|value| is_valid(value))
+        .ok_or_else(|| TuningFileError::InvalidValue(name, line.to_string()))
+}
+
+fn parse_note<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    name: &'static str,
+) -> Result<u8, TuningFileError> {
+    parse_value::<u8>(lines, name, |note| *note <= MAX_MIDI_NOTE)
+}
+
+fn parse_pitch(line: &str) -> Result<f64, TuningFileError> {
+    let token = first_token(line);
+    let invalid_pitch = || TuningFileError::InvalidValue("pitch", line.to_string());
+
+    let cents = if token.contains('.') {
+        token.parse::<f64>().map_err(|_| invalid_pitch())?
+    } else {
+        let (numerator, denominator) = token.split_once('/').unwrap_or((token, "1"));
+        let numerator = numerator.parse::<u64>().map_err(|_| invalid_pitch())?;
+        let denominator = denominator.parse::<u64>().map_err(|_| invalid_pitch())?;
+        if numerator == 0 || denominator == 0 {
+            return Err(invalid_pitch());
+        }
+
+        // Ratio terms beyond 2⁵³ lose precision far below an audible fraction of a cent
+        #[allow(clippy::cast_precision_loss)]
+        let ratio = numerator as f64 / denominator as f64;
+        CENTS_PER_OCTAVE * ratio.log2()
+    };
+
+    if cents.is_finite() {
+        Ok(cents)
+    } else {
+        Err(invalid_pitch())
+    }
+}
+
+fn read_tuning_file(path: &Path) -> Result<String, TuningFileError> {
+    std::fs::read_to_string(path).map_err(|err| TuningFileError::FailedToRead(err.to_string()))
+}
+
+fn load_tuning_file<T: Default>(
+    name: &str,
+    extension: &str,
+    tuning_directory: &Path,
+    load: impl Fn(&Path) -> Result<T, TuningFileError>,
+) -> T {
+    if name.is_empty() {
+        return T::default();
+    }
+
+    let mut path = tuning_directory.join(sanitize_filename::sanitize(name));
+    path.set_extension(extension);
+
+    match load(&path) {
+        Ok(tuning_file) => {
+            log::info!(target: "synthesizer::modules::tuning", "Loaded tuning file '{name}.{extension}'");
+            tuning_file
+        }
+        Err(err) => {
+            log::warn!(target: "synthesizer::modules::tuning", "Failed to load tuning file {}, using the default: {err}", path.display());
+            T::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const PYTHAGOREAN_PENTATONIC: &str = "! pentatonic.scl
+!
+Pythagorean pentatonic
+ 5
+!
+ 9/8
+ 81/64
+ 3/2   the fifth
+ 27/16
+ 2/1
+";
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < expected * 1e-5,
+            "{actual} should be {expected}"
+        );
+    }
+
+    fn temporary_tuning_directory(label: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("accsyn_tunings_{label}_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    #[test]
+    fn default_table_is_equal_temperament_at_a_440() {
+        let table = TuningTable::default();
+
+        assert_close(table.frequency(69), 440.0);
+        assert_close(table.frequency(81), 880.0);
+        assert_close(table.frequency(60), 261.625_58);
+        assert_close(table.frequency(0), 8.175_799);
+    }
+
+    #[test]
+    fn scale_reads_ratios_cents_and_skips_comments() {
+        let scale = Scale::parse(PYTHAGOREAN_PENTATONIC).unwrap();
+
+        assert_eq!(scale.description(), "Pythagorean pentatonic");
+        assert_eq!(scale.degree_count(), 5);
+        assert!((scale.degree_cents(3) - 701.955).abs() < 1e-3);
+        assert!((scale.degree_cents(5) - 1200.0).abs() < 1e-9);
+        assert!((scale.degree_cents(-5) + 1200.0).abs() < 1e-9);
+
+        let cents_scale = Scale::parse("Quarter tones\n2\n50.0\n100.0 cents\n").unwrap();
+        assert!((cents_scale.degree_cents(3) - 150.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn scale_rejects_missing_and_invalid_pitches() {
+        assert_eq!(
+            Scale::parse("Too short\n3\n100.0\n200.0\n"),
+            Err(TuningFileError::MissingLine("pitch"))
+        );
+        assert!(matches!(
+            Scale::parse("Bad ratio\n1\n0/2\n"),
+            Err(TuningFileError::InvalidValue("pitch", _))
+        ));
+        assert!(matches!(
+            Scale::parse("No notes\n0\n"),
+            Err(TuningFileError::InvalidValue("note count", _))
+        ));
+    }
+
+    #[test]
+    fn scale_without_a_mapping_starts_on_middle_c() {
+        let scale = Scale::parse(PYTHAGOREAN_PENTATONIC).unwrap();
+        let table = TuningTable::new(&scale, &KeyboardMapping::default());
+
+        assert_close(table.frequency(60), 261.625_58);
+        assert_close(table.frequency(63), 261.625_58 * 1.5);
+        assert_close(table.frequency(65), 261.625_58 * 2.0);
+        assert_close(table.frequency(55), 261.625_58 / 2.0);
+    }
+
+    #[test]
+    fn keyboard_mapping_sets_the_reference_and_leaves_x_keys_unmapped() {
+        let keyboard_mapping = KeyboardMapping::parse(
+            "! pentatonic on the C, D, E, G, and A keys, the last two keys have no lines
+12
+0
+127
+60
+69
+432.0
+5
+0
+x
+1
+x
+2
+x
+x
+3
+x
+4
+",
+        )
+        .unwrap();
+        let scale = Scale::parse(PYTHAGOREAN_PENTATONIC).unwrap();
+        let table = TuningTable::new(&scale, &keyboard_mapping);
+
+        assert_close(table.frequency(69), 432.0);
+        assert_close(table.frequency(60), 256.0);
+        assert_close(table.frequency(67), 384.0);
+        assert_close(table.frequency(72), 512.0);
+        // The black keys are unmapped and play the white key below them
+        assert_eq!(table.frequency(61), table.frequency(60));
+        assert_eq!(table.frequency(66), table.frequency(64));
+        // So are the keys without a line
+        assert_eq!(table.frequency(71), table.frequency(69));
+    }
+
+    #[test]
+    fn keyboard_mapping_rejects_out_of_range_notes() {
+        assert!(matches!(
+            KeyboardMapping::parse("0\n0\n200\n60\n69\n440.0\n0\n"),
+            Err(TuningFileError::InvalidValue("last note", _))
+        ));
+        assert_eq!(
+            KeyboardMapping::parse("0\n0\n127\n60\n"),
+            Err(TuningFileError::MissingLine("reference note"))
+        );
+    }
+
+    #[test]
+    fn master_tune_and_reference_pitch_scale_every_note() {
+        let parameters = TuningParameters::default();
+        let mut tuning = VoiceTuning::default();
+
+        parameters.master_tune.store(100);
+        tuning.update(&parameters);
+        assert_close(tuning.frequency(69), 440.0 * 2.0_f32.powf(1.0 / 12.0));
+
+        parameters.master_tune.store(0);
+        parameters.reference_pitch.store(432.0);
+        tuning.update(&parameters);
+        assert_close(tuning.frequency(69), 432.0);
+        assert_close(tuning.frequency(57), 216.0);
+    }
+
+    #[test]
+    fn retuning_notes_changes_only_those_notes_until_a_tuning_is_loaded() {
+        let parameters = TuningParameters::default();
+        let mut tuning = VoiceTuning::default();
+
+        parameters.retune_notes(&[(69, 69.5), (60, 72.0)]);
+        tuning.update(&parameters);
+        assert_close(tuning.frequency(69), 440.0 * 2.0_f32.powf(0.5 / 12.0));
+        assert_close(tuning.frequency(60), 523.251_1);
+        assert_close(tuning.frequency(61), 277.182_63);
+
+        parameters.load("", "", &std::env::temp_dir());
+        tuning.update(&parameters);
+        assert_close(tuning.frequency(69), 440.0);
+    }
+
+    #[test]
+    fn retuning_pitch_classes_offsets_every_octave_from_equal_temperament() {
+        let parameters = TuningParameters::default();
+        let mut tuning = VoiceTuning::default();
+        let mut offsets = [0.0; 12];
+        offsets[9] = -50.0;
+
+        parameters.retune_notes(&[(60, 61.0)]);
+        parameters.retune_pitch_classes(&offsets);
+        tuning.update(&parameters);
+
+        assert_close(tuning.frequency(60), 261.625_58);
+        assert_close(tuning.frequency(69), 440.0 * 2.0_f32.powf(-0.5 / 12.0));
+        assert_close(tuning.frequency(81), 880.0 * 2.0_f32.powf(-0.5 / 12.0));
+    }
+
+    #[test]
+    fn load_reads_files_from_the_tuning_directory_and_falls_back_when_missing() {
+        let directory = temporary_tuning_directory("load");
+        std::fs::write(directory.join("pentatonic.scl"), PYTHAGOREAN_PENTATONIC).unwrap();
+        let parameters = TuningParameters::default();
+        let mut tuning = VoiceTuning::default();
+
+        parameters.load("pentatonic", "Does Not Exist", &directory);
+        tuning.update(&parameters);
+
+        assert_eq!(parameters.scale_name(), "pentatonic");
+        assert_eq!(parameters.keyboard_mapping_name(), "Does Not Exist");
+        assert_close(tuning.frequency(63), 261.625_58 * 1.5);
+
+        parameters.load("Does Not Exist", "", &directory);
+        tuning.update(&parameters);
+        assert_close(tuning.frequency(69), 440.0);
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn retuned_table_outlives_the_voice_reference() {
+        let parameters = TuningParameters::default();
+        let mut tuning = VoiceTuning::default();
+        parameters.retune_notes(&[(60, 60.5)]);
+        tuning.update(&parameters);
+        let first_table = Arc::downgrade(&tuning.table);
+
+        parameters.retune_notes(&[(61, 61.5)]);
+        tuning.update(&parameters);
+
+        // The voice letting go is not the last reference, the next retune frees it
+        assert!(first_table.upgrade().is_some());
+        parameters.retune_pitch_classes(&[0.0; EQUAL_TEMPERAMENT_STEPS as usize]);
+        assert!(first_table.upgrade().is_none());
+    }
+
+    #[test]
+    fn loading_a_patch_keeps_the_global_master_tuning() {
+        let live = TuningParameters::default();
+        live.master_tune.store(-30);
+        live.reference_pitch.store(432.0);
+        let preset = TuningParameters::default();
+        preset.master_tune.store(25);
+
+        live.assign_from(&preset);
+
+        assert_eq!(live.master_tune.load(), -30);
+        assert_close(live.reference_pitch.load(), 432.0);
+    }
+
+    #[test]
+    fn only_the_file_names_are_saved_in_patches() {
+        let parameters = TuningParameters::default();
+        parameters.load("Missing Scale", "Missing Map", &std::env::temp_dir());
+        parameters.master_tune.store(-20);
+
+        let json = serde_json::to_string(&parameters).unwrap();
+        let restored: TuningParameters = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"scale":"Missing Scale","keyboard_mapping":"Missing Map"}"#
+        );
+        assert_eq!(restored.scale_name(), "Missing Scale");
+        assert_eq!(restored.master_tune.load(), Defaults::MASTER_TUNE_CENTS);
+    }
+}
@@ -6,9 +6,12 @@ pub const PITCH_BEND_AMOUNT_MAX_VALUE: u16 = 16383;
 pub const CENTS_PER_SEMITONE: u16 = 100;
 pub const MIN_PITCH_BEND_RANGE: u8 = 2;
 pub const MAX_PITCH_BEND_RANGE: u8 = 12;
+pub const MIN_MPE_PITCH_BEND_RANGE: u8 = 1;
+pub const MAX_MPE_PITCH_BEND_RANGE: u8 = 96;
 pub const MAX_MIDI_KEY_VELOCITY: f32 = 1.0;
 pub const MIN_VELOCITY_CURVE_EXPONENT: f32 = 0.25;
 pub const MAX_VELOCITY_CURVE_EXPONENT: f32 = 4.0;
+pub const MAX_RELEASE_VELOCITY_TIME_SCALE_OCTAVES: f32 = 2.0;
 pub const MIDI_CLOCK_OFF_BPM_VALUE: u16 = 0;
 
 // Envelope Constants
@@ -16,12 +19,9 @@ pub const ENVELOPE_INDEX_AMP: i32 = 0;
 pub const ENVELOPE_INDEX_FILTER: i32 = 1;
 pub const ENVELOPE_INDEX_PITCH: i32 = 2;
 
-// LFO Constants
-pub const LFO_INDEX_MOD_WHEEL: i32 = 0;
-pub const LFO_INDEX_FILTER: i32 = 1;
-
 // Audio Constants
 pub const SAMPLE_PRODUCER_LOOP_SLEEP_DURATION_MICROSECONDS: u64 = 100;
+pub const VOICE_MIX_GAIN_SMOOTHING_SECONDS: f32 = 0.02;
 
 // MISC Constants
 pub const SYNTHESIZER_MESSAGE_SENDER_CAPACITY: usize = 10;
@@ -1,33 +1,50 @@
 use crate::modules::effects::EffectIndex;
+use crate::modules::envelope::EnvelopeParameters;
+use crate::modules::lfo::{LfoBank, LfoParameters, MAX_LFO_COUNT};
+use crate::modules::mod_matrix::ModulationSlotParameters;
 use crate::modules::oscillator::WaveShape;
+use crate::modules::oscillator::additive::MAX_PARTIALS;
 use crate::modules::oscillator::constants::OSCILLATOR_WAVESHAPE_PARAMETER_DEFAULTS;
 use crate::synthesizer::ModuleParameters;
 use crate::synthesizer::clock::bpm_from_thirty_second_note_duration;
 use crate::synthesizer::constants::{
-    ENVELOPE_INDEX_AMP, ENVELOPE_INDEX_FILTER, ENVELOPE_INDEX_PITCH, LFO_INDEX_FILTER,
-    LFO_INDEX_MOD_WHEEL, PATCH_DELETE_FAILURE, PATCH_DELETE_FILE_DOES_NOT_EXIST,
-    PATCH_DELETE_SUCCESS, PATCH_SAVE_ALREADY_EXISTS, PATCH_SAVE_FAILURE, PATCH_SAVE_SUCCESS,
+    ENVELOPE_INDEX_AMP, ENVELOPE_INDEX_FILTER, ENVELOPE_INDEX_PITCH, PATCH_DELETE_FAILURE,
+    PATCH_DELETE_FILE_DOES_NOT_EXIST, PATCH_DELETE_SUCCESS, PATCH_SAVE_ALREADY_EXISTS,
+    PATCH_SAVE_FAILURE, PATCH_SAVE_SUCCESS,
 };
 use crate::synthesizer::midi_value_converters::bool_to_normal_value;
 use crate::synthesizer::patches::{Patches, PatchesError, get_module_parameters_from_patch_index};
 use crate::synthesizer::set_parameters::{
-    set_effect_is_enabled, set_effect_parameter, set_envelope_amount, set_envelope_attack_time,
-    set_envelope_decay_time, set_envelope_inverted, set_envelope_release_time,
-    set_envelope_sustain_level, set_envelope_sustain_pedal, set_filter_cutoff, set_filter_poles,
-    set_filter_resonance, set_key_tracking_amount, set_lfo_clock_sync, set_lfo_frequency,
-    set_lfo_key_sync, set_lfo_phase, set_lfo_phase_reset, set_lfo_range,
-    set_module_parameters_from_preset, set_oscillator_balance, set_oscillator_clip_boost,
-    set_oscillator_course_tune, set_oscillator_fine_tune, set_oscillator_hard_sync,
+    load_patch_samples, load_patch_tuning, load_patch_wavetables, set_drift_depth,
+    set_effect_is_enabled, set_effect_parameter, set_envelope_amount, set_envelope_attack_curve,
+    set_envelope_attack_time, set_envelope_decay_curve, set_envelope_decay_time,
+    set_envelope_delay_time, set_envelope_hold_time, set_envelope_inverted,
+    set_envelope_loop_enabled, set_envelope_loop_end, set_envelope_loop_start,
+    set_envelope_release_curve, set_envelope_release_time, set_envelope_sustain_level,
+    set_envelope_sustain_pedal, set_filter_crossfade, set_filter_cutoff, set_filter_drive,
+    set_filter_lfo, set_filter_poles, set_filter_resonance, set_filter_routing, set_filter_type,
+    set_key_tracking_amount, set_legato_enabled, set_lfo_clock_sync, set_lfo_count,
+    set_lfo_frequency, set_lfo_key_sync, set_lfo_phase, set_lfo_phase_reset, set_lfo_range,
+    set_master_tune, set_modulation_amount, set_modulation_destination, set_modulation_source,
+    set_modulation_via_source, set_module_parameters_from_preset, set_mpe_pitch_bend_range,
+    set_note_priority, set_oscillator_additive_partial, set_oscillator_balance,
+    set_oscillator_clip_boost, set_oscillator_course_tune, set_oscillator_cross_modulation_depth,
+    set_oscillator_cross_modulation_mode, set_oscillator_cross_modulation_source,
+    set_oscillator_fine_tune, set_oscillator_hard_sync, set_oscillator_hard_sync_source,
     set_oscillator_key_sync, set_oscillator_level, set_oscillator_mute,
-    set_oscillator_pitch_envelope_amount, set_oscillator_polarity, set_oscillator_shape_parameter1,
-    set_oscillator_shape_parameter2, set_oscillator_soft_clip, set_output_balance,
-    set_output_level, set_output_mute, set_pitch_bend_range, set_portamento_enabled,
-    set_portamento_time, set_velocity_curve,
+    set_oscillator_pitch_envelope_amount, set_oscillator_polarity, set_oscillator_sample,
+    set_oscillator_shape_parameter1, set_oscillator_shape_parameter2, set_oscillator_soft_clip,
+    set_oscillator_supersaw_random_phase, set_oscillator_supersaw_stereo_spread,
+    set_oscillator_supersaw_voice_count, set_oscillator_wavetable, set_output_balance,
+    set_output_level, set_output_mute, set_paraphonic_assignment, set_pitch_bend_range,
+    set_portamento_enabled, set_portamento_time, set_reference_pitch, set_release_velocity_amount,
+    set_sub_oscillator_octave, set_tuning_keyboard_mapping, set_tuning_scale, set_velocity_curve,
+    set_vibrato_lfo, set_voice_count, set_voice_mode, set_voice_stealing_policy,
 };
 use accsyn_core::casting::i32_to_u8_clamped;
 use accsyn_core::defaults::Defaults;
 use accsyn_core::synth_events::{
-    EnvelopeIndex, LFO_SYNC_INTERVAL_NAMES, LFOIndex, LfoSyncInterval, OscillatorIndex,
+    EnvelopeIndex, LFO_SYNC_INTERVAL_NAMES, LfoSyncInterval, OscillatorIndex,
     SynthesizerUpdateEvents,
 };
 use accsyn_core::ui_events::UIUpdates;
@@ -193,6 +210,193 @@ pub fn start_update_event_listener(
                         }
                     }
                 }
+                SynthesizerUpdateEvents::OscillatorWavetable(oscillator_index, wavetable_name) => {
+                    match usize::try_from(oscillator_index) {
+                        Ok(index) if index < module_parameters.oscillators.len() => {
+                            let wavetable_directory = patches
+                                .lock()
+                                .unwrap_or_else(PoisonError::into_inner)
+                                .user_wavetable_directory();
+                            set_oscillator_wavetable(
+                                &module_parameters.oscillators[index],
+                                &wavetable_name,
+                                &wavetable_directory,
+                            );
+                        }
+                        _ => {
+                            log::warn!(
+                                target: "synthesizer::events",
+                                "start_update_event_listener(): Invalid oscillator index: {oscillator_index}"
+                            );
+                        }
+                    }
+                }
+                SynthesizerUpdateEvents::OscillatorSample(oscillator_index, sample_name) => {
+                    match usize::try_from(oscillator_index) {
+                        Ok(index) if index < module_parameters.oscillators.len() => {
+                            let sample_directory = patches
+                                .lock()
+                                .unwrap_or_else(PoisonError::into_inner)
+                                .user_sample_directory();
+                            set_oscillator_sample(
+                                &module_parameters.oscillators[index],
+                                &sample_name,
+                                &sample_directory,
+                            );
+                        }
+                        _ => {
+                            log::warn!(
+                                target: "synthesizer::events",
+                                "start_update_event_listener(): Invalid oscillator index: {oscillator_index}"
+                            );
+                        }
+                    }
+                }
+                SynthesizerUpdateEvents::CrossModulationMode(oscillator_index, mode_index) => {
+                    match usize::try_from(oscillator_index) {
+                        Ok(index) if index < module_parameters.oscillators.len() => {
+                            set_oscillator_cross_modulation_mode(
+                                &module_parameters.oscillators[index],
+                                i32_to_u8_clamped(mode_index),
+                            );
+                        }
+                        _ => {
+                            log::warn!(
+                                target: "synthesizer::events",
+                                "start_update_event_listener(): Invalid oscillator index: {oscillator_index}"
+                            );
+                        }
+                    }
+                }
+                SynthesizerUpdateEvents::CrossModulationSource(oscillator_index, source_index) => {
+                    match usize::try_from(oscillator_index) {
+                        Ok(index) if index < module_parameters.oscillators.len() => {
+                            set_oscillator_cross_modulation_source(
+                                &module_parameters.oscillators[index],
+                                i32_to_u8_clamped(source_index),
+                            );
+                        }
+                        _ => {
+                            log::warn!(
+                                target: "synthesizer::events",
+                                "start_update_event_listener(): Invalid oscillator index: {oscillator_index}"
+                            );
+                        }
+                    }
+                }
+                SynthesizerUpdateEvents::HardSyncSource(oscillator_index, source_index) => {
+                    match usize::try_from(oscillator_index) {
+                        Ok(index) if index < module_parameters.oscillators.len() => {
+                            set_oscillator_hard_sync_source(
+                                &module_parameters.oscillators[index],
+                                i32_to_u8_clamped(source_index),
+                            );
+                        }
+                        _ => {
+                            log::warn!(
+                                target: "synthesizer::events",
+                                "start_update_event_listener(): Invalid oscillator index: {oscillator_index}"
+                            );
+                        }
+                    }
+                }
+                SynthesizerUpdateEvents::SubOctave(octave_index) => {
+                    set_sub_oscillator_octave(
+                        &module_parameters.oscillators[OscillatorIndex::Sub as usize],
+                        i32_to_u8_clamped(octave_index),
+                    );
+                }
+                SynthesizerUpdateEvents::SupersawVoiceCount(oscillator_index, voice_count) => {
+                    match usize::try_from(oscillator_index) {
+                        Ok(index) if index < module_parameters.oscillators.len() => {
+                            set_oscillator_supersaw_voice_count(
+                                &module_parameters.oscillators[index],
+                                i32_to_u8_clamped(voice_count),
+                            );
+                        }
+                        _ => {
+                            log::warn!(
+                                target: "synthesizer::events",
+                                "start_update_event_listener(): Invalid oscillator index: {oscillator_index}"
+                            );
+                        }
+                    }
+                }
+                SynthesizerUpdateEvents::SupersawStereoSpread(oscillator_index, spread) => {
+                    match usize::try_from(oscillator_index) {
+                        Ok(index) if index < module_parameters.oscillators.len() => {
+                            set_oscillator_supersaw_stereo_spread(
+                                &module_parameters.oscillators[index],
+                                spread,
+                            );
+                        }
+                        _ => {
+                            log::warn!(
+                                target: "synthesizer::events",
+                                "start_update_event_listener(): Invalid oscillator index: {oscillator_index}"
+                            );
+                        }
+                    }
+                }
+                SynthesizerUpdateEvents::SupersawRandomPhase(oscillator_index, is_enabled) => {
+                    match usize::try_from(oscillator_index) {
+                        Ok(index) if index < module_parameters.oscillators.len() => {
+                            set_oscillator_supersaw_random_phase(
+                                &module_parameters.oscillators[index],
+                                is_enabled,
+                            );
+                        }
+                        _ => {
+                            log::warn!(
+                                target: "synthesizer::events",
+                                "start_update_event_listener(): Invalid oscillator index: {oscillator_index}"
+                            );
+                        }
+                    }
+                }
+                SynthesizerUpdateEvents::AdditivePartial(
+                    oscillator_index,
+                    partial_index,
+                    amplitude,
+                ) => {
+                    match (
+                        usize::try_from(oscillator_index),
+                        usize::try_from(partial_index),
+                    ) {
+                        (Ok(index), Ok(partial))
+                            if index < module_parameters.oscillators.len()
+                                && partial < MAX_PARTIALS =>
+                        {
+                            set_oscillator_additive_partial(
+                                &module_parameters.oscillators[index],
+                                partial,
+                                amplitude,
+                            );
+                        }
+                        _ => {
+                            log::warn!(
+                                target: "synthesizer::events",
+                                "start_update_event_listener(): Invalid oscillator or partial index: {oscillator_index}, {partial_index}"
+                            );
+                        }
+                    }
+                }
+                SynthesizerUpdateEvents::CrossModulationDepth(oscillator_index, depth) => {
+                    match usize::try_from(oscillator_index) {
+                        Ok(index) if index < module_parameters.oscillators.len() => {
+                            set_oscillator_cross_modulation_depth(
+                                &module_parameters.oscillators[index],
+                                depth,
+                            );
+                        }
+                        _ => {
+                            log::warn!(
+                                target: "synthesizer::events",
+                                "start_update_event_listener(): Invalid oscillator index: {oscillator_index}"
+                            );
+                        }
+                    }
+                }
                 SynthesizerUpdateEvents::Parameter1(oscillator_index, parameter) => {
                     match usize::try_from(oscillator_index) {
                         Ok(idx) if idx < module_parameters.oscillators.len() => {
@@ -253,6 +457,12 @@ pub fn start_update_event_listener(
                         display value to the UI: {e}");
                     }
                 }
+                SynthesizerUpdateEvents::FilterType(filter_type_index) => {
+                    set_filter_type(
+                        &module_parameters.filter,
+                        i32_to_u8_clamped(filter_type_index),
+                    );
+                }
                 SynthesizerUpdateEvents::FilterKeyTrackingAmount(amount) => {
                     set_key_tracking_amount(&module_parameters.filter, amount);
 
@@ -261,6 +471,51 @@ pub fn start_update_event_listener(
                         display value to the UI: {e}");
                     }
                 }
+                SynthesizerUpdateEvents::FilterDrive(drive) => {
+                    set_filter_drive(&module_parameters.filter, drive);
+                }
+                SynthesizerUpdateEvents::SecondFilterCutoffFrequency(frequency) => {
+                    set_filter_cutoff(&module_parameters.second_filter, frequency);
+                }
+                SynthesizerUpdateEvents::SecondFilterResonance(resonance) => {
+                    set_filter_resonance(&module_parameters.second_filter, resonance);
+                }
+                SynthesizerUpdateEvents::SecondFilterPoleCount(poles) => {
+                    set_filter_poles(&module_parameters.second_filter, poles);
+
+                    if let Err(e) = ui_update_sender.send(UIUpdates::SecondFilterPoles(poles)) {
+                        log::error!(target: "synthesizer::event_listener", "Failed to send second filter poles \
+                        display value to the UI: {e}");
+                    }
+                }
+                SynthesizerUpdateEvents::SecondFilterType(filter_type_index) => {
+                    set_filter_type(
+                        &module_parameters.second_filter,
+                        i32_to_u8_clamped(filter_type_index),
+                    );
+                }
+                SynthesizerUpdateEvents::SecondFilterKeyTrackingAmount(amount) => {
+                    set_key_tracking_amount(&module_parameters.second_filter, amount);
+
+                    if let Err(e) =
+                        ui_update_sender.send(UIUpdates::SecondFilterKeyTracking(amount))
+                    {
+                        log::error!(target: "synthesizer::event_listener", "Failed to send second filter key-tracking \
+                        display value to the UI: {e}");
+                    }
+                }
+                SynthesizerUpdateEvents::SecondFilterDrive(drive) => {
+                    set_filter_drive(&module_parameters.second_filter, drive);
+                }
+                SynthesizerUpdateEvents::FilterRouting(routing_index) => {
+                    set_filter_routing(
+                        &module_parameters.filter_routing,
+                        i32_to_u8_clamped(routing_index),
+                    );
+                }
+                SynthesizerUpdateEvents::FilterCrossfade(crossfade) => {
+                    set_filter_crossfade(&module_parameters.filter_routing, crossfade);
+                }
                 SynthesizerUpdateEvents::FilterEnvelopeAmount(amount) => {
                     set_envelope_amount(
                         &module_parameters.envelopes[EnvelopeIndex::Filter as usize],
@@ -273,7 +528,10 @@ pub fn start_update_event_listener(
                     }
                 }
                 SynthesizerUpdateEvents::FilterLfoAmount(amount) => {
-                    set_lfo_range(&module_parameters.lfos[LFOIndex::Filter as usize], amount);
+                    set_lfo_range(
+                        &module_parameters.lfos[module_parameters.lfo_routing.filter_lfo()],
+                        amount,
+                    );
 
                     if let Err(e) = ui_update_sender.send(UIUpdates::FilterLFOAmount(amount)) {
                         log::error!(target: "synthesizer::event_listener", "Failed to send filter LFO amount \
@@ -405,14 +663,111 @@ pub fn start_update_event_listener(
                         }
                     }
                 }
+                SynthesizerUpdateEvents::EnvelopeAttackCurve(envelope_index, normal_value) => {
+                    let Some(envelope_parameters) =
+                        envelope_from_index(&module_parameters.envelopes, envelope_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::EnvelopeAttackCurve: Invalid Envelope index: {envelope_index}"
+                        );
+                        continue;
+                    };
+                    set_envelope_attack_curve(envelope_parameters, normal_value);
+                }
+                SynthesizerUpdateEvents::EnvelopeDecayCurve(envelope_index, normal_value) => {
+                    let Some(envelope_parameters) =
+                        envelope_from_index(&module_parameters.envelopes, envelope_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::EnvelopeDecayCurve: Invalid Envelope index: {envelope_index}"
+                        );
+                        continue;
+                    };
+                    set_envelope_decay_curve(envelope_parameters, normal_value);
+                }
+                SynthesizerUpdateEvents::EnvelopeReleaseCurve(envelope_index, normal_value) => {
+                    let Some(envelope_parameters) =
+                        envelope_from_index(&module_parameters.envelopes, envelope_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::EnvelopeReleaseCurve: Invalid Envelope index: {envelope_index}"
+                        );
+                        continue;
+                    };
+                    set_envelope_release_curve(envelope_parameters, normal_value);
+                }
+                SynthesizerUpdateEvents::EnvelopeDelay(envelope_index, normal_value) => {
+                    let Some(envelope_parameters) =
+                        envelope_from_index(&module_parameters.envelopes, envelope_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::EnvelopeDelay: Invalid Envelope index: {envelope_index}"
+                        );
+                        continue;
+                    };
+                    set_envelope_delay_time(envelope_parameters, normal_value);
+                }
+                SynthesizerUpdateEvents::EnvelopeHold(envelope_index, normal_value) => {
+                    let Some(envelope_parameters) =
+                        envelope_from_index(&module_parameters.envelopes, envelope_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::EnvelopeHold: Invalid Envelope index: {envelope_index}"
+                        );
+                        continue;
+                    };
+                    set_envelope_hold_time(envelope_parameters, normal_value);
+                }
+                SynthesizerUpdateEvents::EnvelopeLoopEnabled(envelope_index, is_enabled) => {
+                    let Some(envelope_parameters) =
+                        envelope_from_index(&module_parameters.envelopes, envelope_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::EnvelopeLoopEnabled: Invalid Envelope index: {envelope_index}"
+                        );
+                        continue;
+                    };
+                    set_envelope_loop_enabled(envelope_parameters, is_enabled);
+                }
+                SynthesizerUpdateEvents::EnvelopeLoopStart(envelope_index, segment_index) => {
+                    let Some(envelope_parameters) =
+                        envelope_from_index(&module_parameters.envelopes, envelope_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::EnvelopeLoopStart: Invalid Envelope index: {envelope_index}"
+                        );
+                        continue;
+                    };
+                    set_envelope_loop_start(envelope_parameters, i32_to_u8_clamped(segment_index));
+                }
+                SynthesizerUpdateEvents::EnvelopeLoopEnd(envelope_index, segment_index) => {
+                    let Some(envelope_parameters) =
+                        envelope_from_index(&module_parameters.envelopes, envelope_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::EnvelopeLoopEnd: Invalid Envelope index: {envelope_index}"
+                        );
+                        continue;
+                    };
+                    set_envelope_loop_end(envelope_parameters, i32_to_u8_clamped(segment_index));
+                }
                 SynthesizerUpdateEvents::LfoFrequency(lfo_index, normal_value) => {
-                    if LFOIndex::from_i32(lfo_index).is_none() {
+                    let Some(lfo_parameters) = lfo_from_index(&module_parameters.lfos, lfo_index)
+                    else {
                         log::warn!(
                             target: "synthesizer::events",
                             "start_ui_event_listener():SynthesizerUpdateEvents::LfoFrequency: Invalid LFO index: {lfo_index}"
                         );
                         continue;
-                    }
+                    };
 
                     let lfo_sync_interval_index = LfoSyncInterval::from_normal_value(normal_value);
                     let lfo_sync_interval =
@@ -426,14 +781,11 @@ pub fn start_update_event_listener(
                             display value to the UI: {e}");
                     }
 
-                    module_parameters.lfos[lfo_index.unsigned_abs() as usize]
+                    lfo_parameters
                         .thirty_second_notes
                         .store(lfo_sync_interval_index.to_thirty_second_notes(), Relaxed);
 
-                    let display_frequency = set_lfo_frequency(
-                        &module_parameters.lfos[lfo_index.unsigned_abs() as usize],
-                        normal_value,
-                    );
+                    let display_frequency = set_lfo_frequency(lfo_parameters, normal_value);
 
                     if let Err(e) = ui_update_sender
                         .send(UIUpdates::LFOFrequencyDisplay(lfo_index, display_frequency))
@@ -442,67 +794,41 @@ pub fn start_update_event_listener(
                     }
                 }
                 SynthesizerUpdateEvents::LfoShapeIndex(lfo_index, wave_shape_index) => {
-                    match lfo_index {
-                        LFO_INDEX_MOD_WHEEL => {
-                            module_parameters.lfos[LFOIndex::ModWheel as usize]
-                                .wave_shape
-                                .store(i32_to_u8_clamped(wave_shape_index), Relaxed);
-                        }
-                        LFO_INDEX_FILTER => {
-                            module_parameters.lfos[LFOIndex::Filter as usize]
-                                .wave_shape
-                                .store(i32_to_u8_clamped(wave_shape_index), Relaxed);
-                        }
-                        _ => {
-                            log::warn!(
-                                target: "synthesizer::events",
-                                "start_ui_event_listener():SynthesizerUpdateEvents::LfoShapeIndex: Invalid\
-                                 LFO index: {lfo_index}"
-                            );
-                        }
+                    if let Some(lfo_parameters) = lfo_from_index(&module_parameters.lfos, lfo_index)
+                    {
+                        lfo_parameters
+                            .wave_shape
+                            .store(i32_to_u8_clamped(wave_shape_index), Relaxed);
+                    } else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::LfoShapeIndex: Invalid LFO index: {lfo_index}"
+                        );
                     }
                 }
                 SynthesizerUpdateEvents::LfoPhase(lfo_index, phase) => {
-                    match lfo_index {
-                        LFO_INDEX_MOD_WHEEL => {
-                            set_lfo_phase(
-                                &module_parameters.lfos[LFOIndex::ModWheel as usize],
-                                phase,
-                            );
-                        }
-                        LFO_INDEX_FILTER => {
-                            set_lfo_phase(
-                                &module_parameters.lfos[LFOIndex::Filter as usize],
-                                phase,
-                            );
-                        }
-                        _ => {
-                            log::warn!(
-                                target: "synthesizer::events",
-                                "start_ui_event_listener():SynthesizerUpdateEvents::LfoPhase: Invalid LFO index: {lfo_index}"
-                            );
-                        }
+                    if let Some(lfo_parameters) = lfo_from_index(&module_parameters.lfos, lfo_index)
+                    {
+                        set_lfo_phase(lfo_parameters, phase);
+                    } else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::LfoPhase: Invalid LFO index: {lfo_index}"
+                        );
                     }
                     if let Err(e) = ui_update_sender.send(UIUpdates::LFOPhase(lfo_index, phase)) {
                         log::error!(target: "synthesizer::event_listener", "Failed to send LFO phase to the UI: {e}");
                     }
                 }
                 SynthesizerUpdateEvents::LfoPhaseReset(lfo_index) => {
-                    match lfo_index {
-                        LFO_INDEX_MOD_WHEEL => {
-                            set_lfo_phase_reset(
-                                &module_parameters.lfos[LFOIndex::ModWheel as usize],
-                            );
-                        }
-                        LFO_INDEX_FILTER => {
-                            set_lfo_phase_reset(&module_parameters.lfos[LFOIndex::Filter as usize]);
-                        }
-                        _ => {
-                            log::warn!(
-                                target: "synthesizer::events",
-                                "start_ui_event_listener():SynthesizerUpdateEvents::LfoPhaseReset: Invalid LFO index: {lfo_index}"
-                            );
-                        }
+                    if let Some(lfo_parameters) = lfo_from_index(&module_parameters.lfos, lfo_index)
+                    {
+                        set_lfo_phase_reset(lfo_parameters);
+                    } else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::LfoPhaseReset: Invalid LFO index: {lfo_index}"
+                        );
                     }
                     if let Err(e) =
                         ui_update_sender.send(UIUpdates::LFOPhase(lfo_index, Defaults::LFO_PHASE))
@@ -511,49 +837,25 @@ pub fn start_update_event_listener(
                     }
                 }
                 SynthesizerUpdateEvents::LfoClockSyncEnabled(lfo_index, is_enabled) => {
-                    match lfo_index {
-                        LFO_INDEX_MOD_WHEEL => {
-                            set_lfo_clock_sync(
-                                &module_parameters.lfos[LFOIndex::ModWheel as usize],
-                                is_enabled,
-                            );
-                        }
-                        LFO_INDEX_FILTER => {
-                            set_lfo_clock_sync(
-                                &module_parameters.lfos[LFOIndex::Filter as usize],
-                                is_enabled,
-                            );
-                        }
-                        _ => {
-                            log::warn!(
-                                target: "synthesizer::events",
-                                "start_ui_event_listener():SynthesizerUpdateEvents::LfoClockSyncEnabled: Invalid LFO index: \
-                                {lfo_index}"
-                            );
-                        }
+                    if let Some(lfo_parameters) = lfo_from_index(&module_parameters.lfos, lfo_index)
+                    {
+                        set_lfo_clock_sync(lfo_parameters, is_enabled);
+                    } else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::LfoClockSyncEnabled: Invalid LFO index: {lfo_index}"
+                        );
                     }
                 }
                 SynthesizerUpdateEvents::LfoKeySyncEnabled(lfo_index, is_enabled) => {
-                    match lfo_index {
-                        LFO_INDEX_MOD_WHEEL => {
-                            set_lfo_key_sync(
-                                &module_parameters.lfos[LFOIndex::ModWheel as usize],
-                                is_enabled,
-                            );
-                        }
-                        LFO_INDEX_FILTER => {
-                            set_lfo_key_sync(
-                                &module_parameters.lfos[LFOIndex::Filter as usize],
-                                is_enabled,
-                            );
-                        }
-                        _ => {
-                            log::warn!(
-                                target: "synthesizer::events",
-                                "start_ui_event_listener():SynthesizerUpdateEvents::LfoKeySyncEnabled: Invalid LFO index: \
-                                {lfo_index}"
-                            );
-                        }
+                    if let Some(lfo_parameters) = lfo_from_index(&module_parameters.lfos, lfo_index)
+                    {
+                        set_lfo_key_sync(lfo_parameters, is_enabled);
+                    } else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_ui_event_listener():SynthesizerUpdateEvents::LfoKeySyncEnabled: Invalid LFO index: {lfo_index}"
+                        );
                     }
                 }
 
@@ -563,18 +865,150 @@ pub fn start_update_event_listener(
                 SynthesizerUpdateEvents::PortamentoTime(milliseconds) => {
                     set_portamento_time(&module_parameters.oscillators, milliseconds);
                 }
+                SynthesizerUpdateEvents::DriftDepth(depth) => {
+                    set_drift_depth(&module_parameters.drift, depth);
+                }
+                SynthesizerUpdateEvents::MasterTune(cents) => {
+                    set_master_tune(&module_parameters.tuning, cents);
+                }
+                SynthesizerUpdateEvents::ReferencePitch(hertz) => {
+                    set_reference_pitch(&module_parameters.tuning, hertz);
+                }
+                SynthesizerUpdateEvents::TuningScale(scale_name) => {
+                    let tuning_directory = patches
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .user_tuning_directory();
+                    set_tuning_scale(&module_parameters.tuning, &scale_name, &tuning_directory);
+                }
+                SynthesizerUpdateEvents::TuningKeyboardMapping(keyboard_mapping_name) => {
+                    let tuning_directory = patches
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .user_tuning_directory();
+                    set_tuning_keyboard_mapping(
+                        &module_parameters.tuning,
+                        &keyboard_mapping_name,
+                        &tuning_directory,
+                    );
+                }
                 SynthesizerUpdateEvents::PitchBendRange(range) => {
                     set_pitch_bend_range(&module_parameters.keyboard, range);
                 }
+                SynthesizerUpdateEvents::MpePitchBendRange(semitones) => {
+                    set_mpe_pitch_bend_range(
+                        &module_parameters.keyboard,
+                        i32_to_u8_clamped(semitones),
+                    );
+                }
                 SynthesizerUpdateEvents::VelocityCurve(curve) => {
                     set_velocity_curve(&module_parameters.keyboard, curve);
                 }
+                SynthesizerUpdateEvents::ReleaseVelocityAmount(amount) => {
+                    set_release_velocity_amount(&module_parameters.keyboard, amount);
+                }
                 SynthesizerUpdateEvents::SustainPedal(is_enabled) => {
                     set_envelope_sustain_pedal(
                         &module_parameters.envelopes,
                         bool_to_normal_value(is_enabled),
                     );
                 }
+                SynthesizerUpdateEvents::VoiceMode(mode_index) => {
+                    set_voice_mode(&module_parameters.voices, i32_to_u8_clamped(mode_index));
+                }
+                SynthesizerUpdateEvents::VoiceCount(voice_count) => {
+                    set_voice_count(&module_parameters.voices, i32_to_u8_clamped(voice_count));
+                }
+                SynthesizerUpdateEvents::VoiceStealingPolicy(policy_index) => {
+                    set_voice_stealing_policy(
+                        &module_parameters.voices,
+                        i32_to_u8_clamped(policy_index),
+                    );
+                }
+                SynthesizerUpdateEvents::NotePriority(priority_index) => {
+                    set_note_priority(&module_parameters.voices, i32_to_u8_clamped(priority_index));
+                }
+                SynthesizerUpdateEvents::LegatoEnabled(is_enabled) => {
+                    set_legato_enabled(&module_parameters.voices, is_enabled);
+                }
+                SynthesizerUpdateEvents::ParaphonicAssignment(assignment_index) => {
+                    set_paraphonic_assignment(
+                        &module_parameters.voices,
+                        i32_to_u8_clamped(assignment_index),
+                    );
+                }
+                SynthesizerUpdateEvents::ModulationSource(slot_index, source_index) => {
+                    let Some(slot_parameters) =
+                        modulation_slot_from_index(&module_parameters.mod_matrix, slot_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_update_event_listener():SynthesizerUpdateEvents::ModulationSource: Invalid modulation slot index: {slot_index}"
+                        );
+                        continue;
+                    };
+                    set_modulation_source(slot_parameters, i32_to_u8_clamped(source_index));
+                }
+                SynthesizerUpdateEvents::ModulationDestination(slot_index, destination_index) => {
+                    let Some(slot_parameters) =
+                        modulation_slot_from_index(&module_parameters.mod_matrix, slot_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_update_event_listener():SynthesizerUpdateEvents::ModulationDestination: Invalid modulation slot index: {slot_index}"
+                        );
+                        continue;
+                    };
+                    set_modulation_destination(
+                        slot_parameters,
+                        i32_to_u8_clamped(destination_index),
+                    );
+                }
+                SynthesizerUpdateEvents::ModulationAmount(slot_index, normal_value) => {
+                    let Some(slot_parameters) =
+                        modulation_slot_from_index(&module_parameters.mod_matrix, slot_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_update_event_listener():SynthesizerUpdateEvents::ModulationAmount: Invalid modulation slot index: {slot_index}"
+                        );
+                        continue;
+                    };
+                    set_modulation_amount(slot_parameters, normal_value);
+                }
+                SynthesizerUpdateEvents::ModulationViaSource(slot_index, source_index) => {
+                    let Some(slot_parameters) =
+                        modulation_slot_from_index(&module_parameters.mod_matrix, slot_index)
+                    else {
+                        log::warn!(
+                            target: "synthesizer::events",
+                            "start_update_event_listener():SynthesizerUpdateEvents::ModulationViaSource: Invalid modulation slot index: {slot_index}"
+                        );
+                        continue;
+                    };
+                    set_modulation_via_source(slot_parameters, i32_to_u8_clamped(source_index));
+                }
+                SynthesizerUpdateEvents::LfoCount(lfo_count) => {
+                    set_lfo_count(&module_parameters.lfos, i32_to_u8_clamped(lfo_count));
+                }
+                SynthesizerUpdateEvents::VibratoLfo(lfo_index) => {
+                    set_vibrato_lfo(&module_parameters.lfo_routing, i32_to_u8_clamped(lfo_index));
+                }
+                SynthesizerUpdateEvents::FilterLfo(lfo_index) => {
+                    set_filter_lfo(&module_parameters.lfo_routing, i32_to_u8_clamped(lfo_index));
+
+                    // The filter LFO amount is the range of the LFO the filter now follows
+                    let filter_lfo_amount = module_parameters.lfos
+                        [module_parameters.lfo_routing.filter_lfo()]
+                    .range
+                    .load();
+                    if let Err(e) =
+                        ui_update_sender.send(UIUpdates::FilterLFOAmount(filter_lfo_amount))
+                    {
+                        log::error!(target: "synthesizer::event_listener", "Failed to send filter LFO amount \
+                        display value to the UI: {e}");
+                    }
+                }
                 SynthesizerUpdateEvents::HardSyncEnabled(is_enabled) => {
                     set_oscillator_hard_sync(&module_parameters.oscillators, is_enabled);
                 }
@@ -684,6 +1118,9 @@ pub fn start_update_event_listener(
                         }
                     };
 
+                    load_patch_wavetables(&patch, &thread_patches.user_wavetable_directory());
+                    load_patch_samples(&patch, &thread_patches.user_sample_directory());
+                    load_patch_tuning(&patch, &thread_patches.user_tuning_directory());
                     set_module_parameters_from_preset(&module_parameters, &patch);
                     log::info!(target: "synthesizer::event_listener", "Preset changed to index {preset_index}");
                 }
@@ -811,3 +1248,26 @@ pub fn start_update_event_listener(
         }
     });
 }
+
+fn envelope_from_index(
+    envelopes: &[EnvelopeParameters],
+    envelope_index: i32,
+) -> Option<&EnvelopeParameters> {
+    EnvelopeIndex::from_i32(envelope_index).map(|index| &envelopes[index as usize])
+}
+
+fn modulation_slot_from_index(
+    slots: &[ModulationSlotParameters],
+    slot_index: i32,
+) -> Option<&ModulationSlotParameters> {
+    usize::try_from(slot_index)
+        .ok()
+        .and_then(|index| slots.get(index))
+}
+
+fn lfo_from_index(lfos: &LfoBank, lfo_index: i32) -> Option<&LfoParameters> {
+    match usize::try_from(lfo_index) {
+        Ok(index) if index < MAX_LFO_COUNT => Some(&lfos[index]),
+        _ => None,
+    }
+}
@@ -1,27 +1,39 @@
 use crate::modules::oscillator::OscillatorParameters;
+use crate::modules::tuning::TuningParameters;
 use crate::synthesizer::midi_value_converters::{
-    midi_value_to_bool, scaled_velocity_from_normal_value,
+    midi_value_to_bool, normal_value_to_cross_modulation_routing,
+    normal_value_to_filter_routing_index, normal_value_to_filter_type_index,
+    normal_value_to_voice_count, normal_value_to_voice_mode_index,
+    normal_value_to_voice_stealing_policy_index, scaled_velocity_from_normal_value,
 };
+use crate::synthesizer::mpe::ExpressionValues;
+use crate::synthesizer::note_stack::HeldNote;
 use crate::synthesizer::set_parameters::{
-    set_envelope_amount, set_envelope_attack_time, set_envelope_decay_time, set_envelope_inverted,
-    set_envelope_release_time, set_envelope_sustain_level, set_envelope_sustain_pedal,
-    set_filter_cutoff, set_filter_poles, set_filter_resonance, set_key_tracking_amount,
-    set_lfo_center_value, set_lfo_clock_sync, set_lfo_frequency, set_lfo_key_sync, set_lfo_phase,
-    set_lfo_phase_reset, set_lfo_range, set_lfo_wave_shape, set_mod_wheel, set_oscillator_balance,
-    set_oscillator_clip_boost, set_oscillator_course_tune, set_oscillator_fine_tune,
-    set_oscillator_hard_sync, set_oscillator_key_sync, set_oscillator_level, set_oscillator_mute,
+    set_envelope_amount, set_envelope_attack_curve, set_envelope_attack_time,
+    set_envelope_decay_curve, set_envelope_decay_time, set_envelope_inverted,
+    set_envelope_release_curve, set_envelope_release_time, set_envelope_sustain_level,
+    set_envelope_sustain_pedal, set_filter_crossfade, set_filter_cutoff, set_filter_drive,
+    set_filter_poles, set_filter_resonance, set_filter_routing, set_filter_type,
+    set_key_tracking_amount, set_lfo_center_value, set_lfo_clock_sync, set_lfo_frequency,
+    set_lfo_key_sync, set_lfo_phase, set_lfo_phase_reset, set_lfo_range, set_lfo_wave_shape,
+    set_mod_wheel, set_oscillator_balance, set_oscillator_clip_boost, set_oscillator_course_tune,
+    set_oscillator_cross_modulation_depth, set_oscillator_cross_modulation_mode,
+    set_oscillator_cross_modulation_source, set_oscillator_fine_tune, set_oscillator_hard_sync,
+    set_oscillator_key_sync, set_oscillator_level, set_oscillator_mute,
     set_oscillator_pitch_envelope_amount, set_oscillator_shape_parameter1,
     set_oscillator_shape_parameter2, set_oscillator_wave_shape, set_output_balance,
     set_output_level, set_output_mute, set_pitch_bend_range, set_portamento_enabled,
-    set_portamento_time, set_velocity_curve,
+    set_portamento_time, set_release_velocity_amount, set_velocity_curve, set_voice_count,
+    set_voice_mode, set_voice_stealing_policy,
 };
+use crate::synthesizer::voices::VoiceMode;
 use crate::synthesizer::{
     CurrentNote, KeyboardParameters, MidiGateEvent, MidiNoteEvent, ModuleParameters,
-    midi_value_converters,
+    NoteAllocation, midi_value_converters,
 };
 use accsyn_core::defaults::Defaults;
 use accsyn_core::math::{normalize_midi_value, store_f32_as_atomic_u32};
-use accsyn_core::midi_events::CC;
+use accsyn_core::midi_events::{CC, TuningChange};
 use accsyn_core::synth_events::{
     EnvelopeIndex, LFOIndex, OscillatorIndex, SynthesizerUpdateEvents,
 };
@@ -54,6 +66,11 @@ pub fn action_midi_note_events(
                 oscillator.gate_flag.store(true, Release);
             }
         }
+        MidiNoteEvent::Glide => {
+            for oscillator in &module_parameters.oscillators {
+                oscillator.glide_flag.store(true, Release);
+            }
+        }
         MidiNoteEvent::NoteOff => {
             module_parameters.envelopes[EnvelopeIndex::Amp as usize]
                 .gate_flag
@@ -92,6 +109,45 @@ pub fn process_midi_channel_pressure_message(parameters: &KeyboardParameters, pr
     parameters.aftertouch_amount.store(aftertouch_amount);
 }
 
+pub fn process_midi_poly_key_pressure_message(
+    module_parameters: &Arc<ModuleParameters>,
+    current_note: &Arc<CurrentNote>,
+    note_allocation: &NoteAllocation,
+    midi_note: u8,
+    pressure_value: u8,
+) {
+    log::trace!(target: "synthesizer::midi", "Poly key pressure received: note={midi_note}, value={pressure_value}");
+    let pressure = normalize_midi_value(pressure_value);
+
+    match module_parameters.voices.voice_mode() {
+        VoiceMode::Mono | VoiceMode::Paraphonic => {
+            // With a single voice the pressure of the sounding note acts like channel pressure
+            if current_note.midi_note.load(Relaxed) == midi_note {
+                module_parameters.keyboard.aftertouch_amount.store(pressure);
+                current_note.expression.store_pressure(pressure);
+            }
+        }
+        VoiceMode::Poly => {
+            note_allocation
+                .voice_allocator
+                .update_note_pressure(midi_note, pressure);
+        }
+    }
+}
+
+pub fn process_midi_tuning_change_message(parameters: &TuningParameters, change: &TuningChange) {
+    match change {
+        TuningChange::Notes(note_pitches) => {
+            log::debug!(target: "synthesizer::midi", "Tuning change received for {} notes", note_pitches.len());
+            parameters.retune_notes(note_pitches);
+        }
+        TuningChange::ScaleOctave(offsets) => {
+            log::debug!(target: "synthesizer::midi", "Scale/octave tuning change received: {offsets:?}");
+            parameters.retune_pitch_classes(offsets);
+        }
+    }
+}
+
 pub fn process_midi_pitch_bend_message(
     oscillators: &[OscillatorParameters; 4],
     range: u8,
@@ -105,36 +161,376 @@ pub fn process_midi_pitch_bend_message(
     );
 }
 
-pub fn process_midi_note_off_message(module_parameters: &mut Arc<ModuleParameters>) {
-    log::debug!(target: "synthesizer::midi", "Note off");
+pub fn process_midi_note_off_message(
+    module_parameters: &mut Arc<ModuleParameters>,
+    current_note: &Arc<CurrentNote>,
+    note_allocation: &mut NoteAllocation,
+    midi_note: u8,
+    release_velocity: u8,
+    ui_update_sender: &Sender<UIUpdates>,
+) {
+    log::debug!(target: "synthesizer::midi", "Note off: note={midi_note}, release velocity={release_velocity}");
+    let release_velocity = normalize_midi_value(release_velocity);
+    note_allocation
+        .voice_allocator
+        .note_off(midi_note, release_velocity);
+    release_note(
+        module_parameters,
+        current_note,
+        note_allocation,
+        midi_note,
+        release_velocity,
+        ui_update_sender,
+    );
+}
+
+pub fn process_mpe_note_off_message(
+    module_parameters: &mut Arc<ModuleParameters>,
+    current_note: &Arc<CurrentNote>,
+    note_allocation: &mut NoteAllocation,
+    channel: u8,
+    midi_note: u8,
+    release_velocity: u8,
+    ui_update_sender: &Sender<UIUpdates>,
+) {
+    log::debug!(target: "synthesizer::midi", "MPE note off: channel={channel}, note={midi_note}, release velocity={release_velocity}");
+    let release_velocity = normalize_midi_value(release_velocity);
+    note_allocation.mpe_channels.note_off(channel, midi_note);
+    note_allocation
+        .voice_allocator
+        .mpe_note_off(channel, midi_note, release_velocity);
+    release_note(
+        module_parameters,
+        current_note,
+        note_allocation,
+        midi_note,
+        release_velocity,
+        ui_update_sender,
+    );
+}
+
+fn release_note(
+    module_parameters: &Arc<ModuleParameters>,
+    current_note: &Arc<CurrentNote>,
+    note_allocation: &mut NoteAllocation,
+    midi_note: u8,
+    release_velocity: f32,
+    ui_update_sender: &Sender<UIUpdates>,
+) {
+    note_allocation.paraphonic_allocator.note_off(midi_note);
+
+    let note_priority = module_parameters.voices.note_priority();
+    let sounding_note = note_allocation.note_stack.selected(note_priority);
+    if !note_allocation.note_stack.remove(midi_note) {
+        return;
+    }
+    store_f32_as_atomic_u32(&current_note.release_velocity, release_velocity);
+
+    match module_parameters.voices.voice_mode() {
+        VoiceMode::Mono => match note_allocation.note_stack.selected(note_priority) {
+            None => action_midi_note_events(MidiNoteEvent::NoteOff, module_parameters),
+            Some(held_note) if Some(held_note) != sounding_note => {
+                log::trace!(target: "synthesizer::midi", "Returning to held note {}", held_note.midi_note);
+                let is_legato = module_parameters.voices.legato_enabled.load(Relaxed);
+                play_mono_note(
+                    module_parameters,
+                    current_note,
+                    held_note,
+                    is_legato,
+                    ui_update_sender,
+                );
+                update_sounding_note_expression(current_note, note_allocation);
+            }
+            Some(_) => {}
+        },
+        VoiceMode::Paraphonic => {
+            let Some(last_held_note) = note_allocation.note_stack.held_notes().last().copied()
+            else {
+                action_midi_note_events(MidiNoteEvent::NoteOff, module_parameters);
+                return;
+            };
+
+            // The remaining notes are redistributed across the oscillators without retriggering the gates
+            update_paraphonic_notes(module_parameters, current_note, note_allocation);
+            current_note
+                .midi_note
+                .store(last_held_note.midi_note, Relaxed);
+            module_parameters
+                .filter
+                .current_note_number
+                .store(last_held_note.midi_note, Relaxed);
+            update_sounding_note_expression(current_note, note_allocation);
+        }
+        VoiceMode::Poly => {}
+    }
+}
+
+pub fn process_all_notes_off(
+    module_parameters: &mut Arc<ModuleParameters>,
+    note_allocation: &mut NoteAllocation,
+) {
+    log::debug!(target: "synthesizer::midi", "All notes off");
+    note_allocation.voice_allocator.all_notes_off();
+    note_allocation.paraphonic_allocator.clear();
+    note_allocation.note_stack.clear();
+    note_allocation.mpe_channels.all_notes_off();
     action_midi_note_events(MidiNoteEvent::NoteOff, module_parameters);
 }
 
 pub fn process_midi_note_on_message(
     module_parameters: &mut Arc<ModuleParameters>,
     current_note: &mut Arc<CurrentNote>,
+    note_allocation: &mut NoteAllocation,
     midi_note: u8,
     velocity: u8,
     ui_update_sender: &Sender<UIUpdates>,
 ) {
     log::debug!(target: "synthesizer::midi", "Note on: note={midi_note}, velocity={velocity}");
+    play_note(
+        module_parameters,
+        current_note,
+        note_allocation,
+        None,
+        midi_note,
+        velocity,
+        ui_update_sender,
+    );
+}
 
-    let scaled_velocity = scaled_velocity_from_normal_value(
-        module_parameters.keyboard.velocity_curve.load(),
-        normalize_midi_value(velocity),
+pub fn process_mpe_note_on_message(
+    module_parameters: &mut Arc<ModuleParameters>,
+    current_note: &mut Arc<CurrentNote>,
+    note_allocation: &mut NoteAllocation,
+    channel: u8,
+    midi_note: u8,
+    velocity: u8,
+    ui_update_sender: &Sender<UIUpdates>,
+) {
+    log::debug!(target: "synthesizer::midi", "MPE note on: channel={channel}, note={midi_note}, velocity={velocity}");
+    note_allocation.mpe_channels.note_on(channel, midi_note);
+    play_note(
+        module_parameters,
+        current_note,
+        note_allocation,
+        Some(channel),
+        midi_note,
+        velocity,
+        ui_update_sender,
     );
+}
+
+pub fn process_mpe_pitch_bend_message(
+    current_note: &Arc<CurrentNote>,
+    note_allocation: &mut NoteAllocation,
+    channel: u8,
+    bend_amount: u16,
+) {
+    log::trace!(target: "synthesizer::midi", "MPE pitch bend: channel={channel}, amount={bend_amount}");
+    let expression = note_allocation
+        .mpe_channels
+        .set_pitch_bend(channel, bend_amount);
+    update_channel_expression(current_note, note_allocation, channel, expression);
+}
+
+pub fn process_mpe_pressure_message(
+    current_note: &Arc<CurrentNote>,
+    note_allocation: &mut NoteAllocation,
+    channel: u8,
+    pressure_value: u8,
+) {
+    log::trace!(target: "synthesizer::midi", "MPE pressure: channel={channel}, value={pressure_value}");
+    let expression = note_allocation
+        .mpe_channels
+        .set_pressure(channel, pressure_value);
+    update_channel_expression(current_note, note_allocation, channel, expression);
+}
+
+pub fn process_mpe_timbre_message(
+    current_note: &Arc<CurrentNote>,
+    note_allocation: &mut NoteAllocation,
+    channel: u8,
+    timbre_value: u8,
+) {
+    log::trace!(target: "synthesizer::midi", "MPE timbre: channel={channel}, value={timbre_value}");
+    let expression = note_allocation
+        .mpe_channels
+        .set_timbre(channel, timbre_value);
+    update_channel_expression(current_note, note_allocation, channel, expression);
+}
+
+fn update_channel_expression(
+    current_note: &Arc<CurrentNote>,
+    note_allocation: &NoteAllocation,
+    channel: u8,
+    expression: ExpressionValues,
+) {
+    note_allocation
+        .voice_allocator
+        .update_channel_expression(channel, expression);
+
+    // Mono and paraphonic mode follow the expression of the channel holding the sounding note
+    let sounding_note = current_note.midi_note.load(Relaxed);
+    if note_allocation.mpe_channels.channel_of_note(sounding_note) == Some(channel) {
+        current_note.expression.store(expression);
+    }
+}
+
+fn update_sounding_note_expression(
+    current_note: &Arc<CurrentNote>,
+    note_allocation: &NoteAllocation,
+) {
+    let sounding_note = current_note.midi_note.load(Relaxed);
+    current_note
+        .expression
+        .store(note_allocation.mpe_channels.note_expression(sounding_note));
+}
+
+fn play_note(
+    module_parameters: &Arc<ModuleParameters>,
+    current_note: &Arc<CurrentNote>,
+    note_allocation: &mut NoteAllocation,
+    channel: Option<u8>,
+    midi_note: u8,
+    velocity: u8,
+    ui_update_sender: &Sender<UIUpdates>,
+) {
+    let note_priority = module_parameters.voices.note_priority();
+    let sounding_note = note_allocation.note_stack.selected(note_priority);
+    note_allocation.note_stack.push(midi_note, velocity);
+    note_allocation.paraphonic_allocator.note_on(midi_note);
+
+    match module_parameters.voices.voice_mode() {
+        VoiceMode::Mono => {
+            let Some(held_note) = note_allocation.note_stack.selected(note_priority) else {
+                return;
+            };
+
+            // With low or high note priority a new key may not take over the sounding note
+            if held_note.midi_note != midi_note {
+                return;
+            }
+
+            let is_legato =
+                module_parameters.voices.legato_enabled.load(Relaxed) && sounding_note.is_some();
+            play_mono_note(
+                module_parameters,
+                current_note,
+                held_note,
+                is_legato,
+                ui_update_sender,
+            );
+            update_sounding_note_expression(current_note, note_allocation);
+        }
+        VoiceMode::Paraphonic => {
+            update_paraphonic_notes(module_parameters, current_note, note_allocation);
+
+            let is_legato =
+                module_parameters.voices.legato_enabled.load(Relaxed) && sounding_note.is_some();
+            play_mono_note(
+                module_parameters,
+                current_note,
+                HeldNote {
+                    midi_note,
+                    velocity,
+                },
+                is_legato,
+                ui_update_sender,
+            );
+            update_sounding_note_expression(current_note, note_allocation);
+        }
+        VoiceMode::Poly => {
+            let scaled_velocity = scaled_velocity_from_normal_value(
+                module_parameters.keyboard.velocity_curve.load(),
+                normalize_midi_value(velocity),
+            );
+
+            store_f32_as_atomic_u32(&current_note.velocity, scaled_velocity);
+            current_note.midi_note.store(midi_note, Relaxed);
+
+            module_parameters
+                .filter
+                .current_note_number
+                .store(midi_note, Relaxed);
+
+            let voice_index = match channel {
+                Some(channel) => note_allocation.voice_allocator.mpe_note_on(
+                    channel,
+                    midi_note,
+                    scaled_velocity,
+                    note_allocation.mpe_channels.expression(channel),
+                    &module_parameters.voices,
+                ),
+                None => note_allocation.voice_allocator.note_on(
+                    midi_note,
+                    scaled_velocity,
+                    &module_parameters.voices,
+                ),
+            };
+            log::trace!(target: "synthesizer::midi", "Note {midi_note} assigned to voice {voice_index}");
+
+            for lfo in &module_parameters.lfos {
+                lfo.gate_flag.store(true, Relaxed);
+            }
+
+            let note_name = Defaults::midi_note_name(midi_note);
+            send_ui_update(ui_update_sender, UIUpdates::MidiScreen(note_name));
+        }
+    }
+}
+
+fn update_paraphonic_notes(
+    module_parameters: &Arc<ModuleParameters>,
+    current_note: &Arc<CurrentNote>,
+    note_allocation: &NoteAllocation,
+) {
+    let Some(oscillator_notes) = note_allocation.paraphonic_allocator.oscillator_notes(
+        &note_allocation.note_stack,
+        module_parameters.voices.paraphonic_assignment(),
+    ) else {
+        return;
+    };
+
+    log::trace!(target: "synthesizer::midi", "Paraphonic oscillator notes: {oscillator_notes:?}");
+    for (oscillator_note, midi_note) in current_note.oscillator_notes.iter().zip(oscillator_notes) {
+        oscillator_note.store(midi_note, Relaxed);
+    }
+}
+
+fn play_mono_note(
+    module_parameters: &Arc<ModuleParameters>,
+    current_note: &Arc<CurrentNote>,
+    held_note: HeldNote,
+    is_legato: bool,
+    ui_update_sender: &Sender<UIUpdates>,
+) {
+    // A legato note change keeps the velocity of the note that opened the gates
+    if !is_legato {
+        let scaled_velocity = scaled_velocity_from_normal_value(
+            module_parameters.keyboard.velocity_curve.load(),
+            normalize_midi_value(held_note.velocity),
+        );
+        store_f32_as_atomic_u32(&current_note.velocity, scaled_velocity);
+    }
 
-    store_f32_as_atomic_u32(&current_note.velocity, scaled_velocity);
-    current_note.midi_note.store(midi_note, Relaxed);
+    current_note.midi_note.store(held_note.midi_note, Relaxed);
+    // The sounding note is held, so the release velocity of the last released note doesn't apply to it
+    store_f32_as_atomic_u32(
+        &current_note.release_velocity,
+        normalize_midi_value(Defaults::RELEASE_VELOCITY),
+    );
 
     module_parameters
         .filter
         .current_note_number
-        .store(midi_note, Relaxed);
+        .store(held_note.midi_note, Relaxed);
 
-    action_midi_note_events(MidiNoteEvent::NoteOn, module_parameters);
+    if is_legato {
+        action_midi_note_events(MidiNoteEvent::Glide, module_parameters);
+    } else {
+        action_midi_note_events(MidiNoteEvent::NoteOn, module_parameters);
+    }
 
-    let note_name = Defaults::midi_note_name(midi_note);
+    let note_name = Defaults::midi_note_name(held_note.midi_note);
     send_ui_update(ui_update_sender, UIUpdates::MidiScreen(note_name));
 }
 
@@ -143,6 +539,7 @@ pub fn process_midi_note_on_message(
 pub fn process_midi_cc_values(
     cc_value: CC,
     module_parameters: &mut Arc<ModuleParameters>,
+    note_allocation: &mut NoteAllocation,
     ui_update_sender: &Sender<UIUpdates>,
 ) {
     log::trace!(target: "synthesizer::midi", "CC received: {cc_value:?}");
@@ -361,6 +758,15 @@ pub fn process_midi_cc_values(
                 normal_value,
             );
         }
+        CC::PitchEnvelopeCurve(value) => {
+            let normal_value = normalize_midi_value(value);
+            update_envelope_curve(
+                &module_parameters,
+                ui_update_sender,
+                EnvelopeIndex::Pitch,
+                normal_value,
+            );
+        }
         CC::PortamentoTime(value) => {
             let normal_value = normalize_midi_value(value);
             set_portamento_time(&module_parameters.oscillators, normal_value);
@@ -663,6 +1069,19 @@ pub fn process_midi_cc_values(
             set_filter_resonance(&module_parameters.filter, normal_value);
             send_ui_update(ui_update_sender, UIUpdates::FilterResonance(normal_value));
         }
+        CC::FilterType(value) => {
+            let filter_type_index = normal_value_to_filter_type_index(normalize_midi_value(value));
+            set_filter_type(&module_parameters.filter, filter_type_index);
+            send_ui_update(
+                ui_update_sender,
+                UIUpdates::FilterType(i32::from(filter_type_index)),
+            );
+        }
+        CC::FilterDrive(value) => {
+            let normal_value = normalize_midi_value(value);
+            set_filter_drive(&module_parameters.filter, normal_value);
+            send_ui_update(ui_update_sender, UIUpdates::FilterDrive(normal_value));
+        }
         CC::AmpEGReleaseTime(value) => {
             let normal_value = normalize_midi_value(value);
             set_envelope_release_time(
@@ -722,6 +1141,15 @@ pub fn process_midi_cc_values(
                 normal_value,
             );
         }
+        CC::AmpEGCurve(value) => {
+            let normal_value = normalize_midi_value(value);
+            update_envelope_curve(
+                &module_parameters,
+                ui_update_sender,
+                EnvelopeIndex::Amp,
+                normal_value,
+            );
+        }
         CC::FilterEnvelopeAttackTime(value) => {
             let normal_value = normalize_midi_value(value);
             set_envelope_attack_time(
@@ -775,6 +1203,15 @@ pub fn process_midi_cc_values(
                 normal_value,
             );
         }
+        CC::FilterEnvelopeCurve(value) => {
+            let normal_value = normalize_midi_value(value);
+            update_envelope_curve(
+                &module_parameters,
+                ui_update_sender,
+                EnvelopeIndex::Filter,
+                normal_value,
+            );
+        }
         CC::FilterEnvelopeAmount(value) => {
             let normal_value = normalize_midi_value(value);
             set_envelope_amount(
@@ -791,6 +1228,125 @@ pub fn process_midi_cc_values(
             set_key_tracking_amount(&module_parameters.filter, normal_value);
             send_ui_update(ui_update_sender, UIUpdates::FilterKeyTracking(normal_value));
         }
+        CC::SubOscillatorCrossModulation(value) => {
+            update_oscillator_cross_modulation(
+                &module_parameters,
+                ui_update_sender,
+                normalize_midi_value(value),
+                OscillatorIndex::Sub,
+            );
+        }
+        CC::Oscillator1CrossModulation(value) => {
+            update_oscillator_cross_modulation(
+                &module_parameters,
+                ui_update_sender,
+                normalize_midi_value(value),
+                OscillatorIndex::One,
+            );
+        }
+        CC::Oscillator2CrossModulation(value) => {
+            update_oscillator_cross_modulation(
+                &module_parameters,
+                ui_update_sender,
+                normalize_midi_value(value),
+                OscillatorIndex::Two,
+            );
+        }
+        CC::Oscillator3CrossModulation(value) => {
+            update_oscillator_cross_modulation(
+                &module_parameters,
+                ui_update_sender,
+                normalize_midi_value(value),
+                OscillatorIndex::Three,
+            );
+        }
+        CC::SubOscillatorCrossModulationDepth(value) => {
+            update_oscillator_cross_modulation_depth(
+                &module_parameters,
+                ui_update_sender,
+                normalize_midi_value(value),
+                OscillatorIndex::Sub,
+            );
+        }
+        CC::Oscillator1CrossModulationDepth(value) => {
+            update_oscillator_cross_modulation_depth(
+                &module_parameters,
+                ui_update_sender,
+                normalize_midi_value(value),
+                OscillatorIndex::One,
+            );
+        }
+        CC::Oscillator2CrossModulationDepth(value) => {
+            update_oscillator_cross_modulation_depth(
+                &module_parameters,
+                ui_update_sender,
+                normalize_midi_value(value),
+                OscillatorIndex::Two,
+            );
+        }
+        CC::Oscillator3CrossModulationDepth(value) => {
+            update_oscillator_cross_modulation_depth(
+                &module_parameters,
+                ui_update_sender,
+                normalize_midi_value(value),
+                OscillatorIndex::Three,
+            );
+        }
+        CC::SecondFilterCutoff(value) => {
+            let normal_value = normalize_midi_value(value);
+            set_filter_cutoff(&module_parameters.second_filter, normal_value);
+            send_ui_update(
+                ui_update_sender,
+                UIUpdates::SecondFilterCutoff(normal_value),
+            );
+        }
+        CC::SecondFilterResonance(value) => {
+            let normal_value = normalize_midi_value(value);
+            set_filter_resonance(&module_parameters.second_filter, normal_value);
+            send_ui_update(
+                ui_update_sender,
+                UIUpdates::SecondFilterResonance(normal_value),
+            );
+        }
+        CC::SecondFilterPoles(value) => {
+            let normal_value = normalize_midi_value(value);
+            set_filter_poles(&module_parameters.second_filter, normal_value);
+            send_ui_update(ui_update_sender, UIUpdates::SecondFilterPoles(normal_value));
+        }
+        CC::SecondFilterType(value) => {
+            let filter_type_index = normal_value_to_filter_type_index(normalize_midi_value(value));
+            set_filter_type(&module_parameters.second_filter, filter_type_index);
+            send_ui_update(
+                ui_update_sender,
+                UIUpdates::SecondFilterType(i32::from(filter_type_index)),
+            );
+        }
+        CC::SecondFilterDrive(value) => {
+            let normal_value = normalize_midi_value(value);
+            set_filter_drive(&module_parameters.second_filter, normal_value);
+            send_ui_update(ui_update_sender, UIUpdates::SecondFilterDrive(normal_value));
+        }
+        CC::SecondFilterKeyTracking(value) => {
+            let normal_value = normalize_midi_value(value);
+            set_key_tracking_amount(&module_parameters.second_filter, normal_value);
+            send_ui_update(
+                ui_update_sender,
+                UIUpdates::SecondFilterKeyTracking(normal_value),
+            );
+        }
+        CC::FilterRouting(value) => {
+            let routing_index = normal_value_to_filter_routing_index(normalize_midi_value(value));
+            set_filter_routing(&module_parameters.filter_routing, routing_index);
+            send_ui_update(
+                ui_update_sender,
+                UIUpdates::FilterRouting(i32::from(routing_index)),
+            );
+        }
+        CC::FilterCrossfade(value) => {
+            let normal_value = normalize_midi_value(value);
+            set_filter_crossfade(&module_parameters.filter_routing, normal_value);
+            send_ui_update(ui_update_sender, UIUpdates::FilterCrossfade(normal_value));
+        }
         CC::ModWheelLFOClockSync(value) => {
             let is_enabled = midi_value_to_bool(value);
 
@@ -876,7 +1432,7 @@ pub fn process_midi_cc_values(
         CC::FilterModLFOAmount(value) => {
             let normal_value = normalize_midi_value(value);
             set_lfo_range(
-                &module_parameters.lfos[LFOIndex::Filter as usize],
+                &module_parameters.lfos[module_parameters.lfo_routing.filter_lfo()],
                 normal_value,
             );
             send_ui_update(ui_update_sender, UIUpdates::FilterLFOAmount(normal_value));
@@ -952,8 +1508,41 @@ pub fn process_midi_cc_values(
                 UIUpdates::LFOKeySync(LFOIndex::ModWheel as i32, is_enabled),
             );
         }
+        CC::VoiceMode(value) => {
+            let mode_index = normal_value_to_voice_mode_index(normalize_midi_value(value));
+            set_voice_mode(&module_parameters.voices, mode_index);
+            send_ui_update(
+                ui_update_sender,
+                UIUpdates::VoiceMode(i32::from(mode_index)),
+            );
+        }
+        CC::VoiceCount(value) => {
+            let voice_count = normal_value_to_voice_count(normalize_midi_value(value));
+            set_voice_count(&module_parameters.voices, voice_count);
+            send_ui_update(
+                ui_update_sender,
+                UIUpdates::VoiceCount(i32::from(voice_count)),
+            );
+        }
+        CC::VoiceStealingPolicy(value) => {
+            let policy_index =
+                normal_value_to_voice_stealing_policy_index(normalize_midi_value(value));
+            set_voice_stealing_policy(&module_parameters.voices, policy_index);
+            send_ui_update(
+                ui_update_sender,
+                UIUpdates::VoiceStealingPolicy(i32::from(policy_index)),
+            );
+        }
+        CC::ReleaseVelocityAmount(value) => {
+            let normal_value = normalize_midi_value(value);
+            set_release_velocity_amount(&module_parameters.keyboard, normal_value);
+            send_ui_update(
+                ui_update_sender,
+                UIUpdates::ReleaseVelocityAmount(normal_value),
+            );
+        }
         CC::AllNotesOff => {
-            process_midi_note_off_message(module_parameters);
+            process_all_notes_off(module_parameters, note_allocation);
         }
     }
 }
@@ -975,6 +1564,32 @@ fn update_envelope_inverted(
     );
 }
 
+// Each envelope has one curve CC, which sets the attack, decay and release curves together
+fn update_envelope_curve(
+    module_parameters: &&mut Arc<ModuleParameters>,
+    ui_update_sender: &Sender<UIUpdates>,
+    envelope_index: EnvelopeIndex,
+    normal_value: f32,
+) {
+    let envelope_parameters = &module_parameters.envelopes[envelope_index as usize];
+    set_envelope_attack_curve(envelope_parameters, normal_value);
+    set_envelope_decay_curve(envelope_parameters, normal_value);
+    set_envelope_release_curve(envelope_parameters, normal_value);
+
+    send_ui_update(
+        ui_update_sender,
+        UIUpdates::EnvelopeAttackCurve(envelope_index as i32, normal_value),
+    );
+    send_ui_update(
+        ui_update_sender,
+        UIUpdates::EnvelopeDecayCurve(envelope_index as i32, normal_value),
+    );
+    send_ui_update(
+        ui_update_sender,
+        UIUpdates::EnvelopeReleaseCurve(envelope_index as i32, normal_value),
+    );
+}
+
 fn update_oscillator_fine_tune(
     module_parameters: &&mut Arc<ModuleParameters>,
     ui_update_sender: &Sender<UIUpdates>,
@@ -1062,6 +1677,46 @@ fn update_oscillator_parameter_1(
     );
 }
 
+// One control picks both the mode and the source, Off leaves the source where it was
+fn update_oscillator_cross_modulation(
+    module_parameters: &&mut Arc<ModuleParameters>,
+    ui_update_sender: &Sender<UIUpdates>,
+    normal_value: f32,
+    oscillator_index: OscillatorIndex,
+) {
+    let oscillator_parameters = &module_parameters.oscillators[oscillator_index as usize];
+    let (mode_index, source_index) = normal_value_to_cross_modulation_routing(normal_value);
+    set_oscillator_cross_modulation_mode(oscillator_parameters, mode_index);
+    send_ui_update(
+        ui_update_sender,
+        UIUpdates::CrossModulationMode(oscillator_index as i32, i32::from(mode_index)),
+    );
+
+    if let Some(source_index) = source_index {
+        set_oscillator_cross_modulation_source(oscillator_parameters, source_index);
+        send_ui_update(
+            ui_update_sender,
+            UIUpdates::CrossModulationSource(oscillator_index as i32, i32::from(source_index)),
+        );
+    }
+}
+
+fn update_oscillator_cross_modulation_depth(
+    module_parameters: &&mut Arc<ModuleParameters>,
+    ui_update_sender: &Sender<UIUpdates>,
+    normal_value: f32,
+    oscillator_index: OscillatorIndex,
+) {
+    set_oscillator_cross_modulation_depth(
+        &module_parameters.oscillators[oscillator_index as usize],
+        normal_value,
+    );
+    send_ui_update(
+        ui_update_sender,
+        UIUpdates::CrossModulationDepth(oscillator_index as i32, normal_value),
+    );
+}
+
 fn update_oscillator_pitch_amount(
     module_parameters: &&mut Arc<ModuleParameters>,
     ui_update_sender: &Sender<UIUpdates>,
@@ -1080,7 +1735,70 @@ fn update_oscillator_pitch_amount(
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::synthesizer::voices::VoiceStates;
     use accsyn_core::defaults::Defaults;
+    use accsyn_core::math::load_f32_from_atomic_u32;
+
+    #[test]
+    fn mono_note_on_resets_the_release_velocity_of_the_last_note() {
+        let mut module_parameters = Arc::new(ModuleParameters::default());
+        let mut current_note = Arc::new(CurrentNote::default());
+        let mut note_allocation = NoteAllocation::new(Arc::new(VoiceStates::default()));
+        let (ui_update_sender, _ui_update_receiver) = crossbeam_channel::unbounded();
+
+        process_midi_note_on_message(
+            &mut module_parameters,
+            &mut current_note,
+            &mut note_allocation,
+            60,
+            100,
+            &ui_update_sender,
+        );
+        process_midi_note_off_message(
+            &mut module_parameters,
+            &current_note,
+            &mut note_allocation,
+            60,
+            10,
+            &ui_update_sender,
+        );
+        process_midi_note_on_message(
+            &mut module_parameters,
+            &mut current_note,
+            &mut note_allocation,
+            62,
+            100,
+            &ui_update_sender,
+        );
+
+        assert_eq!(
+            load_f32_from_atomic_u32(&current_note.release_velocity),
+            normalize_midi_value(Defaults::RELEASE_VELOCITY)
+        );
+    }
+
+    #[test]
+    fn release_velocity_amount_cc_sets_the_amount_and_updates_the_ui() {
+        let mut module_parameters = Arc::new(ModuleParameters::default());
+        let mut note_allocation = NoteAllocation::new(Arc::new(VoiceStates::default()));
+        let (ui_update_sender, ui_update_receiver) = crossbeam_channel::unbounded();
+
+        process_midi_cc_values(
+            CC::ReleaseVelocityAmount(127),
+            &mut module_parameters,
+            &mut note_allocation,
+            &ui_update_sender,
+        );
+
+        assert!(
+            (module_parameters.keyboard.release_velocity_amount.load() - 1.0).abs() < f32::EPSILON
+        );
+        assert!(matches!(
+            ui_update_receiver.try_recv(),
+            Ok(UIUpdates::ReleaseVelocityAmount(amount)) if (amount - 1.0).abs() < f32::EPSILON
+        ));
+    }
 
     #[test]
     fn midi_note_frequencies_covers_all_valid_notes() {
@@ -1,18 +1,26 @@
-use crate::modules::filter::NUMBER_OF_FILER_POLES;
+use crate::modules::filter::{
+    LAST_FILTER_ROUTING_INDEX, LAST_FILTER_TYPE_INDEX, NUMBER_OF_FILER_POLES,
+};
 use crate::modules::oscillator::{
-    FIRST_WAVE_SHAPE_INDEX, LAST_WAVE_SHAPE_INDEX, OscillatorParameters,
+    FIRST_WAVE_SHAPE_INDEX, LAST_CROSS_MODULATION_ROUTING_INDEX, LAST_LFO_WAVE_SHAPE_INDEX,
+    LAST_WAVE_SHAPE_INDEX, OscillatorParameters,
 };
 use crate::synthesizer::constants::{
-    CENTS_PER_SEMITONE, MAX_MIDI_KEY_VELOCITY, MAX_VELOCITY_CURVE_EXPONENT,
-    MIDI_VALUE_TO_BOOL_SWITCH_ON_VALUE, MIN_VELOCITY_CURVE_EXPONENT,
+    CENTS_PER_SEMITONE, MAX_MIDI_KEY_VELOCITY, MAX_RELEASE_VELOCITY_TIME_SCALE_OCTAVES,
+    MAX_VELOCITY_CURVE_EXPONENT, MIDI_VALUE_TO_BOOL_SWITCH_ON_VALUE, MIN_VELOCITY_CURVE_EXPONENT,
     NORMAL_TO_BOOL_SWITCH_ON_VALUE, PITCH_BEND_AMOUNT_MAX_VALUE, PITCH_BEND_AMOUNT_ZERO_POINT,
 };
+use crate::synthesizer::voices::{
+    LAST_VOICE_MODE_INDEX, LAST_VOICE_STEALING_POLICY_INDEX, MAX_VOICE_COUNT, MIN_VOICE_COUNT,
+};
 use accsyn_core::defaults::Defaults;
 use accsyn_core::math;
 use accsyn_core::math::{
     EXPONENTIAL_FILTER_COEFFICIENT, EXPONENTIAL_LFO_COEFFICIENT, f32s_are_equal,
     normal_value_from_exponential_curve_and_coefficient, normalize_float_range,
 };
+use accsyn_core::synth_events::OscillatorIndex;
+use strum::EnumCount;
 
 /// Maps a normalized 0.0-1.0 value to a target f32 range.
 #[must_use]
@@ -109,6 +117,95 @@ pub fn normal_value_to_wave_shape_index(normal_value: f32) -> u8 {
     .clamp(FIRST_WAVE_SHAPE_INDEX, LAST_WAVE_SHAPE_INDEX) as u8
 }
 
+/// Converts a normalized value to a wave shape index for an LFO or modulation effect.
+#[must_use]
+pub fn normal_value_to_lfo_wave_shape_index(normal_value: f32) -> u8 {
+    normal_value_to_unsigned_integer_range(
+        normal_value,
+        FIRST_WAVE_SHAPE_INDEX,
+        LAST_LFO_WAVE_SHAPE_INDEX,
+    )
+    .clamp(FIRST_WAVE_SHAPE_INDEX, LAST_LFO_WAVE_SHAPE_INDEX) as u8
+}
+
+/// Converts a normalized value to a filter type index.
+#[must_use]
+pub fn normal_value_to_filter_type_index(normal_value: f32) -> u8 {
+    // Clamped to the FilterType variant range, safely within u8 range
+    #[allow(clippy::cast_possible_truncation)]
+    let filter_type_index =
+        normal_value_to_unsigned_integer_range(normal_value, 0, LAST_FILTER_TYPE_INDEX) as u8;
+    filter_type_index
+}
+
+/// Converts a normalized value to a filter routing index.
+#[must_use]
+pub fn normal_value_to_filter_routing_index(normal_value: f32) -> u8 {
+    // Clamped to the FilterRouting variant range, safely within u8 range
+    #[allow(clippy::cast_possible_truncation)]
+    let routing_index =
+        normal_value_to_unsigned_integer_range(normal_value, 0, LAST_FILTER_ROUTING_INDEX) as u8;
+    routing_index
+}
+
+/// Converts a normalized value to a cross modulation mode index and, for any mode but Off, a source
+/// oscillator index. The range steps through Off, then each mode with every source oscillator in turn.
+#[must_use]
+pub fn normal_value_to_cross_modulation_routing(normal_value: f32) -> (u8, Option<u8>) {
+    // Clamped to the cross modulation routing range, safely within u8 range
+    #[allow(clippy::cast_possible_truncation)]
+    let routing_index = normal_value_to_unsigned_integer_range(
+        normal_value,
+        0,
+        LAST_CROSS_MODULATION_ROUTING_INDEX,
+    ) as u8;
+
+    if routing_index == 0 {
+        return (0, None);
+    }
+
+    // OscillatorIndex::COUNT is 4, safely within u8 range
+    #[allow(clippy::cast_possible_truncation)]
+    let source_count = OscillatorIndex::COUNT as u8;
+    let mode_index = 1 + (routing_index - 1) / source_count;
+    let source_index = (routing_index - 1) % source_count;
+    (mode_index, Some(source_index))
+}
+
+/// Converts a normalized value to a voice mode index.
+#[must_use]
+pub fn normal_value_to_voice_mode_index(normal_value: f32) -> u8 {
+    // Clamped to the VoiceMode variant range, safely within u8 range
+    #[allow(clippy::cast_possible_truncation)]
+    let voice_mode_index =
+        normal_value_to_unsigned_integer_range(normal_value, 0, LAST_VOICE_MODE_INDEX) as u8;
+    voice_mode_index
+}
+
+/// Converts a normalized value to a poly mode voice count.
+#[must_use]
+pub fn normal_value_to_voice_count(normal_value: f32) -> u8 {
+    // Clamped to MIN_VOICE_COUNT..=MAX_VOICE_COUNT (1–16), safely within u8 range
+    #[allow(clippy::cast_possible_truncation)]
+    let voice_count = normal_value_to_unsigned_integer_range(
+        normal_value,
+        u32::from(MIN_VOICE_COUNT),
+        MAX_VOICE_COUNT as u32,
+    ) as u8;
+    voice_count
+}
+
+/// Converts a normalized value to a voice stealing policy index.
+#[must_use]
+pub fn normal_value_to_voice_stealing_policy_index(normal_value: f32) -> u8 {
+    // Clamped to the VoiceStealingPolicy variant range, safely within u8 range
+    #[allow(clippy::cast_possible_truncation)]
+    let policy_index =
+        normal_value_to_unsigned_integer_range(normal_value, 0, LAST_VOICE_STEALING_POLICY_INDEX)
+            as u8;
+    policy_index
+}
+
 pub(crate) fn exponential_curve_filter_cutoff_from_normal_value(normal_value: f32) -> f32 {
     if normal_value == 0.0 {
         return 0.0;
@@ -211,6 +308,18 @@ pub(crate) fn scaled_velocity_from_normal_value(velocity_curve: f32, velocity: f
     velocity.powf(velocity_curve)
 }
 
+/// Returns the factor applied to envelope release times for a normalized release velocity.
+///
+/// The default release velocity leaves release times unchanged; faster releases shorten them and slower
+/// releases lengthen them, by up to a factor of four at full amount.
+pub(crate) fn release_time_scale_from_release_velocity(release_velocity: f32, amount: f32) -> f32 {
+    let neutral_release_velocity = math::normalize_midi_value(Defaults::RELEASE_VELOCITY);
+    let offset =
+        ((release_velocity - neutral_release_velocity) / neutral_release_velocity).clamp(-1.0, 1.0);
+
+    (-offset * amount.clamp(0.0, 1.0) * MAX_RELEASE_VELOCITY_TIME_SCALE_OCTAVES).exp2()
+}
+
 /// Applies a MIDI pitch bend value to all oscillators based on the configured semitone range.
 pub fn update_current_note_from_midi_pitch_bend(
     pitch_bend_amount: u16,
@@ -243,6 +352,7 @@ fn midi_value_to_pitch_bend_cents(pitch_bend_amount: u16, max_bend_in_cents: u16
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::modules::oscillator::WaveShape;
     use accsyn_core::math::{f32s_are_equal, normalize_midi_value};
 
     #[test]
@@ -412,6 +522,70 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    // Tests for normal_value_to_lfo_wave_shape_index
+    #[test]
+    fn test_normal_value_to_lfo_wave_shape_index_stops_at_noise() {
+        assert_eq!(normal_value_to_lfo_wave_shape_index(0.0), 0);
+        assert_eq!(
+            normal_value_to_lfo_wave_shape_index(1.0),
+            WaveShape::Noise as u8
+        );
+        assert_eq!(
+            u32::from(normal_value_to_wave_shape_index(1.0)),
+            LAST_WAVE_SHAPE_INDEX
+        );
+    }
+
+    // Tests for normal_value_to_filter_type_index
+    #[test]
+    fn test_normal_value_to_filter_type_index_boundaries() {
+        let actual_min = normal_value_to_filter_type_index(0.0);
+        let expected_min = 0;
+
+        // At 1.0, should return the last filter type (Peak)
+        let actual_max = normal_value_to_filter_type_index(1.0);
+        let expected_max = 4;
+
+        assert_eq!(actual_min, expected_min);
+        assert_eq!(actual_max, expected_max);
+    }
+
+    #[test]
+    fn test_normal_value_to_filter_routing_index_boundaries() {
+        assert_eq!(normal_value_to_filter_routing_index(0.0), 0);
+        assert_eq!(normal_value_to_filter_routing_index(1.0), 3);
+    }
+
+    #[test]
+    fn test_normal_value_to_cross_modulation_routing_steps_through_every_source() {
+        assert_eq!(normal_value_to_cross_modulation_routing(0.0), (0, None));
+        assert_eq!(
+            normal_value_to_cross_modulation_routing(1.0 / 12.0),
+            (1, Some(0))
+        );
+        assert_eq!(
+            normal_value_to_cross_modulation_routing(4.0 / 12.0),
+            (1, Some(3))
+        );
+        assert_eq!(
+            normal_value_to_cross_modulation_routing(5.0 / 12.0),
+            (2, Some(0))
+        );
+        assert_eq!(normal_value_to_cross_modulation_routing(1.0), (3, Some(3)));
+    }
+
+    #[test]
+    fn test_normal_value_to_voice_count_boundaries() {
+        assert_eq!(normal_value_to_voice_count(0.0), 1);
+        assert_eq!(normal_value_to_voice_count(1.0), 16);
+    }
+
+    #[test]
+    fn test_normal_value_to_voice_mode_index_boundaries() {
+        assert_eq!(normal_value_to_voice_mode_index(0.0), 0);
+        assert_eq!(normal_value_to_voice_mode_index(1.0), 2);
+    }
+
     // Tests for velocity_curve_from_normal_value
     #[test]
     fn test_velocity_curve_zero() {
@@ -494,6 +668,39 @@ mod tests {
         assert!(f32s_are_equal(actual, expected));
     }
 
+    // Tests for release_time_scale_from_release_velocity
+    #[test]
+    fn test_release_time_scale_default_release_velocity() {
+        let release_velocity = normalize_midi_value(Defaults::RELEASE_VELOCITY);
+
+        let actual = release_time_scale_from_release_velocity(release_velocity, 1.0);
+
+        assert!(f32s_are_equal(actual, 1.0));
+    }
+
+    #[test]
+    fn test_release_time_scale_zero_amount() {
+        let actual = release_time_scale_from_release_velocity(1.0, 0.0);
+
+        assert!(f32s_are_equal(actual, 1.0));
+    }
+
+    #[test]
+    fn test_release_time_scale_slowest_release() {
+        let actual = release_time_scale_from_release_velocity(0.0, 1.0);
+        let expected = 4.0; // Two octaves longer
+
+        assert!(f32s_are_equal(actual, expected));
+    }
+
+    #[test]
+    fn test_release_time_scale_fast_release_half_amount() {
+        let actual = release_time_scale_from_release_velocity(1.0, 0.5);
+        let expected = 0.5; // One octave shorter
+
+        assert!(f32s_are_equal(actual, expected));
+    }
+
     // Tests for midi_value_to_pitch_bend_cents
     #[test]
     fn test_pitch_bend_at_zero_point() {
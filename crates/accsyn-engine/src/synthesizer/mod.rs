@@ -4,38 +4,61 @@ mod event_listener;
 mod midi_messages;
 /// Functions for converting normalized MIDI values to synthesizer parameter ranges.
 pub mod midi_value_converters;
+/// Per-note expression from MPE member channels.
+pub mod mpe;
+/// Held-note tracking and note priority for mono mode.
+pub mod note_stack;
+/// Oscillator note assignment for paraphonic mode.
+pub mod paraphonic;
 /// Patch and preset file management for saving and loading synthesizer state.
 pub mod patches;
 mod sample_generator;
 mod set_parameters;
+/// Global settings saved apart from the patches.
+pub mod settings;
+/// Polyphonic voice allocation and the parameters controlling it.
+pub mod voices;
 
 use self::constants::MAX_MIDI_KEY_VELOCITY;
 
 use accsyn_core::audio_events::OutputStreamParameters;
 use accsyn_core::defaults::Defaults;
+use accsyn_core::math::normalize_midi_value;
 use accsyn_core::midi_events::MidiEvent;
 use accsyn_core::synth_events::{OscillatorIndex, SynthesizerUpdateEvents};
 use accsyn_core::ui_events::UIUpdates;
 
 use crate::modules::effects::AudioEffectParameters;
 use crate::modules::envelope::EnvelopeParameters;
-use crate::modules::filter::FilterParameters;
-use crate::modules::lfo::LfoParameters;
+use crate::modules::filter::{FilterParameters, FilterRoutingParameters};
+use crate::modules::lfo::{LfoBank, LfoRoutingParameters};
 use crate::modules::mixer::MixerInput;
-use crate::modules::oscillator::OscillatorParameters;
+use crate::modules::mod_matrix::{
+    MOD_MATRIX_SLOT_COUNT, ModulationSlotParameters, deserialize_modulation_slots,
+};
+use crate::modules::oscillator::drift::DriftParameters;
+use crate::modules::oscillator::{OscillatorParameters, deserialize_oscillators};
+use crate::modules::tuning::TuningParameters;
 use crate::synthesizer::constants::{
     MIDI_CLOCK_OFF_BPM_VALUE, SYNTHESIZER_MESSAGE_SENDER_CAPACITY,
 };
 use crate::synthesizer::event_listener::start_update_event_listener;
 use crate::synthesizer::midi_messages::{
-    process_midi_cc_values, process_midi_channel_pressure_message, process_midi_note_off_message,
-    process_midi_note_on_message, process_midi_pitch_bend_message,
-    process_midi_program_change_message,
+    process_all_notes_off, process_midi_cc_values, process_midi_channel_pressure_message,
+    process_midi_note_off_message, process_midi_note_on_message, process_midi_pitch_bend_message,
+    process_midi_poly_key_pressure_message, process_midi_program_change_message,
+    process_midi_tuning_change_message, process_mpe_note_off_message, process_mpe_note_on_message,
+    process_mpe_pitch_bend_message, process_mpe_pressure_message, process_mpe_timbre_message,
 };
+use crate::synthesizer::mpe::{MpeChannels, NoteExpression};
+use crate::synthesizer::note_stack::NoteStack;
+use crate::synthesizer::paraphonic::ParaphonicAllocator;
 use crate::synthesizer::sample_generator::sample_generator;
+use crate::synthesizer::voices::{VoiceAllocator, VoiceParameters, VoiceStates};
 
 use crate::synthesizer::clock::{Clock, ClockParameters};
 use crate::synthesizer::patches::Patches;
+use crate::synthesizer::settings::GlobalSettings;
 use accsyn_core::parameter_types::{Balance, NormalizedValue};
 use anyhow::Result;
 use crossbeam_channel::{Receiver, Sender};
@@ -44,7 +67,7 @@ use serde::{Deserialize, Serialize};
 use std::default::Default;
 use std::sync::atomic::Ordering::Relaxed;
 use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU32};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::thread;
 use strum::EnumCount;
 
@@ -52,6 +75,7 @@ use strum::EnumCount;
 enum MidiNoteEvent {
     NoteOn = 1,
     NoteOff = 2,
+    Glide = 3,
 }
 
 #[derive(Default, Debug, Clone, Copy)]
@@ -66,6 +90,9 @@ enum MidiGateEvent {
 struct CurrentNote {
     midi_note: AtomicU8,
     velocity: AtomicU32,
+    oscillator_notes: [AtomicU8; OscillatorIndex::COUNT],
+    expression: NoteExpression,
+    release_velocity: AtomicU32,
 }
 
 impl Default for CurrentNote {
@@ -73,6 +100,30 @@ impl Default for CurrentNote {
         Self {
             midi_note: AtomicU8::new(0),
             velocity: AtomicU32::new(MAX_MIDI_KEY_VELOCITY.to_bits()),
+            oscillator_notes: Default::default(),
+            expression: NoteExpression::default(),
+            release_velocity: AtomicU32::new(
+                normalize_midi_value(Defaults::RELEASE_VELOCITY).to_bits(),
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct NoteAllocation {
+    note_stack: NoteStack,
+    voice_allocator: VoiceAllocator,
+    paraphonic_allocator: ParaphonicAllocator,
+    mpe_channels: MpeChannels,
+}
+
+impl NoteAllocation {
+    fn new(voice_states: Arc<VoiceStates>) -> Self {
+        Self {
+            note_stack: NoteStack::new(),
+            voice_allocator: VoiceAllocator::new(voice_states),
+            paraphonic_allocator: ParaphonicAllocator::new(),
+            mpe_channels: MpeChannels::new(),
         }
     }
 }
@@ -89,6 +140,10 @@ pub struct KeyboardParameters {
     pub polarity_flipped: AtomicBool,
     /// Maximum pitch bend range in semitones.
     pub pitch_bend_range: AtomicU8,
+    /// Maximum per-note pitch bend range of MPE member channels in semitones.
+    pub mpe_pitch_bend_range: AtomicU8,
+    /// How strongly the release velocity of a note off shortens or lengthens the envelope release times.
+    pub release_velocity_amount: NormalizedValue,
 }
 
 impl KeyboardParameters {
@@ -103,6 +158,10 @@ impl KeyboardParameters {
             .store(parameters.polarity_flipped.load(Relaxed), Relaxed);
         self.pitch_bend_range
             .store(parameters.pitch_bend_range.load(Relaxed), Relaxed);
+        self.mpe_pitch_bend_range
+            .store(parameters.mpe_pitch_bend_range.load(Relaxed), Relaxed);
+        self.release_velocity_amount
+            .store(parameters.release_velocity_amount.load());
     }
 }
 
@@ -114,6 +173,8 @@ impl Default for KeyboardParameters {
             velocity_curve: NormalizedValue::new(Defaults::VELOCITY_CURVE_NORMAL_VALUE),
             polarity_flipped: AtomicBool::new(false),
             pitch_bend_range: AtomicU8::new(Defaults::PITCH_BEND_RANGE),
+            mpe_pitch_bend_range: AtomicU8::new(Defaults::MPE_PITCH_BEND_RANGE),
+            release_velocity_amount: NormalizedValue::new(Defaults::RELEASE_VELOCITY_AMOUNT),
         }
     }
 }
@@ -203,20 +264,29 @@ pub struct ModuleParameters {
     /// Filter module parameters.
     #[serde(default)]
     pub filter: FilterParameters,
+    /// Second filter module parameters.
+    #[serde(default)]
+    pub second_filter: FilterParameters,
+    /// Routing between the two filters.
+    #[serde(default)]
+    pub filter_routing: FilterRoutingParameters,
     /// Output mixer parameters including per-oscillator levels.
     #[serde(default)]
     pub mixer: MixerParameters,
     #[serde(default)]
     /// Keyboard-related parameters such as velocity curve and pitch bend range.
     pub keyboard: KeyboardParameters,
-    /// Parameters for the two LFOs (mod wheel and filter).
+    /// Parameters for the LFO bank, starting with the mod wheel and filter LFOs.
     #[serde(default)]
-    pub lfos: [LfoParameters; 2],
+    pub lfos: LfoBank,
+    /// The LFOs driving the mod wheel vibrato and the filter cutoff.
+    #[serde(default)]
+    pub lfo_routing: LfoRoutingParameters,
     /// Parameters for the three envelopes (amplitude, pitch, and filter).
     #[serde(default)]
     pub envelopes: [EnvelopeParameters; 3],
     /// Parameters for each of the four oscillators.
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_oscillators")]
     pub oscillators: [OscillatorParameters; OscillatorIndex::COUNT],
     /// Parameters for the audio effects chain.
     #[serde(default)]
@@ -224,12 +294,26 @@ pub struct ModuleParameters {
     /// Parameters for clock and clock synchronization
     #[serde(default)]
     pub clock: ClockParameters,
+    /// Voice mode, voice count, and voice stealing policy.
+    #[serde(default)]
+    pub voices: VoiceParameters,
+    /// Source, destination, amount, and via source for each modulation matrix slot.
+    #[serde(default, deserialize_with = "deserialize_modulation_slots")]
+    pub mod_matrix: [ModulationSlotParameters; MOD_MATRIX_SLOT_COUNT],
+    /// Analog drift depth and seed shared by every oscillator.
+    #[serde(default)]
+    pub drift: DriftParameters,
+    /// Scala scale and keyboard mapping played by every oscillator and the filter key tracking, plus the
+    /// global master tune and reference pitch.
+    #[serde(default)]
+    pub tuning: TuningParameters,
 }
 
 /// Top-level synthesizer coordinating MIDI input, DSP processing, and audio output.
 pub struct Synthesizer {
     output_stream_parameters: OutputStreamParameters,
     current_note: Arc<CurrentNote>,
+    voice_states: Arc<VoiceStates>,
     module_parameters: Arc<ModuleParameters>,
     ui_update_sender: Sender<SynthesizerUpdateEvents>,
     ui_update_receiver: Receiver<SynthesizerUpdateEvents>,
@@ -251,9 +335,17 @@ impl Synthesizer {
         let module_parameters = patches::init_module_parameters()?;
         let patches = Patches::new()?;
 
+        match GlobalSettings::load(&patches.global_settings_file()) {
+            Ok(settings) => settings.apply(&module_parameters),
+            Err(err) => {
+                log::warn!(target: "synthesizer", "Could not load the global settings, using the defaults: {err}");
+            }
+        }
+
         Ok(Self {
             output_stream_parameters,
             current_note: Arc::new(CurrentNote::default()),
+            voice_states: Arc::new(Default::default()),
             module_parameters: Arc::new(module_parameters),
             ui_update_sender,
             ui_update_receiver,
@@ -306,6 +398,7 @@ impl Synthesizer {
             sample_buffer_receiver,
             self.output_stream_parameters.clone(),
             &self.current_note,
+            &self.voice_states,
             &self.module_parameters,
         )?;
 
@@ -319,6 +412,7 @@ impl Synthesizer {
         synthesizer_update_sender: Sender<SynthesizerUpdateEvents>,
     ) {
         let mut current_note = self.current_note.clone();
+        let mut note_allocation = NoteAllocation::new(self.voice_states.clone());
         let mut module_parameters = self.module_parameters.clone();
         let mut clock = Clock::new();
 
@@ -331,13 +425,21 @@ impl Synthesizer {
                         process_midi_note_on_message(
                             &mut module_parameters,
                             &mut current_note,
+                            &mut note_allocation,
                             midi_note,
                             velocity,
                             &ui_update_sender,
                         );
                     }
-                    MidiEvent::NoteOff => {
-                        process_midi_note_off_message(&mut module_parameters);
+                    MidiEvent::NoteOff(midi_note, release_velocity) => {
+                        process_midi_note_off_message(
+                            &mut module_parameters,
+                            &current_note,
+                            &mut note_allocation,
+                            midi_note,
+                            release_velocity,
+                            &ui_update_sender,
+                        );
                     }
                     MidiEvent::PitchBend(bend_amount) => {
                         process_midi_pitch_bend_message(
@@ -352,6 +454,61 @@ impl Synthesizer {
                             pressure_value,
                         );
                     }
+                    MidiEvent::MpeNoteOn(channel, midi_note, velocity) => {
+                        process_mpe_note_on_message(
+                            &mut module_parameters,
+                            &mut current_note,
+                            &mut note_allocation,
+                            channel,
+                            midi_note,
+                            velocity,
+                            &ui_update_sender,
+                        );
+                    }
+                    MidiEvent::MpeNoteOff(channel, midi_note, release_velocity) => {
+                        process_mpe_note_off_message(
+                            &mut module_parameters,
+                            &current_note,
+                            &mut note_allocation,
+                            channel,
+                            midi_note,
+                            release_velocity,
+                            &ui_update_sender,
+                        );
+                    }
+                    MidiEvent::MpePitchBend(channel, bend_amount) => {
+                        process_mpe_pitch_bend_message(
+                            &current_note,
+                            &mut note_allocation,
+                            channel,
+                            bend_amount,
+                        );
+                    }
+                    MidiEvent::MpePressure(channel, pressure_value) => {
+                        process_mpe_pressure_message(
+                            &current_note,
+                            &mut note_allocation,
+                            channel,
+                            pressure_value,
+                        );
+                    }
+                    MidiEvent::MpeTimbre(channel, timbre_value) => {
+                        process_mpe_timbre_message(
+                            &current_note,
+                            &mut note_allocation,
+                            channel,
+                            timbre_value,
+                        );
+                    }
+                    MidiEvent::PolyKeyPressure(midi_note, pressure_value) => {
+                        process_midi_poly_key_pressure_message(
+                            &module_parameters,
+                            &current_note,
+                            &note_allocation,
+                            midi_note,
+                            pressure_value,
+                        );
+                    }
                     MidiEvent::ProgramChange(program_number) => {
                         process_midi_program_change_message(
                             &ui_update_sender,
@@ -359,6 +516,9 @@ impl Synthesizer {
                             program_number,
                         );
                     }
+                    MidiEvent::TuningChange(change) => {
+                        process_midi_tuning_change_message(&module_parameters.tuning, &change);
+                    }
                     MidiEvent::Stop => {
                         log::trace!(target: "synthesizer", "Midi Transport Stop Event");
                         module_parameters
@@ -384,10 +544,15 @@ impl Synthesizer {
                         }
                     }
                     MidiEvent::ControlChange(cc_value) => {
-                        process_midi_cc_values(cc_value, &mut module_parameters, &ui_update_sender);
+                        process_midi_cc_values(
+                            cc_value,
+                            &mut module_parameters,
+                            &mut note_allocation,
+                            &ui_update_sender,
+                        );
                     }
                     MidiEvent::Reset => {
-                        process_midi_note_off_message(&mut module_parameters);
+                        process_all_notes_off(&mut module_parameters, &mut note_allocation);
 
                         module_parameters
                             .clock
@@ -412,6 +577,21 @@ impl Synthesizer {
     pub fn patches(&self) -> Arc<Mutex<Patches>> {
         self.patches.clone()
     }
+
+    /// Saves the global settings currently in use, called once on shutdown rather than on every change.
+    pub fn save_global_settings(&self) {
+        let settings_file = self
+            .patches
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .global_settings_file();
+
+        if let Err(err) =
+            GlobalSettings::from_module_parameters(&self.module_parameters).save(&settings_file)
+        {
+            log::error!(target: "synthesizer", "Could not save the global settings to {}: {err}", settings_file.display());
+        }
+    }
 }
 
 fn create_quad_mixer_inputs(module_parameters: &Arc<ModuleParameters>) -> [MixerInput; 4] {
@@ -444,7 +624,7 @@ fn create_mixer_input_from_oscillator_parameters(
     oscillator: OscillatorIndex,
 ) -> MixerInput {
     MixerInput {
-        sample: 0.0,
+        sample: (0.0, 0.0),
         level: parameters.quad_mixer_inputs[oscillator as usize]
             .level
             .load(),
@@ -0,0 +1,238 @@
+use crate::synthesizer::constants::{CENTS_PER_SEMITONE, PITCH_BEND_AMOUNT_ZERO_POINT};
+use accsyn_core::math::{load_f32_from_atomic_u32, normalize_midi_value, store_f32_as_atomic_u32};
+use std::sync::atomic::AtomicU32;
+
+const MIDI_CHANNEL_COUNT: usize = 16;
+const MIDI_CHANNEL_MASK: u8 = 0x0F;
+
+/// The per-note pitch bend, pressure and timbre of one MPE note.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct ExpressionValues {
+    /// Pitch bend as a fraction of the MPE pitch bend range (-1.0–1.0).
+    pub pitch_bend: f32,
+    /// Pressure (0.0–1.0).
+    pub pressure: f32,
+    /// Timbre from CC 74, centred on 64 (-1.0–1.0).
+    pub timbre: f32,
+}
+
+impl ExpressionValues {
+    /// Returns the pitch bend in cents for the given per-note pitch bend range in semitones.
+    #[must_use]
+    pub fn pitch_bend_cents(&self, range_in_semitones: u8) -> i16 {
+        let max_bend_in_cents = f32::from(u16::from(range_in_semitones) * CENTS_PER_SEMITONE);
+        // The bend is clamped to ±1.0 of a u8 semitone range, at most ±25500 cents, within i16 range
+        #[allow(clippy::cast_possible_truncation)]
+        let cents = (self.pitch_bend.clamp(-1.0, 1.0) * max_bend_in_cents) as i16;
+        cents
+    }
+}
+
+/// Per-note expression shared between the MIDI thread and the audio thread, stored as f32 bits.
+#[derive(Debug)]
+pub struct NoteExpression {
+    pitch_bend: AtomicU32,
+    pressure: AtomicU32,
+    timbre: AtomicU32,
+}
+
+impl NoteExpression {
+    /// Publishes new expression values for the note.
+    pub fn store(&self, values: ExpressionValues) {
+        store_f32_as_atomic_u32(&self.pitch_bend, values.pitch_bend);
+        store_f32_as_atomic_u32(&self.pressure, values.pressure);
+        store_f32_as_atomic_u32(&self.timbre, values.timbre);
+    }
+
+    /// Publishes a new pressure for the note, leaving its pitch bend and timbre as they are.
+    pub fn store_pressure(&self, pressure: f32) {
+        store_f32_as_atomic_u32(&self.pressure, pressure);
+    }
+
+    /// Returns the most recently published expression values.
+    #[must_use]
+    pub fn load(&self) -> ExpressionValues {
+        ExpressionValues {
+            pitch_bend: load_f32_from_atomic_u32(&self.pitch_bend),
+            pressure: load_f32_from_atomic_u32(&self.pressure),
+            timbre: load_f32_from_atomic_u32(&self.timbre),
+        }
+    }
+}
+
+impl Default for NoteExpression {
+    fn default() -> Self {
+        Self {
+            pitch_bend: AtomicU32::new(0.0_f32.to_bits()),
+            pressure: AtomicU32::new(0.0_f32.to_bits()),
+            timbre: AtomicU32::new(0.0_f32.to_bits()),
+        }
+    }
+}
+
+/// The latest expression received on every MPE member channel.
+///
+/// Owned by the MIDI event thread. Expression sent before a note on is kept for the channel, so a note
+/// starts with the bend, pressure and timbre the controller set up for it.
+#[derive(Debug)]
+pub struct MpeChannels {
+    channels: [ExpressionValues; MIDI_CHANNEL_COUNT],
+    held_notes: [Option<u8>; MIDI_CHANNEL_COUNT],
+}
+
+impl MpeChannels {
+    /// Creates channel state with every channel at rest.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            channels: [ExpressionValues::default(); MIDI_CHANNEL_COUNT],
+            held_notes: [None; MIDI_CHANNEL_COUNT],
+        }
+    }
+
+    /// Returns the latest expression on a member channel (1-16).
+    #[must_use]
+    pub fn expression(&self, channel: u8) -> ExpressionValues {
+        self.channels[channel_index(channel)]
+    }
+
+    /// Stores a 14-bit per-note pitch bend and returns the channel's updated expression.
+    pub fn set_pitch_bend(&mut self, channel: u8, bend_amount: u16) -> ExpressionValues {
+        let expression = &mut self.channels[channel_index(channel)];
+        expression.pitch_bend = ((f32::from(bend_amount)
+            - f32::from(PITCH_BEND_AMOUNT_ZERO_POINT))
+            / f32::from(PITCH_BEND_AMOUNT_ZERO_POINT))
+        .clamp(-1.0, 1.0);
+        *expression
+    }
+
+    /// Stores a per-note pressure and returns the channel's updated expression.
+    pub fn set_pressure(&mut self, channel: u8, pressure_value: u8) -> ExpressionValues {
+        let expression = &mut self.channels[channel_index(channel)];
+        expression.pressure = normalize_midi_value(pressure_value);
+        *expression
+    }
+
+    /// Stores a per-note timbre and returns the channel's updated expression.
+    pub fn set_timbre(&mut self, channel: u8, timbre_value: u8) -> ExpressionValues {
+        let expression = &mut self.channels[channel_index(channel)];
+        expression.timbre = (normalize_midi_value(timbre_value) * 2.0 - 1.0).clamp(-1.0, 1.0);
+        *expression
+    }
+
+    /// Records the note held on a member channel.
+    pub fn note_on(&mut self, channel: u8, midi_note: u8) {
+        self.held_notes[channel_index(channel)] = Some(midi_note);
+    }
+
+    /// Clears the note held on a member channel if it is the released note.
+    pub fn note_off(&mut self, channel: u8, midi_note: u8) {
+        let held_note = &mut self.held_notes[channel_index(channel)];
+        if *held_note == Some(midi_note) {
+            *held_note = None;
+        }
+    }
+
+    /// Returns the member channel holding the note, if any.
+    #[must_use]
+    pub fn channel_of_note(&self, midi_note: u8) -> Option<u8> {
+        self.held_notes
+            .iter()
+            .position(|&held_note| held_note == Some(midi_note))
+            .and_then(|index| u8::try_from(index + 1).ok())
+    }
+
+    /// Returns the expression of the member channel holding the note, or rest values for a non-MPE note.
+    #[must_use]
+    pub fn note_expression(&self, midi_note: u8) -> ExpressionValues {
+        self.channel_of_note(midi_note)
+            .map(|channel| self.expression(channel))
+            .unwrap_or_default()
+    }
+
+    /// Forgets every held note, keeping the latest expression of each channel.
+    pub fn all_notes_off(&mut self) {
+        self.held_notes = [None; MIDI_CHANNEL_COUNT];
+    }
+}
+
+impl Default for MpeChannels {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn channel_index(channel: u8) -> usize {
+    usize::from(channel.wrapping_sub(1) & MIDI_CHANNEL_MASK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use accsyn_core::math::f32s_are_equal;
+
+    #[test]
+    fn expression_is_kept_per_channel() {
+        let mut channels = MpeChannels::new();
+
+        channels.set_pitch_bend(2, 16383);
+        channels.set_pressure(2, 127);
+        channels.set_timbre(3, 0);
+
+        assert!(channels.expression(2).pitch_bend > 0.999);
+        assert!(f32s_are_equal(channels.expression(2).pressure, 1.0));
+        assert!(f32s_are_equal(channels.expression(2).timbre, 0.0));
+        assert!(f32s_are_equal(channels.expression(3).timbre, -1.0));
+        assert_eq!(channels.expression(4), ExpressionValues::default());
+    }
+
+    #[test]
+    fn centred_bend_and_timbre_are_at_rest() {
+        let mut channels = MpeChannels::new();
+
+        let expression = channels.set_pitch_bend(5, PITCH_BEND_AMOUNT_ZERO_POINT);
+        assert!(f32s_are_equal(expression.pitch_bend, 0.0));
+        let expression = channels.set_timbre(5, 64);
+        assert!(expression.timbre.abs() < 0.01);
+    }
+
+    #[test]
+    fn pitch_bend_cents_scales_by_the_per_note_range() {
+        let expression = ExpressionValues {
+            pitch_bend: -0.5,
+            ..ExpressionValues::default()
+        };
+
+        assert_eq!(expression.pitch_bend_cents(48), -2400);
+        assert_eq!(expression.pitch_bend_cents(2), -100);
+    }
+
+    #[test]
+    fn held_notes_are_found_by_channel() {
+        let mut channels = MpeChannels::new();
+        channels.set_pressure(7, 127);
+
+        channels.note_on(7, 60);
+        channels.note_on(8, 64);
+        channels.note_off(8, 64);
+
+        assert_eq!(channels.channel_of_note(60), Some(7));
+        assert_eq!(channels.channel_of_note(64), None);
+        assert!(f32s_are_equal(channels.note_expression(60).pressure, 1.0));
+        assert_eq!(channels.note_expression(64), ExpressionValues::default());
+    }
+
+    #[test]
+    fn note_expression_round_trips_through_the_atomics() {
+        let note_expression = NoteExpression::default();
+        let values = ExpressionValues {
+            pitch_bend: 0.25,
+            pressure: 0.5,
+            timbre: -0.75,
+        };
+
+        note_expression.store(values);
+
+        assert_eq!(note_expression.load(), values);
+    }
+}
@@ -0,0 +1,201 @@
+use strum_macros::{EnumCount, EnumIter, FromRepr};
+
+const NOTE_STACK_CAPACITY: usize = 128;
+
+/// Which of the held notes sounds in mono mode.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
+#[repr(u8)]
+pub enum NotePriority {
+    /// The most recently pressed held note sounds.
+    #[default]
+    Last,
+    /// The lowest held note sounds.
+    Lowest,
+    /// The highest held note sounds.
+    Highest,
+}
+
+impl NotePriority {
+    /// Converts a numeric index to the corresponding note priority, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
+}
+
+/// A key that is currently held down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeldNote {
+    /// MIDI note number of the held key.
+    pub midi_note: u8,
+    /// Raw MIDI velocity the key was pressed with.
+    pub velocity: u8,
+}
+
+/// The keys currently held down, in the order they were pressed.
+#[derive(Debug)]
+pub struct NoteStack {
+    held_notes: Vec<HeldNote>,
+}
+
+impl NoteStack {
+    /// Creates an empty note stack with room for every MIDI note.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            held_notes: Vec::with_capacity(NOTE_STACK_CAPACITY),
+        }
+    }
+
+    /// Adds a pressed key to the top of the stack, moving it there if it is already held.
+    pub fn push(&mut self, midi_note: u8, velocity: u8) {
+        self.remove(midi_note);
+        self.held_notes.push(HeldNote {
+            midi_note,
+            velocity,
+        });
+    }
+
+    /// Removes a released key from the stack, returning whether it was held.
+    pub fn remove(&mut self, midi_note: u8) -> bool {
+        let Some(position) = self
+            .held_notes
+            .iter()
+            .position(|held_note| held_note.midi_note == midi_note)
+        else {
+            return false;
+        };
+
+        self.held_notes.remove(position);
+        true
+    }
+
+    /// Returns whether the key is currently held.
+    #[must_use]
+    pub fn contains(&self, midi_note: u8) -> bool {
+        self.held_notes
+            .iter()
+            .any(|held_note| held_note.midi_note == midi_note)
+    }
+
+    /// Returns the held keys in the order they were pressed.
+    #[must_use]
+    pub fn held_notes(&self) -> &[HeldNote] {
+        &self.held_notes
+    }
+
+    /// Releases every held key.
+    pub fn clear(&mut self) {
+        self.held_notes.clear();
+    }
+
+    /// Returns the held note that should sound for the given priority, if any keys are held.
+    #[must_use]
+    pub fn selected(&self, priority: NotePriority) -> Option<HeldNote> {
+        match priority {
+            NotePriority::Last => self.held_notes.last().copied(),
+            NotePriority::Lowest => self
+                .held_notes
+                .iter()
+                .min_by_key(|held_note| held_note.midi_note)
+                .copied(),
+            NotePriority::Highest => self
+                .held_notes
+                .iter()
+                .max_by_key(|held_note| held_note.midi_note)
+                .copied(),
+        }
+    }
+}
+
+impl Default for NoteStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selected_note(note_stack: &NoteStack, priority: NotePriority) -> Option<u8> {
+        note_stack
+            .selected(priority)
+            .map(|held_note| held_note.midi_note)
+    }
+
+    #[test]
+    fn empty_stack_has_no_selected_note() {
+        let note_stack = NoteStack::new();
+        assert_eq!(selected_note(&note_stack, NotePriority::Last), None);
+    }
+
+    #[test]
+    fn releasing_the_last_note_returns_to_the_previously_held_note() {
+        let mut note_stack = NoteStack::new();
+        note_stack.push(60, 100);
+        note_stack.push(64, 90);
+
+        assert!(note_stack.remove(64));
+
+        let held_note = note_stack.selected(NotePriority::Last);
+        assert_eq!(
+            held_note,
+            Some(HeldNote {
+                midi_note: 60,
+                velocity: 100
+            })
+        );
+    }
+
+    #[test]
+    fn lowest_priority_selects_the_lowest_held_note() {
+        let mut note_stack = NoteStack::new();
+        note_stack.push(64, 100);
+        note_stack.push(60, 100);
+        note_stack.push(67, 100);
+
+        assert_eq!(selected_note(&note_stack, NotePriority::Lowest), Some(60));
+    }
+
+    #[test]
+    fn highest_priority_selects_the_highest_held_note() {
+        let mut note_stack = NoteStack::new();
+        note_stack.push(64, 100);
+        note_stack.push(67, 100);
+        note_stack.push(60, 100);
+
+        assert_eq!(selected_note(&note_stack, NotePriority::Highest), Some(67));
+    }
+
+    #[test]
+    fn pushing_a_held_note_again_moves_it_to_the_top() {
+        let mut note_stack = NoteStack::new();
+        note_stack.push(60, 100);
+        note_stack.push(64, 100);
+        note_stack.push(60, 80);
+
+        assert_eq!(selected_note(&note_stack, NotePriority::Last), Some(60));
+        assert!(note_stack.remove(60));
+        assert_eq!(selected_note(&note_stack, NotePriority::Last), Some(64));
+    }
+
+    #[test]
+    fn removing_a_note_that_is_not_held_returns_false() {
+        let mut note_stack = NoteStack::new();
+        note_stack.push(60, 100);
+
+        assert!(!note_stack.remove(61));
+    }
+
+    #[test]
+    fn clear_releases_every_held_note() {
+        let mut note_stack = NoteStack::new();
+        note_stack.push(60, 100);
+        note_stack.push(64, 100);
+
+        note_stack.clear();
+
+        assert_eq!(selected_note(&note_stack, NotePriority::Last), None);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::synthesizer::note_stack::NoteStack;
+use accsyn_core::synth_events::OscillatorIndex;
+use strum::EnumCount;
+use strum_macros::{EnumCount, EnumIter, FromRepr};
+
+const OSCILLATOR_COUNT: usize = OscillatorIndex::COUNT;
+
+/// How held notes are spread across the four oscillators in paraphonic mode.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
+#[repr(u8)]
+pub enum ParaphonicAssignment {
+    /// Each new note takes the next oscillator in turn, Sub through Osc3.
+    #[default]
+    Rotate,
+    /// The lowest held notes are assigned to the oscillators in ascending order.
+    Lowest,
+}
+
+impl ParaphonicAssignment {
+    /// Converts a numeric index to the corresponding assignment, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
+}
+
+/// Tracks which held note each oscillator plays in paraphonic mode.
+#[derive(Debug, Default)]
+pub struct ParaphonicAllocator {
+    rotating_notes: [Option<u8>; OSCILLATOR_COUNT],
+    next_oscillator: usize,
+}
+
+impl ParaphonicAllocator {
+    /// Creates an allocator with no notes assigned.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assigns a new note to the next oscillator in the rotation, preferring a free one.
+    pub fn note_on(&mut self, midi_note: u8) {
+        if self.rotating_notes.contains(&Some(midi_note)) {
+            return;
+        }
+
+        let oscillator_index = (0..OSCILLATOR_COUNT)
+            .map(|offset| (self.next_oscillator + offset) % OSCILLATOR_COUNT)
+            .find(|&index| self.rotating_notes[index].is_none())
+            .unwrap_or(self.next_oscillator);
+
+        self.rotating_notes[oscillator_index] = Some(midi_note);
+        self.next_oscillator = (oscillator_index + 1) % OSCILLATOR_COUNT;
+    }
+
+    /// Frees the oscillator playing the released note.
+    pub fn note_off(&mut self, midi_note: u8) {
+        for assigned_note in &mut self.rotating_notes {
+            if *assigned_note == Some(midi_note) {
+                *assigned_note = None;
+            }
+        }
+    }
+
+    /// Frees every oscillator and restarts the rotation at the Sub oscillator.
+    pub fn clear(&mut self) {
+        self.rotating_notes = [None; OSCILLATOR_COUNT];
+        self.next_oscillator = 0;
+    }
+
+    /// Returns the note each oscillator should play, or `None` when no keys are held.
+    ///
+    /// Oscillators without a note of their own double the held notes so every oscillator keeps sounding.
+    #[must_use]
+    pub fn oscillator_notes(
+        &self,
+        note_stack: &NoteStack,
+        assignment: ParaphonicAssignment,
+    ) -> Option<[u8; OSCILLATOR_COUNT]> {
+        let mut notes = [None; OSCILLATOR_COUNT];
+
+        match assignment {
+            ParaphonicAssignment::Rotate => {
+                for (note, assigned_note) in notes.iter_mut().zip(self.rotating_notes) {
+                    *note = assigned_note.filter(|&midi_note| note_stack.contains(midi_note));
+                }
+            }
+            ParaphonicAssignment::Lowest => {
+                let mut held_notes = note_stack
+                    .held_notes()
+                    .iter()
+                    .map(|held_note| held_note.midi_note)
+                    .collect::<Vec<u8>>();
+                held_notes.sort_unstable();
+                for (note, held_note) in notes.iter_mut().zip(held_notes) {
+                    *note = Some(held_note);
+                }
+            }
+        }
+
+        let assigned_notes = notes.iter().flatten().copied().collect::<Vec<u8>>();
+        let fallback_notes = if assigned_notes.is_empty() {
+            vec![note_stack.held_notes().last()?.midi_note]
+        } else {
+            assigned_notes
+        };
+
+        let mut oscillator_notes = [0; OSCILLATOR_COUNT];
+        for (index, oscillator_note) in oscillator_notes.iter_mut().enumerate() {
+            *oscillator_note = notes[index].unwrap_or(fallback_notes[index % fallback_notes.len()]);
+        }
+
+        Some(oscillator_notes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hold(note_stack: &mut NoteStack, allocator: &mut ParaphonicAllocator, midi_note: u8) {
+        note_stack.push(midi_note, 100);
+        allocator.note_on(midi_note);
+    }
+
+    fn release(note_stack: &mut NoteStack, allocator: &mut ParaphonicAllocator, midi_note: u8) {
+        note_stack.remove(midi_note);
+        allocator.note_off(midi_note);
+    }
+
+    #[test]
+    fn no_held_notes_returns_none() {
+        let note_stack = NoteStack::new();
+        let allocator = ParaphonicAllocator::new();
+
+        assert_eq!(
+            allocator.oscillator_notes(&note_stack, ParaphonicAssignment::Rotate),
+            None
+        );
+    }
+
+    #[test]
+    fn single_note_is_played_by_every_oscillator() {
+        let mut note_stack = NoteStack::new();
+        let mut allocator = ParaphonicAllocator::new();
+        hold(&mut note_stack, &mut allocator, 60);
+
+        assert_eq!(
+            allocator.oscillator_notes(&note_stack, ParaphonicAssignment::Rotate),
+            Some([60, 60, 60, 60])
+        );
+        assert_eq!(
+            allocator.oscillator_notes(&note_stack, ParaphonicAssignment::Lowest),
+            Some([60, 60, 60, 60])
+        );
+    }
+
+    #[test]
+    fn rotate_assigns_each_new_note_to_the_next_oscillator() {
+        let mut note_stack = NoteStack::new();
+        let mut allocator = ParaphonicAllocator::new();
+        for midi_note in [60, 64, 67, 72] {
+            hold(&mut note_stack, &mut allocator, midi_note);
+        }
+
+        assert_eq!(
+            allocator.oscillator_notes(&note_stack, ParaphonicAssignment::Rotate),
+            Some([60, 64, 67, 72])
+        );
+    }
+
+    #[test]
+    fn rotate_reuses_the_oscillator_freed_by_a_released_note() {
+        let mut note_stack = NoteStack::new();
+        let mut allocator = ParaphonicAllocator::new();
+        for midi_note in [60, 64, 67, 72] {
+            hold(&mut note_stack, &mut allocator, midi_note);
+        }
+        release(&mut note_stack, &mut allocator, 64);
+        hold(&mut note_stack, &mut allocator, 65);
+
+        assert_eq!(
+            allocator.oscillator_notes(&note_stack, ParaphonicAssignment::Rotate),
+            Some([60, 65, 67, 72])
+        );
+    }
+
+    #[test]
+    fn rotate_steals_the_next_oscillator_when_all_four_are_held() {
+        let mut note_stack = NoteStack::new();
+        let mut allocator = ParaphonicAllocator::new();
+        for midi_note in [60, 64, 67, 72, 76] {
+            hold(&mut note_stack, &mut allocator, midi_note);
+        }
+
+        assert_eq!(
+            allocator.oscillator_notes(&note_stack, ParaphonicAssignment::Rotate),
+            Some([76, 64, 67, 72])
+        );
+    }
+
+    #[test]
+    fn lowest_assigns_the_lowest_held_notes_in_ascending_order() {
+        let mut note_stack = NoteStack::new();
+        let mut allocator = ParaphonicAllocator::new();
+        for midi_note in [72, 60, 76, 67, 64] {
+            hold(&mut note_stack, &mut allocator, midi_note);
+        }
+
+        assert_eq!(
+            allocator.oscillator_notes(&note_stack, ParaphonicAssignment::Lowest),
+            Some([60, 64, 67, 72])
+        );
+    }
+
+    #[test]
+    fn free_oscillators_double_the_held_notes() {
+        let mut note_stack = NoteStack::new();
+        let mut allocator = ParaphonicAllocator::new();
+        hold(&mut note_stack, &mut allocator, 60);
+        hold(&mut note_stack, &mut allocator, 67);
+
+        assert_eq!(
+            allocator.oscillator_notes(&note_stack, ParaphonicAssignment::Lowest),
+            Some([60, 67, 60, 67])
+        );
+        assert_eq!(
+            allocator.oscillator_notes(&note_stack, ParaphonicAssignment::Rotate),
+            Some([60, 67, 60, 67])
+        );
+    }
+}
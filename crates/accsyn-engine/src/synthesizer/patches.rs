@@ -11,6 +11,10 @@ use thiserror::Error;
 const APP_SUPPORT_DIRECTORY: &str = "Library/Application Support";
 const DATA_DIRECTORY: &str = "AccidentalSynthesizer";
 const USER_PATCH_DIRECTORY: &str = "patches";
+const USER_WAVETABLE_DIRECTORY: &str = "wavetables";
+const USER_SAMPLE_DIRECTORY: &str = "samples";
+const USER_TUNING_DIRECTORY: &str = "tunings";
+const GLOBAL_SETTINGS_FILE: &str = "settings.json";
 const PATCH_FILE_EXTENSION: &str = "json";
 
 /// Errors that can occur during patch file operations.
@@ -49,11 +53,16 @@ pub enum PatchesError {
     PatchContentCouldNotBeCreated(String),
 }
 
-/// File system paths used for application data, patches, and presets storage.
+/// File system paths used for application data, patches, presets, wavetable, sample, and tuning storage, and
+/// the global settings file.
 pub struct Paths {
     base: PathBuf,
     application_data: PathBuf,
     user_patches: PathBuf,
+    user_wavetables: PathBuf,
+    user_samples: PathBuf,
+    user_tunings: PathBuf,
+    global_settings: PathBuf,
 }
 
 /// Stores one patch name and value
@@ -221,6 +230,31 @@ impl Patches {
     pub fn user_patch_directory(&self) -> PathBuf {
         self.paths.user_patches.clone()
     }
+
+    /// Returns the path to the directory where the user wavetables are stored, next to the user patches
+    #[must_use]
+    pub fn user_wavetable_directory(&self) -> PathBuf {
+        self.paths.user_wavetables.clone()
+    }
+
+    /// Returns the path to the directory where the user samples are stored, next to the user patches
+    #[must_use]
+    pub fn user_sample_directory(&self) -> PathBuf {
+        self.paths.user_samples.clone()
+    }
+
+    /// Returns the path to the directory where the user Scala scales and keyboard mappings are stored, next
+    /// to the user patches
+    #[must_use]
+    pub fn user_tuning_directory(&self) -> PathBuf {
+        self.paths.user_tunings.clone()
+    }
+
+    /// Returns the path to the file the global settings are saved to, next to the user patches
+    #[must_use]
+    pub fn global_settings_file(&self) -> PathBuf {
+        self.paths.global_settings.clone()
+    }
 }
 
 /// Returns the embedded factory/system patches as `(name, content)` pairs, independent of any
@@ -363,14 +397,22 @@ fn create_data_paths() -> Result<Paths> {
     base.push(APP_SUPPORT_DIRECTORY);
     let application_data = base.join(DATA_DIRECTORY);
     let user_patches = application_data.join(USER_PATCH_DIRECTORY);
+    let user_wavetables = application_data.join(USER_WAVETABLE_DIRECTORY);
+    let user_samples = application_data.join(USER_SAMPLE_DIRECTORY);
+    let user_tunings = application_data.join(USER_TUNING_DIRECTORY);
+    let global_settings = application_data.join(GLOBAL_SETTINGS_FILE);
 
-    log::debug!(target: "synthesizer::patches", "Data paths resolved: base={}, data={}, patches={}",
-        base.display(), application_data.display(), user_patches.display());
+    log::debug!(target: "synthesizer::patches", "Data paths resolved: base={}, data={}, patches={}, wavetables={}, samples={}, tunings={}, settings={}",
+        base.display(), application_data.display(), user_patches.display(), user_wavetables.display(), user_samples.display(), user_tunings.display(), global_settings.display());
 
     let paths = Paths {
         base,
         application_data,
         user_patches,
+        user_wavetables,
+        user_samples,
+        user_tunings,
+        global_settings,
     };
 
     Ok(paths)
@@ -406,6 +448,36 @@ fn initialize_application_storage(paths: &mut Paths) -> Result<()> {
         log::info!(target: "synthesizer::patches", "Created user patches directory: {}", paths.user_patches.display());
     }
 
+    if !paths.user_wavetables.exists() {
+        log::debug!(target: "synthesizer::patches", "User wavetables directory does not exist. Creating: {}", paths
+            .user_wavetables.display());
+        std::fs::create_dir(&paths.user_wavetables).map_err(|e| {
+            log::error!(target: "synthesizer::patches", "Failed to create user wavetables directory {}: {e}", paths.user_wavetables.display());
+            e
+        })?;
+        log::info!(target: "synthesizer::patches", "Created user wavetables directory: {}", paths.user_wavetables.display());
+    }
+
+    if !paths.user_samples.exists() {
+        log::debug!(target: "synthesizer::patches", "User samples directory does not exist. Creating: {}", paths
+            .user_samples.display());
+        std::fs::create_dir(&paths.user_samples).map_err(|e| {
+            log::error!(target: "synthesizer::patches", "Failed to create user samples directory {}: {e}", paths.user_samples.display());
+            e
+        })?;
+        log::info!(target: "synthesizer::patches", "Created user samples directory: {}", paths.user_samples.display());
+    }
+
+    if !paths.user_tunings.exists() {
+        log::debug!(target: "synthesizer::patches", "User tunings directory does not exist. Creating: {}", paths
+            .user_tunings.display());
+        std::fs::create_dir(&paths.user_tunings).map_err(|e| {
+            log::error!(target: "synthesizer::patches", "Failed to create user tunings directory {}: {e}", paths.user_tunings.display());
+            e
+        })?;
+        log::info!(target: "synthesizer::patches", "Created user tunings directory: {}", paths.user_tunings.display());
+    }
+
     Ok(())
 }
 
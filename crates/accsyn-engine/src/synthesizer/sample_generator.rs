@@ -1,34 +1,319 @@
 use crate::modules::amplifier::amplify_stereo;
 use crate::modules::effects::Effects;
 use crate::modules::envelope::Envelope;
-use crate::modules::filter::Filter;
-use crate::modules::lfo::Lfo;
+use crate::modules::filter::DualFilter;
+use crate::modules::lfo::{Lfo, MAX_LFO_COUNT};
 use crate::modules::mixer::{MixerInput, output_mix, quad_mix};
-use crate::modules::oscillator::{HardSyncRole, Oscillator, WaveShape};
+use crate::modules::mod_matrix::{
+    EffectOffsets, ModulationOffsets, ModulationSource, ModulationSourceValues,
+    calculate_modulation_offsets, interpolate_effect_offsets,
+};
+use crate::modules::oscillator::{Oscillator, WaveShape};
 use crate::synthesizer;
-use crate::synthesizer::constants::SAMPLE_PRODUCER_LOOP_SLEEP_DURATION_MICROSECONDS;
+use crate::synthesizer::constants::{
+    SAMPLE_PRODUCER_LOOP_SLEEP_DURATION_MICROSECONDS, VOICE_MIX_GAIN_SMOOTHING_SECONDS,
+};
+use crate::synthesizer::midi_value_converters::release_time_scale_from_release_velocity;
+use crate::synthesizer::mpe::ExpressionValues;
+use crate::synthesizer::voices::{MAX_VOICE_COUNT, VoiceMode, VoiceState, VoiceStates};
 use crate::synthesizer::{CurrentNote, ModuleParameters};
 use accsyn_core::audio_events::OutputStreamParameters;
-use accsyn_core::math::load_f32_from_atomic_u32;
-use accsyn_core::synth_events::{EnvelopeIndex, LFOIndex, OscillatorIndex};
+use accsyn_core::math::{load_f32_from_atomic_u32, normalize_midi_value, store_f32_as_atomic_u32};
+use accsyn_core::synth_events::{EnvelopeIndex, OscillatorIndex};
 use anyhow::Result;
 use crossbeam_channel::Receiver;
 use rtrb::Producer;
 use std::sync::Arc;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU8;
 use std::sync::atomic::Ordering::Relaxed;
 use std::thread;
 use std::time::Duration;
 
-struct Modules {
+/// One complete signal path: four oscillators, the three envelopes, and the two filters.
+struct Voice {
     amp_envelope: Envelope,
     filter_envelope: Envelope,
     pitch_envelope: Envelope,
-    filter_lfo: Lfo,
-    filter: Filter,
-    mod_wheel_lfo: Lfo,
-    effects: Effects,
+    filter: DualFilter,
     oscillators: [Oscillator; 4],
+    amp_level: f32,
+    filter_envelope_level: f32,
+    pitch_envelope_level: f32,
+    modulation: ModulationOffsets,
+    modulation_start: ModulationOffsets,
+    modulation_target: ModulationOffsets,
+    oscillator_outputs: [f32; 4],
+    hard_sync_triggers: [Option<f32>; 4],
+}
+
+impl Voice {
+    fn new(sample_rate: u32, voice_index: usize) -> Self {
+        let mut voice = Self {
+            amp_envelope: Envelope::new(sample_rate),
+            filter_envelope: Envelope::new(sample_rate),
+            pitch_envelope: Envelope::new(sample_rate),
+            filter: DualFilter::new(sample_rate),
+            oscillators: [
+                Oscillator::new(sample_rate, WaveShape::default()),
+                Oscillator::new(sample_rate, WaveShape::default()),
+                Oscillator::new(sample_rate, WaveShape::default()),
+                Oscillator::new(sample_rate, WaveShape::default()),
+            ],
+            amp_level: 0.0,
+            filter_envelope_level: 0.0,
+            pitch_envelope_level: 0.0,
+            modulation: ModulationOffsets::default(),
+            modulation_start: ModulationOffsets::default(),
+            modulation_target: ModulationOffsets::default(),
+            oscillator_outputs: [0.0; 4],
+            hard_sync_triggers: [None; 4],
+        };
+
+        voice.oscillators[OscillatorIndex::Sub as usize].set_is_sub_oscillator(true);
+        let oscillator_count = voice.oscillators.len();
+        for (index, oscillator) in voice.oscillators.iter_mut().enumerate() {
+            // Voice and oscillator counts are small constants, well within u32 range
+            #[allow(clippy::cast_possible_truncation)]
+            let stream = (voice_index * oscillator_count + index) as u32;
+            oscillator.set_drift_stream(stream);
+        }
+
+        voice
+    }
+
+    /// Silences the voice and clears its oscillator, envelope, filter and modulation state in place, keeping
+    /// everything it allocated so a voice mode or count change never allocates on the audio thread.
+    fn reset(&mut self) {
+        self.amp_envelope.reset();
+        self.filter_envelope.reset();
+        self.pitch_envelope.reset();
+        self.filter.reset();
+        for oscillator in &mut self.oscillators {
+            oscillator.reset_voice_state();
+        }
+        self.amp_level = 0.0;
+        self.filter_envelope_level = 0.0;
+        self.pitch_envelope_level = 0.0;
+        self.modulation = ModulationOffsets::default();
+        self.modulation_start = ModulationOffsets::default();
+        self.modulation_target = ModulationOffsets::default();
+        self.oscillator_outputs = [0.0; 4];
+        self.hard_sync_triggers = [None; 4];
+    }
+
+    fn set_parameters(
+        &mut self,
+        module_parameters: &ModuleParameters,
+        midi_note: u8,
+        oscillator_notes: [u8; 4],
+        voice_state: Option<&VoiceState>,
+        expression: ExpressionValues,
+        modulation: ModulationOffsets,
+    ) {
+        // The offsets ramp from the last buffer's target to this one's, see ramp_modulation
+        self.modulation_start = self.modulation_target;
+        self.modulation_target = modulation;
+        self.modulation = self.modulation_start;
+
+        self.amp_envelope
+            .set_parameters(&module_parameters.envelopes[EnvelopeIndex::Amp as usize]);
+        self.filter_envelope
+            .set_parameters(&module_parameters.envelopes[EnvelopeIndex::Filter as usize]);
+        self.pitch_envelope
+            .set_parameters(&module_parameters.envelopes[EnvelopeIndex::Pitch as usize]);
+
+        self.filter.set_microtuning(&module_parameters.tuning);
+        let filter_note_number = match voice_state {
+            Some(_) => midi_note,
+            None => module_parameters.filter.current_note_number.load(Relaxed),
+        };
+        self.filter.set_parameters(
+            &module_parameters.filter,
+            &module_parameters.second_filter,
+            filter_note_number,
+        );
+
+        // MPE pressure drives the aftertouch clipper boost of its own note alongside channel aftertouch
+        let aftertouch = module_parameters
+            .keyboard
+            .aftertouch_amount
+            .load()
+            .max(expression.pressure);
+        // Pitch is retuned once per buffer, so its modulation goes straight to this buffer's target
+        let note_pitch_bend = expression
+            .pitch_bend_cents(
+                module_parameters
+                    .keyboard
+                    .mpe_pitch_bend_range
+                    .load(Relaxed),
+            )
+            .saturating_add(self.modulation_target.pitch_cents());
+
+        for (index, oscillator) in self.oscillators.iter_mut().enumerate() {
+            oscillator.set_aftertouch(aftertouch);
+            oscillator.set_parameters(&module_parameters.oscillators[index]);
+            oscillator.set_note_pitch_bend(note_pitch_bend);
+            oscillator.set_drift(&module_parameters.drift);
+            oscillator.set_microtuning(&module_parameters.tuning);
+            if let Some(voice_state) = voice_state {
+                oscillator.set_gate(&voice_state.oscillator_gate_flags[index]);
+            }
+            oscillator.tune(oscillator_notes[index]);
+        }
+
+        self.apply_modulation(module_parameters);
+    }
+
+    /// Moves the modulation offsets `fraction` of the way from the last buffer's offsets to this buffer's.
+    fn ramp_modulation(&mut self, module_parameters: &ModuleParameters, fraction: f32) {
+        if self.modulation_start == self.modulation_target {
+            return;
+        }
+
+        self.modulation = self
+            .modulation_start
+            .interpolate(&self.modulation_target, fraction);
+        self.apply_modulation(module_parameters);
+    }
+
+    fn apply_modulation(&mut self, module_parameters: &ModuleParameters) {
+        self.filter.apply_modulation(
+            &self.modulation.filter,
+            &self.modulation.second_filter,
+            self.modulation.filter_crossfade,
+        );
+        self.filter_envelope
+            .set_amount_modulation(self.modulation.filter_envelope_amount);
+        for (index, oscillator) in self.oscillators.iter_mut().enumerate() {
+            oscillator.set_shape_modulation(
+                &module_parameters.oscillators[index],
+                &self.modulation.oscillators[index],
+            );
+        }
+    }
+
+    /// Scales the release times of all three envelopes, applied on the next `set_parameters`.
+    fn set_release_time_scale(&mut self, scale: f32) {
+        self.amp_envelope.set_release_time_scale(scale);
+        self.filter_envelope.set_release_time_scale(scale);
+        self.pitch_envelope.set_release_time_scale(scale);
+    }
+
+    fn check_gates(&mut self, gate_flags: [&AtomicU8; 3]) {
+        self.filter_envelope
+            .check_gate(gate_flags[EnvelopeIndex::Filter as usize]);
+        self.amp_envelope
+            .check_gate(gate_flags[EnvelopeIndex::Amp as usize]);
+        self.pitch_envelope
+            .check_gate(gate_flags[EnvelopeIndex::Pitch as usize]);
+    }
+
+    fn is_sounding(&self) -> bool {
+        self.amp_envelope.is_active() || self.amp_envelope.get_is_inverted()
+    }
+
+    /// Returns the modulation matrix source values for this voice, filling in its own envelopes, velocity,
+    /// key, MPE expression and release velocity on top of the shared sources.
+    fn modulation_source_values(
+        &self,
+        shared_source_values: &ModulationSourceValues,
+        midi_note: u8,
+        velocity: f32,
+        expression: ExpressionValues,
+        release_velocity: f32,
+    ) -> ModulationSourceValues {
+        let mut source_values = *shared_source_values;
+        source_values.set(ModulationSource::AmpEnvelope, self.amp_level);
+        source_values.set(ModulationSource::FilterEnvelope, self.filter_envelope_level);
+        source_values.set(ModulationSource::PitchEnvelope, self.pitch_envelope_level);
+        source_values.set(ModulationSource::Velocity, velocity);
+        source_values.set(ModulationSource::Key, normalize_midi_value(midi_note));
+        source_values.set(ModulationSource::NotePitchBend, expression.pitch_bend);
+        source_values.set(ModulationSource::NotePressure, expression.pressure);
+        source_values.set(ModulationSource::NoteTimbre, expression.timbre);
+        source_values.set(ModulationSource::ReleaseVelocity, release_velocity);
+        source_values
+    }
+
+    fn generate(
+        &mut self,
+        module_parameters: &ModuleParameters,
+        mut quad_mixer_inputs: [MixerInput; 4],
+        vibrato_value: f32,
+        filter_lfo_value: f32,
+        velocity: f32,
+    ) -> (f32, f32) {
+        let pitch_envelope_value = self.pitch_envelope.generate();
+        self.pitch_envelope_level = pitch_envelope_value;
+
+        for (index, input) in quad_mixer_inputs.iter_mut().enumerate() {
+            let modulation = &self.modulation.oscillators[index];
+            input.level = (input.level + modulation.level).clamp(0.0, 1.0);
+            input.balance = (input.balance + modulation.balance).clamp(-1.0, 1.0);
+
+            let pitch_envelope_amount = module_parameters.oscillators[index]
+                .pitch_envelope_amount
+                .load();
+            // Cross modulation and sync sources before this oscillator have already produced this frame's
+            // sample, later ones (and the oscillator itself) are heard one sample late
+            let cross_modulation_source = self.oscillators[index].cross_modulation_source();
+            self.oscillators[index]
+                .set_cross_modulation_input(self.oscillator_outputs[cross_modulation_source]);
+            let hard_sync_trigger = self.oscillators[index]
+                .hard_sync_source()
+                .source_oscillator(index)
+                .and_then(|source| {
+                    // A later source's cycle start was found in the previous frame, one more sample back
+                    self.hard_sync_triggers[source]
+                        .map(|delay| if source > index { delay + 1.0 } else { delay })
+                });
+            self.oscillators[index].set_hard_sync_trigger(hard_sync_trigger);
+            self.oscillator_outputs[index] = self.oscillators[index].generate(
+                Some(vibrato_value),
+                Some(pitch_envelope_value * pitch_envelope_amount),
+            );
+            input.sample = self.oscillators[index].stereo_sample();
+            self.hard_sync_triggers[index] = self.oscillators[index].hard_sync_trigger();
+        }
+
+        // Any per-oscillator processing should happen before this stereo mix down
+        let (oscillator_mix_left, oscillator_mix_right) = quad_mix(quad_mixer_inputs);
+
+        self.amp_level = self.amp_envelope.generate();
+
+        let (left_envelope_sample, right_envelope_sample) = amplify_stereo(
+            oscillator_mix_left,
+            oscillator_mix_right,
+            Some(velocity),
+            Some(self.amp_level),
+        );
+
+        let mut filter_envelope_value = self.filter_envelope.generate();
+        self.filter_envelope_level = filter_envelope_value;
+
+        if self.filter_envelope.get_is_inverted() {
+            filter_envelope_value -= 1.0;
+        }
+
+        self.filter.process(
+            &module_parameters.filter_routing,
+            left_envelope_sample,
+            right_envelope_sample,
+            filter_envelope_value,
+            filter_lfo_value,
+        )
+    }
+}
+
+struct Modules {
+    voices: Vec<Voice>,
+    lfos: Vec<Lfo>,
+    lfo_values: [f32; MAX_LFO_COUNT],
+    effects: Effects,
+    effects_modulation_start: EffectOffsets,
+    effects_modulation_target: EffectOffsets,
+    voice_mix_gain: f32,
+    voice_mix_gain_smoothing: f32,
     #[cfg(debug_assertions)]
     profile_counter: u64,
 }
@@ -43,9 +328,11 @@ pub fn sample_generator(
     sample_buffer_receiver: Receiver<Producer<f32>>,
     output_stream_parameters: OutputStreamParameters,
     current_note: &Arc<CurrentNote>,
+    voice_states: &Arc<VoiceStates>,
     module_parameters: &Arc<ModuleParameters>,
 ) -> Result<()> {
     let current_note = current_note.clone();
+    let voice_states = voice_states.clone();
     let module_parameters = module_parameters.clone();
 
     log::debug!(target: "synthesizer::sample_generator", "Blocking till we receive a sample buffer producer from the audio module");
@@ -57,6 +344,7 @@ pub fn sample_generator(
             &sample_buffer_receiver,
             &output_stream_parameters,
             &current_note,
+            &voice_states,
             &module_parameters,
             sample_buffer,
         );
@@ -74,6 +362,7 @@ fn generate_audio_samples(
     sample_buffer_receiver: &Receiver<Producer<f32>>,
     output_stream_parameters: &OutputStreamParameters,
     current_note: &Arc<CurrentNote>,
+    voice_states: &Arc<VoiceStates>,
     module_parameters: &Arc<ModuleParameters>,
     mut sample_buffer: Producer<f32>,
 ) {
@@ -82,6 +371,8 @@ fn generate_audio_samples(
 
     let mut previous_sample_rate = 0;
     let mut previous_buffer_size = 0;
+    let mut previous_voice_mode = VoiceMode::default();
+    let mut previous_voice_count = 1;
 
     let mut modules = initialize_synth_modules(sample_rate);
 
@@ -120,102 +411,217 @@ fn generate_audio_samples(
             previous_buffer_size = current_buffer_size;
         }
 
+        let voice_mode = module_parameters.voices.voice_mode();
+        let voice_count = match voice_mode {
+            VoiceMode::Mono | VoiceMode::Paraphonic => 1,
+            VoiceMode::Poly => module_parameters.voices.voice_count(),
+        };
+
+        if voice_mode != previous_voice_mode {
+            log::info!(
+                target: "synthesizer::sample_generator",
+                "Voice mode changed from {previous_voice_mode:?} to {voice_mode:?}. Resetting voices."
+            );
+
+            for voice in &mut modules.voices {
+                voice.reset();
+            }
+            previous_voice_mode = voice_mode;
+        } else if voice_count < previous_voice_count {
+            for voice in &mut modules.voices[voice_count..previous_voice_count] {
+                voice.reset();
+            }
+        }
+        previous_voice_count = voice_count;
+
         // Process the module parameters per buffer
-        modules
-            .amp_envelope
-            .set_parameters(&module_parameters.envelopes[EnvelopeIndex::Amp as usize]);
-        modules
-            .filter_envelope
-            .set_parameters(&module_parameters.envelopes[EnvelopeIndex::Filter as usize]);
-        modules
-            .pitch_envelope
-            .set_parameters(&module_parameters.envelopes[EnvelopeIndex::Pitch as usize]);
-        modules
-            .filter_lfo
-            .set_parameters(&module_parameters.lfos[LFOIndex::Filter as usize]);
-        modules.filter.set_parameters(&module_parameters.filter);
-        modules
-            .mod_wheel_lfo
-            .set_parameters(&module_parameters.lfos[LFOIndex::ModWheel as usize]);
+        let lfo_count = module_parameters.lfos.lfo_count();
+        for (lfo, lfo_parameters) in modules.lfos[..lfo_count]
+            .iter_mut()
+            .zip(module_parameters.lfos.active_lfos())
+        {
+            lfo.set_parameters(lfo_parameters);
+        }
+        modules.lfo_values[lfo_count..].fill(0.0);
 
         modules.effects.set_parameters(&module_parameters.effects);
 
-        for (index, oscillator) in modules.oscillators.iter_mut().enumerate() {
-            oscillator.set_aftertouch(module_parameters.keyboard.aftertouch_amount.load());
-            oscillator.set_parameters(&module_parameters.oscillators[index]);
-            oscillator.tune(current_note.midi_note.load(Relaxed));
+        // The modulation matrix is evaluated once per buffer from the most recent source values, and the offsets
+        // are interpolated from the last buffer's across this one so modulated parameters move without steps
+        let shared_source_values = shared_modulation_source_values(&modules, module_parameters);
+
+        // The effects are shared by every voice, so only the shared sources can drive them
+        modules.effects_modulation_start = modules.effects_modulation_target;
+        modules.effects_modulation_target =
+            calculate_modulation_offsets(&module_parameters.mod_matrix, &shared_source_values)
+                .effects;
+        let effects_modulation_is_ramping =
+            modules.effects_modulation_start != modules.effects_modulation_target;
+        modules
+            .effects
+            .apply_modulation(&modules.effects_modulation_start);
+
+        // Buffer sizes are far below f32's exact integer range
+        #[allow(clippy::cast_precision_loss)]
+        let modulation_fraction_increment = (current_buffer_size.max(1) as f32).recip();
+        let mut modulation_fraction = 0.0;
+
+        let mut voice_velocities = [0.0; MAX_VOICE_COUNT];
+        for (index, voice) in modules.voices[..voice_count].iter_mut().enumerate() {
+            let (midi_note, oscillator_notes, voice_state, velocity) = match voice_mode {
+                VoiceMode::Mono => {
+                    let midi_note = current_note.midi_note.load(Relaxed);
+                    let velocity = load_f32_from_atomic_u32(&current_note.velocity);
+                    (midi_note, [midi_note; 4], None, velocity)
+                }
+                VoiceMode::Paraphonic => {
+                    let oscillator_notes = current_note
+                        .oscillator_notes
+                        .each_ref()
+                        .map(|oscillator_note| oscillator_note.load(Relaxed));
+                    let velocity = load_f32_from_atomic_u32(&current_note.velocity);
+                    (
+                        current_note.midi_note.load(Relaxed),
+                        oscillator_notes,
+                        None,
+                        velocity,
+                    )
+                }
+                VoiceMode::Poly => {
+                    let voice_state = &voice_states[index];
+                    let midi_note = voice_state.midi_note.load(Relaxed);
+                    let velocity = load_f32_from_atomic_u32(&voice_state.velocity);
+                    (midi_note, [midi_note; 4], Some(voice_state), velocity)
+                }
+            };
+
+            let (expression, release_velocity) = match voice_state {
+                Some(voice_state) => (
+                    voice_state.expression.load(),
+                    load_f32_from_atomic_u32(&voice_state.release_velocity),
+                ),
+                None => (
+                    current_note.expression.load(),
+                    load_f32_from_atomic_u32(&current_note.release_velocity),
+                ),
+            };
+
+            let source_values = voice.modulation_source_values(
+                &shared_source_values,
+                midi_note,
+                velocity,
+                expression,
+                release_velocity,
+            );
+            let modulation =
+                calculate_modulation_offsets(&module_parameters.mod_matrix, &source_values);
+
+            voice.set_release_time_scale(release_time_scale_from_release_velocity(
+                release_velocity,
+                module_parameters.keyboard.release_velocity_amount.load(),
+            ));
+            voice.set_parameters(
+                module_parameters,
+                midi_note,
+                oscillator_notes,
+                voice_state,
+                expression,
+                modulation,
+            );
+            voice_velocities[index] = velocity;
         }
 
         // Begin processing the audio buffer
-        let mut quad_mixer_inputs: [MixerInput; 4] =
+        let quad_mixer_inputs: [MixerInput; 4] =
             synthesizer::create_quad_mixer_inputs(module_parameters);
 
         let vibrato_amount = module_parameters.keyboard.mod_wheel_amount.load();
-        modules.mod_wheel_lfo.set_range(vibrato_amount / 4.0);
+        let vibrato_lfo = module_parameters.lfo_routing.vibrato_lfo();
+        let filter_lfo = module_parameters.lfo_routing.filter_lfo();
+        modules.lfos[vibrato_lfo].set_range(vibrato_amount / 4.0);
 
         let output_level = module_parameters.mixer.level.load();
         let output_balance = module_parameters.mixer.balance.load();
         let output_is_muted = module_parameters.mixer.is_muted.load(Relaxed);
-        let velocity = load_f32_from_atomic_u32(&current_note.velocity);
         let output_soft_clip_is_enabled =
             module_parameters.mixer.soft_clip_is_enabled.load(Relaxed);
 
+        let mono_gate_flags = [
+            &module_parameters.envelopes[EnvelopeIndex::Amp as usize].gate_flag,
+            &module_parameters.envelopes[EnvelopeIndex::Filter as usize].gate_flag,
+            &module_parameters.envelopes[EnvelopeIndex::Pitch as usize].gate_flag,
+        ];
+
         // Loop Here
 
         while local_buffer.len() < stereo_buffer_size {
             // Begin generating and processing the samples for the frame
-            modules
-                .filter_envelope
-                .check_gate(&module_parameters.envelopes[EnvelopeIndex::Filter as usize].gate_flag);
-            modules
-                .amp_envelope
-                .check_gate(&module_parameters.envelopes[EnvelopeIndex::Amp as usize].gate_flag);
-            modules
-                .pitch_envelope
-                .check_gate(&module_parameters.envelopes[EnvelopeIndex::Pitch as usize].gate_flag);
-
-            let pitch_envelope_value = modules.pitch_envelope.generate();
-
-            let vibrato_value = modules.mod_wheel_lfo.generate(None);
-
-            for (index, input) in quad_mixer_inputs.iter_mut().enumerate() {
-                let pitch_envelope_amount = module_parameters.oscillators[index]
-                    .pitch_envelope_amount
-                    .load();
-                input.sample = modules.oscillators[index].generate(
-                    Some(vibrato_value),
-                    Some(pitch_envelope_value * pitch_envelope_amount),
-                );
+            for (lfo_value, lfo) in modules
+                .lfo_values
+                .iter_mut()
+                .zip(&mut modules.lfos[..lfo_count])
+            {
+                *lfo_value = lfo.generate(None);
+            }
+            // An LFO past the running count reads 0.0, so a route to it is silent
+            let vibrato_value = modules.lfo_values[vibrato_lfo];
+            let filter_lfo_value = modules.lfo_values[filter_lfo];
+
+            modulation_fraction = (modulation_fraction + modulation_fraction_increment).min(1.0);
+            if effects_modulation_is_ramping {
+                modules
+                    .effects
+                    .apply_modulation(&interpolate_effect_offsets(
+                        &modules.effects_modulation_start,
+                        &modules.effects_modulation_target,
+                        modulation_fraction,
+                    ));
             }
 
-            // Any per-oscillator processing should happen before this stereo mix down
-            let (oscillator_mix_left, oscillator_mix_right) = quad_mix(quad_mixer_inputs);
-
-            let amp_envelope_value = Some(modules.amp_envelope.generate());
-
-            let (left_envelope_sample, right_envelope_sample) = amplify_stereo(
-                oscillator_mix_left,
-                oscillator_mix_right,
-                Some(velocity),
-                amp_envelope_value,
-            );
-
-            let mut filter_envelope_value = modules.filter_envelope.generate();
-            let filter_lfo_value = modules.filter_lfo.generate(None);
+            let mut voice_mix_left = 0.0;
+            let mut voice_mix_right = 0.0;
+            let mut sounding_voice_count: u8 = 0;
+
+            for (index, voice) in modules.voices[..voice_count].iter_mut().enumerate() {
+                match voice_mode {
+                    VoiceMode::Mono | VoiceMode::Paraphonic => voice.check_gates(mono_gate_flags),
+                    VoiceMode::Poly => {
+                        let [amp_gate_flag, filter_gate_flag, pitch_gate_flag] =
+                            &voice_states[index].envelope_gate_flags;
+                        voice.check_gates([amp_gate_flag, filter_gate_flag, pitch_gate_flag]);
+
+                        if !voice.is_sounding() {
+                            continue;
+                        }
+                    }
+                }
+
+                voice.ramp_modulation(module_parameters, modulation_fraction);
+
+                let (voice_left, voice_right) = voice.generate(
+                    module_parameters,
+                    quad_mixer_inputs,
+                    vibrato_value,
+                    filter_lfo_value,
+                    voice_velocities[index],
+                );
 
-            if modules.filter_envelope.get_is_inverted() {
-                filter_envelope_value -= 1.0;
+                voice_mix_left += voice_left;
+                voice_mix_right += voice_right;
+                sounding_voice_count += 1;
             }
 
-            let (filtered_left, filtered_right) = modules.filter.process(
-                left_envelope_sample,
-                right_envelope_sample,
-                filter_envelope_value,
-                filter_lfo_value,
-            );
+            // Summed voices are scaled by 1/sqrt(N) of the voices sounding right now, so a single note plays at
+            // full level whatever the voice count. The gain glides to avoid steps as voices start and finish
+            let target_voice_mix_gain = f32::from(sounding_voice_count.max(1)).sqrt().recip();
+            modules.voice_mix_gain +=
+                (target_voice_mix_gain - modules.voice_mix_gain) * modules.voice_mix_gain_smoothing;
 
-            let (mut effected_left, mut effected_right) =
-                modules.effects.process((filtered_left, filtered_right));
+            // Any per-voice processing should happen before this voice mix down
+            let (mut effected_left, mut effected_right) = modules.effects.process((
+                voice_mix_left * modules.voice_mix_gain,
+                voice_mix_right * modules.voice_mix_gain,
+            ));
 
             if module_parameters.keyboard.polarity_flipped.load(Relaxed) {
                 effected_left *= -1.0;
@@ -239,6 +645,16 @@ fn generate_audio_samples(
             local_buffer.push(output_right);
         }
 
+        // Publish each voice's level so the voice allocator can find the quietest voice to steal
+        if voice_mode == VoiceMode::Poly {
+            for (voice, voice_state) in modules.voices[..voice_count]
+                .iter()
+                .zip(voice_states.iter())
+            {
+                store_f32_as_atomic_u32(&voice_state.level, voice.amp_level);
+            }
+        }
+
         // Add a performance counter in debug builds only. use RUST_LOG=hot_loop cargo run to print perf data
         #[cfg(debug_assertions)]
         {
@@ -282,6 +698,38 @@ fn generate_audio_samples(
     }
 }
 
+fn shared_modulation_source_values(
+    modules: &Modules,
+    module_parameters: &ModuleParameters,
+) -> ModulationSourceValues {
+    let pitch_bend_range_cents =
+        f32::from(module_parameters.keyboard.pitch_bend_range.load(Relaxed)) * 100.0;
+    let pitch_bend = if pitch_bend_range_cents > 0.0 {
+        f32::from(
+            module_parameters.oscillators[OscillatorIndex::Sub as usize]
+                .pitch_bend
+                .load(),
+        ) / pitch_bend_range_cents
+    } else {
+        0.0
+    };
+
+    let mut source_values = ModulationSourceValues::default();
+    for (lfo_index, lfo_value) in modules.lfo_values.iter().enumerate() {
+        source_values.set(ModulationSource::lfo(lfo_index), *lfo_value);
+    }
+    source_values.set(
+        ModulationSource::Aftertouch,
+        module_parameters.keyboard.aftertouch_amount.load(),
+    );
+    source_values.set(
+        ModulationSource::ModWheel,
+        module_parameters.keyboard.mod_wheel_amount.load(),
+    );
+    source_values.set(ModulationSource::PitchBend, pitch_bend.clamp(-1.0, 1.0));
+    source_values
+}
+
 fn output_soft_clip(sample: f32) -> f32 {
     const THRESHOLD: f32 = 0.90;
     let abs_sample = sample.abs();
@@ -295,30 +743,21 @@ fn output_soft_clip(sample: f32) -> f32 {
 }
 
 fn initialize_synth_modules(sample_rate: u32) -> Modules {
-    let mut modules = Modules {
-        amp_envelope: Envelope::new(sample_rate),
-        filter_envelope: Envelope::new(sample_rate),
-        pitch_envelope: Envelope::new(sample_rate),
-        filter_lfo: Lfo::new(sample_rate),
-        filter: Filter::new(sample_rate),
-        mod_wheel_lfo: Lfo::new(sample_rate),
+    Modules {
+        voices: (0..MAX_VOICE_COUNT)
+            .map(|voice_index| Voice::new(sample_rate, voice_index))
+            .collect(),
+        lfos: (0..MAX_LFO_COUNT).map(|_| Lfo::new(sample_rate)).collect(),
+        lfo_values: [0.0; MAX_LFO_COUNT],
         effects: Effects::new(sample_rate),
-        oscillators: [
-            Oscillator::new(sample_rate, WaveShape::default()),
-            Oscillator::new(sample_rate, WaveShape::default()),
-            Oscillator::new(sample_rate, WaveShape::default()),
-            Oscillator::new(sample_rate, WaveShape::default()),
-        ],
+        effects_modulation_start: EffectOffsets::default(),
+        effects_modulation_target: EffectOffsets::default(),
+        voice_mix_gain: 1.0,
+        // Sample rates are well below f32's exact integer range
+        #[allow(clippy::cast_precision_loss)]
+        voice_mix_gain_smoothing: 1.0
+            - (-1.0 / (VOICE_MIX_GAIN_SMOOTHING_SECONDS * sample_rate as f32)).exp(),
         #[cfg(debug_assertions)]
         profile_counter: 0,
-    };
-
-    modules.oscillators[OscillatorIndex::Sub as usize].set_is_sub_oscillator(true);
-    let oscillator_hard_sync_buffer = Arc::new(AtomicBool::new(false));
-    modules.oscillators[OscillatorIndex::One as usize]
-        .set_hard_sync_role(HardSyncRole::Source(oscillator_hard_sync_buffer.clone()));
-    modules.oscillators[OscillatorIndex::Two as usize]
-        .set_hard_sync_role(HardSyncRole::Synced(oscillator_hard_sync_buffer.clone()));
-
-    modules
+    }
 }
@@ -1,19 +1,39 @@
 use crate::modules::effects::{AudioEffectParameters, EffectIndex};
 use crate::modules::envelope::{
-    EnvelopeParameters, MAX_ATTACK_MILLISECONDS, MAX_DECAY_MILLISECONDS, MAX_RELEASE_MILLISECONDS,
-    MIN_ATTACK_MILLISECONDS, MIN_DECAY_MILLISECONDS, MIN_RELEASE_MILLISECONDS,
+    EnvelopeParameters, EnvelopeSegment, MAX_ATTACK_MILLISECONDS, MAX_DECAY_MILLISECONDS,
+    MAX_DELAY_MILLISECONDS, MAX_HOLD_MILLISECONDS, MAX_RELEASE_MILLISECONDS,
+    MIN_ATTACK_MILLISECONDS, MIN_DECAY_MILLISECONDS, MIN_DELAY_MILLISECONDS, MIN_HOLD_MILLISECONDS,
+    MIN_RELEASE_MILLISECONDS,
+};
+use crate::modules::filter::{
+    FilterParameters, FilterRouting, FilterRoutingParameters, FilterType,
+};
+use crate::modules::lfo::{LfoBank, LfoParameters, LfoRoutingParameters};
+use crate::modules::mod_matrix::{
+    ModulationDestination, ModulationSlotParameters, ModulationSource,
 };
-use crate::modules::filter::FilterParameters;
-use crate::modules::lfo::LfoParameters;
-use crate::modules::oscillator::OscillatorParameters;
 use crate::modules::oscillator::constants::{MAX_CLIP_BOOST, MIN_CLIP_BOOST};
-use crate::synthesizer::constants::{MAX_PITCH_BEND_RANGE, MIN_PITCH_BEND_RANGE};
+use crate::modules::oscillator::drift::DriftParameters;
+use crate::modules::oscillator::supersaw::clamp_supersaw_voice_count;
+use crate::modules::oscillator::{
+    CrossModulationMode, HardSyncSource, OscillatorParameters, SubOctave,
+};
+use crate::modules::tuning::TuningParameters;
+use crate::synthesizer::constants::{
+    MAX_MPE_PITCH_BEND_RANGE, MAX_PITCH_BEND_RANGE, MIN_MPE_PITCH_BEND_RANGE, MIN_PITCH_BEND_RANGE,
+};
 use crate::synthesizer::midi_value_converters::{
     exponential_curve_filter_cutoff_from_normal_value,
     exponential_curve_lfo_frequency_from_normal_value, normal_value_to_bool,
-    normal_value_to_f32_range, normal_value_to_number_of_filter_poles,
-    normal_value_to_signed_integer_range, normal_value_to_unsigned_integer_range,
-    normal_value_to_wave_shape_index, velocity_curve_from_normal_value,
+    normal_value_to_f32_range, normal_value_to_lfo_wave_shape_index,
+    normal_value_to_number_of_filter_poles, normal_value_to_signed_integer_range,
+    normal_value_to_unsigned_integer_range, normal_value_to_wave_shape_index,
+    velocity_curve_from_normal_value,
+};
+use crate::synthesizer::note_stack::NotePriority;
+use crate::synthesizer::paraphonic::ParaphonicAssignment;
+use crate::synthesizer::voices::{
+    VoiceMode, VoiceParameters, VoiceStealingPolicy, clamp_voice_count,
 };
 use crate::synthesizer::{KeyboardParameters, MixerParameters, ModuleParameters};
 use accsyn_core::defaults::Defaults;
@@ -25,9 +45,22 @@ use accsyn_core::math::{
     exponential_curve_level_adjustment_from_normal_value,
 };
 use accsyn_core::synth_events::OscillatorIndex;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::Ordering::Relaxed;
 
+pub fn set_lfo_count(parameters: &LfoBank, lfo_count: u8) {
+    parameters.set_lfo_count(usize::from(lfo_count));
+}
+
+pub fn set_vibrato_lfo(parameters: &LfoRoutingParameters, lfo_index: u8) {
+    parameters.set_vibrato_lfo(usize::from(lfo_index));
+}
+
+pub fn set_filter_lfo(parameters: &LfoRoutingParameters, lfo_index: u8) {
+    parameters.set_filter_lfo(usize::from(lfo_index));
+}
+
 pub fn set_lfo_clock_sync(parameters: &LfoParameters, is_enabled: bool) {
     parameters.clock_synced.store(is_enabled, Relaxed);
 }
@@ -60,7 +93,7 @@ pub fn set_lfo_phase(parameters: &LfoParameters, normal_value: f32) {
 }
 
 pub fn set_lfo_wave_shape(parameters: &LfoParameters, normal_value: f32) {
-    let wave_shape_index = normal_value_to_wave_shape_index(normal_value);
+    let wave_shape_index = normal_value_to_lfo_wave_shape_index(normal_value);
     parameters.wave_shape.store(wave_shape_index, Relaxed);
 }
 
@@ -124,6 +157,55 @@ pub fn set_envelope_inverted(envelope_parameters: &EnvelopeParameters, normal_va
     envelope_parameters.is_inverted.store(is_inverted, Relaxed);
 }
 
+pub fn set_envelope_attack_curve(envelope_parameters: &EnvelopeParameters, normal_value: f32) {
+    let curve = normal_value_to_f32_range(normal_value, -1.0, 1.0);
+    envelope_parameters.attack_curve.store(curve);
+}
+
+pub fn set_envelope_decay_curve(envelope_parameters: &EnvelopeParameters, normal_value: f32) {
+    let curve = normal_value_to_f32_range(normal_value, -1.0, 1.0);
+    envelope_parameters.decay_curve.store(curve);
+}
+
+pub fn set_envelope_release_curve(envelope_parameters: &EnvelopeParameters, normal_value: f32) {
+    let curve = normal_value_to_f32_range(normal_value, -1.0, 1.0);
+    envelope_parameters.release_curve.store(curve);
+}
+
+pub fn set_envelope_delay_time(envelope_parameters: &EnvelopeParameters, normal_value: f32) {
+    let milliseconds = exponential_curve_envelope_time_from_normal_value(
+        normal_value,
+        EXPONENTIAL_ENVELOPE_CURVE_ATTACK_VALUES,
+        MIN_DELAY_MILLISECONDS,
+        MAX_DELAY_MILLISECONDS,
+    );
+    envelope_parameters.delay_ms.store(milliseconds);
+}
+
+pub fn set_envelope_hold_time(envelope_parameters: &EnvelopeParameters, normal_value: f32) {
+    let milliseconds = exponential_curve_envelope_time_from_normal_value(
+        normal_value,
+        EXPONENTIAL_ENVELOPE_CURVE_ATTACK_VALUES,
+        MIN_HOLD_MILLISECONDS,
+        MAX_HOLD_MILLISECONDS,
+    );
+    envelope_parameters.hold_ms.store(milliseconds);
+}
+
+pub fn set_envelope_loop_enabled(envelope_parameters: &EnvelopeParameters, is_enabled: bool) {
+    envelope_parameters.loop_enabled.store(is_enabled, Relaxed);
+}
+
+pub fn set_envelope_loop_start(envelope_parameters: &EnvelopeParameters, segment_index: u8) {
+    let segment = EnvelopeSegment::from_index(segment_index);
+    envelope_parameters.loop_start.store(segment as u8, Relaxed);
+}
+
+pub fn set_envelope_loop_end(envelope_parameters: &EnvelopeParameters, segment_index: u8) {
+    let segment = EnvelopeSegment::from_index(segment_index);
+    envelope_parameters.loop_end.store(segment as u8, Relaxed);
+}
+
 pub fn set_filter_resonance(filter_parameters: &FilterParameters, normal_value: f32) {
     let resonance = normal_value_to_f32_range(
         normal_value,
@@ -139,6 +221,26 @@ pub fn set_filter_poles(filter_parameters: &FilterParameters, normal_value: f32)
     filter_parameters.filter_poles.store(filter_poles);
 }
 
+pub fn set_filter_type(filter_parameters: &FilterParameters, filter_type_index: u8) {
+    let filter_type = FilterType::from_index(filter_type_index);
+    filter_parameters
+        .filter_type
+        .store(filter_type as u8, Relaxed);
+}
+
+pub fn set_filter_drive(filter_parameters: &FilterParameters, normal_value: f32) {
+    filter_parameters.drive.store(normal_value);
+}
+
+pub fn set_filter_routing(routing_parameters: &FilterRoutingParameters, routing_index: u8) {
+    let routing = FilterRouting::from_index(routing_index);
+    routing_parameters.routing.store(routing as u8, Relaxed);
+}
+
+pub fn set_filter_crossfade(routing_parameters: &FilterRoutingParameters, normal_value: f32) {
+    routing_parameters.crossfade.store(normal_value);
+}
+
 pub fn set_filter_cutoff(filter_parameters: &FilterParameters, normal_value: f32) {
     let cutoff_frequency = exponential_curve_filter_cutoff_from_normal_value(normal_value);
     filter_parameters.cutoff_frequency.store(cutoff_frequency);
@@ -164,6 +266,12 @@ pub fn set_velocity_curve(parameters: &KeyboardParameters, normal_value: f32) {
     parameters.velocity_curve.store(velocity_curve);
 }
 
+pub fn set_release_velocity_amount(parameters: &KeyboardParameters, normal_value: f32) {
+    parameters
+        .release_velocity_amount
+        .store(normal_value.clamp(0.0, 1.0));
+}
+
 pub fn set_pitch_bend_range(parameters: &KeyboardParameters, normal_value: f32) {
     // Bounded to [MIN_PITCH_BEND_RANGE, MAX_PITCH_BEND_RANGE] (2–12), safely within u8 range
     #[allow(clippy::cast_possible_truncation)]
@@ -175,6 +283,65 @@ pub fn set_pitch_bend_range(parameters: &KeyboardParameters, normal_value: f32)
     parameters.pitch_bend_range.store(range, Relaxed);
 }
 
+pub fn set_mpe_pitch_bend_range(parameters: &KeyboardParameters, semitones: u8) {
+    parameters.mpe_pitch_bend_range.store(
+        semitones.clamp(MIN_MPE_PITCH_BEND_RANGE, MAX_MPE_PITCH_BEND_RANGE),
+        Relaxed,
+    );
+}
+
+pub fn set_voice_mode(parameters: &VoiceParameters, mode_index: u8) {
+    let mode = VoiceMode::from_index(mode_index);
+    parameters.mode.store(mode as u8, Relaxed);
+}
+
+pub fn set_voice_count(parameters: &VoiceParameters, voice_count: u8) {
+    parameters
+        .voice_count
+        .store(clamp_voice_count(voice_count), Relaxed);
+}
+
+pub fn set_voice_stealing_policy(parameters: &VoiceParameters, policy_index: u8) {
+    let policy = VoiceStealingPolicy::from_index(policy_index);
+    parameters.stealing_policy.store(policy as u8, Relaxed);
+}
+
+pub fn set_note_priority(parameters: &VoiceParameters, priority_index: u8) {
+    let priority = NotePriority::from_index(priority_index);
+    parameters.note_priority.store(priority as u8, Relaxed);
+}
+
+pub fn set_legato_enabled(parameters: &VoiceParameters, is_enabled: bool) {
+    parameters.legato_enabled.store(is_enabled, Relaxed);
+}
+
+pub fn set_paraphonic_assignment(parameters: &VoiceParameters, assignment_index: u8) {
+    let assignment = ParaphonicAssignment::from_index(assignment_index);
+    parameters
+        .paraphonic_assignment
+        .store(assignment as u8, Relaxed);
+}
+
+pub fn set_modulation_source(parameters: &ModulationSlotParameters, source_index: u8) {
+    let source = ModulationSource::from_index(source_index);
+    parameters.source.store(source as u8, Relaxed);
+}
+
+pub fn set_modulation_destination(parameters: &ModulationSlotParameters, destination_index: u8) {
+    let destination = ModulationDestination::from_index(destination_index);
+    parameters.destination.store(destination.index(), Relaxed);
+}
+
+pub fn set_modulation_amount(parameters: &ModulationSlotParameters, normal_value: f32) {
+    let amount = normal_value_to_f32_range(normal_value, -1.0, 1.0);
+    parameters.amount.store(amount);
+}
+
+pub fn set_modulation_via_source(parameters: &ModulationSlotParameters, source_index: u8) {
+    let source = ModulationSource::from_index(source_index);
+    parameters.via_source.store(source as u8, Relaxed);
+}
+
 pub fn set_mod_wheel(parameters: &KeyboardParameters, normal_value: f32) {
     parameters.mod_wheel_amount.store(normal_value);
 }
@@ -207,6 +374,93 @@ pub fn set_oscillator_hard_sync(parameters: &[OscillatorParameters; 4], is_enabl
     }
 }
 
+pub fn set_oscillator_hard_sync_source(parameters: &OscillatorParameters, source_index: u8) {
+    let source = HardSyncSource::from_index(source_index);
+    parameters.hard_sync_source.store(source as u8, Relaxed);
+}
+
+pub fn set_drift_depth(parameters: &DriftParameters, normal_value: f32) {
+    parameters.depth.store(normal_value);
+}
+
+pub fn set_master_tune(parameters: &TuningParameters, cents: i32) {
+    // Clamped to [MASTER_TUNE_MIN_CENTS, MASTER_TUNE_MAX_CENTS] (±100), safely within i8 range
+    #[allow(clippy::cast_possible_truncation)]
+    let cents = cents.clamp(
+        i32::from(Defaults::MASTER_TUNE_MIN_CENTS),
+        i32::from(Defaults::MASTER_TUNE_MAX_CENTS),
+    ) as i8;
+    parameters.master_tune.store(cents);
+}
+
+pub fn set_reference_pitch(parameters: &TuningParameters, hertz: f32) {
+    let hertz = if hertz.is_finite() {
+        hertz.clamp(Defaults::MIN_REFERENCE_PITCH, Defaults::MAX_REFERENCE_PITCH)
+    } else {
+        Defaults::REFERENCE_PITCH
+    };
+    parameters.reference_pitch.store(hertz);
+}
+
+pub fn set_tuning_scale(parameters: &TuningParameters, scale_name: &str, tuning_directory: &Path) {
+    parameters.load(
+        scale_name,
+        &parameters.keyboard_mapping_name(),
+        tuning_directory,
+    );
+}
+
+pub fn set_tuning_keyboard_mapping(
+    parameters: &TuningParameters,
+    keyboard_mapping_name: &str,
+    tuning_directory: &Path,
+) {
+    parameters.load(
+        &parameters.scale_name(),
+        keyboard_mapping_name,
+        tuning_directory,
+    );
+}
+
+pub fn load_patch_tuning(preset: &ModuleParameters, tuning_directory: &Path) {
+    preset.tuning.load(
+        &preset.tuning.scale_name(),
+        &preset.tuning.keyboard_mapping_name(),
+        tuning_directory,
+    );
+}
+
+pub fn set_sub_oscillator_octave(parameters: &OscillatorParameters, octave_index: u8) {
+    let octave = SubOctave::from_index(octave_index);
+    parameters.sub_octave.store(octave as u8, Relaxed);
+}
+
+pub fn set_oscillator_supersaw_voice_count(parameters: &OscillatorParameters, voice_count: u8) {
+    parameters
+        .supersaw
+        .voice_count
+        .store(clamp_supersaw_voice_count(voice_count), Relaxed);
+}
+
+pub fn set_oscillator_supersaw_stereo_spread(parameters: &OscillatorParameters, normal_value: f32) {
+    parameters.supersaw.stereo_spread.store(normal_value);
+}
+
+pub fn set_oscillator_supersaw_random_phase(parameters: &OscillatorParameters, is_enabled: bool) {
+    parameters.supersaw.random_phase.store(is_enabled, Relaxed);
+}
+
+pub fn set_oscillator_additive_partial(
+    parameters: &OscillatorParameters,
+    partial_index: usize,
+    normal_value: f32,
+) {
+    parameters
+        .additive
+        .partials
+        .store(partial_index, normal_value);
+}
+
 pub fn set_portamento_time(parameters: &[OscillatorParameters; 4], normal_value: f32) {
     // Exponential curve with coefficient 6.214_608 maps [0.0, 1.0] → [1, ~499] ms; safely within u16 range
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
@@ -246,6 +500,21 @@ pub fn set_oscillator_pitch_envelope_amount(
     amount
 }
 
+pub fn set_oscillator_cross_modulation_mode(parameters: &OscillatorParameters, mode_index: u8) {
+    let mode = CrossModulationMode::from_index(mode_index);
+    parameters.cross_modulation_mode.store(mode as u8, Relaxed);
+}
+
+pub fn set_oscillator_cross_modulation_source(parameters: &OscillatorParameters, source_index: u8) {
+    parameters
+        .cross_modulation_source
+        .store(source_index.min(OscillatorIndex::Three as u8), Relaxed);
+}
+
+pub fn set_oscillator_cross_modulation_depth(parameters: &OscillatorParameters, normal_value: f32) {
+    parameters.cross_modulation_depth.store(normal_value);
+}
+
 pub fn set_portamento_enabled(parameters: &[OscillatorParameters; 4], normal_value: f32) {
     for parameters in parameters {
         parameters
@@ -319,6 +588,40 @@ pub fn set_oscillator_wave_shape(parameters: &OscillatorParameters, normal_value
     wave_shape_index
 }
 
+pub fn set_oscillator_wavetable(
+    parameters: &OscillatorParameters,
+    wavetable_name: &str,
+    wavetable_directory: &Path,
+) {
+    parameters
+        .wavetable
+        .load(wavetable_name, wavetable_directory);
+}
+
+pub fn load_patch_wavetables(preset: &ModuleParameters, wavetable_directory: &Path) {
+    for oscillator in &preset.oscillators {
+        let wavetable_name = oscillator.wavetable.name();
+        oscillator
+            .wavetable
+            .load(&wavetable_name, wavetable_directory);
+    }
+}
+
+pub fn set_oscillator_sample(
+    parameters: &OscillatorParameters,
+    sample_name: &str,
+    sample_directory: &Path,
+) {
+    parameters.sample.load(sample_name, sample_directory);
+}
+
+pub fn load_patch_samples(preset: &ModuleParameters, sample_directory: &Path) {
+    for oscillator in &preset.oscillators {
+        let sample_name = oscillator.sample.name();
+        oscillator.sample.load(&sample_name, sample_directory);
+    }
+}
+
 pub fn set_effect_is_enabled(
     parameters: &[AudioEffectParameters],
     effect: EffectIndex,
@@ -346,11 +649,24 @@ pub fn set_module_parameters_from_preset(
 ) {
     log::info!(target: "synthesizer::parameters", "Applying preset parameters to synthesizer modules");
     parameters.filter.assign_from(&preset.filter);
+    parameters.second_filter.assign_from(&preset.second_filter);
+    parameters
+        .filter_routing
+        .assign_from(&preset.filter_routing);
     parameters.mixer.assign_from(&preset.mixer);
     parameters.keyboard.assign_from(&preset.keyboard);
-    parameters.lfos.iter().enumerate().for_each(|(index, lfo)| {
-        lfo.assign_from(&preset.lfos[index]);
-    });
+    parameters.voices.assign_from(&preset.voices);
+    parameters.drift.assign_from(&preset.drift);
+    parameters.tuning.assign_from(&preset.tuning);
+    parameters
+        .mod_matrix
+        .iter()
+        .enumerate()
+        .for_each(|(index, slot)| {
+            slot.assign_from(&preset.mod_matrix[index]);
+        });
+    parameters.lfos.assign_from(&preset.lfos);
+    parameters.lfo_routing.assign_from(&preset.lfo_routing);
     parameters
         .envelopes
         .iter()
@@ -0,0 +1,171 @@
+use crate::synthesizer::ModuleParameters;
+use crate::synthesizer::set_parameters::{set_master_tune, set_reference_pitch};
+use accsyn_core::defaults::Defaults;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+/// Errors that can occur while reading or writing the global settings file.
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum SettingsError {
+    /// The settings file exists but could not be read.
+    #[error("Failed to read settings file: {0}")]
+    FailedToRead(String),
+
+    /// The settings file could not be written.
+    #[error("Failed to write settings file: {0}")]
+    FailedToWrite(String),
+
+    /// The settings file content is not valid settings JSON.
+    #[error("Invalid settings file content: {0}")]
+    InvalidContent(String),
+}
+
+/// Settings that apply whatever patch is loaded, saved to their own file in the application data directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GlobalSettings {
+    /// Offset in cents applied to every note on top of the tuning.
+    pub master_tune: i8,
+    /// Frequency of A4 in Hz.
+    pub reference_pitch: f32,
+}
+
+impl GlobalSettings {
+    /// Captures the global settings currently in use by the synthesizer.
+    #[must_use]
+    pub fn from_module_parameters(parameters: &ModuleParameters) -> Self {
+        Self {
+            master_tune: parameters.tuning.master_tune.load(),
+            reference_pitch: parameters.tuning.reference_pitch.load(),
+        }
+    }
+
+    /// Applies the settings to the synthesizer, clamping any hand edited values to their ranges.
+    pub fn apply(&self, parameters: &ModuleParameters) {
+        set_master_tune(&parameters.tuning, i32::from(self.master_tune));
+        set_reference_pitch(&parameters.tuning, self.reference_pitch);
+    }
+
+    /// Reads the settings file, returning the default settings if it hasn't been saved yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        if !path.exists() {
+            log::debug!(target: "synthesizer::settings", "No settings file at {}, using the defaults", path.display());
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| SettingsError::FailedToRead(err.to_string()))?;
+        serde_json::from_str(&content).map_err(|err| SettingsError::InvalidContent(err.to_string()))
+    }
+
+    /// Writes the settings file, replacing any saved settings.
+    ///
+    /// The settings are written to a temporary file that is then renamed over the settings file, so an
+    /// interrupted save leaves the previous settings intact.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the settings cannot be serialized or the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|err| SettingsError::InvalidContent(err.to_string()))?;
+        let temporary_path = path.with_extension("json.tmp");
+        std::fs::write(&temporary_path, content)
+            .map_err(|err| SettingsError::FailedToWrite(err.to_string()))?;
+        std::fs::rename(&temporary_path, path).map_err(|err| {
+            let _ = std::fs::remove_file(&temporary_path);
+            SettingsError::FailedToWrite(err.to_string())
+        })
+    }
+}
+
+impl Default for GlobalSettings {
+    fn default() -> Self {
+        Self {
+            master_tune: Defaults::MASTER_TUNE_CENTS,
+            reference_pitch: Defaults::REFERENCE_PITCH,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use accsyn_core::math::f32s_are_equal;
+    use std::path::PathBuf;
+
+    fn temporary_settings_file(label: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "accsyn-settings-{label}-{}.json",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn settings_round_trip_through_the_settings_file() {
+        let path = temporary_settings_file("round-trip");
+        let parameters = ModuleParameters::default();
+        parameters.tuning.master_tune.store(-35);
+        parameters.tuning.reference_pitch.store(432.0);
+
+        GlobalSettings::from_module_parameters(&parameters)
+            .save(&path)
+            .unwrap();
+        let restored = ModuleParameters::default();
+        GlobalSettings::load(&path).unwrap().apply(&restored);
+
+        assert_eq!(restored.tuning.master_tune.load(), -35);
+        assert!(f32s_are_equal(
+            restored.tuning.reference_pitch.load(),
+            432.0
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn saving_replaces_the_settings_file_without_leaving_a_temporary_file() {
+        let path = temporary_settings_file("replace");
+        std::fs::write(&path, r#"{"master_tune": 10}"#).unwrap();
+        let settings = GlobalSettings {
+            master_tune: -5,
+            ..GlobalSettings::default()
+        };
+
+        settings.save(&path).unwrap();
+
+        assert_eq!(GlobalSettings::load(&path), Ok(settings));
+        assert!(!path.with_extension("json.tmp").exists());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_settings_file_loads_the_defaults() {
+        let path = temporary_settings_file("missing");
+
+        assert_eq!(GlobalSettings::load(&path), Ok(GlobalSettings::default()));
+    }
+
+    #[test]
+    fn hand_edited_settings_are_clamped_when_applied() {
+        let path = temporary_settings_file("clamped");
+        std::fs::write(&path, r#"{"master_tune": 127, "reference_pitch": 1000.0}"#).unwrap();
+        let parameters = ModuleParameters::default();
+
+        GlobalSettings::load(&path).unwrap().apply(&parameters);
+
+        assert_eq!(
+            parameters.tuning.master_tune.load(),
+            Defaults::MASTER_TUNE_MAX_CENTS
+        );
+        assert!(f32s_are_equal(
+            parameters.tuning.reference_pitch.load(),
+            Defaults::MAX_REFERENCE_PITCH
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,689 @@
+use crate::synthesizer::MidiGateEvent;
+use crate::synthesizer::mpe::{ExpressionValues, NoteExpression};
+use crate::synthesizer::note_stack::NotePriority;
+use crate::synthesizer::paraphonic::ParaphonicAssignment;
+use accsyn_core::defaults::Defaults;
+use accsyn_core::math::{load_f32_from_atomic_u32, normalize_midi_value, store_f32_as_atomic_u32};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::Ordering::{Relaxed, Release};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU32};
+use strum::EnumCount as StrumEnumCount;
+use strum_macros::{EnumCount, EnumIter, FromRepr};
+
+/// Maximum number of voices the poly engine can allocate.
+pub const MAX_VOICE_COUNT: usize = 16;
+/// Default number of voices used in poly mode.
+pub const DEFAULT_VOICE_COUNT: u8 = 8;
+/// Fewest voices available in poly mode.
+pub const MIN_VOICE_COUNT: u8 = 1;
+/// Index of the last voice mode variant.
+// COUNT is capped by the number of voice modes which will always be less than u32::MAX
+#[allow(clippy::cast_possible_truncation)]
+pub const LAST_VOICE_MODE_INDEX: u32 = (VoiceMode::COUNT - 1) as u32;
+/// Index of the last voice stealing policy variant.
+// COUNT is capped by the number of stealing policies which will always be less than u32::MAX
+#[allow(clippy::cast_possible_truncation)]
+pub const LAST_VOICE_STEALING_POLICY_INDEX: u32 = (VoiceStealingPolicy::COUNT - 1) as u32;
+const ENVELOPES_PER_VOICE: usize = 3;
+const OSCILLATORS_PER_VOICE: usize = 4;
+const SILENT_VOICE_LEVEL: f32 = 0.0001;
+
+/// How incoming notes are assigned to the synthesizer voices.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
+#[repr(u8)]
+pub enum VoiceMode {
+    /// A single voice follows the most recent note.
+    #[default]
+    Mono,
+    /// Each note is assigned its own voice from the voice pool.
+    Poly,
+    /// Up to four held notes are spread across the oscillators of a single voice.
+    Paraphonic,
+}
+
+impl VoiceMode {
+    /// Converts a numeric index to the corresponding voice mode, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
+}
+
+/// Which sounding voice is taken over when every voice is in use.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, EnumCount, EnumIter, FromRepr)]
+#[repr(u8)]
+pub enum VoiceStealingPolicy {
+    /// Steal the voice that was triggered the longest time ago.
+    #[default]
+    Oldest,
+    /// Steal the voice with the lowest amp envelope level.
+    Quietest,
+}
+
+impl VoiceStealingPolicy {
+    /// Converts a numeric index to the corresponding stealing policy, defaulting on invalid values.
+    #[must_use]
+    pub fn from_index(index: u8) -> Self {
+        Self::from_repr(index).unwrap_or_default()
+    }
+}
+
+/// Shared atomic parameters controlling voice allocation.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoiceParameters {
+    /// Index selecting the active `VoiceMode`.
+    pub mode: AtomicU8,
+    /// Number of voices available in poly mode (1 to `MAX_VOICE_COUNT`).
+    pub voice_count: AtomicU8,
+    /// Index selecting the active `VoiceStealingPolicy`.
+    pub stealing_policy: AtomicU8,
+    /// Index selecting which held note sounds in mono mode (`NotePriority`).
+    pub note_priority: AtomicU8,
+    /// Whether moving between held notes in mono mode glides without retriggering the envelopes.
+    pub legato_enabled: AtomicBool,
+    /// Index selecting how held notes are spread across the oscillators (`ParaphonicAssignment`).
+    pub paraphonic_assignment: AtomicU8,
+}
+
+impl VoiceParameters {
+    /// Replace all the values in these `VoiceParameters` with the values from the provided `VoiceParameters`.
+    pub fn assign_from(&self, parameters: &VoiceParameters) {
+        self.mode.store(parameters.mode.load(Relaxed), Relaxed);
+        self.voice_count.store(
+            clamp_voice_count(parameters.voice_count.load(Relaxed)),
+            Relaxed,
+        );
+        self.stealing_policy
+            .store(parameters.stealing_policy.load(Relaxed), Relaxed);
+        self.note_priority
+            .store(parameters.note_priority.load(Relaxed), Relaxed);
+        self.legato_enabled
+            .store(parameters.legato_enabled.load(Relaxed), Relaxed);
+        self.paraphonic_assignment
+            .store(parameters.paraphonic_assignment.load(Relaxed), Relaxed);
+    }
+
+    /// Returns the active voice mode.
+    #[must_use]
+    pub fn voice_mode(&self) -> VoiceMode {
+        VoiceMode::from_index(self.mode.load(Relaxed))
+    }
+
+    /// Returns the number of voices available in poly mode, clamped to the valid range.
+    #[must_use]
+    pub fn voice_count(&self) -> usize {
+        usize::from(clamp_voice_count(self.voice_count.load(Relaxed)))
+    }
+
+    /// Returns the active voice stealing policy.
+    #[must_use]
+    pub fn stealing_policy(&self) -> VoiceStealingPolicy {
+        VoiceStealingPolicy::from_index(self.stealing_policy.load(Relaxed))
+    }
+
+    /// Returns the active mono note priority.
+    #[must_use]
+    pub fn note_priority(&self) -> NotePriority {
+        NotePriority::from_index(self.note_priority.load(Relaxed))
+    }
+
+    /// Returns the active paraphonic note assignment.
+    #[must_use]
+    pub fn paraphonic_assignment(&self) -> ParaphonicAssignment {
+        ParaphonicAssignment::from_index(self.paraphonic_assignment.load(Relaxed))
+    }
+}
+
+impl Default for VoiceParameters {
+    fn default() -> Self {
+        Self {
+            mode: AtomicU8::new(VoiceMode::default() as u8),
+            voice_count: AtomicU8::new(DEFAULT_VOICE_COUNT),
+            stealing_policy: AtomicU8::new(VoiceStealingPolicy::default() as u8),
+            note_priority: AtomicU8::new(NotePriority::default() as u8),
+            legato_enabled: AtomicBool::new(false),
+            paraphonic_assignment: AtomicU8::new(ParaphonicAssignment::default() as u8),
+        }
+    }
+}
+
+pub(crate) fn clamp_voice_count(voice_count: u8) -> u8 {
+    // MAX_VOICE_COUNT is a small fixed constant, well within u8 range
+    #[allow(clippy::cast_possible_truncation)]
+    voice_count.clamp(MIN_VOICE_COUNT, MAX_VOICE_COUNT as u8)
+}
+
+/// Per-voice note and gate state shared between the MIDI thread and the audio thread.
+#[derive(Debug)]
+pub struct VoiceState {
+    /// MIDI note number currently assigned to the voice.
+    pub midi_note: AtomicU8,
+    /// Scaled note-on velocity stored as f32 bits.
+    pub velocity: AtomicU32,
+    /// Gate flags for the amp, filter and pitch envelopes: 0 = waiting, 1 = gate on, 2 = gate off.
+    pub envelope_gate_flags: [AtomicU8; ENVELOPES_PER_VOICE],
+    /// Gate flags for each oscillator, set on note-on for key sync and portamento.
+    pub oscillator_gate_flags: [AtomicBool; OSCILLATORS_PER_VOICE],
+    /// Most recent amp envelope level published by the audio thread, stored as f32 bits.
+    pub level: AtomicU32,
+    /// Per-note pitch bend, pressure and timbre of an MPE note, at rest for other notes.
+    pub expression: NoteExpression,
+    /// Normalized release velocity of the voice's last note off, stored as f32 bits.
+    pub release_velocity: AtomicU32,
+}
+
+impl Default for VoiceState {
+    fn default() -> Self {
+        Self {
+            midi_note: AtomicU8::new(0),
+            velocity: AtomicU32::new(0.0_f32.to_bits()),
+            envelope_gate_flags: Default::default(),
+            oscillator_gate_flags: Default::default(),
+            level: AtomicU32::new(0.0_f32.to_bits()),
+            expression: NoteExpression::default(),
+            release_velocity: AtomicU32::new(
+                normalize_midi_value(Defaults::RELEASE_VELOCITY).to_bits(),
+            ),
+        }
+    }
+}
+
+/// The full pool of voice states.
+pub type VoiceStates = [VoiceState; MAX_VOICE_COUNT];
+
+/// Assigns incoming notes to voices, stealing a voice when all of them are in use.
+///
+/// Owned by the MIDI event thread. Gate changes are published through the shared `VoiceStates`
+/// for the audio thread to pick up on its next sample.
+#[derive(Debug)]
+pub struct VoiceAllocator {
+    voice_states: Arc<VoiceStates>,
+    held_notes: [Option<u8>; MAX_VOICE_COUNT],
+    assigned_notes: [Option<u8>; MAX_VOICE_COUNT],
+    assigned_channels: [Option<u8>; MAX_VOICE_COUNT],
+    note_on_order: [u64; MAX_VOICE_COUNT],
+    note_on_counter: u64,
+}
+
+impl VoiceAllocator {
+    /// Creates an allocator driving the given shared voice states.
+    #[must_use]
+    pub fn new(voice_states: Arc<VoiceStates>) -> Self {
+        Self {
+            voice_states,
+            held_notes: [None; MAX_VOICE_COUNT],
+            assigned_notes: [None; MAX_VOICE_COUNT],
+            assigned_channels: [None; MAX_VOICE_COUNT],
+            note_on_order: [0; MAX_VOICE_COUNT],
+            note_on_counter: 0,
+        }
+    }
+
+    /// Assigns the note to a voice, opens its gates and returns the chosen voice index.
+    pub fn note_on(&mut self, midi_note: u8, velocity: f32, parameters: &VoiceParameters) -> usize {
+        self.assign_voice(
+            midi_note,
+            None,
+            velocity,
+            ExpressionValues::default(),
+            parameters,
+        )
+    }
+
+    /// Assigns a note from an MPE member channel to a voice that follows the channel's expression.
+    pub fn mpe_note_on(
+        &mut self,
+        channel: u8,
+        midi_note: u8,
+        velocity: f32,
+        expression: ExpressionValues,
+        parameters: &VoiceParameters,
+    ) -> usize {
+        self.assign_voice(midi_note, Some(channel), velocity, expression, parameters)
+    }
+
+    /// Closes the gates of every voice holding the note with the given normalized release velocity.
+    pub fn note_off(&mut self, midi_note: u8, release_velocity: f32) {
+        for voice_index in 0..MAX_VOICE_COUNT {
+            if self.held_notes[voice_index] == Some(midi_note) {
+                self.release_voice(voice_index, release_velocity);
+            }
+        }
+    }
+
+    /// Closes the gates of the voice holding the note on the MPE member channel with the given normalized
+    /// release velocity.
+    pub fn mpe_note_off(&mut self, channel: u8, midi_note: u8, release_velocity: f32) {
+        for voice_index in 0..MAX_VOICE_COUNT {
+            if self.held_notes[voice_index] == Some(midi_note)
+                && self.assigned_channels[voice_index] == Some(channel)
+            {
+                self.release_voice(voice_index, release_velocity);
+            }
+        }
+    }
+
+    /// Publishes new expression to every voice assigned to the MPE member channel, including released
+    /// voices so a bend or timbre change carries on through the release tail.
+    pub fn update_channel_expression(&self, channel: u8, expression: ExpressionValues) {
+        for (voice_state, assigned_channel) in
+            self.voice_states.iter().zip(self.assigned_channels.iter())
+        {
+            if *assigned_channel == Some(channel) {
+                voice_state.expression.store(expression);
+            }
+        }
+    }
+
+    /// Closes the gates of every held voice with the release velocity of a keyboard without release sensing.
+    pub fn all_notes_off(&mut self) {
+        let release_velocity = normalize_midi_value(Defaults::RELEASE_VELOCITY);
+        for voice_index in 0..MAX_VOICE_COUNT {
+            if self.held_notes[voice_index].is_some() {
+                self.release_voice(voice_index, release_velocity);
+            }
+        }
+    }
+
+    /// Publishes polyphonic key pressure to every voice holding the note.
+    pub fn update_note_pressure(&self, midi_note: u8, pressure: f32) {
+        for (voice_state, held_note) in self.voice_states.iter().zip(self.held_notes.iter()) {
+            if *held_note == Some(midi_note) {
+                voice_state.expression.store_pressure(pressure);
+            }
+        }
+    }
+
+    fn assign_voice(
+        &mut self,
+        midi_note: u8,
+        channel: Option<u8>,
+        velocity: f32,
+        expression: ExpressionValues,
+        parameters: &VoiceParameters,
+    ) -> usize {
+        let voice_levels = self.voice_levels();
+        let voice_index = self.select_voice(
+            midi_note,
+            channel,
+            parameters.voice_count(),
+            parameters.stealing_policy(),
+            &voice_levels,
+        );
+
+        self.note_on_counter += 1;
+        self.held_notes[voice_index] = Some(midi_note);
+        self.assigned_notes[voice_index] = Some(midi_note);
+        self.assigned_channels[voice_index] = channel;
+        self.note_on_order[voice_index] = self.note_on_counter;
+
+        let voice_state = &self.voice_states[voice_index];
+        store_f32_as_atomic_u32(&voice_state.velocity, velocity);
+        // The release velocity of the note this voice played last doesn't apply to the new note
+        store_f32_as_atomic_u32(
+            &voice_state.release_velocity,
+            normalize_midi_value(Defaults::RELEASE_VELOCITY),
+        );
+        voice_state.midi_note.store(midi_note, Relaxed);
+        voice_state.expression.store(expression);
+        for gate_flag in &voice_state.envelope_gate_flags {
+            gate_flag.store(MidiGateEvent::GateOn as u8, Relaxed);
+        }
+        for gate_flag in &voice_state.oscillator_gate_flags {
+            gate_flag.store(true, Release);
+        }
+
+        voice_index
+    }
+
+    fn release_voice(&mut self, voice_index: usize, release_velocity: f32) {
+        self.held_notes[voice_index] = None;
+        store_f32_as_atomic_u32(
+            &self.voice_states[voice_index].release_velocity,
+            release_velocity,
+        );
+        for gate_flag in &self.voice_states[voice_index].envelope_gate_flags {
+            gate_flag.store(MidiGateEvent::GateOff as u8, Relaxed);
+        }
+    }
+
+    fn voice_levels(&self) -> [f32; MAX_VOICE_COUNT] {
+        let mut voice_levels = [0.0; MAX_VOICE_COUNT];
+        for (level, voice_state) in voice_levels.iter_mut().zip(self.voice_states.iter()) {
+            *level = load_f32_from_atomic_u32(&voice_state.level);
+        }
+        voice_levels
+    }
+
+    fn select_voice(
+        &self,
+        midi_note: u8,
+        channel: Option<u8>,
+        voice_count: usize,
+        stealing_policy: VoiceStealingPolicy,
+        voice_levels: &[f32; MAX_VOICE_COUNT],
+    ) -> usize {
+        let voice_count = voice_count.clamp(1, MAX_VOICE_COUNT);
+
+        // MPE notes only reuse a voice from the same member channel, so two channels can sound the same
+        // note with their own expression
+        if let Some(voice_index) = (0..voice_count).find(|&index| {
+            self.assigned_notes[index] == Some(midi_note)
+                && self.assigned_channels[index] == channel
+        }) {
+            return voice_index;
+        }
+
+        let is_released = |index: &usize| self.held_notes[*index].is_none();
+
+        if let Some(voice_index) = (0..voice_count)
+            .filter(is_released)
+            .filter(|&index| voice_levels[index] <= SILENT_VOICE_LEVEL)
+            .min_by_key(|&index| self.note_on_order[index])
+        {
+            return voice_index;
+        }
+
+        if let Some(voice_index) = self.steal_voice(
+            (0..voice_count).filter(is_released),
+            stealing_policy,
+            voice_levels,
+        ) {
+            return voice_index;
+        }
+
+        self.steal_voice(0..voice_count, stealing_policy, voice_levels)
+            .unwrap_or_default()
+    }
+
+    fn steal_voice(
+        &self,
+        candidates: impl Iterator<Item = usize>,
+        stealing_policy: VoiceStealingPolicy,
+        voice_levels: &[f32; MAX_VOICE_COUNT],
+    ) -> Option<usize> {
+        match stealing_policy {
+            VoiceStealingPolicy::Oldest => {
+                candidates.min_by_key(|&index| self.note_on_order[index])
+            }
+            VoiceStealingPolicy::Quietest => {
+                candidates.min_by(|&a, &b| voice_levels[a].total_cmp(&voice_levels[b]))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_allocator() -> VoiceAllocator {
+        VoiceAllocator::new(Arc::new(Default::default()))
+    }
+
+    fn poly_parameters(voice_count: u8, stealing_policy: VoiceStealingPolicy) -> VoiceParameters {
+        let parameters = VoiceParameters::default();
+        parameters.mode.store(VoiceMode::Poly as u8, Relaxed);
+        parameters.voice_count.store(voice_count, Relaxed);
+        parameters
+            .stealing_policy
+            .store(stealing_policy as u8, Relaxed);
+        parameters
+    }
+
+    #[test]
+    fn note_on_assigns_each_new_note_to_a_free_voice() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(4, VoiceStealingPolicy::Oldest);
+
+        let first = allocator.note_on(60, 1.0, &parameters);
+        let second = allocator.note_on(64, 1.0, &parameters);
+        let third = allocator.note_on(67, 1.0, &parameters);
+
+        assert_ne!(first, second);
+        assert_ne!(second, third);
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn note_on_opens_the_gates_of_the_assigned_voice() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(4, VoiceStealingPolicy::Oldest);
+
+        let voice_index = allocator.note_on(60, 0.5, &parameters);
+        let voice_state = &allocator.voice_states[voice_index];
+
+        assert_eq!(voice_state.midi_note.load(Relaxed), 60);
+        assert_eq!(load_f32_from_atomic_u32(&voice_state.velocity), 0.5);
+        for gate_flag in &voice_state.envelope_gate_flags {
+            assert_eq!(gate_flag.load(Relaxed), MidiGateEvent::GateOn as u8);
+        }
+        for gate_flag in &voice_state.oscillator_gate_flags {
+            assert!(gate_flag.load(Relaxed));
+        }
+    }
+
+    #[test]
+    fn note_off_only_closes_the_gates_of_the_voice_holding_that_note() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(4, VoiceStealingPolicy::Oldest);
+
+        let held_voice = allocator.note_on(60, 1.0, &parameters);
+        let released_voice = allocator.note_on(64, 1.0, &parameters);
+        allocator.note_off(64, 0.5);
+
+        let released_flag =
+            allocator.voice_states[released_voice].envelope_gate_flags[0].load(Relaxed);
+        let held_flag = allocator.voice_states[held_voice].envelope_gate_flags[0].load(Relaxed);
+        assert_eq!(released_flag, MidiGateEvent::GateOff as u8);
+        assert_eq!(held_flag, MidiGateEvent::GateOn as u8);
+    }
+
+    #[test]
+    fn note_off_stores_the_release_velocity_before_closing_the_gates() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(4, VoiceStealingPolicy::Oldest);
+
+        let voice_index = allocator.note_on(60, 1.0, &parameters);
+        allocator.note_off(60, 0.25);
+
+        let release_velocity =
+            load_f32_from_atomic_u32(&allocator.voice_states[voice_index].release_velocity);
+        assert_eq!(release_velocity, 0.25);
+    }
+
+    #[test]
+    fn note_on_resets_the_release_velocity_of_a_reused_voice() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(1, VoiceStealingPolicy::Oldest);
+
+        allocator.note_on(60, 1.0, &parameters);
+        allocator.note_off(60, 0.25);
+        let voice_index = allocator.note_on(62, 1.0, &parameters);
+
+        let release_velocity =
+            load_f32_from_atomic_u32(&allocator.voice_states[voice_index].release_velocity);
+        assert_eq!(
+            release_velocity,
+            normalize_midi_value(Defaults::RELEASE_VELOCITY)
+        );
+    }
+
+    #[test]
+    fn repeated_note_reuses_the_voice_it_was_assigned_to() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(4, VoiceStealingPolicy::Oldest);
+
+        let first = allocator.note_on(60, 1.0, &parameters);
+        allocator.note_off(60, 0.5);
+        allocator.note_on(64, 1.0, &parameters);
+        let repeated = allocator.note_on(60, 1.0, &parameters);
+
+        assert_eq!(first, repeated);
+    }
+
+    #[test]
+    fn oldest_policy_steals_the_first_triggered_voice_when_all_are_held() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(2, VoiceStealingPolicy::Oldest);
+
+        let oldest = allocator.note_on(60, 1.0, &parameters);
+        allocator.note_on(64, 1.0, &parameters);
+        let stolen = allocator.note_on(67, 1.0, &parameters);
+
+        assert_eq!(stolen, oldest);
+    }
+
+    #[test]
+    fn quietest_policy_steals_the_voice_with_the_lowest_level() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(2, VoiceStealingPolicy::Quietest);
+
+        let loud = allocator.note_on(60, 1.0, &parameters);
+        let quiet = allocator.note_on(64, 1.0, &parameters);
+        store_f32_as_atomic_u32(&allocator.voice_states[loud].level, 0.9);
+        store_f32_as_atomic_u32(&allocator.voice_states[quiet].level, 0.2);
+
+        let stolen = allocator.note_on(67, 1.0, &parameters);
+
+        assert_eq!(stolen, quiet);
+    }
+
+    #[test]
+    fn released_voices_are_stolen_before_held_voices() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(2, VoiceStealingPolicy::Oldest);
+
+        let held = allocator.note_on(60, 1.0, &parameters);
+        let releasing = allocator.note_on(64, 1.0, &parameters);
+        store_f32_as_atomic_u32(&allocator.voice_states[held].level, 0.8);
+        store_f32_as_atomic_u32(&allocator.voice_states[releasing].level, 0.8);
+        allocator.note_off(64, 0.5);
+
+        let stolen = allocator.note_on(67, 1.0, &parameters);
+
+        assert_eq!(stolen, releasing);
+    }
+
+    #[test]
+    fn note_on_never_assigns_a_voice_beyond_the_voice_count() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(3, VoiceStealingPolicy::Oldest);
+
+        for note in 40..80 {
+            let voice_index = allocator.note_on(note, 1.0, &parameters);
+            assert!(voice_index < 3);
+        }
+    }
+
+    #[test]
+    fn all_notes_off_closes_every_held_voice() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(4, VoiceStealingPolicy::Oldest);
+
+        let voices = [
+            allocator.note_on(60, 1.0, &parameters),
+            allocator.note_on(64, 1.0, &parameters),
+        ];
+        allocator.all_notes_off();
+
+        for voice_index in voices {
+            let gate_flag =
+                allocator.voice_states[voice_index].envelope_gate_flags[0].load(Relaxed);
+            assert_eq!(gate_flag, MidiGateEvent::GateOff as u8);
+        }
+    }
+
+    #[test]
+    fn mpe_notes_on_different_channels_get_their_own_voices() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(4, VoiceStealingPolicy::Oldest);
+        let bent = ExpressionValues {
+            pitch_bend: 0.5,
+            ..ExpressionValues::default()
+        };
+
+        let first = allocator.mpe_note_on(2, 60, 1.0, bent, &parameters);
+        let second = allocator.mpe_note_on(3, 60, 1.0, ExpressionValues::default(), &parameters);
+        allocator.mpe_note_off(3, 60, 0.5);
+
+        assert_ne!(first, second);
+        assert_eq!(allocator.voice_states[first].expression.load(), bent);
+        let first_flag = allocator.voice_states[first].envelope_gate_flags[0].load(Relaxed);
+        let second_flag = allocator.voice_states[second].envelope_gate_flags[0].load(Relaxed);
+        assert_eq!(first_flag, MidiGateEvent::GateOn as u8);
+        assert_eq!(second_flag, MidiGateEvent::GateOff as u8);
+    }
+
+    #[test]
+    fn channel_expression_only_reaches_voices_on_that_channel() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(4, VoiceStealingPolicy::Oldest);
+        let pressed = ExpressionValues {
+            pressure: 0.75,
+            ..ExpressionValues::default()
+        };
+
+        let mpe_voice = allocator.mpe_note_on(5, 60, 1.0, ExpressionValues::default(), &parameters);
+        let plain_voice = allocator.note_on(64, 1.0, &parameters);
+        allocator.update_channel_expression(5, pressed);
+
+        assert_eq!(allocator.voice_states[mpe_voice].expression.load(), pressed);
+        assert_eq!(
+            allocator.voice_states[plain_voice].expression.load(),
+            ExpressionValues::default()
+        );
+    }
+
+    #[test]
+    fn note_pressure_only_reaches_voices_holding_that_note() {
+        let mut allocator = create_allocator();
+        let parameters = poly_parameters(4, VoiceStealingPolicy::Oldest);
+
+        let pressed_voice = allocator.note_on(60, 1.0, &parameters);
+        let other_voice = allocator.note_on(64, 1.0, &parameters);
+        allocator.update_note_pressure(60, 0.5);
+
+        assert_eq!(
+            allocator.voice_states[pressed_voice]
+                .expression
+                .load()
+                .pressure,
+            0.5
+        );
+        assert_eq!(
+            allocator.voice_states[other_voice]
+                .expression
+                .load()
+                .pressure,
+            0.0
+        );
+    }
+
+    #[test]
+    fn assign_from_clamps_out_of_range_voice_count() {
+        let preset = VoiceParameters::default();
+        preset.voice_count.store(200, Relaxed);
+        let parameters = VoiceParameters::default();
+
+        parameters.assign_from(&preset);
+
+        assert_eq!(
+            usize::from(parameters.voice_count.load(Relaxed)),
+            MAX_VOICE_COUNT
+        );
+    }
+
+    #[test]
+    fn assign_from_clamps_zero_voice_count_to_one() {
+        let preset = VoiceParameters::default();
+        preset.voice_count.store(0, Relaxed);
+        let parameters = VoiceParameters::default();
+
+        parameters.assign_from(&preset);
+
+        assert_eq!(parameters.voice_count.load(Relaxed), MIN_VOICE_COUNT);
+    }
+}
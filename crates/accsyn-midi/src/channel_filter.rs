@@ -0,0 +1,258 @@
+use crate::constants::{
+    MPE_LOWER_ZONE_CHANNEL_NAME, MPE_LOWER_ZONE_CHANNEL_UI_INDEX, MPE_LOWER_ZONE_MASTER_CHANNEL,
+    MPE_MAX_MEMBER_CHANNEL_COUNT, MPE_UPPER_ZONE_CHANNEL_NAME, MPE_UPPER_ZONE_CHANNEL_UI_INDEX,
+    MPE_UPPER_ZONE_MASTER_CHANNEL,
+};
+use std::ops::RangeInclusive;
+
+/// The MPE zone layout: which end of the channel range holds the master channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MpeZone {
+    /// Master channel 1, with notes on the channels above it.
+    Lower,
+    /// Master channel 16, with notes on the channels below it.
+    Upper,
+}
+
+impl MpeZone {
+    /// Returns the zone's master channel (1-16).
+    #[must_use]
+    pub fn master_channel(self) -> u8 {
+        match self {
+            Self::Lower => MPE_LOWER_ZONE_MASTER_CHANNEL,
+            Self::Upper => MPE_UPPER_ZONE_MASTER_CHANNEL,
+        }
+    }
+
+    /// Returns the zone's member channels (1-16) next to the master channel, empty for a count of zero.
+    #[must_use]
+    pub fn member_channels(self, member_channel_count: u8) -> RangeInclusive<u8> {
+        let member_channel_count = member_channel_count.min(MPE_MAX_MEMBER_CHANNEL_COUNT);
+        match self {
+            Self::Lower => {
+                MPE_LOWER_ZONE_MASTER_CHANNEL + 1
+                    ..=MPE_LOWER_ZONE_MASTER_CHANNEL + member_channel_count
+            }
+            Self::Upper => {
+                MPE_UPPER_ZONE_MASTER_CHANNEL - member_channel_count
+                    ..=MPE_UPPER_ZONE_MASTER_CHANNEL - 1
+            }
+        }
+    }
+}
+
+/// Which incoming MIDI channels are played and how.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelFilter {
+    /// Every channel is played as one keyboard.
+    #[default]
+    Omni,
+    /// Only the given channel (1-16) is played.
+    Channel(u8),
+    /// The zone's master and member channels are played; the master channel controls the whole synthesizer
+    /// and each member channel carries one note with its own pitch bend, pressure and timbre. The member
+    /// channel count follows the controller's MPE Configuration Message.
+    Mpe {
+        /// Which end of the channel range holds the master channel.
+        zone: MpeZone,
+        /// Number of member channels (0-15) next to the master channel.
+        member_channel_count: u8,
+    },
+}
+
+impl ChannelFilter {
+    /// Converts a channel name from the UI channel list into a filter, falling back to Omni.
+    #[must_use]
+    pub fn from_channel_name(channel_name: &str) -> Self {
+        match channel_name {
+            MPE_LOWER_ZONE_CHANNEL_NAME => Self::mpe(MpeZone::Lower),
+            MPE_UPPER_ZONE_CHANNEL_NAME => Self::mpe(MpeZone::Upper),
+            _ => channel_name.parse().map_or(Self::Omni, Self::Channel),
+        }
+    }
+
+    /// Creates an MPE filter for the zone with every other channel as a member channel, until the
+    /// controller's MPE Configuration Message says otherwise.
+    #[must_use]
+    pub fn mpe(zone: MpeZone) -> Self {
+        Self::Mpe {
+            zone,
+            member_channel_count: MPE_MAX_MEMBER_CHANNEL_COUNT,
+        }
+    }
+
+    /// Returns the single channel being played, or `None` when every channel is.
+    #[must_use]
+    pub fn channel(self) -> Option<u8> {
+        match self {
+            Self::Channel(channel) => Some(channel),
+            Self::Omni | Self::Mpe { .. } => None,
+        }
+    }
+
+    /// Returns whether messages on the given channel (1-16) are played.
+    #[must_use]
+    pub fn accepts(self, message_channel: u8) -> bool {
+        match self {
+            Self::Omni => true,
+            Self::Channel(channel) => channel == message_channel,
+            Self::Mpe { zone, .. } => {
+                message_channel == zone.master_channel()
+                    || self.is_mpe_member_channel(message_channel)
+            }
+        }
+    }
+
+    /// Returns whether the given channel (1-16) is an MPE member channel carrying per-note expression.
+    #[must_use]
+    pub fn is_mpe_member_channel(self, message_channel: u8) -> bool {
+        match self {
+            Self::Mpe {
+                zone,
+                member_channel_count,
+            } => zone
+                .member_channels(member_channel_count)
+                .contains(&message_channel),
+            Self::Omni | Self::Channel(_) => false,
+        }
+    }
+
+    /// Returns whether the given channel (1-16) is the master channel of an MPE zone.
+    #[must_use]
+    pub fn is_mpe_master_channel(self, message_channel: u8) -> bool {
+        match self {
+            Self::Mpe { zone, .. } => message_channel == zone.master_channel(),
+            Self::Omni | Self::Channel(_) => false,
+        }
+    }
+
+    /// Sets the number of MPE member channels from an MPE Configuration Message, clamped to 0-15. A count of
+    /// zero leaves only the master channel, played like a single channel. Other filters are left unchanged.
+    pub fn set_mpe_member_channel_count(&mut self, count: u8) {
+        if let Self::Mpe {
+            member_channel_count,
+            ..
+        } = self
+        {
+            *member_channel_count = count.min(MPE_MAX_MEMBER_CHANNEL_COUNT);
+        }
+    }
+
+    /// Returns the index of the filter in the UI channel list.
+    #[must_use]
+    pub fn ui_index(self) -> i32 {
+        match self {
+            Self::Omni => 0,
+            Self::Channel(channel) => i32::from(channel),
+            Self::Mpe {
+                zone: MpeZone::Lower,
+                ..
+            } => MPE_LOWER_ZONE_CHANNEL_UI_INDEX,
+            Self::Mpe {
+                zone: MpeZone::Upper,
+                ..
+            } => MPE_UPPER_ZONE_CHANNEL_UI_INDEX,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_names_convert_to_filters() {
+        assert_eq!(
+            ChannelFilter::from_channel_name("Omni"),
+            ChannelFilter::Omni
+        );
+        assert_eq!(
+            ChannelFilter::from_channel_name("10"),
+            ChannelFilter::Channel(10)
+        );
+        assert_eq!(
+            ChannelFilter::from_channel_name(MPE_LOWER_ZONE_CHANNEL_NAME),
+            ChannelFilter::mpe(MpeZone::Lower)
+        );
+        assert_eq!(
+            ChannelFilter::from_channel_name(MPE_UPPER_ZONE_CHANNEL_NAME),
+            ChannelFilter::mpe(MpeZone::Upper)
+        );
+    }
+
+    #[test]
+    fn single_channel_filter_only_accepts_its_channel() {
+        let filter = ChannelFilter::Channel(3);
+
+        assert!(filter.accepts(3));
+        assert!(!filter.accepts(4));
+        assert!(!filter.is_mpe_member_channel(3));
+    }
+
+    #[test]
+    fn mpe_zones_accept_every_channel_and_exclude_the_master_from_the_members() {
+        let lower = ChannelFilter::mpe(MpeZone::Lower);
+        let upper = ChannelFilter::mpe(MpeZone::Upper);
+
+        assert!((1..=16).all(|channel| lower.accepts(channel) && upper.accepts(channel)));
+        assert!(!lower.is_mpe_member_channel(1));
+        assert!(lower.is_mpe_member_channel(2));
+        assert!(lower.is_mpe_member_channel(16));
+        assert!(!upper.is_mpe_member_channel(16));
+        assert!(upper.is_mpe_member_channel(1));
+    }
+
+    #[test]
+    fn mpe_member_channel_count_limits_the_zone() {
+        let mut lower = ChannelFilter::mpe(MpeZone::Lower);
+        let mut upper = ChannelFilter::mpe(MpeZone::Upper);
+
+        lower.set_mpe_member_channel_count(3);
+        upper.set_mpe_member_channel_count(3);
+
+        assert!(lower.is_mpe_member_channel(4));
+        assert!(!lower.is_mpe_member_channel(5));
+        assert!(!lower.accepts(5));
+        assert!(upper.is_mpe_member_channel(13));
+        assert!(!upper.is_mpe_member_channel(12));
+        assert!(!upper.accepts(12));
+        assert!(upper.is_mpe_master_channel(16));
+    }
+
+    #[test]
+    fn mpe_zone_without_member_channels_only_plays_the_master_channel() {
+        let mut lower = ChannelFilter::mpe(MpeZone::Lower);
+
+        lower.set_mpe_member_channel_count(0);
+
+        assert!(lower.accepts(1));
+        assert!(!lower.is_mpe_member_channel(1));
+        assert!((2..=16).all(|channel| !lower.accepts(channel)));
+    }
+
+    #[test]
+    fn member_channel_count_is_clamped_and_ignored_outside_mpe() {
+        let mut lower = ChannelFilter::mpe(MpeZone::Lower);
+        let mut omni = ChannelFilter::Omni;
+
+        lower.set_mpe_member_channel_count(40);
+        omni.set_mpe_member_channel_count(3);
+
+        assert_eq!(lower, ChannelFilter::mpe(MpeZone::Lower));
+        assert_eq!(omni, ChannelFilter::Omni);
+    }
+
+    #[test]
+    fn ui_index_matches_the_position_in_the_channel_list() {
+        assert_eq!(ChannelFilter::Omni.ui_index(), 0);
+        assert_eq!(ChannelFilter::Channel(16).ui_index(), 16);
+        assert_eq!(
+            ChannelFilter::mpe(MpeZone::Lower).ui_index(),
+            MPE_LOWER_ZONE_CHANNEL_UI_INDEX
+        );
+        assert_eq!(
+            ChannelFilter::mpe(MpeZone::Upper).ui_index(),
+            MPE_UPPER_ZONE_CHANNEL_UI_INDEX
+        );
+    }
+}
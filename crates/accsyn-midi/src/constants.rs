@@ -8,16 +8,60 @@ pub const CC_MESSAGE_NUMBER_BYTE_INDEX: usize = 1;
 pub const CC_MESSAGE_VALUE_BYTE_INDEX: usize = 2;
 /// Byte index of the pressure value within a Channel Pressure message.
 pub const CHANNEL_PRESSURE_VALUE_BYTE_INDEX: usize = 1;
+/// Byte index of the note number within a Polyphonic Key Pressure message.
+pub const POLY_KEY_PRESSURE_NOTE_BYTE_INDEX: usize = 1;
+/// Byte index of the pressure value within a Polyphonic Key Pressure message.
+pub const POLY_KEY_PRESSURE_VALUE_BYTE_INDEX: usize = 2;
 /// Byte index of the program number within a Program Change message.
 pub const PROGRAM_CHANGE_VALUE_BYTE_INDEX: usize = 1;
 /// Polling interval in milliseconds for checking MIDI device list changes.
 pub const DEVICE_LIST_POLLING_INTERVAL: u64 = 2000;
-/// MIDI message types to ignore (`SysEx` and timing messages).
-pub const MESSAGE_TYPE_IGNORE_LIST: Ignore = Ignore::Sysex;
+/// MIDI message types to ignore (active sensing), `SysEx` is kept for MIDI Tuning Standard messages.
+pub const MESSAGE_TYPE_IGNORE_LIST: Ignore = Ignore::ActiveSense;
 /// Bitmask to extract the MIDI channel from a status byte.
 pub const MESSAGE_STATUS_BYTE_CHANNEL_MASK: u8 = 0x0F;
 /// MIDI Status byte for clock messages
 pub const MIDI_CLOCK_BYTE: u8 = 0xF8;
+/// MIDI Status byte that starts a `SysEx` message.
+pub const SYSEX_START_BYTE: u8 = 0xF0;
+/// Byte that ends a `SysEx` message.
+pub const SYSEX_END_BYTE: u8 = 0xF7;
+/// Byte index of the universal real-time or non-real-time ID within a `SysEx` message.
+pub const SYSEX_UNIVERSAL_ID_BYTE_INDEX: usize = 1;
+/// Byte index of the first sub-ID within a universal `SysEx` message.
+pub const SYSEX_SUB_ID_1_BYTE_INDEX: usize = 3;
+/// Byte index of the second sub-ID within a universal `SysEx` message.
+pub const SYSEX_SUB_ID_2_BYTE_INDEX: usize = 4;
+/// Universal `SysEx` ID for non-real-time messages.
+pub const UNIVERSAL_NON_REAL_TIME_ID: u8 = 0x7E;
+/// Universal `SysEx` ID for real-time messages.
+pub const UNIVERSAL_REAL_TIME_ID: u8 = 0x7F;
+/// Universal `SysEx` sub-ID for MIDI Tuning Standard messages.
+pub const MIDI_TUNING_STANDARD_SUB_ID: u8 = 0x08;
+/// Channel list name selecting an MPE lower zone.
+pub const MPE_LOWER_ZONE_CHANNEL_NAME: &str = "MPE Lower";
+/// Channel list name selecting an MPE upper zone.
+pub const MPE_UPPER_ZONE_CHANNEL_NAME: &str = "MPE Upper";
+/// Index of the MPE lower zone entry in the UI channel list, after Omni and channels 1-16.
+pub const MPE_LOWER_ZONE_CHANNEL_UI_INDEX: i32 = 17;
+/// Index of the MPE upper zone entry in the UI channel list.
+pub const MPE_UPPER_ZONE_CHANNEL_UI_INDEX: i32 = 18;
+/// Master channel of an MPE lower zone.
+pub const MPE_LOWER_ZONE_MASTER_CHANNEL: u8 = 1;
+/// Master channel of an MPE upper zone.
+pub const MPE_UPPER_ZONE_MASTER_CHANNEL: u8 = 16;
+/// Most member channels an MPE zone can have, every channel other than its master channel.
+pub const MPE_MAX_MEMBER_CHANNEL_COUNT: u8 = 15;
+/// CC number selecting the most significant byte of a registered parameter number.
+pub const RPN_MSB_CC_NUMBER: u8 = 101;
+/// CC number selecting the least significant byte of a registered parameter number.
+pub const RPN_LSB_CC_NUMBER: u8 = 100;
+/// CC number setting the most significant byte of the selected registered parameter.
+pub const DATA_ENTRY_MSB_CC_NUMBER: u8 = 6;
+/// Registered parameter number of the MPE Configuration Message, whose value is the member channel count.
+pub const MPE_CONFIGURATION_RPN: (u8, u8) = (0, 6);
+/// CC number carrying per-note timbre on MPE member channels.
+pub const MPE_TIMBRE_CC_NUMBER: u8 = 74;
 /// Byte index of the status byte within a MIDI message.
 pub const MESSAGE_STATUS_BYTE_INDEX: usize = 0;
 /// Bitmask to extract the message type from a status byte.
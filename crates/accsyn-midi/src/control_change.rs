@@ -8,6 +8,7 @@ pub(crate) fn get_supported_cc_from_cc_number(cc_number: u8, cc_value: u8) -> Op
         5 => Some(CC::PitchBendRange(cc_value)),
         7 => Some(CC::Volume(cc_value)),
         8 => Some(CC::Mute(cc_value)),
+        9 => Some(CC::SubOscillatorCrossModulationDepth(cc_value)),
         10 => Some(CC::Balance(cc_value)),
         12 => Some(CC::SubOscillatorShapeParameter1(cc_value)),
         13 => Some(CC::SubOscillatorShapeParameter2(cc_value)),
@@ -27,6 +28,12 @@ pub(crate) fn get_supported_cc_from_cc_number(cc_number: u8, cc_value: u8) -> Op
         27 => Some(CC::PitchEnvelopeSustainLevel(cc_value)),
         28 => Some(CC::PitchEnvelopeReleaseTime(cc_value)),
         29 => Some(CC::PitchEnvelopeInverted(cc_value)),
+        30 => Some(CC::PitchEnvelopeCurve(cc_value)),
+        31 => Some(CC::SubOscillatorCrossModulation(cc_value)),
+        33 => Some(CC::Oscillator1CrossModulation(cc_value)),
+        34 => Some(CC::Oscillator2CrossModulation(cc_value)),
+        35 => Some(CC::Oscillator3CrossModulation(cc_value)),
+        36 => Some(CC::Oscillator1CrossModulationDepth(cc_value)),
         37 => Some(CC::PortamentoTime(cc_value)),
         38 => Some(CC::OscillatorHardSync(cc_value)),
         40 => Some(CC::SubOscillatorShape(cc_value)),
@@ -65,8 +72,15 @@ pub(crate) fn get_supported_cc_from_cc_number(cc_number: u8, cc_value: u8) -> Op
         73 => Some(CC::AmpEGAttackTime(cc_value)),
         74 => Some(CC::FilterCutoff(cc_value)),
         75 => Some(CC::AmpEGDecayTime(cc_value)),
+        76 => Some(CC::FilterType(cc_value)),
+        77 => Some(CC::FilterDrive(cc_value)),
+        78 => Some(CC::SecondFilterCutoff(cc_value)),
         79 => Some(CC::AmpEGSustainLevel(cc_value)),
         80 => Some(CC::AmpEGInverted(cc_value)),
+        81 => Some(CC::AmpEGCurve(cc_value)),
+        82 => Some(CC::SecondFilterResonance(cc_value)),
+        83 => Some(CC::SecondFilterPoles(cc_value)),
+        84 => Some(CC::SecondFilterType(cc_value)),
         85 => Some(CC::FilterEnvelopeAttackTime(cc_value)),
         86 => Some(CC::FilterEnvelopeDecayTime(cc_value)),
         87 => Some(CC::FilterEnvelopeSustainLevel(cc_value)),
@@ -74,6 +88,12 @@ pub(crate) fn get_supported_cc_from_cc_number(cc_number: u8, cc_value: u8) -> Op
         89 => Some(CC::FilterEnvelopeInverted(cc_value)),
         90 => Some(CC::FilterEnvelopeAmount(cc_value)),
         91 => Some(CC::KeyTrackingAmount(cc_value)),
+        92 => Some(CC::FilterEnvelopeCurve(cc_value)),
+        93 => Some(CC::SecondFilterDrive(cc_value)),
+        94 => Some(CC::SecondFilterKeyTracking(cc_value)),
+        95 => Some(CC::FilterRouting(cc_value)),
+        96 => Some(CC::FilterCrossfade(cc_value)),
+        97 => Some(CC::Oscillator2CrossModulationDepth(cc_value)),
         100 => Some(CC::ModWheelLFOKeySync(cc_value)),
         101 => Some(CC::ModWheelLFOClockSync(cc_value)),
         102 => Some(CC::ModWheelLFOFrequency(cc_value)),
@@ -89,6 +109,11 @@ pub(crate) fn get_supported_cc_from_cc_number(cc_number: u8, cc_value: u8) -> Op
         112 => Some(CC::FilterModLFOReset),
         113 => Some(CC::FilterModLFOClockSync(cc_value)),
         114 => Some(CC::FilterModLFOKeySync(cc_value)),
+        115 => Some(CC::VoiceMode(cc_value)),
+        116 => Some(CC::VoiceCount(cc_value)),
+        117 => Some(CC::VoiceStealingPolicy(cc_value)),
+        118 => Some(CC::ReleaseVelocityAmount(cc_value)),
+        119 => Some(CC::Oscillator3CrossModulationDepth(cc_value)),
         123 => Some(CC::AllNotesOff),
         _ => {
             log::debug!(target: "midi::cc", "Unmapped CC number: {cc_number}, value: {cc_value}");
@@ -1,36 +1,54 @@
+use crate::channel_filter::ChannelFilter;
 use crate::constants::{
     CC_MESSAGE_NUMBER_BYTE_INDEX, CC_MESSAGE_VALUE_BYTE_INDEX, CHANNEL_PRESSURE_VALUE_BYTE_INDEX,
-    MESSAGE_STATUS_BYTE_CHANNEL_MASK, MESSAGE_STATUS_BYTE_INDEX, MESSAGE_STATUS_BYTE_TYPE_MASK,
-    MESSAGE_TYPE_IGNORE_LIST, MIDI_CLOCK_BYTE, MIDI_INPUT_CLIENT_NAME, MIDI_INPUT_CONNECTION_NAME,
-    NOTE_MESSAGE_NUMBER_BYTE_INDEX, NOTE_MESSAGE_VELOCITY_BYTE_INDEX,
+    DATA_ENTRY_MSB_CC_NUMBER, MESSAGE_STATUS_BYTE_CHANNEL_MASK, MESSAGE_STATUS_BYTE_INDEX,
+    MESSAGE_STATUS_BYTE_TYPE_MASK, MESSAGE_TYPE_IGNORE_LIST, MIDI_CLOCK_BYTE,
+    MIDI_INPUT_CLIENT_NAME, MIDI_INPUT_CONNECTION_NAME, MPE_CONFIGURATION_RPN,
+    MPE_TIMBRE_CC_NUMBER, NOTE_MESSAGE_NUMBER_BYTE_INDEX, NOTE_MESSAGE_VELOCITY_BYTE_INDEX,
     PITCH_BEND_MESSAGE_LSB_BYTE_INDEX, PITCH_BEND_MESSAGE_MSB_BYTE_INDEX,
+    POLY_KEY_PRESSURE_NOTE_BYTE_INDEX, POLY_KEY_PRESSURE_VALUE_BYTE_INDEX,
     PROGRAM_CHANGE_VALUE_BYTE_INDEX, RAW_CHANNEL_TO_USER_READABLE_CHANNEL_OFFSET,
+    RPN_LSB_CC_NUMBER, RPN_MSB_CC_NUMBER, SYSEX_START_BYTE,
 };
-use crate::{Status, control_change};
+use crate::{Status, control_change, tuning_standard};
+use accsyn_core::defaults::Defaults;
 use accsyn_core::midi_events::MidiEvent;
 use anyhow::Result;
 use crossbeam_channel::Sender;
 use midir::{MidiInput, MidiInputConnection, MidiInputPort};
 use std::sync::{Arc, Mutex, PoisonError};
 
+/// The registered parameter number selected on an MPE master channel, followed per input connection.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RegisteredParameterSelection {
+    msb: Option<u8>,
+    lsb: Option<u8>,
+}
+
+impl RegisteredParameterSelection {
+    fn is_mpe_configuration(self) -> bool {
+        (self.msb, self.lsb) == (Some(MPE_CONFIGURATION_RPN.0), Some(MPE_CONFIGURATION_RPN.1))
+    }
+}
+
 pub(crate) fn create_midi_input_listener(
     input_port: &MidiInputPort,
-    current_channel_arc: Arc<Mutex<Option<u8>>>,
+    channel_filter_arc: Arc<Mutex<ChannelFilter>>,
     midi_message_sender: Sender<MidiEvent>,
-    current_note_arc: Arc<Mutex<Option<u8>>>,
 ) -> Result<MidiInputConnection<()>> {
     let mut midi_input = MidiInput::new(MIDI_INPUT_CLIENT_NAME)?;
     midi_input.ignore(MESSAGE_TYPE_IGNORE_LIST);
 
+    let mut registered_parameter = RegisteredParameterSelection::default();
     let connection_result = midi_input.connect(
         input_port,
         MIDI_INPUT_CONNECTION_NAME,
         move |_, message, ()| {
             process_midi_message(
                 message,
-                &current_channel_arc,
+                &channel_filter_arc,
+                &mut registered_parameter,
                 &midi_message_sender,
-                &current_note_arc,
             );
         },
         (),
@@ -41,24 +59,43 @@ pub(crate) fn create_midi_input_listener(
 
 pub(crate) fn process_midi_message(
     message: &[u8],
-    current_channel_arc: &Arc<Mutex<Option<u8>>>,
+    channel_filter_arc: &Arc<Mutex<ChannelFilter>>,
+    registered_parameter: &mut RegisteredParameterSelection,
     midi_message_sender: &Sender<MidiEvent>,
-    current_note_arc: &Arc<Mutex<Option<u8>>>,
 ) {
     if message.is_empty() {
         return;
     }
 
     let message_channel = channel_from_status_byte(message[MESSAGE_STATUS_BYTE_INDEX]);
+    let channel_filter = *channel_filter_arc
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
 
+    // Clock and SysEx status bytes carry no channel
     if message[0] != MIDI_CLOCK_BYTE
-        && !message_channel_matches_current_channel(message_channel, current_channel_arc)
+        && message[0] != SYSEX_START_BYTE
+        && !channel_filter.accepts(message_channel)
     {
         log::trace!(target: "midi::input", "Dropping message {message:?} for non-matching channel {message_channel}");
         return;
     }
 
-    let Some(event) = event_from_message_status(message, current_note_arc) else {
+    let is_channel_message = message[MESSAGE_STATUS_BYTE_INDEX] < SYSEX_START_BYTE;
+    if is_channel_message
+        && channel_filter.is_mpe_master_channel(message_channel)
+        && apply_mpe_configuration(message, channel_filter_arc, registered_parameter)
+    {
+        return;
+    }
+
+    let event = if is_channel_message && channel_filter.is_mpe_member_channel(message_channel) {
+        mpe_event_from_message_status(message, message_channel)
+    } else {
+        event_from_message_status(message, channel_filter.channel())
+    };
+
+    let Some(event) = event else {
         return;
     };
 
@@ -70,46 +107,91 @@ pub(crate) fn process_midi_message(
     }
 }
 
-fn message_channel_matches_current_channel(
-    message_channel: u8,
-    current_channel_arc: &Arc<Mutex<Option<u8>>>,
+/// Follows the registered parameter selected on the MPE master channel and sets the zone's member channel
+/// count from an MPE Configuration Message (RPN 6). Returns whether the message was an RPN select or data
+/// entry, which configure the zone rather than reaching the synthesizer.
+fn apply_mpe_configuration(
+    message: &[u8],
+    channel_filter_arc: &Arc<Mutex<ChannelFilter>>,
+    registered_parameter: &mut RegisteredParameterSelection,
 ) -> bool {
-    let current_channel = current_channel_arc
-        .lock()
-        .unwrap_or_else(PoisonError::into_inner);
-    if current_channel.is_none() {
-        return true;
-    }
-    if let Some(channel) = *current_channel
-        && channel == message_channel
+    if message_status_from_status_byte(message[MESSAGE_STATUS_BYTE_INDEX]) != Status::ControlChange
     {
-        return true;
+        return false;
     }
+    let (Some(&cc_number), Some(&cc_value)) = (
+        message.get(CC_MESSAGE_NUMBER_BYTE_INDEX),
+        message.get(CC_MESSAGE_VALUE_BYTE_INDEX),
+    ) else {
+        return false;
+    };
 
-    false
+    match cc_number {
+        RPN_MSB_CC_NUMBER => registered_parameter.msb = Some(cc_value),
+        RPN_LSB_CC_NUMBER => registered_parameter.lsb = Some(cc_value),
+        DATA_ENTRY_MSB_CC_NUMBER => {
+            if registered_parameter.is_mpe_configuration() {
+                log::debug!(target: "midi::input", "MPE Configuration Message received with {cc_value} member channels");
+                channel_filter_arc
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .set_mpe_member_channel_count(cc_value);
+            }
+        }
+        _ => return false,
+    }
+    true
 }
 
-fn event_from_message_status(
-    message: &[u8],
-    current_note_arc: &Arc<Mutex<Option<u8>>>,
-) -> Option<MidiEvent> {
+fn event_from_message_status(message: &[u8], current_channel: Option<u8>) -> Option<MidiEvent> {
     match message_status_from_status_byte(message[MESSAGE_STATUS_BYTE_INDEX]) {
-        Status::NoteOn => process_note_on_message(message, current_note_arc),
-        Status::NoteOff => process_note_off_message(message, current_note_arc),
+        Status::NoteOn => process_note_on_message(message),
+        Status::NoteOff => process_note_off_message(message),
         Status::ControlChange => process_cc_message(message),
         Status::PitchBend => process_pitch_bend_message(message),
         Status::Stop => Some(MidiEvent::Stop),
         Status::ProgramChange => process_program_change_message(message),
         Status::ChannelPressure => process_channel_pressure_message(message),
+        Status::PolyphonicKeyPressure => process_poly_key_pressure_message(message),
         Status::Clock => Some(MidiEvent::Clock),
         Status::Reset => Some(MidiEvent::Reset),
-        _ => {
+        Status::SystemExclusive => {
+            tuning_standard::tuning_change_from_sysex(message, current_channel)
+                .map(MidiEvent::TuningChange)
+        }
+        Status::Unknown => {
             log::debug!(target: "midi::input", "Unhandled MIDI status type: 0x{:02X}", message[MESSAGE_STATUS_BYTE_INDEX]);
             None
         }
     }
 }
 
+/// Converts a message from an MPE member channel into its per-note event. Notes, pitch bend, channel
+/// pressure and CC 74 follow the note on that channel; every other message acts as it does on the master
+/// channel.
+fn mpe_event_from_message_status(message: &[u8], channel: u8) -> Option<MidiEvent> {
+    let status = message_status_from_status_byte(message[MESSAGE_STATUS_BYTE_INDEX]);
+    if status == Status::ControlChange
+        && message.get(CC_MESSAGE_NUMBER_BYTE_INDEX) == Some(&MPE_TIMBRE_CC_NUMBER)
+    {
+        let timbre_value = *message.get(CC_MESSAGE_VALUE_BYTE_INDEX)?;
+        return Some(MidiEvent::MpeTimbre(channel, timbre_value));
+    }
+
+    let event = match event_from_message_status(message, None)? {
+        MidiEvent::NoteOn(midi_note, velocity) => {
+            MidiEvent::MpeNoteOn(channel, midi_note, velocity)
+        }
+        MidiEvent::NoteOff(midi_note, release_velocity) => {
+            MidiEvent::MpeNoteOff(channel, midi_note, release_velocity)
+        }
+        MidiEvent::PitchBend(bend_amount) => MidiEvent::MpePitchBend(channel, bend_amount),
+        MidiEvent::ChannelPressure(pressure) => MidiEvent::MpePressure(channel, pressure),
+        event => event,
+    };
+    Some(event)
+}
+
 fn process_program_change_message(message: &[u8]) -> Option<MidiEvent> {
     let program_number = *message.get(PROGRAM_CHANGE_VALUE_BYTE_INDEX)?;
     Some(MidiEvent::ProgramChange(program_number))
@@ -120,6 +202,12 @@ fn process_channel_pressure_message(message: &[u8]) -> Option<MidiEvent> {
     Some(MidiEvent::ChannelPressure(pressure_amount))
 }
 
+fn process_poly_key_pressure_message(message: &[u8]) -> Option<MidiEvent> {
+    let midi_note = *message.get(POLY_KEY_PRESSURE_NOTE_BYTE_INDEX)?;
+    let pressure_amount = *message.get(POLY_KEY_PRESSURE_VALUE_BYTE_INDEX)?;
+    Some(MidiEvent::PolyKeyPressure(midi_note, pressure_amount))
+}
+
 fn process_pitch_bend_message(message: &[u8]) -> Option<MidiEvent> {
     let amount_most_significant_byte = *message.get(PITCH_BEND_MESSAGE_MSB_BYTE_INDEX)?;
     let amount_least_significant_byte = *message.get(PITCH_BEND_MESSAGE_LSB_BYTE_INDEX)?;
@@ -136,49 +224,28 @@ fn process_cc_message(message: &[u8]) -> Option<MidiEvent> {
         .map(MidiEvent::ControlChange)
 }
 
-fn process_note_off_message(
-    message: &[u8],
-    current_note_arc: &Arc<Mutex<Option<u8>>>,
-) -> Option<MidiEvent> {
+fn process_note_off_message(message: &[u8]) -> Option<MidiEvent> {
     let midi_note = *message.get(NOTE_MESSAGE_NUMBER_BYTE_INDEX)?;
-    log::trace!(target: "midi::input", "Note Off Message {midi_note:?} received");
-
-    let mut current_note = current_note_arc
-        .lock()
-        .unwrap_or_else(PoisonError::into_inner);
-    match *current_note {
-        Some(note) if note == midi_note => {
-            *current_note = None;
-            Some(MidiEvent::NoteOff)
-        }
-        _ => None,
-    }
+    // A note off without a velocity byte is treated like one from a keyboard without release sensing
+    let release_velocity = message
+        .get(NOTE_MESSAGE_VELOCITY_BYTE_INDEX)
+        .copied()
+        .unwrap_or(Defaults::RELEASE_VELOCITY);
+    log::trace!(target: "midi::input", "Note Off Message {midi_note:?} Release Velocity {release_velocity} received");
+    Some(MidiEvent::NoteOff(midi_note, release_velocity))
 }
 
-fn process_note_on_message(
-    message: &[u8],
-    current_note_arc: &Arc<Mutex<Option<u8>>>,
-) -> Option<MidiEvent> {
+fn process_note_on_message(message: &[u8]) -> Option<MidiEvent> {
     let midi_note = *message.get(NOTE_MESSAGE_NUMBER_BYTE_INDEX)?;
     let midi_velocity = *message.get(NOTE_MESSAGE_VELOCITY_BYTE_INDEX)?;
 
     log::trace!(target: "midi::input", "Note On Message {midi_note:?} Velocity {midi_velocity} received");
 
-    let mut current_note = current_note_arc
-        .lock()
-        .unwrap_or_else(PoisonError::into_inner);
-
     if midi_velocity > 0 {
-        *current_note = Some(midi_note);
         return Some(MidiEvent::NoteOn(midi_note, midi_velocity));
     }
 
-    if matches!(*current_note, Some(note) if note == midi_note) {
-        *current_note = None;
-        return Some(MidiEvent::NoteOff);
-    }
-
-    None
+    Some(MidiEvent::NoteOff(midi_note, Defaults::RELEASE_VELOCITY))
 }
 
 fn channel_from_status_byte(status: u8) -> u8 {
@@ -201,6 +268,7 @@ fn message_status_from_status_byte(status: u8) -> Status {
         0xC0 => Status::ProgramChange,
         0xD0 => Status::ChannelPressure,
         0xE0 => Status::PitchBend,
+        0xF0 => Status::SystemExclusive,
         0xFC => Status::Stop,
         0xF8 => Status::Clock,
         0xFF => Status::Reset,
@@ -217,30 +285,218 @@ mod tests {
     fn process_midi_message_does_not_panic_on_empty_message() {
         use crossbeam_channel::unbounded;
         let (tx, _rx) = unbounded::<MidiEvent>();
-        let channel = Arc::new(Mutex::new(None::<u8>));
-        let note = Arc::new(Mutex::new(None::<u8>));
+        let channel = Arc::new(Mutex::new(ChannelFilter::Omni));
         // must not panic
-        process_midi_message(&[], &channel, &tx, &note);
+        process_midi_message(
+            &[],
+            &channel,
+            &mut RegisteredParameterSelection::default(),
+            &tx,
+        );
     }
 
     #[test]
     fn process_midi_message_does_not_panic_on_single_byte_note_on_status() {
         use crossbeam_channel::unbounded;
         let (tx, _rx) = unbounded::<MidiEvent>();
-        let channel = Arc::new(Mutex::new(None::<u8>));
-        let note = Arc::new(Mutex::new(None::<u8>));
+        let channel = Arc::new(Mutex::new(ChannelFilter::Omni));
         // 0x90 = Note On ch 1, but no subsequent bytes
-        process_midi_message(&[0x90], &channel, &tx, &note);
+        process_midi_message(
+            &[0x90],
+            &channel,
+            &mut RegisteredParameterSelection::default(),
+            &tx,
+        );
     }
 
     #[test]
     fn process_midi_message_does_not_panic_on_two_byte_note_on_message() {
         use crossbeam_channel::unbounded;
         let (tx, _rx) = unbounded::<MidiEvent>();
-        let channel = Arc::new(Mutex::new(None::<u8>));
-        let note = Arc::new(Mutex::new(None::<u8>));
+        let channel = Arc::new(Mutex::new(ChannelFilter::Omni));
         // 0x90 = Note On, note=60, missing velocity byte
-        process_midi_message(&[0x90, 60], &channel, &tx, &note);
+        process_midi_message(
+            &[0x90, 60],
+            &channel,
+            &mut RegisteredParameterSelection::default(),
+            &tx,
+        );
+    }
+
+    #[test]
+    fn note_off_message_carries_the_released_note_number() {
+        let result = event_from_message_status(&[0x80, 60, 0], None);
+        assert_eq!(result, Some(MidiEvent::NoteOff(60, 0)));
+    }
+
+    #[test]
+    fn note_off_message_carries_the_release_velocity() {
+        let result = event_from_message_status(&[0x80, 60, 100], None);
+        assert_eq!(result, Some(MidiEvent::NoteOff(60, 100)));
+    }
+
+    #[test]
+    fn note_off_without_a_velocity_byte_uses_the_default_release_velocity() {
+        let result = event_from_message_status(&[0x80, 60], None);
+        assert_eq!(
+            result,
+            Some(MidiEvent::NoteOff(60, Defaults::RELEASE_VELOCITY))
+        );
+    }
+
+    #[test]
+    fn note_on_with_zero_velocity_is_a_note_off_for_that_note() {
+        let result = event_from_message_status(&[0x90, 64, 0], None);
+        assert_eq!(
+            result,
+            Some(MidiEvent::NoteOff(64, Defaults::RELEASE_VELOCITY))
+        );
+    }
+
+    #[test]
+    fn poly_key_pressure_carries_the_note_and_pressure() {
+        let result = event_from_message_status(&[0xA0, 60, 90], None);
+        assert_eq!(result, Some(MidiEvent::PolyKeyPressure(60, 90)));
+    }
+
+    #[test]
+    fn poly_key_pressure_without_a_pressure_byte_is_dropped() {
+        let result = event_from_message_status(&[0xA0, 60], None);
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn tuning_sysex_is_passed_on_whatever_the_channel_filter() {
+        use accsyn_core::midi_events::TuningChange;
+        use crossbeam_channel::unbounded;
+        let (tx, rx) = unbounded::<MidiEvent>();
+        let channel = Arc::new(Mutex::new(ChannelFilter::Channel(5)));
+        // Real-time single note tuning change moving A4 to A#4
+        let message = [
+            0xF0, 0x7F, 0x7F, 0x08, 0x02, 0x00, 0x01, 69, 70, 0x00, 0x00, 0xF7,
+        ];
+
+        process_midi_message(
+            &message,
+            &channel,
+            &mut RegisteredParameterSelection::default(),
+            &tx,
+        );
+
+        assert_eq!(
+            rx.try_recv().ok(),
+            Some(MidiEvent::TuningChange(TuningChange::Notes(vec![(
+                69, 70.0
+            )])))
+        );
+    }
+
+    #[test]
+    fn mpe_member_channel_messages_follow_their_note() {
+        use crate::channel_filter::MpeZone;
+        use crossbeam_channel::unbounded;
+        let (tx, rx) = unbounded::<MidiEvent>();
+        let channel = Arc::new(Mutex::new(ChannelFilter::mpe(MpeZone::Lower)));
+        let mut registered_parameter = RegisteredParameterSelection::default();
+
+        // Note on, pitch bend, channel pressure and CC 74 on member channel 3
+        process_midi_message(&[0x92, 60, 100], &channel, &mut registered_parameter, &tx);
+        process_midi_message(
+            &[0xE2, 0x00, 0x50],
+            &channel,
+            &mut registered_parameter,
+            &tx,
+        );
+        process_midi_message(&[0xD2, 90], &channel, &mut registered_parameter, &tx);
+        process_midi_message(&[0xB2, 74, 20], &channel, &mut registered_parameter, &tx);
+        process_midi_message(&[0x82, 60, 30], &channel, &mut registered_parameter, &tx);
+
+        let events: Vec<MidiEvent> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                MidiEvent::MpeNoteOn(3, 60, 100),
+                MidiEvent::MpePitchBend(3, 0x50 << 7),
+                MidiEvent::MpePressure(3, 90),
+                MidiEvent::MpeTimbre(3, 20),
+                MidiEvent::MpeNoteOff(3, 60, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn mpe_master_channel_messages_act_on_the_whole_synthesizer() {
+        use crate::channel_filter::MpeZone;
+        use crossbeam_channel::unbounded;
+        let (tx, rx) = unbounded::<MidiEvent>();
+        let channel = Arc::new(Mutex::new(ChannelFilter::mpe(MpeZone::Upper)));
+        let mut registered_parameter = RegisteredParameterSelection::default();
+
+        // Pitch bend and CC 74 on master channel 16
+        process_midi_message(
+            &[0xEF, 0x00, 0x50],
+            &channel,
+            &mut registered_parameter,
+            &tx,
+        );
+        process_midi_message(&[0xBF, 74, 20], &channel, &mut registered_parameter, &tx);
+
+        let events: Vec<MidiEvent> = rx.try_iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                MidiEvent::PitchBend(0x50 << 7),
+                MidiEvent::ControlChange(accsyn_core::midi_events::CC::FilterCutoff(20)),
+            ]
+        );
+    }
+
+    #[test]
+    fn mpe_configuration_message_sets_the_member_channel_count() {
+        use crate::channel_filter::MpeZone;
+        use crossbeam_channel::unbounded;
+        let (tx, rx) = unbounded::<MidiEvent>();
+        let channel = Arc::new(Mutex::new(ChannelFilter::mpe(MpeZone::Lower)));
+        let mut registered_parameter = RegisteredParameterSelection::default();
+
+        // RPN 6 with four member channels on master channel 1, then a note on channel 6 outside the zone
+        process_midi_message(&[0xB0, 101, 0], &channel, &mut registered_parameter, &tx);
+        process_midi_message(&[0xB0, 100, 6], &channel, &mut registered_parameter, &tx);
+        process_midi_message(&[0xB0, 6, 4], &channel, &mut registered_parameter, &tx);
+        process_midi_message(&[0x95, 60, 100], &channel, &mut registered_parameter, &tx);
+        process_midi_message(&[0x94, 62, 100], &channel, &mut registered_parameter, &tx);
+
+        let mut expected = ChannelFilter::mpe(MpeZone::Lower);
+        expected.set_mpe_member_channel_count(4);
+        assert_eq!(*channel.lock().unwrap(), expected);
+        assert_eq!(
+            rx.try_iter().collect::<Vec<MidiEvent>>(),
+            vec![MidiEvent::MpeNoteOn(5, 62, 100)]
+        );
+    }
+
+    #[test]
+    fn data_entry_for_another_registered_parameter_leaves_the_zone_alone() {
+        use crate::channel_filter::MpeZone;
+        use crossbeam_channel::unbounded;
+        let (tx, _rx) = unbounded::<MidiEvent>();
+        let channel = Arc::new(Mutex::new(ChannelFilter::mpe(MpeZone::Upper)));
+        let mut registered_parameter = RegisteredParameterSelection::default();
+
+        // RPN 0 (pitch bend sensitivity) on master channel 16
+        process_midi_message(&[0xBF, 101, 0], &channel, &mut registered_parameter, &tx);
+        process_midi_message(&[0xBF, 100, 0], &channel, &mut registered_parameter, &tx);
+        process_midi_message(&[0xBF, 6, 2], &channel, &mut registered_parameter, &tx);
+
+        assert_eq!(*channel.lock().unwrap(), ChannelFilter::mpe(MpeZone::Upper));
+    }
+
+    #[test]
+    fn message_type_from_status_byte_returns_correct_status_for_sysex() {
+        assert_eq!(
+            message_status_from_status_byte(0xF0),
+            Status::SystemExclusive
+        );
     }
 
     #[test]
@@ -1,10 +1,12 @@
 //! MIDI input handling for the `AccSyn` synthesizer.
 //!
 //! Provides MIDI device monitoring, message parsing, virtual input port creation,
-//! and channel filtering using midir.
+//! channel filtering and MPE zone routing using midir.
 
 #![warn(missing_docs)]
 
+/// MIDI channel filtering and MPE zone layout.
+pub mod channel_filter;
 /// MIDI protocol constants: byte indexes, masks, and configuration values.
 pub mod constants;
 /// MIDI Control Change number to synthesizer parameter mapping.
@@ -13,12 +15,17 @@ pub mod control_change;
 pub mod device_monitor;
 /// MIDI input message listener and event processing.
 pub mod input_listener;
+/// MIDI Tuning Standard `SysEx` message parsing.
+pub mod tuning_standard;
 
+use crate::channel_filter::ChannelFilter;
 use crate::constants::{
     MESSAGE_TYPE_IGNORE_LIST, MIDI_INPUT_CLIENT_NAME, MIDI_INPUT_CONNECTION_NAME,
     MIDI_MESSAGE_SENDER_CAPACITY,
 };
-use crate::input_listener::{create_midi_input_listener, process_midi_message};
+use crate::input_listener::{
+    RegisteredParameterSelection, create_midi_input_listener, process_midi_message,
+};
 
 use accsyn_core::midi_events::MidiEvent;
 use accsyn_core::ui_events::UIUpdates;
@@ -53,7 +60,7 @@ pub enum MidiDeviceUpdateEvents {
     InputPort(Option<(usize, MidiInputPort)>),
     /// User selected a MIDI input port from the UI by name.
     UIMidiInputPort(String),
-    /// User changed the MIDI channel filter from the UI.
+    /// User changed the MIDI channel filter or MPE zone from the UI.
     UIMidiInputChannelIndex(String),
 }
 
@@ -66,6 +73,7 @@ enum Status {
     ProgramChange,
     ChannelPressure,
     PitchBend,
+    SystemExclusive,
     Stop,
     Clock,
     Reset,
@@ -80,8 +88,7 @@ pub struct Midi {
     device_update_sender: Sender<MidiDeviceUpdateEvents>,
     input_listener: Arc<Mutex<Option<MidiInputConnection<()>>>>,
     virtual_input_port: Arc<Mutex<Option<MidiInputConnection<()>>>>,
-    current_note: Arc<Mutex<Option<u8>>>,
-    current_channel: Arc<Mutex<Option<u8>>>,
+    channel_filter: Arc<Mutex<ChannelFilter>>,
 }
 
 impl Default for Midi {
@@ -109,8 +116,7 @@ impl Midi {
             ui_update_receiver,
             input_listener: Arc::new(Mutex::new(None)),
             virtual_input_port: Arc::new(Mutex::new(None)),
-            current_note: Arc::new(Mutex::new(None)),
-            current_channel: Arc::new(Mutex::new(None)),
+            channel_filter: Arc::new(Mutex::new(ChannelFilter::default())),
         }
     }
 
@@ -152,15 +158,11 @@ impl Midi {
 
     fn create_virtual_input_port(&self) -> Result<()> {
         let virtual_input_port_arc = self.virtual_input_port.clone();
-        let current_channel_arc = self.current_channel.clone();
+        let channel_filter_arc = self.channel_filter.clone();
         let message_sender_arc = self.message_sender.clone();
-        let current_note_arc = self.current_note.clone();
 
-        let new_virtual_input_port = create_midi_virtual_input(
-            current_channel_arc.clone(),
-            message_sender_arc.clone(),
-            current_note_arc.clone(),
-        )?;
+        let new_virtual_input_port =
+            create_midi_virtual_input(channel_filter_arc.clone(), message_sender_arc.clone())?;
 
         let mut virtual_input_port = virtual_input_port_arc
             .lock()
@@ -177,9 +179,8 @@ impl Midi {
         ui_update_sender: Sender<UIUpdates>,
     ) {
         let mut input_listener_arc = self.input_listener.clone();
-        let current_channel_arc = self.current_channel.clone();
+        let channel_filter_arc = self.channel_filter.clone();
         let message_sender_arc = self.message_sender.clone();
-        let current_note_arc = self.current_note.clone();
 
         thread::spawn(move || {
             log::debug!(target: "midi::control", "Control listener thread started");
@@ -206,9 +207,8 @@ impl Midi {
                         if let Some(port) = input_port {
                             reload_midi_input_listener(
                                 &mut input_listener_arc,
-                                &current_channel_arc,
+                                &channel_filter_arc,
                                 &message_sender_arc,
-                                &current_note_arc,
                                 &port.1,
                             );
 
@@ -230,9 +230,8 @@ impl Midi {
                         if let Some(port) = midi_port_from_port_name(&port_name) {
                             reload_midi_input_listener(
                                 &mut input_listener_arc,
-                                &current_channel_arc,
+                                &channel_filter_arc,
                                 &message_sender_arc,
-                                &current_note_arc,
                                 &port.1,
                             );
 
@@ -256,12 +255,12 @@ impl Midi {
                             channel = channel_index.as_str();
                             "Channel filter changed"
                         );
-                        let mut current_channel = current_channel_arc
+                        let mut channel_filter = channel_filter_arc
                             .lock()
                             .unwrap_or_else(PoisonError::into_inner);
-                        *current_channel = channel_index.parse().ok();
+                        *channel_filter = ChannelFilter::from_channel_name(&channel_index);
 
-                        let channel_index_number = i32::from(current_channel.unwrap_or(0));
+                        let channel_index_number = channel_filter.ui_index();
                         if let Err(e) =
                             ui_update_sender.send(UIUpdates::MidiChannelIndex(channel_index_number))
                         {
@@ -276,18 +275,16 @@ impl Midi {
 
 fn reload_midi_input_listener(
     input_listener_arc: &mut Arc<Mutex<Option<MidiInputConnection<()>>>>,
-    current_channel_arc: &Arc<Mutex<Option<u8>>>,
+    channel_filter_arc: &Arc<Mutex<ChannelFilter>>,
     message_sender_arc: &Sender<MidiEvent>,
-    current_note_arc: &Arc<Mutex<Option<u8>>>,
     port: &MidiInputPort,
 ) {
     log::info!(target: "midi::input", "Reloading input listener");
 
     let new_input_listener = match create_midi_input_listener(
         port,
-        current_channel_arc.clone(),
+        channel_filter_arc.clone(),
         message_sender_arc.clone(),
-        current_note_arc.clone(),
     ) {
         Ok(listener) => listener,
         Err(err) => {
@@ -330,21 +327,21 @@ fn midi_port_from_port_name(port_name: &str) -> Option<(usize, MidiInputPort)> {
 }
 
 pub(crate) fn create_midi_virtual_input(
-    current_channel_arc: Arc<Mutex<Option<u8>>>,
+    channel_filter_arc: Arc<Mutex<ChannelFilter>>,
     midi_message_sender: Sender<MidiEvent>,
-    current_note_arc: Arc<Mutex<Option<u8>>>,
 ) -> Result<MidiInputConnection<()>> {
     let mut midi_input = MidiInput::new(MIDI_INPUT_CLIENT_NAME)?;
     midi_input.ignore(MESSAGE_TYPE_IGNORE_LIST);
 
+    let mut registered_parameter = RegisteredParameterSelection::default();
     let connection_result = midi_input.create_virtual(
         MIDI_INPUT_CONNECTION_NAME,
         move |_, message, ()| {
             process_midi_message(
                 message,
-                &current_channel_arc,
+                &channel_filter_arc,
+                &mut registered_parameter,
                 &midi_message_sender,
-                &current_note_arc,
             );
         },
         (),
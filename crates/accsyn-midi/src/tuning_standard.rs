@@ -0,0 +1,331 @@
+use crate::constants::{
+    MIDI_TUNING_STANDARD_SUB_ID, RAW_CHANNEL_TO_USER_READABLE_CHANNEL_OFFSET, SYSEX_END_BYTE,
+    SYSEX_SUB_ID_1_BYTE_INDEX, SYSEX_SUB_ID_2_BYTE_INDEX, SYSEX_UNIVERSAL_ID_BYTE_INDEX,
+    UNIVERSAL_NON_REAL_TIME_ID, UNIVERSAL_REAL_TIME_ID,
+};
+use accsyn_core::math::MIDI_DATA_BYTE_7BIT_MASK;
+use accsyn_core::midi_events::TuningChange;
+
+const BULK_DUMP: u8 = 0x01;
+const SINGLE_NOTE_CHANGE: u8 = 0x02;
+const BULK_DUMP_WITH_BANK: u8 = 0x04;
+const SINGLE_NOTE_CHANGE_WITH_BANK: u8 = 0x07;
+const SCALE_OCTAVE_ONE_BYTE: u8 = 0x08;
+const SCALE_OCTAVE_TWO_BYTE: u8 = 0x09;
+
+// Bulk dumps carry the tuning program (and bank) and a 16 character name before the 128 notes
+const BULK_DUMP_DATA_BYTE_INDEX: usize = 22;
+const BULK_DUMP_WITH_BANK_DATA_BYTE_INDEX: usize = 23;
+// Single note changes carry the tuning program (and bank) before the note count
+const SINGLE_NOTE_CHANGE_COUNT_BYTE_INDEX: usize = 6;
+const SINGLE_NOTE_CHANGE_WITH_BANK_COUNT_BYTE_INDEX: usize = 7;
+const SCALE_OCTAVE_CHANNEL_MASK_BYTE_INDEX: usize = 5;
+const SCALE_OCTAVE_DATA_BYTE_INDEX: usize = 8;
+
+const MAX_MIDI_NOTE: u8 = 127;
+const MIDI_NOTE_COUNT: usize = 128;
+const PITCH_CLASS_COUNT: usize = 12;
+const FREQUENCY_DATA_LENGTH: usize = 3;
+const NOTE_CHANGE_LENGTH: usize = 4;
+const NO_CHANGE_FREQUENCY_DATA: [u8; FREQUENCY_DATA_LENGTH] = [0x7F; FREQUENCY_DATA_LENGTH];
+// The two bytes after the semitone are a 14-bit fraction of a semitone
+const SEMITONE_FRACTION_STEPS: f32 = 16384.0;
+// One byte offsets are whole cents from -64 to +63, two byte offsets are 14-bit from -100 to +100 cents
+const ONE_BYTE_OFFSET_CENTRE: i16 = 0x40;
+const TWO_BYTE_OFFSET_CENTRE: i16 = 0x2000;
+const TWO_BYTE_OFFSET_CENTS_PER_STEP: f32 = 100.0 / 8192.0;
+const CHANNEL_MASK_HIGH_BITS: u8 = 0x03;
+
+/// Parses a MIDI Tuning Standard message from a universal real-time or non-real-time `SysEx` message.
+///
+/// Handles bulk tuning dumps, single note tuning changes and scale/octave tuning in both the one and two
+/// byte forms. Every device ID and tuning program is accepted, as the synthesizer plays a single live
+/// tuning. Scale/octave messages only apply when their channel mask includes the current channel.
+pub(crate) fn tuning_change_from_sysex(
+    message: &[u8],
+    current_channel: Option<u8>,
+) -> Option<TuningChange> {
+    let message = message.strip_suffix(&[SYSEX_END_BYTE]).unwrap_or(message);
+    let universal_id = *message.get(SYSEX_UNIVERSAL_ID_BYTE_INDEX)?;
+    if (universal_id != UNIVERSAL_REAL_TIME_ID && universal_id != UNIVERSAL_NON_REAL_TIME_ID)
+        || *message.get(SYSEX_SUB_ID_1_BYTE_INDEX)? != MIDI_TUNING_STANDARD_SUB_ID
+    {
+        log::trace!(target: "midi::input", "Ignoring SysEx message that is not a MIDI Tuning Standard message");
+        return None;
+    }
+
+    let real_time = universal_id == UNIVERSAL_REAL_TIME_ID;
+    match (*message.get(SYSEX_SUB_ID_2_BYTE_INDEX)?, real_time) {
+        (BULK_DUMP, false) => bulk_dump(message, BULK_DUMP_DATA_BYTE_INDEX),
+        (BULK_DUMP_WITH_BANK, false) => bulk_dump(message, BULK_DUMP_WITH_BANK_DATA_BYTE_INDEX),
+        (SINGLE_NOTE_CHANGE, true) => {
+            single_note_change(message, SINGLE_NOTE_CHANGE_COUNT_BYTE_INDEX)
+        }
+        (SINGLE_NOTE_CHANGE_WITH_BANK, _) => {
+            single_note_change(message, SINGLE_NOTE_CHANGE_WITH_BANK_COUNT_BYTE_INDEX)
+        }
+        (SCALE_OCTAVE_ONE_BYTE, _) => scale_octave(message, current_channel, 1),
+        (SCALE_OCTAVE_TWO_BYTE, _) => scale_octave(message, current_channel, 2),
+        (sub_id, _) => {
+            log::debug!(target: "midi::input", "Unhandled MIDI Tuning Standard message: 0x{sub_id:02X}");
+            None
+        }
+    }
+}
+
+// The checksum after the notes isn't checked, tuning software disagrees on which bytes it covers
+fn bulk_dump(message: &[u8], data_byte_index: usize) -> Option<TuningChange> {
+    let frequency_data =
+        message.get(data_byte_index..data_byte_index + MIDI_NOTE_COUNT * FREQUENCY_DATA_LENGTH)?;
+    let note_pitches = (0..=MAX_MIDI_NOTE)
+        .zip(frequency_data.chunks_exact(FREQUENCY_DATA_LENGTH))
+        .filter_map(|(note, data)| pitch_from_frequency_data(data).map(|pitch| (note, pitch)))
+        .collect();
+    Some(TuningChange::Notes(note_pitches))
+}
+
+fn single_note_change(message: &[u8], count_byte_index: usize) -> Option<TuningChange> {
+    let note_count = usize::from(*message.get(count_byte_index)?);
+    let note_pitches = message
+        .get(count_byte_index + 1..)?
+        .chunks_exact(NOTE_CHANGE_LENGTH)
+        .take(note_count)
+        .filter_map(|change| {
+            let (&note, data) = change.split_first()?;
+            pitch_from_frequency_data(data).map(|pitch| (note & MIDI_DATA_BYTE_7BIT_MASK, pitch))
+        })
+        .collect();
+    Some(TuningChange::Notes(note_pitches))
+}
+
+fn scale_octave(
+    message: &[u8],
+    current_channel: Option<u8>,
+    bytes_per_offset: usize,
+) -> Option<TuningChange> {
+    let channel_mask =
+        message.get(SCALE_OCTAVE_CHANNEL_MASK_BYTE_INDEX..SCALE_OCTAVE_DATA_BYTE_INDEX)?;
+    if !channel_mask_includes_channel(channel_mask, current_channel) {
+        log::trace!(target: "midi::input", "Dropping scale/octave tuning for non-matching channels {channel_mask:?}");
+        return None;
+    }
+
+    let offset_data = message.get(
+        SCALE_OCTAVE_DATA_BYTE_INDEX
+            ..SCALE_OCTAVE_DATA_BYTE_INDEX + PITCH_CLASS_COUNT * bytes_per_offset,
+    )?;
+    let mut offsets = [0.0; PITCH_CLASS_COUNT];
+    for (offset, data) in offsets
+        .iter_mut()
+        .zip(offset_data.chunks_exact(bytes_per_offset))
+    {
+        *offset = match *data {
+            [cents] => {
+                f32::from(i16::from(cents & MIDI_DATA_BYTE_7BIT_MASK) - ONE_BYTE_OFFSET_CENTRE)
+            }
+            [most_significant_byte, least_significant_byte] => {
+                let steps = i16::from(most_significant_byte & MIDI_DATA_BYTE_7BIT_MASK) << 7
+                    | i16::from(least_significant_byte & MIDI_DATA_BYTE_7BIT_MASK);
+                f32::from(steps - TWO_BYTE_OFFSET_CENTRE) * TWO_BYTE_OFFSET_CENTS_PER_STEP
+            }
+            _ => return None,
+        };
+    }
+    Some(TuningChange::ScaleOctave(offsets))
+}
+
+// Pitch as a fractional MIDI note number, or None for the no change value
+fn pitch_from_frequency_data(data: &[u8]) -> Option<f32> {
+    let &[semitone, most_significant_byte, least_significant_byte] = data else {
+        return None;
+    };
+    if [semitone, most_significant_byte, least_significant_byte] == NO_CHANGE_FREQUENCY_DATA {
+        return None;
+    }
+
+    let fraction = u16::from(most_significant_byte & MIDI_DATA_BYTE_7BIT_MASK) << 7
+        | u16::from(least_significant_byte & MIDI_DATA_BYTE_7BIT_MASK);
+    Some(
+        f32::from(semitone & MIDI_DATA_BYTE_7BIT_MASK)
+            + f32::from(fraction) / SEMITONE_FRACTION_STEPS,
+    )
+}
+
+// The three mask bytes hold channels 16-15, 14-8 and 7-1, each with the lowest channel in the lowest bit
+fn channel_mask_includes_channel(channel_mask: &[u8], current_channel: Option<u8>) -> bool {
+    let &[high, middle, low] = channel_mask else {
+        return false;
+    };
+    let channels = u16::from(high & CHANNEL_MASK_HIGH_BITS) << 14
+        | u16::from(middle & MIDI_DATA_BYTE_7BIT_MASK) << 7
+        | u16::from(low & MIDI_DATA_BYTE_7BIT_MASK);
+
+    match current_channel {
+        None => channels != 0,
+        Some(channel) => channel
+            .checked_sub(RAW_CHANNEL_TO_USER_READABLE_CHANNEL_OFFSET)
+            .and_then(|raw_channel| 1_u16.checked_shl(u32::from(raw_channel)))
+            .is_some_and(|channel_bit| channels & channel_bit != 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "{actual} should be {expected}"
+        );
+    }
+
+    fn scale_octave_message(universal_id: u8, form: u8, mask: [u8; 3], offsets: &[u8]) -> Vec<u8> {
+        let mut message = vec![0xF0, universal_id, 0x7F, 0x08, form];
+        message.extend_from_slice(&mask);
+        message.extend_from_slice(offsets);
+        message.push(0xF7);
+        message
+    }
+
+    #[test]
+    fn single_note_change_retunes_each_listed_note() {
+        let message = [
+            0xF0, 0x7F, 0x7F, 0x08, 0x02, 0x00, 0x02, 69, 69, 0x40, 0x00, 60, 0x7F, 0x7F, 0x7F,
+            0xF7,
+        ];
+
+        let Some(TuningChange::Notes(note_pitches)) = tuning_change_from_sysex(&message, None)
+        else {
+            panic!("Expected a note tuning change");
+        };
+
+        assert_eq!(note_pitches.len(), 1);
+        assert_eq!(note_pitches[0].0, 69);
+        assert_close(note_pitches[0].1, 69.5);
+    }
+
+    #[test]
+    fn single_note_change_with_bank_is_accepted_in_real_time_and_non_real_time() {
+        for universal_id in [UNIVERSAL_REAL_TIME_ID, UNIVERSAL_NON_REAL_TIME_ID] {
+            let message = [
+                0xF0,
+                universal_id,
+                0x00,
+                0x08,
+                0x07,
+                0x01,
+                0x02,
+                0x01,
+                72,
+                71,
+                0x00,
+                0x00,
+                0xF7,
+            ];
+
+            assert_eq!(
+                tuning_change_from_sysex(&message, None),
+                Some(TuningChange::Notes(vec![(72, 71.0)]))
+            );
+        }
+    }
+
+    #[test]
+    fn bulk_dump_retunes_every_note_except_the_no_change_ones() {
+        let mut message = vec![0xF0, 0x7E, 0x7F, 0x08, 0x01, 0x00];
+        message.extend_from_slice(b"Quarter tones up");
+        for note in 0..=MAX_MIDI_NOTE {
+            if note == 0 {
+                message.extend_from_slice(&NO_CHANGE_FREQUENCY_DATA);
+            } else {
+                message.extend_from_slice(&[note, 0x20, 0x00]);
+            }
+        }
+        message.extend_from_slice(&[0x00, 0xF7]);
+
+        let Some(TuningChange::Notes(note_pitches)) = tuning_change_from_sysex(&message, None)
+        else {
+            panic!("Expected a note tuning change");
+        };
+
+        assert_eq!(note_pitches.len(), 127);
+        assert_eq!(note_pitches[0].0, 1);
+        assert_close(note_pitches[0].1, 1.25);
+        assert_close(note_pitches[126].1, 127.25);
+    }
+
+    #[test]
+    fn truncated_bulk_dump_is_ignored() {
+        let mut message = vec![0xF0, 0x7E, 0x7F, 0x08, 0x01, 0x00];
+        message.extend_from_slice(&[0x20; 16 + 3 * 100]);
+        message.push(0xF7);
+
+        assert_eq!(tuning_change_from_sysex(&message, None), None);
+    }
+
+    #[test]
+    fn bulk_dump_is_only_non_real_time() {
+        let message = [0xF0, 0x7F, 0x7F, 0x08, 0x01, 0x00, 0xF7];
+        assert_eq!(tuning_change_from_sysex(&message, None), None);
+    }
+
+    #[test]
+    fn scale_octave_one_byte_form_is_whole_cents_around_0x40() {
+        let mut offsets = [0x40; 12];
+        offsets[0] = 0x00;
+        offsets[9] = 0x7F;
+        let message = scale_octave_message(0x7E, 0x08, [0x03, 0x7F, 0x7F], &offsets);
+
+        let Some(TuningChange::ScaleOctave(cents)) = tuning_change_from_sysex(&message, None)
+        else {
+            panic!("Expected a scale/octave tuning change");
+        };
+
+        assert_close(cents[0], -64.0);
+        assert_close(cents[1], 0.0);
+        assert_close(cents[9], 63.0);
+    }
+
+    #[test]
+    fn scale_octave_two_byte_form_spans_a_semitone_either_way() {
+        let mut offsets = [0x40, 0x00].repeat(12);
+        offsets[0..2].copy_from_slice(&[0x00, 0x00]);
+        offsets[2..4].copy_from_slice(&[0x60, 0x00]);
+        let message = scale_octave_message(0x7F, 0x09, [0x00, 0x00, 0x01], &offsets);
+
+        let Some(TuningChange::ScaleOctave(cents)) = tuning_change_from_sysex(&message, Some(1))
+        else {
+            panic!("Expected a scale/octave tuning change");
+        };
+
+        assert_close(cents[0], -100.0);
+        assert_close(cents[1], 50.0);
+        assert_close(cents[2], 0.0);
+    }
+
+    #[test]
+    fn scale_octave_only_applies_to_the_channels_in_its_mask() {
+        let message = scale_octave_message(0x7F, 0x08, [0x02, 0x00, 0x04], &[0x40; 12]);
+
+        assert!(tuning_change_from_sysex(&message, None).is_some());
+        assert!(tuning_change_from_sysex(&message, Some(3)).is_some());
+        assert!(tuning_change_from_sysex(&message, Some(16)).is_some());
+        assert!(tuning_change_from_sysex(&message, Some(1)).is_none());
+        assert!(tuning_change_from_sysex(&message, Some(15)).is_none());
+    }
+
+    #[test]
+    fn other_sysex_messages_are_ignored() {
+        // General MIDI system on and a manufacturer specific message
+        assert_eq!(
+            tuning_change_from_sysex(&[0xF0, 0x7E, 0x7F, 0x09, 0x01, 0xF7], None),
+            None
+        );
+        assert_eq!(
+            tuning_change_from_sysex(&[0xF0, 0x43, 0x10, 0x08, 0x02, 0xF7], None),
+            None
+        );
+        assert_eq!(tuning_change_from_sysex(&[0xF0, 0xF7], None), None);
+    }
+}